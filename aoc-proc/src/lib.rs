@@ -1,9 +1,9 @@
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::{ToTokens, quote};
-use syn::parse::Parser;
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
-use syn::{Expr, Token};
+use syn::{Expr, LitStr, Token};
 
 #[proc_macro]
 pub fn aoc_event(input: TokenStream) -> TokenStream {
@@ -53,3 +53,222 @@ pub fn aoc_event(input: TokenStream) -> TokenStream {
 
     output.into()
 }
+
+mod kw {
+    syn::custom_keyword!(file);
+    syn::custom_keyword!(parse);
+    syn::custom_keyword!(part1);
+    syn::custom_keyword!(part2);
+}
+
+enum Source {
+    Example(LitStr),
+    File(LitStr),
+}
+
+struct Case {
+    source: Source,
+    /// `None` for a part written as `_`, which isn't checked.
+    answers: [Option<Expr>; 2],
+}
+
+#[derive(Default)]
+struct Examples {
+    parse: Option<Expr>,
+    part1: Option<Expr>,
+    part2: Option<Expr>,
+    cases: Vec<Case>,
+}
+
+fn answer(input: ParseStream) -> syn::Result<Option<Expr>> {
+    if input.peek(Token![_]) {
+        input.parse::<Token![_]>()?;
+        Ok(None)
+    } else {
+        input.parse().map(Some)
+    }
+}
+
+impl Parse for Examples {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut examples = Examples::default();
+        loop {
+            let slot = if input.peek(kw::parse) {
+                input.parse::<kw::parse>()?;
+                &mut examples.parse
+            } else if input.peek(kw::part1) {
+                input.parse::<kw::part1>()?;
+                &mut examples.part1
+            } else if input.peek(kw::part2) {
+                input.parse::<kw::part2>()?;
+                &mut examples.part2
+            } else {
+                break;
+            };
+            input.parse::<Token![=]>()?;
+            *slot = Some(input.parse()?);
+            input.parse::<Token![;]>()?;
+        }
+        while !input.is_empty() {
+            let source = if input.peek(kw::file) {
+                let keyword = input.parse::<kw::file>()?;
+                if examples
+                    .cases
+                    .iter()
+                    .any(|case| matches!(case.source, Source::File(_)))
+                {
+                    return Err(syn::Error::new(
+                        keyword.span,
+                        "only one `file` case is allowed, it becomes the `input` test",
+                    ));
+                }
+                Source::File(input.parse()?)
+            } else {
+                Source::Example(input.parse()?)
+            };
+            input.parse::<Token![=>]>()?;
+            let content;
+            syn::parenthesized!(content in input);
+            let first = answer(&content)?;
+            content.parse::<Token![,]>()?;
+            let second = answer(&content)?;
+            content.parse::<Option<Token![,]>>()?;
+            examples.cases.push(Case {
+                source,
+                answers: [first, second],
+            });
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(examples)
+    }
+}
+
+/// Drops the blank lines an example starts with, as it usually begins on
+/// the line after the opening quote, and any trailing whitespace. Leading
+/// spaces on the first real line are kept, some puzzles need them.
+fn normalize(example: &str) -> String {
+    let mut s = example.trim_end();
+    while let Some(i) = s.find('\n')
+        && s[..i].trim().is_empty()
+    {
+        s = &s[i + 1..];
+    }
+    if s.trim().is_empty() { "" } else { s }.to_string()
+}
+
+/// Tests running `parse` and both parts on each puzzle example, plus an
+/// `input` test on the real input, from at most one `file` case, that passes
+/// without checking anything when the file is missing:
+///
+/// ```ignore
+/// aoc_examples! {
+///     part1 = |(w, r)| part1(w, r);
+///     "
+/// px{a<2006:qkq,m>2090:A,rfg}
+/// ..." => (19114, 167409079868000),
+///     file "data/2023/day19" => (_, 121158073425385),
+/// }
+/// ```
+///
+/// By default this calls `parse(&str)`, `part1(&parsed)` and
+/// `part2(&parsed)`; `parse = ...;`, `part1 = ...;` or `part2 = ...;` first
+/// replace them with closures over the same arguments. A `_` answer skips
+/// that part.
+#[proc_macro]
+pub fn aoc_examples(input: TokenStream) -> TokenStream {
+    let examples = syn::parse_macro_input!(input as Examples);
+    let parse = examples
+        .parse
+        .map_or_else(|| quote!(parse(data)), |f| quote!((#f)(data)));
+    let parts = [
+        examples
+            .part1
+            .map_or_else(|| quote!(|p| part1(p)), |f| f.into_token_stream()),
+        examples
+            .part2
+            .map_or_else(|| quote!(|p| part2(p)), |f| f.into_token_stream()),
+    ];
+
+    let mut n = 0;
+    let tests = examples.cases.iter().map(|case| {
+        let checks = case
+            .answers
+            .iter()
+            .zip(&parts)
+            .zip(["part1", "part2"])
+            .filter_map(|((answer, part), name)| {
+                let answer = answer.as_ref()?;
+                Some(quote! {
+                    assert_eq!(#answer, apply(&parsed, #part), #name);
+                })
+            });
+        let checks = quote! {
+            let parsed = #parse;
+            #( #checks )*
+        };
+        match &case.source {
+            Source::Example(s) => {
+                n += 1;
+                let name = Ident::new(&format!("example{n}"), Span::call_site());
+                let s = LitStr::new(&normalize(&s.value()), s.span());
+                quote! {
+                    #[test]
+                    fn #name() {
+                        let data = #s;
+                        #checks
+                    }
+                }
+            }
+            Source::File(path) => quote! {
+                #[test]
+                fn input() {
+                    let Ok(data) = std::fs::read_to_string(#path) else {
+                        eprintln!("skipped: {} is missing", #path);
+                        return;
+                    };
+                    let data = data.as_str();
+                    #checks
+                }
+            },
+        }
+    });
+
+    quote! {
+        #[cfg(test)]
+        mod examples {
+            #![allow(clippy::redundant_closure)]
+            use super::*;
+
+            /// Gives closures their argument type up front.
+            fn apply<T, R>(parsed: &T, f: impl FnOnce(&T) -> R) -> R {
+                f(parsed)
+            }
+
+            #( #tests )*
+        }
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_leading_blank_lines() {
+        assert_eq!(normalize("\n  \n\nab\ncd\n"), "ab\ncd");
+    }
+
+    #[test]
+    fn normalize_keeps_leading_spaces() {
+        assert_eq!(normalize("\n    [D]\n[N] [C]\n"), "    [D]\n[N] [C]");
+    }
+
+    #[test]
+    fn normalize_whitespace_only() {
+        assert_eq!(normalize(""), "");
+        assert_eq!(normalize("\n   \n\t\n"), "");
+    }
+}
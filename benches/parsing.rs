@@ -0,0 +1,105 @@
+//! Integer extraction from `parsing` against the split-and-trim parsers the
+//! days used before. Run with `cargo bench --bench parsing`.
+
+use adventofcode::parsing::{integers, ints};
+use std::hint::black_box;
+use std::time::Instant;
+
+fn time(name: &str, lines: &[String], f: impl Fn(&str) -> i64) {
+    let rounds = 200;
+    let start = Instant::now();
+    let mut total = 0;
+    for _ in 0..rounds {
+        for line in lines {
+            total += f(black_box(line));
+        }
+    }
+    let per_line = start.elapsed() / (rounds * lines.len()) as u32;
+    println!("{name:<28} {:>6} ns/line  ({total})", per_line.as_nanos());
+}
+
+fn robots(n: usize) -> Vec<String> {
+    (0..n as i64)
+        .map(|i| format!("p={},{} v={},-{}", i % 101, i % 103, i % 7 - 3, i % 5))
+        .collect()
+}
+
+fn sensors(n: usize) -> Vec<String> {
+    (0..n as i64)
+        .map(|i| {
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x=-{}, y={}",
+                i * 7919 % 4_000_000,
+                i * 104_729 % 4_000_000,
+                i * 31 % 1000,
+                i * 17 % 1000
+            )
+        })
+        .collect()
+}
+
+fn blueprints(n: usize) -> Vec<String> {
+    (1..=n)
+        .map(|i| {
+            format!(
+                "Blueprint {i}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+                 Each obsidian robot costs {} ore and {} clay. \
+                 Each geode robot costs {} ore and {} obsidian.",
+                i % 3 + 2,
+                i % 4 + 2,
+                i % 3 + 2,
+                i % 15 + 5,
+                i % 3 + 2,
+                i % 12 + 7
+            )
+        })
+        .collect()
+}
+
+fn main() {
+    let n = 10_000;
+
+    let lines = robots(n);
+    time("robots: split_once", &lines, |x| {
+        let (p, v) = x.split_once(' ').unwrap();
+        let (px, py) = p.trim_start_matches("p=").split_once(',').unwrap();
+        let (vx, vy) = v.trim_start_matches("v=").split_once(',').unwrap();
+        let r: [i32; 4] = [
+            px.parse().unwrap(),
+            py.parse().unwrap(),
+            vx.parse().unwrap(),
+            vy.parse().unwrap(),
+        ];
+        r.iter().map(|&v| v as i64).sum()
+    });
+    time("robots: ints::<4, i32>", &lines, |x| {
+        let r = ints::<4, i32>(x).unwrap();
+        r.iter().map(|&v| v as i64).sum()
+    });
+
+    let lines = sensors(n);
+    time("sensors: split and trim", &lines, |line| {
+        line.split_ascii_whitespace()
+            .filter(|p| p.contains('='))
+            .map(|p| {
+                let p = p.trim_end_matches([',', ':']);
+                p[2..].parse::<i32>().unwrap() as i64
+            })
+            .sum()
+    });
+    time("sensors: ints::<4, i32>", &lines, |line| {
+        let r = ints::<4, i32>(line).unwrap();
+        r.iter().map(|&v| v as i64).sum()
+    });
+
+    let lines = blueprints(n);
+    time("blueprints: filter_map", &lines, |line| {
+        line.split_ascii_whitespace()
+            .filter_map(|p| p.parse::<u16>().ok())
+            .map(|v| v as i64)
+            .sum()
+    });
+    time("blueprints: integers", &lines, |line| {
+        integers::<u16>(line).skip(1).map(|v| v as i64).sum()
+    });
+}
@@ -1,4 +1,31 @@
+use adventofcode::parsing::{self, ParseError, signed};
 use ahash::AHashSet as HashSet;
+use nom::{combinator::verify, error::context};
+
+fn parse(data: &str) -> Result<Vec<i64>, ParseError> {
+    let data = data.trim();
+    let change = verify(signed::<i64>, |x| x.abs() < 1 << 32);
+    let nums = parsing::lines(data, context("a 32-bit change", change))?;
+    // part 2 only ends if two frequencies in the first pass are the same
+    // modulo the drift per pass, or there is no drift at all
+    let drift: i64 = nums.iter().sum();
+    let mut seen = HashSet::new();
+    let mut curr: i64 = 0;
+    let repeats = drift == 0
+        || nums.iter().any(|x| {
+            let new = seen.insert(curr.rem_euclid(drift));
+            curr += x;
+            !new
+        });
+    if !repeats {
+        return Err(ParseError::at(
+            data,
+            data,
+            "changes that repeat a frequency",
+        ));
+    }
+    Ok(nums)
+}
 
 fn part1(nums: &[i64]) -> i64 {
     nums.iter().sum()
@@ -23,11 +50,8 @@ fn part2(nums: &[i64]) -> i64 {
 }
 
 pub fn main() {
-    let nums: Vec<i64> = std::fs::read_to_string("data/2018/day1")
-        .unwrap()
-        .lines()
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+    let data = std::fs::read_to_string("data/2018/day1").unwrap();
+    let nums = parse(&data).unwrap();
     println!("part1: {}", part1(&nums));
     println!("part2: {}", part2(&nums));
 }
@@ -35,6 +59,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -59,4 +84,10 @@ mod tests {
         let nums = vec![7, 7, -2, -7, -4];
         assert_eq!(14, part2(&nums));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "+3\n+3\n+4\n-2\n-4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,40 +1,39 @@
+use adventofcode::ocr;
+use adventofcode::parsing::{self, PResult, ParseError};
 use nom::{
+    Parser,
     bytes::complete::tag,
     character::complete::{char as ch, digit1, space0},
-    combinator::{eof, map_res, opt, recognize},
-    IResult, Parser,
+    combinator::{map_res, opt, recognize},
+    sequence::preceded,
 };
 
 type Vec2 = (i32, i32);
 
-fn parse_i32(input: &str) -> IResult<&str, i32> {
+fn parse_i32(input: &str) -> PResult<'_, i32> {
     let num = (space0, opt(ch('-')), digit1);
     map_res(recognize(num), |s: &str| s.trim_start().parse()).parse(input)
 }
 
-fn parse_vec2(input: &str) -> IResult<&str, Vec2> {
+fn parse_vec2(input: &str) -> PResult<'_, Vec2> {
     let (r, (_, x, _, y, _)) = (ch('<'), parse_i32, tag(", "), parse_i32, ch('>')).parse(input)?;
     Ok((r, (x, y)))
 }
 
-fn parse_star(input: &str) -> IResult<&str, (Vec2, Vec2)> {
-    let (r, (_, vp, _, _, ve, _)) = (
+fn parse_star(input: &str) -> PResult<'_, (Vec2, Vec2)> {
+    let (r, (_, vp, _, _, ve)) = (
         tag("position="),
         parse_vec2,
         ch(' '),
         tag("velocity="),
         parse_vec2,
-        eof,
     )
         .parse(input)?;
     Ok((r, (vp, ve)))
 }
 
-fn parse(data: &str) -> Vec<(Vec2, Vec2)> {
-    data.lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| parse_star(line.trim()).map(|r| r.1).unwrap())
-        .collect()
+fn parse(data: &str) -> Result<Vec<(Vec2, Vec2)>, ParseError> {
+    parsing::lines(data.trim(), preceded(space0, parse_star))
 }
 
 fn step_forward(stars: &mut [(Vec2, Vec2)]) {
@@ -68,16 +67,18 @@ fn area(stars: &[(Vec2, Vec2)]) -> i64 {
     (max.0 - min.0) as i64 * (max.1 - min.1) as i64
 }
 
-fn print_stars(stars: &[(Vec2, Vec2)]) {
+fn render(stars: &[(Vec2, Vec2)]) -> String {
     let (min, max) = aabb(stars);
     let w = (max.0 - min.0 + 1) as usize;
     let h = (max.1 - min.1 + 1) as usize;
-    let mut grid = vec![vec![b' '; w]; h];
+    let mut grid = vec![vec![b'.'; w]; h];
     stars
         .iter()
         .for_each(|(p, _)| grid[(p.1 - min.1) as usize][(p.0 - min.0) as usize] = b'#');
     grid.iter()
-        .for_each(|r| println!("{}", unsafe { std::str::from_utf8_unchecked(r) }));
+        .map(|r| unsafe { std::str::from_utf8_unchecked(r) })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 fn solve(stars: &mut [(Vec2, Vec2)]) -> i32 {
@@ -96,16 +97,20 @@ fn solve(stars: &mut [(Vec2, Vec2)]) -> i32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day10").unwrap();
-    let mut stars = parse(&data);
+    let mut stars = parse(&data).unwrap();
     let t = solve(&mut stars);
-    println!("part1:");
-    print_stars(&stars);
+    let img = render(&stars);
+    match ocr::recognize_str(&img) {
+        Ok(text) => println!("part1: {text}"),
+        Err(e) => println!("part1: {e}\n{img}"),
+    }
     println!("part2: {t}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -142,9 +147,24 @@ mod tests {
         position=<14,  7> velocity=<-2,  0>
         position=<-3,  6> velocity=< 2, -1>"
             .to_string();
-        let mut stars = parse(&data);
+        let mut stars = parse(&data).unwrap();
         let t = solve(&mut stars);
-        print_stars(&stars);
         assert_eq!(3, t);
+        let hi = "
+#...#..###
+#...#...#.
+#...#...#.
+#####...#.
+#...#...#.
+#...#...#.
+#...#...#.
+#...#..###";
+        assert_eq!(hi.trim(), render(&stars));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "position=< 9,  1> velocity=< 0,  2>\nposition=<-3, 11> velocity=< 1, -2>";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
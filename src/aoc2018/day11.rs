@@ -1,5 +1,7 @@
-fn parse(data: &str) -> i32 {
-    data.trim_end().parse().unwrap()
+use adventofcode::parsing::{self, ParseError, signed};
+
+fn parse(data: &str) -> Result<i32, ParseError> {
+    parsing::parse_all(data.trim(), signed)
 }
 
 fn power_level(serial: i32, x: i32, y: i32) -> i32 {
@@ -73,7 +75,7 @@ fn part2(g: &[Vec<i32>]) -> (i32, i32, i32) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day11").unwrap();
-    let serial = parse(&data);
+    let serial = parse(&data).unwrap();
     let g = grid(serial);
     let p1 = part1(&g);
     let p2 = part2(&g);
@@ -84,6 +86,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn test_power_level() {
@@ -106,4 +109,10 @@ mod tests {
         assert_eq!((21, 61), part1(&g));
         assert_eq!((232, 251, 12), part2(&g));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "18";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
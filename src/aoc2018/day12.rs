@@ -1,37 +1,45 @@
-use std::collections::VecDeque;
+use adventofcode::{
+    cycle::Detector,
+    parsing::{self, PResult, ParseError},
+};
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::one_of,
+    combinator::verify,
+    error::context,
+    multi::{count, many1},
+    sequence::{preceded, separated_pair},
+};
 
-fn parse(data: &str) -> (Vec<bool>, [bool; 32]) {
-    let mut lines = data.lines();
-
-    let first_line = lines.next().unwrap();
-    let initial = first_line.trim().split_ascii_whitespace().last().unwrap();
-    let initial = initial.as_bytes().iter().map(|&b| b == b'#').collect();
-
-    lines.next();
+fn pots(input: &str) -> PResult<'_, Vec<bool>> {
+    many1(one_of(".#").map(|c| c == '#')).parse(input)
+}
 
-    let mut rules = [false; 32];
-    lines.for_each(|line| {
-        let (pattern, outcome) = line.trim().split_once(" => ").unwrap();
-        if outcome == "." {
-            return;
-        }
-        let pattern = pattern
-            .as_bytes()
-            .iter()
+fn rule(input: &str) -> PResult<'_, (usize, bool)> {
+    let pattern = count(one_of(".#"), 5).map(|p| {
+        p.iter()
             .enumerate()
-            .fold(
-                0,
-                |acc, (i, b)| {
-                    if *b == b'#' {
-                        acc | (1 << i)
-                    } else {
-                        acc
-                    }
-                },
-            );
-        rules[pattern] = true;
+            .filter(|e| *e.1 == '#')
+            .fold(0, |acc, (i, _)| acc | (1 << i))
     });
-    (initial, rules)
+    let rule = separated_pair(pattern, tag(" => "), one_of(".#").map(|c| c == '#'));
+    // the endless empty pots on both sides have to stay empty
+    context(
+        "no rule growing a plant among empty pots",
+        verify(rule, |&r| r != (0, true)),
+    )
+    .parse(input)
+}
+
+fn parse(data: &str) -> Result<(Vec<bool>, [bool; 32]), ParseError> {
+    let [initial, rules] = parsing::split_sections::<2>(data.trim())?;
+    let initial = parsing::parse_all(initial, preceded(tag("initial state: "), pots))?;
+    let mut table = [false; 32];
+    for (pattern, outcome) in parsing::lines(rules, rule)? {
+        table[pattern] |= outcome;
+    }
+    Ok((initial, table))
 }
 
 fn step(pots: &[bool], rules: &[bool]) -> Vec<bool> {
@@ -82,30 +90,41 @@ fn part1(initial: &[bool], rules: &[bool], days: usize) -> i64 {
 }
 
 fn part2(initial: &[bool], rules: &[bool]) -> i64 {
-    let days = 1000;
-    let mut pots = Vec::with_capacity(initial.len() + 4 * days);
-    pots.extend(vec![false; 2 * days]);
-    pots.extend(initial);
-    pots.extend(vec![false; 2 * days]);
-
-    let mut q = VecDeque::new();
-    q.push_front(sum_of_numbers(&pots, days));
-    for i in 0..days {
-        pots = step(&pots, rules);
-        q.push_front(sum_of_numbers(&pots, days));
-        if q.len() > 3 && q[0] - q[1] == q[1] - q[2] && q[0] - q[1] == q[2] - q[3] {
-            return (500_0000_0000 - i as i64 - 1) * (q[0] - q[1]) + q[0];
-        }
-        if q.len() > 3 {
-            q.pop_back();
+    // the pattern is kept trimmed and padded, `offset` is the number of its first pot
+    let mut pots = initial.to_vec();
+    let mut offset = 0;
+    let mut detector = Detector::new();
+    let mut sums = vec![];
+    loop {
+        // once every pot is dead nothing grows back
+        let Some(first) = pots.iter().position(|&p| p) else {
+            return 0;
+        };
+        let last = pots.iter().rposition(|&p| p).unwrap();
+        pots = pots[first..=last].to_vec();
+        offset += first as i64;
+        sums.push(
+            pots.iter()
+                .enumerate()
+                .filter(|e| *e.1)
+                .map(|(i, _)| i as i64 + offset)
+                .sum(),
+        );
+        if let Some(cycle) = detector.push(pots.clone()) {
+            return cycle.extrapolate(&sums, 500_0000_0000);
         }
+
+        let mut padded = vec![false; 4];
+        padded.extend(&pots);
+        padded.extend([false; 4]);
+        pots = step(&padded, rules);
+        offset -= 4;
     }
-    panic!("did not converge")
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day12").unwrap();
-    let (initial, rules) = parse(&data);
+    let (initial, rules) = parse(&data).unwrap();
     println!("part1: {:?}", part1(&initial, &rules, 20));
     println!("part2: {:?}", part2(&initial, &rules));
 }
@@ -113,6 +132,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -133,7 +153,19 @@ mod tests {
 ###.# => #
 ####. => #"
             .to_string();
-        let (initial, rules) = parse(&data);
+        let (initial, rules) = parse(&data).unwrap();
         assert_eq!(325, part1(&initial, &rules, 20));
     }
+
+    #[test]
+    fn extinct() {
+        let (initial, rules) = parse("initial state: #..##\n\n..#.. => .\n").unwrap();
+        assert_eq!(0, part2(&initial, &rules));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "initial state: #..#.#\n\n...## => #\n..#.. => .\n#.### => #";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
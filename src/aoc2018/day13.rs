@@ -1,3 +1,4 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashMap, HashSet};
 
 #[derive(Clone)]
@@ -14,16 +15,9 @@ impl Cart {
     }
 }
 
-fn parse(data: &str) -> (Vec<Vec<u8>>, Vec<Cart>) {
-    let mut map = data
-        .lines()
-        .map(|line| line.as_bytes().to_vec())
-        .collect::<Vec<_>>();
-
-    // map should be non-empty and rectangular
-    assert!(!map.is_empty());
-    let w = map[0].len();
-    assert!(map.iter().all(|row| row.len() == w));
+fn parse(data: &str) -> Result<(Vec<Vec<u8>>, Vec<Cart>), ParseError> {
+    // rows keep their trailing spaces, so the map stays rectangular
+    let mut map = parsing::grid_of(data.trim_end_matches('\n'), " -|/\\+^v<>")?;
 
     // find carts (and tracks underneath)
     let mut carts = vec![];
@@ -44,7 +38,7 @@ fn parse(data: &str) -> (Vec<Vec<u8>>, Vec<Cart>) {
         }
     }
 
-    (map, carts)
+    Ok((map, carts))
 }
 
 fn turn(c: u8, slash: u8) -> u8 {
@@ -211,7 +205,7 @@ fn part2(map: &[Vec<u8>], mut carts: Vec<Cart>) -> (usize, usize) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day13").unwrap();
-    let (map, carts) = parse(&data);
+    let (map, carts) = parse(&data).unwrap();
     let p1 = part1(&map, carts.clone());
     let p2 = part2(&map, carts);
     println!("part1: {},{}", p1.0, p1.1);
@@ -221,6 +215,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -231,7 +226,7 @@ mod tests {
 \-+-/  \-+--/
     \------/ "#
             .to_string();
-        let (map, carts) = parse(&data);
+        let (map, carts) = parse(&data).unwrap();
         // print_map(&map, &carts);
         assert_eq!((7, 3), part1(&map, carts));
     }
@@ -246,8 +241,14 @@ mod tests {
   |   ^
   \<->/"#
             .to_string();
-        let (map, carts) = parse(&data);
+        let (map, carts) = parse(&data).unwrap();
         // print_map(&map, &carts);
         assert_eq!((6, 4), part2(&map, carts));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "/->-\\  \n|   v  \n\\---/  ";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,5 +1,7 @@
-fn parse(data: &str) -> usize {
-    data.trim().parse().unwrap()
+use adventofcode::parsing::{self, ParseError, unsigned};
+
+fn parse(data: &str) -> Result<usize, ParseError> {
+    parsing::parse_all(data.trim(), unsigned)
 }
 
 fn part1(num: usize) -> String {
@@ -71,7 +73,7 @@ fn part2(recipes: &str) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day14").unwrap();
-    let num = parse(&data);
+    let num = parse(&data).unwrap();
     println!("part1: {}", part1(num));
     println!("part2: {}", part2(data.trim()));
 }
@@ -79,6 +81,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn test_part1() {
@@ -95,4 +98,10 @@ mod tests {
         assert_eq!(18, part2("92510"));
         assert_eq!(2018, part2("59414"));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "2018";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashMap, HashSet};
 
 #[derive(Clone, PartialEq)]
@@ -203,12 +204,13 @@ impl std::fmt::Display for Map {
     }
 }
 
-fn parse(data: &str) -> Map {
-    let grid = data
-        .trim()
-        .lines()
-        .map(|line| line.as_bytes().to_vec())
-        .collect::<Vec<_>>();
+fn parse(data: &str) -> Result<Map, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(data, "#.GE")?;
+    // positions are kept as i8
+    if grid.len() > 128 || grid[0].len() > 128 {
+        return Err(ParseError::at(data, data, "a map of at most 128 by 128"));
+    }
     let units = grid
         .iter()
         .enumerate()
@@ -222,13 +224,13 @@ fn parse(data: &str) -> Map {
             })
         })
         .collect();
-    Map {
+    Ok(Map {
         grid,
         units,
         turns: 0,
         elv_attack: 3,
         elv_died: false,
-    }
+    })
 }
 
 fn part1(mut map: Map) -> usize {
@@ -255,7 +257,7 @@ fn part2(map: Map) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day15").unwrap();
-    let map = parse(&data);
+    let map = parse(&data).unwrap();
     println!("part1: {}", part1(map.clone()));
     println!("part2: {}", part2(map));
 }
@@ -263,6 +265,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn test_find_path() {
@@ -273,7 +276,7 @@ mod tests {
 #...G.#
 #######"
             .to_string();
-        let map = parse(&data);
+        let map = parse(&data).unwrap();
         assert_eq!(Some((1, 3)), map.find_path((1, 2)));
     }
 
@@ -290,7 +293,7 @@ mod tests {
 #G..G..G#
 #########"
             .to_string();
-        let mut map = parse(&data);
+        let mut map = parse(&data).unwrap();
         for _ in 0..3 {
             map.play_round();
         }
@@ -305,7 +308,7 @@ mod tests {
 #.......#
 #########"
             .to_string();
-        let expected = parse(&data);
+        let expected = parse(&data).unwrap();
         assert_eq!(expected.grid, map.grid);
     }
 
@@ -320,7 +323,7 @@ mod tests {
 #.....#
 #######"
             .to_string();
-        let map = parse(&data);
+        let map = parse(&data).unwrap();
         assert_eq!(27730, part1(map.clone()));
         assert_eq!(4988, part2(map));
     }
@@ -336,7 +339,7 @@ mod tests {
 #...E.#
 #######"
             .to_string();
-        let map = parse(&data);
+        let map = parse(&data).unwrap();
         assert_eq!(36334, part1(map));
     }
 
@@ -351,7 +354,7 @@ mod tests {
 #..E#.#
 #######"
             .to_string();
-        let mut map = parse(&data);
+        let mut map = parse(&data).unwrap();
         while map.play_round() {}
         assert_eq!(39514, map.outcome());
     }
@@ -367,7 +370,7 @@ mod tests {
 #...E.#
 #######"
             .to_string();
-        let map = parse(&data);
+        let map = parse(&data).unwrap();
         assert_eq!(27755, part1(map.clone()));
         assert_eq!(3478, part2(map));
     }
@@ -383,7 +386,7 @@ mod tests {
 #...#G#
 #######"
             .to_string();
-        let map = parse(&data);
+        let map = parse(&data).unwrap();
         assert_eq!(28944, part1(map.clone()));
         assert_eq!(6474, part2(map));
     }
@@ -401,8 +404,14 @@ mod tests {
 #.....G.#
 #########"
             .to_string();
-        let map = parse(&data);
+        let map = parse(&data).unwrap();
         assert_eq!(18740, part1(map.clone()));
         assert_eq!(1140, part2(map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#######\n#.E...#\n#...G.#\n#######";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
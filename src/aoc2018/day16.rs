@@ -1,12 +1,12 @@
+use adventofcode::parsing::{self, Error, PResult, ParseError, unsigned};
 use nom::{
+    Parser,
     bytes::complete::tag,
-    character::{
-        complete::{char as ch, digit1, line_ending},
-        streaming::space1,
-    },
-    combinator::{map_res, recognize},
+    character::complete::{char as ch, line_ending, space1},
+    combinator::verify,
+    error::context,
     multi::{count, separated_list1},
-    IResult, Parser,
+    sequence::{delimited, preceded},
 };
 
 type Vec4 = [i32; 4];
@@ -40,35 +40,39 @@ fn operate(reg: &mut [i32], ins: &Vec4) {
     };
 }
 
-fn parse_num(input: &str) -> IResult<&str, i32> {
-    map_res(recognize(digit1), str::parse).parse(input)
+fn parse_num(input: &str) -> PResult<'_, i32> {
+    unsigned(input)
 }
 
-fn parse_registers(input: &str) -> IResult<&str, Vec<i32>> {
-    let (r, (_, nums, _)) =
-        (ch('['), separated_list1(tag(", "), parse_num), ch(']')).parse(input)?;
-    Ok((r, nums))
+fn parse_four<'a>(sep: &'static str) -> impl Parser<&'a str, Output = Vec4, Error = Error<'a>> {
+    let next = || preceded(tag(sep), parse_num);
+    (parse_num, next(), next(), next()).map(|(a, b, c, d)| [a, b, c, d])
 }
 
-fn parse_registers_before(input: &str) -> IResult<&str, Vec4> {
-    let (r, (_, _, nums)) = (tag("Before:"), space1, parse_registers).parse(input)?;
-    let nums = [nums[0], nums[1], nums[2], nums[3]];
-    Ok((r, nums))
+fn parse_registers(input: &str) -> PResult<'_, Vec4> {
+    delimited(ch('['), parse_four(", "), ch(']')).parse(input)
 }
 
-fn parse_registers_after(input: &str) -> IResult<&str, Vec4> {
-    let (r, (_, _, nums)) = (tag("After:"), space1, parse_registers).parse(input)?;
-    let nums = [nums[0], nums[1], nums[2], nums[3]];
-    Ok((r, nums))
+fn parse_registers_before(input: &str) -> PResult<'_, Vec4> {
+    preceded((tag("Before:"), space1), parse_registers).parse(input)
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, Vec4> {
-    let (r, nums) = (separated_list1(ch(' '), parse_num)).parse(input)?;
-    let nums = [nums[0], nums[1], nums[2], nums[3]];
-    Ok((r, nums))
+fn parse_registers_after(input: &str) -> PResult<'_, Vec4> {
+    preceded((tag("After:"), space1), parse_registers).parse(input)
 }
 
-fn parse_sample(input: &str) -> IResult<&str, Sample> {
+/// Every opcode is tried on every sample, so all operands must work as
+/// register numbers.
+fn parse_instruction(input: &str) -> PResult<'_, Vec4> {
+    let valid = |ins: &Vec4| ins[0] < 16 && ins[1..].iter().all(|&r| r < 4);
+    context(
+        "an opcode below 16 and registers 0 to 3",
+        verify(parse_four(" "), valid),
+    )
+    .parse(input)
+}
+
+fn parse_sample(input: &str) -> PResult<'_, Sample> {
     let (r, (before, _, instruction, _, after)) = (
         parse_registers_before,
         line_ending,
@@ -87,18 +91,18 @@ fn parse_sample(input: &str) -> IResult<&str, Sample> {
     ))
 }
 
-fn parse_samples(input: &str) -> IResult<&str, Vec<Sample>> {
+fn parse_samples(input: &str) -> PResult<'_, Vec<Sample>> {
     separated_list1(count(line_ending, 2), parse_sample).parse(input)
 }
 
-fn parse_program(input: &str) -> IResult<&str, Vec<Vec4>> {
+fn parse_program(input: &str) -> PResult<'_, Vec<Vec4>> {
     separated_list1(line_ending, parse_instruction).parse(input)
 }
 
-fn parse(data: &str) -> (Vec<Sample>, Vec<Vec4>) {
-    let (_, (samples, _, program)) =
-        (parse_samples, count(line_ending, 4), parse_program).parse(data).unwrap();
-    (samples, program)
+fn parse(data: &str) -> Result<(Vec<Sample>, Vec<Vec4>), ParseError> {
+    let input = (parse_samples, count(line_ending, 4), parse_program);
+    let (samples, _, program) = parsing::parse_all(data.trim_end(), input)?;
+    Ok((samples, program))
 }
 
 fn try_opcode(op: i32, sample: &Sample) -> bool {
@@ -180,7 +184,7 @@ fn part2(samples: &[Sample], program: Vec<Vec4>) -> i32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day16").unwrap();
-    let (samples, program) = parse(&data);
+    let (samples, program) = parse(&data).unwrap();
     println!("part1: {}", part1(&samples));
     println!("part2: {}", part2(&samples, program));
 }
@@ -188,6 +192,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -199,4 +204,10 @@ After:  [3, 2, 2, 1]"
         let sample = parse_sample(data.trim_start()).unwrap().1;
         assert_eq!(3, num_of_candidates(&sample));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]\n\nBefore: [0, 1, 2, 3]\n0 1 2 3\nAfter:  [0, 1, 2, 3]\n\n\n\n9 2 1 2\n0 1 2 3";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,11 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::Parser;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, one_of};
+use nom::combinator::verify;
+use nom::error::context;
+use nom::sequence::separated_pair;
+
 struct Map {
     grid: Vec<Vec<u8>>,
     spring_x: usize,
@@ -82,26 +90,30 @@ impl Map {
     }
 }
 
-fn parse(data: &str) -> Map {
-    let walls = data
-        .trim()
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once(", ").unwrap();
-            let (a, n1) = a.split_once('=').unwrap();
-            let n1 = n1.parse::<usize>().unwrap();
-            let (_, range) = b.split_once('=').unwrap();
-            let (left, right) = range.split_once("..").unwrap();
-            let left = left.parse::<usize>().unwrap();
-            let right = right.parse::<usize>().unwrap();
-            (a, n1, left, right)
-        })
-        .collect::<Vec<_>>();
+/// A vein of clay, `x=495, y=2..7` is `('x', 495, 2, 7)`.
+fn vein(line: &str) -> PResult<'_, (char, usize, usize, usize)> {
+    let vein = (
+        one_of("xy"),
+        char('='),
+        unsigned,
+        tag(", "),
+        one_of("xy"),
+        char('='),
+        separated_pair(unsigned, tag(".."), unsigned),
+    );
+    verify(vein, |&(a, _, _, _, b, _, (l, r))| a != b && l <= r)
+        .map(|(a, _, n, _, _, _, (l, r))| (a, n, l, r))
+        .parse(line)
+}
+
+fn parse(data: &str) -> Result<Map, ParseError> {
+    let data = data.trim();
+    let walls = parsing::lines(data, context("a vein of clay", vein))?;
 
     let mut min = (usize::MAX, usize::MAX);
     let mut max = (usize::MIN, usize::MIN);
     for &(a, n1, left, right) in &walls {
-        if a == "x" {
+        if a == 'x' {
             min.0 = min.0.min(n1);
             max.0 = max.0.max(n1);
             min.1 = min.1.min(left);
@@ -113,11 +125,18 @@ fn parse(data: &str) -> Map {
             max.0 = max.0.max(right);
         }
     }
-    let width = max.0 - min.0 + 3;
-    let height = max.1 - min.1 + 2;
+    // the water has to fall onto the scan, which has to fit in memory
+    if walls.is_empty() || !(min.0..=max.0).contains(&500) {
+        return Err(ParseError::at(data, data, "clay on both sides of x=500"));
+    }
+    let width = (max.0 - min.0).saturating_add(3);
+    let height = (max.1 - min.1).saturating_add(2);
+    if width.saturating_mul(height) > 1 << 24 {
+        return Err(ParseError::at(data, data, "a scan of at most 2^24 squares"));
+    }
     let mut grid = vec![vec![b'.'; width]; height];
     for (a, num, left, right) in walls {
-        if a == "x" {
+        if a == 'x' {
             let j = num - min.0 + 1;
             for row in &mut grid[left - min.1 + 1..=right - min.1 + 1] {
                 row[j] = b'#';
@@ -130,7 +149,7 @@ fn parse(data: &str) -> Map {
     }
     let spring_x = 500 - min.0 + 1;
     grid[0][spring_x] = b'+';
-    Map { grid, spring_x }
+    Ok(Map { grid, spring_x })
 }
 
 // fn print_map(map: &Map) {
@@ -153,7 +172,7 @@ fn part2(map: &Map) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day17").unwrap();
-    let mut map = parse(&data);
+    let mut map = parse(&data).unwrap();
     map.flow();
     println!("part1: {}", part1(&map));
     println!("part2: {}", part2(&map));
@@ -162,6 +181,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -174,9 +194,15 @@ x=506, y=1..2
 x=498, y=10..13
 x=504, y=10..13
 y=13, x=498..504";
-        let mut map = parse(data);
+        let mut map = parse(data).unwrap();
         map.flow();
         assert_eq!(57, part1(&map));
         assert_eq!(29, part2(&map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "x=495, y=2..7\ny=7, x=495..501\nx=501, y=3..7";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
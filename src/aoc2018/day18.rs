@@ -1,11 +1,12 @@
-use ahash::HashMap;
+use adventofcode::{
+    cycle,
+    parsing::{self, ParseError},
+};
 
-fn parse(data: &str) -> Vec<Vec<u8>> {
-    let mut m = data
-        .trim()
-        .lines()
-        .map(|line| {
-            let mut r = line.as_bytes().to_vec();
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut m = parsing::grid_of(data.trim(), ".|#")?
+        .into_iter()
+        .map(|mut r| {
             r.insert(0, b' ');
             r.push(b' ');
             r
@@ -13,7 +14,7 @@ fn parse(data: &str) -> Vec<Vec<u8>> {
         .collect::<Vec<_>>();
     m.insert(0, vec![b' '; m[0].len()]);
     m.push(vec![b' '; m[0].len()]);
-    m
+    Ok(m)
 }
 
 // fn print_map(map: &[Vec<u8>]) {
@@ -105,26 +106,23 @@ fn part1(map: &mut [Vec<u8>]) -> usize {
     resource_value(map)
 }
 
-fn part2(mut map: Vec<Vec<u8>>) -> usize {
-    let mut seen = HashMap::default();
-    let mut values = vec![0; 11];
-    let mut i = 10;
-    loop {
-        i += 1;
-        step(&mut map);
-        if let Some(&j) = seen.get(&map) {
-            let period = i - j;
-            let k: usize = (10_0000_0000 - i) % period;
-            return values[k + j];
-        }
-        seen.insert(map.clone(), i);
-        values.push(resource_value(&map));
-    }
+fn part2(map: Vec<Vec<u8>>) -> usize {
+    // part1 already ran 10 minutes
+    let map = cycle::nth(
+        map,
+        |m| {
+            let mut m = m.clone();
+            step(&mut m);
+            m
+        },
+        10_0000_0000 - 10,
+    );
+    resource_value(&map)
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day18").unwrap();
-    let mut map = parse(&data);
+    let mut map = parse(&data).unwrap();
     println!("part1: {}", part1(&mut map));
     println!("part2: {}", part2(map));
 }
@@ -132,6 +130,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -146,7 +145,13 @@ mod tests {
 ||...#|.#|
 |.||||..|.
 ...#.|..|.";
-        let mut map = parse(data);
+        let mut map = parse(data).unwrap();
         assert_eq!(1147, part1(&mut map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = ".#.|\n|..#\n#|..";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
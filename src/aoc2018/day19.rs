@@ -1,15 +1,22 @@
+use adventofcode::parsing::{self, PResult, ParseError};
 use nom::{
-    IResult, Parser,
+    Parser,
     bytes::complete::{tag, take},
     character::{
         self,
         complete::{char as ch, line_ending},
     },
-    combinator::all_consuming,
+    combinator::verify,
+    error::context,
     multi::separated_list1,
     sequence::{preceded, separated_pair},
 };
 
+const OPS: [&str; 16] = [
+    "addr", "addi", "mulr", "muli", "banr", "bani", "borr", "bori", "setr", "seti", "gtir", "gtri",
+    "gtrr", "eqir", "eqri", "eqrr",
+];
+
 #[derive(Debug)]
 struct Instruction<'a> {
     op: &'a str,
@@ -64,13 +71,36 @@ impl Program {
     }
 }
 
-fn parse_ip(s: &str) -> IResult<&str, u8> {
-    preceded(tag("#ip "), character::complete::u8).parse(s)
+fn parse_ip(s: &str) -> PResult<'_, u8> {
+    let register = verify(character::complete::u8, |&r| r < 6);
+    preceded(tag("#ip "), context("a register from 0 to 5", register)).parse(s)
+}
+
+/// Whether `in1` and `in2` of `op` name registers rather than values.
+fn register_operands(op: &str) -> (bool, bool) {
+    let set = op.starts_with("set");
+    (
+        op != "seti" && !op.ends_with("ir"),
+        !set && op.ends_with('r'),
+    )
+}
+
+fn valid(ins: &Instruction) -> bool {
+    let (r1, r2) = register_operands(ins.op);
+    ins.out < 6 && (!r1 || ins.in1 < 6) && (!r2 || ins.in2 < 6)
+}
+
+fn parse_instruction(s: &str) -> PResult<'_, Instruction<'_>> {
+    context(
+        "an instruction on registers 0 to 5",
+        verify(parse_operation, valid),
+    )
+    .parse(s)
 }
 
-fn parse_instruction(s: &str) -> IResult<&str, Instruction<'_>> {
+fn parse_operation(s: &str) -> PResult<'_, Instruction<'_>> {
     let (r, (op, _, in1, _, in2, _, out)) = (
-        take(4usize),
+        context("an opcode", verify(take(4usize), |op| OPS.contains(op))),
         ch(' '),
         character::complete::u64,
         ch(' '),
@@ -82,16 +112,16 @@ fn parse_instruction(s: &str) -> IResult<&str, Instruction<'_>> {
     Ok((r, Instruction { op, in1, in2, out }))
 }
 
-fn parse_instructions(s: &str) -> IResult<&str, Vec<Instruction<'_>>> {
+fn parse_instructions(s: &str) -> PResult<'_, Vec<Instruction<'_>>> {
     separated_list1(line_ending, parse_instruction).parse(s)
 }
 
 #[allow(unused)]
-fn parse(data: &str) -> (u8, Vec<Instruction<'_>>) {
-    let (_, r) = all_consuming(separated_pair(parse_ip, line_ending, parse_instructions))
-        .parse(data.trim())
-        .unwrap();
-    r
+fn parse(data: &str) -> Result<(u8, Vec<Instruction<'_>>), ParseError> {
+    parsing::parse_all(
+        data.trim(),
+        separated_pair(parse_ip, line_ending, parse_instructions),
+    )
 }
 
 #[allow(unused)]
@@ -125,6 +155,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -137,7 +168,13 @@ addr 1 2 3
 setr 1 0 0
 seti 8 0 4
 seti 9 0 5";
-        let (ip_reg, instructions) = parse(data);
+        let (ip_reg, instructions) = parse(data).unwrap();
         assert_eq!(6, part1_sim(ip_reg as usize, &instructions));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#ip 0\nseti 5 0 1\naddi 0 1 0\nsetr 1 0 0";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
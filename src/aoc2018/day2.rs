@@ -1,3 +1,11 @@
+use adventofcode::parsing::{self, ParseError};
+use nom::{bytes::complete::take_while1, error::context};
+
+fn parse(data: &str) -> Result<Vec<&str>, ParseError> {
+    let id = take_while1(|c: char| c.is_ascii_lowercase());
+    parsing::lines(data.trim(), context("a box id", id))
+}
+
 fn part1(ids: &[&str]) -> usize {
     let mut two = 0;
     let mut three = 0;
@@ -42,7 +50,7 @@ fn part2(ids: &[&str]) -> String {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day2").unwrap();
-    let ids: Vec<&str> = data.lines().collect();
+    let ids = parse(&data).unwrap();
     println!("part1: {}", part1(&ids));
     println!("part2: {}", part2(&ids));
 }
@@ -50,11 +58,18 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz";
-        let ids: Vec<&str> = data.lines().collect();
+        let ids = parse(data).unwrap();
         assert_eq!("fgij", part2(&ids));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "abcde\nfghij\nfguij";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
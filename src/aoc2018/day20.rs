@@ -1,10 +1,46 @@
 use std::collections::VecDeque;
 
+use adventofcode::parsing::ParseError;
 use ahash::{HashMap, HashSet};
 
-fn parse(data: &str) -> &[u8] {
-    let s = data.trim().as_bytes();
-    &s[1..s.len() - 1]
+fn parse(data: &str) -> Result<&[u8], ParseError> {
+    let s = data.trim();
+    let Some(body) = s.strip_prefix('^') else {
+        return Err(ParseError::at(s, s, "'^'"));
+    };
+    let Some(body) = body.strip_suffix('$') else {
+        return Err(ParseError::at(s, &s[s.len()..], "'$'"));
+    };
+    // every step moves two squares, which has to stay within i16
+    if body.len() > 16000 {
+        return Err(ParseError::at(s, body, "a route of at most 16000 steps"));
+    }
+    let mut depth = 0usize;
+    for (i, c) in body.char_indices() {
+        let ok = match c {
+            'N' | 'E' | 'S' | 'W' | '|' => true,
+            '(' => {
+                depth += 1;
+                true
+            }
+            ')' if depth > 0 => {
+                depth -= 1;
+                true
+            }
+            _ => false,
+        };
+        if !ok {
+            return Err(ParseError::at(
+                s,
+                &body[i..],
+                "a direction or a balanced branch",
+            ));
+        }
+    }
+    if depth > 0 {
+        return Err(ParseError::at(s, &s[s.len() - 1..], "')'"));
+    }
+    Ok(body.as_bytes())
 }
 
 fn reveal_position(m: &mut HashMap<(i16, i16), u8>, (x, y): (i16, i16), b: u8) {
@@ -140,7 +176,7 @@ fn solve(map: &HashMap<(i16, i16), u8>) -> (usize, usize) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day20").unwrap();
-    let regex = parse(&data);
+    let regex = parse(&data).unwrap();
     let map = explore(regex);
     let (p1, p2) = solve(&map);
     println!("part1: {p1}");
@@ -150,6 +186,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -160,7 +197,7 @@ mod tests {
 #-###
 #.|X#
 #####";
-        let regex = parse(data);
+        let regex = parse(data).unwrap();
         let map = explore(regex);
         assert_eq!(expected.trim(), draw_map(&map));
         assert_eq!(3, solve(&map).0);
@@ -179,7 +216,7 @@ mod tests {
 #-#-#####
 #.|.|.|.#
 #########";
-        let regex = parse(data);
+        let regex = parse(data).unwrap();
         let map = explore(regex);
         assert_eq!(expected.trim(), draw_map(&map));
         assert_eq!(10, solve(&map).0);
@@ -200,7 +237,7 @@ mod tests {
 #-###-###-#
 #.|.|.#.|.#
 ###########";
-        let regex = parse(data);
+        let regex = parse(data).unwrap();
         let map = explore(regex);
         assert_eq!(expected.trim(), draw_map(&map));
         assert_eq!(18, solve(&map).0);
@@ -223,7 +260,7 @@ mod tests {
 ###-#-###-#-#
 #.|.#.|.|.#.#
 #############";
-        let regex = parse(data);
+        let regex = parse(data).unwrap();
         let map = explore(regex);
         assert_eq!(expected.trim(), draw_map(&map));
         assert_eq!(23, solve(&map).0);
@@ -248,9 +285,15 @@ mod tests {
 #-#-#####-#-#-#
 #.#.|.|.|.#.|.#
 ###############";
-        let regex = parse(data);
+        let regex = parse(data).unwrap();
         let map = explore(regex);
         assert_eq!(expected.trim(), draw_map(&map));
         assert_eq!(31, solve(&map).0);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "^ENWWW(NEEE|SSE(EE|N))$";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -28,15 +28,3 @@ pub fn main() {
     println!("part1: {p1}");
     println!("part2: {p2}");
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn case1() {
-        let (p1, p2) = solve();
-        assert_eq!(6132825, p1);
-        assert_eq!(8307757, p2);
-    }
-}
@@ -1,22 +1,30 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use adventofcode::{
+    parsing::{self, ParseError, unsigned},
+    search,
+};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char as ch, line_ending},
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
 
-fn parse(data: &str) -> (u32, (u32, u32)) {
-    let mut lines = data.trim().lines();
-    let depth = lines
-        .next()
-        .unwrap()
-        .trim_start_matches("depth: ")
-        .parse()
-        .unwrap();
-    let (x, y) = lines
-        .next()
-        .unwrap()
-        .trim_start_matches("target: ")
-        .split_once(',')
-        .unwrap();
-    let x = x.parse().unwrap();
-    let y = y.parse().unwrap();
-    (depth, (x, y))
+/// Small enough that the erosion levels fit in u32 and part 2's map, twice
+/// the target in each direction, fits in memory.
+fn parse(data: &str) -> Result<(u32, (u32, u32)), ParseError> {
+    let depth = context("a depth below 2^24", verify(unsigned, |&d| d < 1 << 24));
+    let coordinate = || context("a coordinate below 2048", verify(unsigned, |&c| c < 2048));
+    let target = separated_pair(coordinate(), ch(','), coordinate());
+    let (depth, _, target) = parsing::parse_all(
+        data.trim(),
+        (
+            preceded(tag("depth: "), depth),
+            line_ending,
+            preceded(tag("target: "), target),
+        ),
+    )?;
+    Ok((depth, target))
 }
 
 fn geologic_index((x, y): (u32, u32), row: &[u32], target: (u32, u32)) -> u32 {
@@ -61,55 +69,34 @@ fn manhattan((i1, j1): (u32, u32), (i2, j2): (u32, u32)) -> u32 {
     i1.abs_diff(i2) + j1.abs_diff(j2)
 }
 
+// tools: 0 neither, 1 torch, 2 climbing gear; region type r forbids tool r
 fn part2(depth: u32, target: (u32, u32)) -> u32 {
     let (map, w, h) = gen_map(depth, target, 2);
-    let mut q = BinaryHeap::new();
-    q.push((Reverse(0), (0, 0), 1, 0));
-    let mut dist = vec![u32::MAX; w * h * 3];
-    let imap = |x: u32, y: u32| y as usize * w + x as usize;
-    let idist = |x: u32, y: u32, s: u8| y as usize * (3 * w) + x as usize * 3 + s as usize;
-    while let Some((Reverse(f), (x, y), tool, t)) = q.pop() {
-        if (x, y) == target {
-            if tool == 1 {
-                return t;
-            } else {
-                q.push((Reverse(f + 7), (x, y), 1, t + 7));
-                continue;
-            }
-        }
-        if t > dist[idist(x, y, tool)] {
-            continue;
-        }
+    let region = |x: u32, y: u32| map[y as usize * w + x as usize];
+    let neighbors = |&((x, y), tool): &((u32, u32), u8)| {
+        let r = region(x, y);
+        let mut next = vec![(((x, y), 3 - r - tool), 7)];
         for (x1, y1) in [
             (x + 1, y),
             (x, y + 1),
-            (x.saturating_sub(1), y),
-            (x, y.saturating_sub(1)),
+            (x.wrapping_sub(1), y),
+            (x, y.wrapping_sub(1)),
         ] {
-            if (x1, y1) == (x, y) {
-                continue;
-            }
-            let r = map[imap(x, y)];
-            let r1 = map[imap(x1, y1)];
-            let h = manhattan((x1, y1), target);
-            if tool == r1 {
-                let tool1 = 3 - r - r1;
-                if t + 8 < dist[idist(x1, y1, tool1)] {
-                    dist[idist(x1, y1, tool1)] = t + 8;
-                    q.push((Reverse(t + 8 + h), (x1, y1), tool1, t + 8));
-                }
-            } else if t + 1 < dist[idist(x1, y1, tool)] {
-                dist[idist(x1, y1, tool)] = t + 1;
-                q.push((Reverse(t + 1 + h), (x1, y1), tool, t + 1));
+            if (x1 as usize) < w && (y1 as usize) < h && region(x1, y1) != tool {
+                next.push((((x1, y1), tool), 1));
             }
         }
-    }
-    unreachable!()
+        next
+    };
+    let heuristic = |&(p, _): &((u32, u32), u8)| manhattan(p, target);
+    search::astar(((0, 0), 1), neighbors, heuristic, |&s| s == (target, 1))
+        .unwrap()
+        .1
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day22").unwrap();
-    let (depth, target) = parse(&data);
+    let (depth, target) = parse(&data).unwrap();
     println!("part1: {}", part1(depth, target));
     println!("part2: {}", part2(depth, target));
 }
@@ -117,6 +104,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -125,4 +113,10 @@ mod tests {
         assert_eq!(114, part1(depth, target));
         assert_eq!(45, part2(depth, target));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "depth: 510\ntarget: 10,10";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,6 +1,15 @@
-use std::collections::BinaryHeap;
+use std::{collections::BinaryHeap, ops::RangeInclusive};
 
+use adventofcode::parsing::{self, PResult, ParseError, signed, unsigned};
 use arrayvec::ArrayVec;
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::char as ch,
+    combinator::verify,
+    error::context,
+    sequence::{delimited, preceded},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Point {
@@ -29,22 +38,36 @@ struct Bot {
     radius: i32,
 }
 
-fn parse(data: &str) -> Vec<Bot> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once(">, r=").unwrap();
-            let mut pos = a.trim_start_matches("pos=<").split(',');
-            let x = pos.next().unwrap().parse().unwrap();
-            let y = pos.next().unwrap().parse().unwrap();
-            let z = pos.next().unwrap().parse().unwrap();
-            let radius = b.parse().unwrap();
-            Bot {
-                pos: Point { x, y, z },
-                radius,
-            }
+/// The cube part 2 searches.
+const SEARCH: RangeInclusive<i32> = i32::MIN / 4..=i32::MAX / 4;
+
+fn coordinate(input: &str) -> PResult<'_, i32> {
+    context(
+        "a coordinate within 2^29",
+        verify(signed, |c| SEARCH.contains(c)),
+    )
+    .parse(input)
+}
+
+fn bot(input: &str) -> PResult<'_, Bot> {
+    let position = (coordinate, ch(','), coordinate, ch(','), coordinate);
+    let radius = context(
+        "a radius within 2^29",
+        verify(unsigned, |&r| r <= *SEARCH.end()),
+    );
+    (
+        delimited(tag("pos=<"), position, tag(">")),
+        preceded(tag(", r="), radius),
+    )
+        .map(|((x, _, y, _, z), radius)| Bot {
+            pos: Point { x, y, z },
+            radius,
         })
-        .collect()
+        .parse(input)
+}
+
+fn parse(data: &str) -> Result<Vec<Bot>, ParseError> {
+    parsing::lines(data.trim(), bot)
 }
 
 #[inline]
@@ -144,9 +167,10 @@ fn divide(c: Cuboid) -> ArrayVec<Cuboid, 8> {
 
 fn part2(nanobots: &[Bot]) -> i32 {
     let mut q = BinaryHeap::new();
+    let (min, max) = (*SEARCH.start(), *SEARCH.end());
     let b = Cuboid {
-        min: Point::new(i32::MIN / 4, i32::MIN / 4, i32::MIN / 4),
-        max: Point::new(i32::MAX / 4, i32::MAX / 4, i32::MAX / 4),
+        min: Point::new(min, min, min),
+        max: Point::new(max, max, max),
     };
     q.push((nanobots.len(), b));
     let (mut max, mut dist) = (0, 0);
@@ -182,7 +206,7 @@ fn part2(nanobots: &[Bot]) -> i32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day23").unwrap();
-    let nanobots = parse(&data);
+    let nanobots = parse(&data).unwrap();
     println!("part1: {}", part1(&nanobots));
     println!("part2: {}", part2(&nanobots));
 }
@@ -190,6 +214,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -203,7 +228,7 @@ pos=<0,0,3>, r=1
 pos=<1,1,1>, r=1
 pos=<1,1,2>, r=1
 pos=<1,3,1>, r=1";
-        let nanobots = parse(data);
+        let nanobots = parse(data).unwrap();
         assert_eq!(7, part1(&nanobots));
     }
 
@@ -216,15 +241,13 @@ pos=<16,12,12>, r=4
 pos=<14,14,14>, r=6
 pos=<50,50,50>, r=200
 pos=<10,10,10>, r=5";
-        let nanobots = parse(data);
+        let nanobots = parse(data).unwrap();
         assert_eq!(36, part2(&nanobots));
     }
 
     #[test]
-    fn case3() {
-        let data = std::fs::read_to_string("data/2018/day23").unwrap();
-        let nanobots = parse(&data);
-        assert_eq!(602, part1(&nanobots));
-        assert_eq!(110620102, part2(&nanobots));
+    fn fuzz() {
+        let seed = "pos=<0,0,0>, r=4\npos=<1,0,-3>, r=1";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
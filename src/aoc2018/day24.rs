@@ -1,4 +1,6 @@
+use adventofcode::parsing::{ParseError, ints, split_sections};
 use ahash::HashMap;
+use aoc_proc::aoc_examples;
 
 #[derive(Clone)]
 struct Group {
@@ -8,7 +10,7 @@ struct Group {
     damage_type: u8,
     immune: u8,
     weak: u8,
-    initiative: i8,
+    initiative: i64,
 }
 
 impl Group {
@@ -73,8 +75,8 @@ struct Army {
 }
 
 impl Army {
-    fn select_targets(&self, enemy: &Army) -> Vec<(i8, System, usize, usize)> {
-        let mut ids: Vec<(i64, i8, usize)> = self
+    fn select_targets(&self, enemy: &Army) -> Vec<(i64, System, usize, usize)> {
+        let mut ids: Vec<(i64, i64, usize)> = self
             .groups
             .iter()
             .enumerate()
@@ -92,74 +94,87 @@ impl Army {
     }
 }
 
-fn parse_group<'a>(line: &'a str, damage_types: &mut HashMap<&'a str, usize>) -> Group {
-    let (count, line) = line.split_once(" units each with ").unwrap();
-    let count = count.parse().unwrap();
-    let (hp, mut line) = line.split_once(" hit points ").unwrap();
-    let hp = hp.parse().unwrap();
+fn damage_bit<'a>(
+    line: &str,
+    type_: &'a str,
+    damage_types: &mut HashMap<&'a str, usize>,
+) -> Result<u8, ParseError> {
+    let len = damage_types.len();
+    match *damage_types.entry(type_).or_insert(len) {
+        i @ 0..8 => Ok(1 << i),
+        _ => Err(ParseError::at(line, type_, "at most 8 damage types")),
+    }
+}
 
-    let (mut immune, mut weak) = (0, 0);
-    if line.starts_with('(') {
-        let (special, remain) = line
-            .trim_start_matches('(')
-            .split_once(") with an attack that does ")
-            .unwrap();
-        line = remain;
+fn parse_group<'a>(
+    line: &'a str,
+    damage_types: &mut HashMap<&'a str, usize>,
+) -> Result<Group, ParseError> {
+    let error = |at: &str, expected: &str| ParseError::at(line, at, expected);
+    let [units, hp, attack, initiative] = ints::<4, i64>(line)?;
+    if units < 0 || hp <= 0 || attack < 0 {
+        return Err(error(line, "a group with positive hit points"));
+    }
 
+    let (mut immune, mut weak) = (0, 0);
+    if let Some((_, special)) = line.split_once('(') {
+        let (special, _) = special
+            .split_once(')')
+            .ok_or_else(|| error(special, "')'"))?;
         for p in special.split("; ") {
-            let (tag, list) = p.split_once(" to ").unwrap();
-            let m = if tag == "immune" {
-                &mut immune
-            } else {
-                &mut weak
+            let (tag, list) = p.split_once(" to ").ok_or_else(|| error(p, "' to '"))?;
+            let m = match tag {
+                "immune" => &mut immune,
+                "weak" => &mut weak,
+                _ => return Err(error(tag, "immune or weak")),
             };
             for t in list.split(", ") {
-                let len = damage_types.len();
-                let e = damage_types.entry(t).or_insert(len);
-                *m |= 1 << *e;
+                *m |= damage_bit(line, t, damage_types)?;
             }
         }
-    } else {
-        line = line.trim_start_matches("with an attack that does ");
     }
 
-    let (attack, initiative) = line.split_once(" damage at initiative ").unwrap();
-    let (attack, type_) = attack.split_once(" ").unwrap();
-    let attack = attack.parse().unwrap();
-    let len = damage_types.len();
-    let damage_type = *damage_types.entry(type_).or_insert(len);
-    let damage_type = 1 << damage_type;
-    let initiative = initiative.parse().unwrap();
-    Group {
-        units: count,
+    let (attack_part, _) = line
+        .split_once(" damage at initiative ")
+        .ok_or_else(|| error(line, "' damage at initiative '"))?;
+    let type_ = attack_part.rsplit(' ').next().unwrap();
+    Ok(Group {
+        units,
         hp,
         attack,
-        damage_type,
+        damage_type: damage_bit(line, type_, damage_types)?,
         immune,
         weak,
         initiative,
-    }
+    })
 }
 
-fn parse_groups<'a>(lines: &'a str, damage_types: &mut HashMap<&'a str, usize>) -> Army {
+fn parse_groups<'a>(
+    lines: &'a str,
+    damage_types: &mut HashMap<&'a str, usize>,
+) -> Result<Army, ParseError> {
     let mut lines = lines.lines();
-    let system = lines.next().unwrap();
-    let system = match system {
-        "Immune System:" => System::Immune,
-        "Infection:" => System::Infection,
-        _ => unreachable!(),
+    let system = match lines.next() {
+        Some("Immune System:") => System::Immune,
+        Some("Infection:") => System::Infection,
+        header => {
+            let header = header.unwrap_or_default();
+            return Err(ParseError::at(header, header, "an army name"));
+        }
     };
-    let groups = lines.map(|line| parse_group(line, damage_types)).collect();
-    Army { system, groups }
+    let groups = lines
+        .map(|line| parse_group(line, damage_types))
+        .collect::<Result<_, _>>()?;
+    Ok(Army { system, groups })
 }
 
-fn parse(data: &str) -> (Army, Army) {
-    let (a, b) = data.trim().split_once("\n\n").unwrap();
+fn parse(data: &str) -> Result<(Army, Army), ParseError> {
+    let [a, b] = split_sections(data)?;
     let mut damage_types = HashMap::default();
-    (
-        parse_groups(a, &mut damage_types),
-        parse_groups(b, &mut damage_types),
-    )
+    Ok((
+        parse_groups(a, &mut damage_types)?,
+        parse_groups(b, &mut damage_types)?,
+    ))
 }
 
 fn fight(mut immune: Army, mut infection: Army) -> (i64, i64) {
@@ -210,35 +225,34 @@ fn part2(immune: Army, infection: Army) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day24").unwrap();
-    let (immune, infection) = parse(&data);
+    let (immune, infection) = parse(&data).unwrap();
     println!("part1: {}", part1(immune.clone(), infection.clone()));
     println!("part2: {}", part2(immune, infection));
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn case1() {
-        let data = "
+aoc_examples! {
+    parse = |s| parse(s).unwrap();
+    part1 = |(immune, infection)| part1(immune.clone(), infection.clone());
+    part2 = |(immune, infection)| part2(immune.clone(), infection.clone());
+    "
 Immune System:
 17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
 989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
 
 Infection:
 801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
-4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4";
-        let (immune, infection) = parse(data);
-        assert_eq!(5216, part1(immune.clone(), infection.clone()));
-        assert_eq!(51, part2(immune, infection));
-    }
+4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4" => (5216, 51),
+    file "data/2018/day24" => (9328, 2172),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
 
     #[test]
-    fn case2() {
-        let data = std::fs::read_to_string("data/2018/day24").unwrap();
-        let (immune, infection) = parse(&data);
-        assert_eq!(9328, part1(immune.clone(), infection.clone()));
-        assert_eq!(2172, part2(immune, infection));
+    fn fuzz() {
+        let seed = "Immune System:\n17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2\n\nInfection:\n801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,17 +1,25 @@
-use ahash::HashSet;
+use adventofcode::{
+    dsu::Dsu,
+    parsing::{self, PResult, ParseError, signed},
+};
+use nom::{
+    Parser,
+    character::complete::{char as ch, space0},
+    combinator::verify,
+    error::context,
+    sequence::{delimited, preceded},
+};
 
-fn parse(data: &str) -> Vec<[i8; 4]> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let mut point = [0; 4];
-            for (i, n) in line.trim().split(',').enumerate() {
-                let n = n.parse().unwrap();
-                point[i] = n;
-            }
-            point
-        })
-        .collect()
+/// Small enough that distances fit in i8.
+fn coordinate(input: &str) -> PResult<'_, i8> {
+    let small = |n: &i8| (-15..=15).contains(n);
+    context("a coordinate from -15 to 15", verify(signed, small)).parse(input)
+}
+
+fn parse(data: &str) -> Result<Vec<[i8; 4]>, ParseError> {
+    let rest = || preceded(ch(','), coordinate);
+    let point = (coordinate, rest(), rest(), rest()).map(|(a, b, c, d)| [a, b, c, d]);
+    parsing::lines(data.trim(), delimited(space0, point, space0))
 }
 
 #[inline]
@@ -19,44 +27,6 @@ fn manhattan(a: &[i8; 4], b: &[i8; 4]) -> i8 {
     a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum()
 }
 
-struct Dsu {
-    parent: Vec<usize>,
-    size: Vec<u32>,
-}
-
-impl Dsu {
-    fn new(n: usize) -> Self {
-        Self {
-            parent: (0..n).collect(),
-            size: vec![1; n],
-        }
-    }
-
-    fn find(&mut self, x: usize) -> usize {
-        if self.parent[x] != x {
-            self.parent[x] = self.find(self.parent[x]);
-        }
-        self.parent[x]
-    }
-
-    fn union(&mut self, x: usize, y: usize) {
-        let (px, py) = (self.find(x), self.find(y));
-        if px == py {
-            return;
-        }
-        match self.size[px].cmp(&self.size[py]) {
-            std::cmp::Ordering::Less => {
-                self.size[py] += self.size[px];
-                self.parent[px] = py;
-            }
-            _ => {
-                self.size[px] += self.size[py];
-                self.parent[py] = px;
-            }
-        }
-    }
-}
-
 fn part1(points: &[[i8; 4]]) -> usize {
     let n = points.len();
     let mut dsu = Dsu::new(n);
@@ -67,19 +37,19 @@ fn part1(points: &[[i8; 4]]) -> usize {
             }
         }
     }
-    let roots: HashSet<usize> = (0..n).map(|i| dsu.find(i)).collect();
-    roots.len()
+    dsu.components()
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day25").unwrap();
-    let points = parse(&data);
+    let points = parse(&data).unwrap();
     println!("part1: {}", part1(&points));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -92,7 +62,7 @@ mod tests {
  0,0,0,6
  9,0,0,0
 12,0,0,0";
-        let points = parse(data);
+        let points = parse(data).unwrap();
         assert_eq!(2, part1(&points));
     }
 
@@ -109,7 +79,7 @@ mod tests {
 -1,0,-1,0
 0,2,1,-2
 3,0,0,0";
-        let points = parse(data);
+        let points = parse(data).unwrap();
         assert_eq!(4, part1(&points));
     }
 
@@ -126,7 +96,7 @@ mod tests {
 2,-2,0,-1
 1,-1,0,-1
 3,2,0,2";
-        let points = parse(data);
+        let points = parse(data).unwrap();
         assert_eq!(3, part1(&points));
     }
 
@@ -143,7 +113,13 @@ mod tests {
 -2,2,3,-1
 1,2,2,0
 -1,-2,0,-2";
-        let points = parse(data);
+        let points = parse(data).unwrap();
         assert_eq!(8, part1(&points));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = " 0,0,0,0\n 3,0,-1,0\n-2,0,12,3";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,4 +1,12 @@
-use std::str::FromStr;
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::char,
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
 
 struct Rect {
     id: u16,
@@ -8,32 +16,27 @@ struct Rect {
     h: u16,
 }
 
-#[derive(Debug)]
-struct InvalidRectangle;
-
-impl FromStr for Rect {
-    type Err = InvalidRectangle;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (id, r) = s.split_once(" @ ").ok_or(InvalidRectangle)?;
-        let (xy, wh) = r.split_once(": ").ok_or(InvalidRectangle)?;
-        let (x, y) = xy.split_once(',').ok_or(InvalidRectangle)?;
-        let (w, h) = wh.split_once('x').ok_or(InvalidRectangle)?;
-
-        let id = id[1..].parse().map_err(|_| InvalidRectangle)?;
-        let x = x.parse().map_err(|_| InvalidRectangle)?;
-        let y = y.parse().map_err(|_| InvalidRectangle)?;
-        let w = w.parse().map_err(|_| InvalidRectangle)?;
-        let h = h.parse().map_err(|_| InvalidRectangle)?;
-        Ok(Rect { id, x, y, w, h })
-    }
+fn parse(data: &str) -> Result<Vec<Rect>, ParseError> {
+    let corner = separated_pair(unsigned, char(','), unsigned);
+    let size = separated_pair(unsigned, char('x'), unsigned);
+    let rect = (
+        preceded(char('#'), unsigned),
+        preceded(tag(" @ "), corner),
+        preceded(tag(": "), size),
+    );
+    let rect = rect.map(|(id, (x, y), (w, h))| Rect { id, x, y, w, h });
+    // the fabric is 1000 inches square
+    let fits = |r: &Rect| r.x.max(r.y) <= 1000 && r.w <= 1000 - r.x && r.h <= 1000 - r.y;
+    let rect = context("a claim within 1000 inches", verify(rect, fits));
+    parsing::lines(data.trim(), rect)
 }
 
 fn part1(rects: &[Rect], fabric: &mut [[u8; 1000]]) -> usize {
     for r in rects {
         for i in r.x..(r.x + r.w) {
             for j in r.y..(r.y + r.h) {
-                fabric[i as usize][j as usize] += 1;
+                let e = &mut fabric[i as usize][j as usize];
+                *e = e.saturating_add(1);
             }
         }
     }
@@ -53,7 +56,7 @@ fn part2(rects: &[Rect], fabric: &mut [[u8; 1000]]) -> u16 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day3").unwrap();
-    let rects: Vec<Rect> = data.lines().map(|line| line.parse().unwrap()).collect();
+    let rects = parse(&data).unwrap();
     let mut fabric = [[0u8; 1000]; 1000];
     println!("part1: {}", part1(&rects, &mut fabric));
     println!("part2: {}", part2(&rects, &mut fabric));
@@ -62,13 +65,20 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2";
-        let rects: Vec<Rect> = data.lines().map(|line| line.parse().unwrap()).collect();
+        let rects = parse(data).unwrap();
         let mut fabric = [[0u8; 1000]; 1000];
         assert_eq!(4, part1(&rects, &mut fabric));
         assert_eq!(3, part2(&rects, &mut fabric));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,6 +1,14 @@
-use std::str::FromStr;
-
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::char,
+    combinator::verify,
+    error::context,
+    sequence::{delimited, preceded},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 enum Event {
@@ -18,42 +26,49 @@ struct Record {
     event: Event,
 }
 
-#[derive(Debug)]
-struct InvalidRecord;
-
-impl FromStr for Record {
-    type Err = InvalidRecord;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (datetime, event) = s.split_once("] ").ok_or(InvalidRecord)?;
-        let (md, hm) = datetime[1..].split_once(' ').ok_or(InvalidRecord)?;
-        let (month, day) = md[5..].split_once('-').ok_or(InvalidRecord)?;
-        let (hour, minute) = hm.split_once(':').ok_or(InvalidRecord)?;
-        let month = month.parse().map_err(|_| InvalidRecord)?;
-        let day = day.parse().map_err(|_| InvalidRecord)?;
-        let hour = hour.parse().map_err(|_| InvalidRecord)?;
-        let minute = minute.parse().map_err(|_| InvalidRecord)?;
-        let event = match event {
-            "falls asleep" => Event::FallAsleep,
-            "wakes up" => Event::WakeUp,
-            e => {
-                let id = e.split_ascii_whitespace().nth(1).ok_or(InvalidRecord)?;
-                let id = id[1..].parse().map_err(|_| InvalidRecord)?;
-                Event::BeginShfit(id)
-            }
-        };
-        Ok(Self {
-            month,
-            day,
-            hour,
-            minute,
-            event,
-        })
+fn parse(data: &str) -> Result<Vec<Record>, ParseError> {
+    let data = data.trim();
+    let hour = context("an hour", verify(unsigned, |&h| h < 24));
+    let minute = context("a minute", verify(unsigned, |&m| m < 60));
+    let datetime = (
+        unsigned::<u16>,
+        preceded(char('-'), unsigned),
+        preceded(char('-'), unsigned),
+        preceded(char(' '), hour),
+        preceded(char(':'), minute),
+    );
+    let event = alt((
+        tag("falls asleep").map(|_| Event::FallAsleep),
+        tag("wakes up").map(|_| Event::WakeUp),
+        delimited(tag("Guard #"), unsigned, tag(" begins shift")).map(Event::BeginShfit),
+    ));
+    let record = (delimited(char('['), datetime, tag("] ")), event);
+    let record = record.map(|((_, month, day, hour, minute), event)| Record {
+        month,
+        day,
+        hour,
+        minute,
+        event,
+    });
+    let mut records = parsing::lines(data, record)?;
+    records.sort_unstable_by_key(|r| (r.month, r.day, r.hour, r.minute));
+    if !matches!(records[0].event, Event::BeginShfit(_)) {
+        return Err(ParseError::at(data, data, "a guard on shift first"));
     }
+    // naps are within the midnight hour
+    for w in records.windows(2) {
+        let nap = (w[0].hour, w[1].hour, &w[1].event) == (0, 0, &Event::WakeUp);
+        if w[0].event == Event::FallAsleep && (!nap || w[0].minute >= w[1].minute) {
+            return Err(ParseError::at(data, data, "a wake-up after each nap"));
+        }
+    }
+    if records.last().unwrap().event == Event::FallAsleep {
+        return Err(ParseError::at(data, data, "a wake-up after each nap"));
+    }
+    Ok(records)
 }
 
-fn build_table(mut records: Vec<Record>) -> HashMap<u16, [i32; 60]> {
-    records.sort_unstable_by_key(|r| (r.month, r.day, r.hour, r.minute));
+fn build_table(records: &[Record]) -> HashMap<u16, [i32; 60]> {
     let mut table = HashMap::new();
     let mut current = 0;
     for (i, r) in records.iter().enumerate() {
@@ -116,8 +131,8 @@ fn part2(table: &HashMap<u16, [i32; 60]>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day4").unwrap();
-    let records: Vec<Record> = data.lines().map(|line| line.parse().unwrap()).collect();
-    let table = build_table(records);
+    let records = parse(&data).unwrap();
+    let table = build_table(&records);
     println!("part1: {}", part1(&table));
     println!("part2: {}", part2(&table));
 }
@@ -125,13 +140,20 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep\n[1518-11-01 00:25] wakes up\n[1518-11-01 00:30] falls asleep\n[1518-11-01 00:55] wakes up\n[1518-11-01 23:58] Guard #99 begins shift\n[1518-11-02 00:40] falls asleep\n[1518-11-02 00:50] wakes up\n[1518-11-03 00:05] Guard #10 begins shift\n[1518-11-03 00:24] falls asleep\n[1518-11-03 00:29] wakes up\n[1518-11-04 00:02] Guard #99 begins shift\n[1518-11-04 00:36] falls asleep\n[1518-11-04 00:46] wakes up\n[1518-11-05 00:03] Guard #99 begins shift\n[1518-11-05 00:45] falls asleep\n[1518-11-05 00:55] wakes up".to_string();
-        let records: Vec<Record> = data.lines().map(|line| line.parse().unwrap()).collect();
-        let table = build_table(records);
+        let records = parse(&data).unwrap();
+        let table = build_table(&records);
         assert_eq!(240, part1(&table));
         assert_eq!(4455, part2(&table));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep\n[1518-11-01 00:25] wakes up\n[1518-11-01 23:58] Guard #99 begins shift\n[1518-11-02 00:40] falls asleep\n[1518-11-02 00:50] wakes up";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,12 @@
+use adventofcode::parsing::{self, ParseError};
+use nom::{bytes::complete::take_while1, error::context};
+
+fn parse(data: &str) -> Result<&str, ParseError> {
+    // `react` relies on the units being ASCII
+    let units = take_while1(|c: char| c.is_ascii_alphabetic());
+    parsing::parse_all(data.trim(), context("units a-z or A-Z", units))
+}
+
 fn react(mut p: Vec<u8>) -> String {
     const DIFF: i16 = (b'a' as i16 - b'A' as i16).abs();
     loop {
@@ -44,7 +53,7 @@ fn part2(polymer: &str) -> usize {
 
 pub fn main() {
     let polymer = std::fs::read_to_string("data/2018/day5").unwrap();
-    let reducted = react(polymer.trim().as_bytes().to_vec());
+    let reducted = react(parse(&polymer).unwrap().as_bytes().to_vec());
     println!("part1: {}", part1(&reducted));
     println!("part2: {}", part2(&reducted));
 }
@@ -52,6 +61,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -59,4 +69,10 @@ mod tests {
         assert_eq!("dabCBAcaDA", react(polymer.as_bytes().to_vec()));
         assert_eq!(4, part2(&polymer));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "dabAcCaCBAcCcaDA";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,13 +1,10 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::AHashSet as HashSet;
+use nom::bytes::complete::tag;
+use nom::sequence::separated_pair;
 
-fn parse(data: &str) -> Vec<(i32, i32)> {
-    data.lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let (x, y) = line.split_once(", ").unwrap();
-            (x.parse::<i32>().unwrap(), y.parse::<i32>().unwrap())
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<(i32, i32)>, ParseError> {
+    parsing::lines(data.trim(), separated_pair(unsigned, tag(", "), unsigned))
 }
 
 fn manhattan_distance((x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> i32 {
@@ -95,7 +92,7 @@ fn part2(coords: &[(i32, i32)], threshold: i32) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day6").unwrap();
-    let coords = parse(&data);
+    let coords = parse(&data).unwrap();
     println!("part1: {}", part1(&coords));
     println!("part2: {}", part2(&coords, 10000));
 }
@@ -103,12 +100,19 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9".to_string();
-        let coords = parse(&data);
+        let coords = parse(&data).unwrap();
         assert_eq!(17, part1(&coords));
         assert_eq!(16, part2(&coords, 32));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "1, 1\n1, 6\n8, 3";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,15 +1,35 @@
+use adventofcode::dot::{self, Dot};
+use adventofcode::parsing::{self, PResult, ParseError};
+use nom::Parser;
+use nom::bytes::complete::tag;
+use nom::character::complete::satisfy;
+use nom::error::context;
+use nom::sequence::{delimited, separated_pair};
 use std::{cmp::Reverse, collections::BinaryHeap};
 
-fn parse(data: &str) -> Vec<(u8, u8)> {
-    data.lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let s = line.as_bytes();
-            let a = s[5] - b'A';
-            let b = s[36] - b'A';
-            (a, b)
-        })
-        .collect()
+fn step(input: &str) -> PResult<'_, u8> {
+    context("a step letter", satisfy(|c| c.is_ascii_uppercase()))
+        .map(|c| c as u8 - b'A')
+        .parse(input)
+}
+
+fn parse(data: &str) -> Result<Vec<(u8, u8)>, ParseError> {
+    let requirement = delimited(
+        tag("Step "),
+        separated_pair(step, tag(" must be finished before step "), step),
+        tag(" can begin."),
+    );
+    parsing::lines(data.trim(), requirement)
+}
+
+/// Edges from every step to the steps waiting on it.
+fn to_dot(reqs: &[(u8, u8)]) -> Dot {
+    let mut dot = Dot::digraph();
+    let name = |s: u8| ((s + b'A') as char).to_string();
+    for &(a, b) in reqs {
+        dot.edge(&name(a), &name(b), None);
+    }
+    dot
 }
 
 fn part1(reqs: &[(u8, u8)]) -> String {
@@ -81,7 +101,8 @@ fn part2(reqs: &[(u8, u8)], n: usize, step: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day7").unwrap();
-    let reqs = parse(&data);
+    let reqs = parse(&data).unwrap();
+    dot::export(|| to_dot(&reqs));
     println!("part1: {}", part1(&reqs));
     println!("part2: {}", part2(&reqs, 5, 60));
 }
@@ -89,12 +110,19 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "Step C must be finished before step A can begin.\nStep C must be finished before step F can begin.\nStep A must be finished before step B can begin.\nStep A must be finished before step D can begin.\nStep B must be finished before step E can begin.\nStep D must be finished before step E can begin.\nStep F must be finished before step E can begin.\n".to_string();
-        let reqs = parse(&data);
+        let reqs = parse(&data).unwrap();
         assert_eq!("CABDFE", part1(&reqs));
         assert_eq!(15, part2(&reqs, 2, 0));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Step C must be finished before step A can begin.\nStep A must be finished before step B can begin.";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
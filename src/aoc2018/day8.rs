@@ -1,30 +1,24 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::Parser;
+use nom::character::complete::space1;
+use nom::multi::count;
+use nom::sequence::preceded;
+
 struct TreeNode {
     children: Vec<TreeNode>,
     metadata: Vec<i32>,
 }
 
-fn parse(data: &str) -> Vec<i32> {
-    data.split_ascii_whitespace()
-        .map(|n| n.parse().unwrap())
-        .collect()
+/// The child and metadata counts, then the children and the metadata.
+fn tree(input: &str) -> PResult<'_, TreeNode> {
+    let (input, (m, n)) = (unsigned, preceded(space1, unsigned)).parse(input)?;
+    let (input, children) = count(preceded(space1, tree), m).parse(input)?;
+    let (input, metadata) = count(preceded(space1, unsigned), n).parse(input)?;
+    Ok((input, TreeNode { children, metadata }))
 }
 
-fn parse_tree(mut nums: &[i32]) -> (&[i32], TreeNode) {
-    assert!(nums.len() >= 2);
-    let m = nums[0] as usize;
-    let n = nums[1] as usize;
-    nums = &nums[2..];
-    let children = (0..m)
-        .map(|_| {
-            let (rest, node) = parse_tree(nums);
-            nums = rest;
-            node
-        })
-        .collect();
-
-    assert!(nums.len() >= n);
-    let metadata = nums[..n].to_vec();
-    (&nums[n..], TreeNode { children, metadata })
+fn parse(data: &str) -> Result<TreeNode, ParseError> {
+    parsing::parse_all(data.trim(), tree)
 }
 
 fn part1(root: &TreeNode) -> i64 {
@@ -52,8 +46,7 @@ fn part2(root: &TreeNode) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day8").unwrap();
-    let nums = parse(&data);
-    let (_, root) = parse_tree(&nums);
+    let root = parse(&data).unwrap();
     println!("part1: {}", part1(&root));
     println!("part2: {}", part2(&root));
 }
@@ -61,14 +54,19 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2".to_string();
-        let nums = parse(&data);
-        let (rest, root) = parse_tree(&nums);
-        assert!(rest.is_empty());
+        let root = parse(&data).unwrap();
         assert_eq!(138, part1(&root));
         assert_eq!(66, part2(&root));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
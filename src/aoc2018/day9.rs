@@ -1,10 +1,14 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::bytes::complete::tag;
+use nom::combinator::verify;
+use nom::error::context;
+use nom::sequence::{separated_pair, terminated};
 use std::collections::VecDeque;
 
-fn parse(data: &str) -> (usize, usize) {
-    let parts = data.split_ascii_whitespace().collect::<Vec<_>>();
-    let players = parts[0].parse().unwrap();
-    let worth = parts[6].parse().unwrap();
-    (players, worth)
+fn parse(data: &str) -> Result<(usize, usize), ParseError> {
+    let players = context("at least one player", verify(unsigned, |&n: &usize| n > 0));
+    let game = separated_pair(players, tag(" players; last marble is worth "), unsigned);
+    parsing::parse_all(data.trim(), terminated(game, tag(" points")))
 }
 
 fn part1(players: usize, worth: usize) -> usize {
@@ -33,7 +37,7 @@ fn part1(players: usize, worth: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2018/day9").unwrap();
-    let (players, worth) = parse(&data);
+    let (players, worth) = parse(&data).unwrap();
     println!("part1: {}", part1(players, worth));
     println!("part2: {}", part1(players, worth * 100));
 }
@@ -41,46 +45,53 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "9 players; last marble is worth 25 points".to_string();
-        let (players, worth) = parse(&data);
+        let (players, worth) = parse(&data).unwrap();
         assert_eq!(32, part1(players, worth));
     }
 
     #[test]
     fn case2() {
         let data = "10 players; last marble is worth 1618 points".to_string();
-        let (players, worth) = parse(&data);
+        let (players, worth) = parse(&data).unwrap();
         assert_eq!(8317, part1(players, worth));
     }
 
     #[test]
     fn case3() {
         let data = "13 players; last marble is worth 7999 points".to_string();
-        let (players, worth) = parse(&data);
+        let (players, worth) = parse(&data).unwrap();
         assert_eq!(146373, part1(players, worth));
     }
 
     #[test]
     fn case4() {
         let data = "17 players; last marble is worth 1104 points".to_string();
-        let (players, worth) = parse(&data);
+        let (players, worth) = parse(&data).unwrap();
         assert_eq!(2764, part1(players, worth));
     }
 
     #[test]
     fn case5() {
         let data = "21 players; last marble is worth 6111 points".to_string();
-        let (players, worth) = parse(&data);
+        let (players, worth) = parse(&data).unwrap();
         assert_eq!(54718, part1(players, worth));
     }
 
     #[test]
     fn case6() {
         let data = "30 players; last marble is worth 5807 points".to_string();
-        let (players, worth) = parse(&data);
+        let (players, worth) = parse(&data).unwrap();
         assert_eq!(37305, part1(players, worth));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "9 players; last marble is worth 25 points";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
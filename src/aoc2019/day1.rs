@@ -1,9 +1,13 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::combinator::map;
+
+fn parse(data: &str) -> Result<Vec<i64>, ParseError> {
+    parsing::lines(data.trim(), map(unsigned::<u32>, i64::from))
+}
+
 pub fn main() {
-    let numbers: Vec<i64> = std::fs::read_to_string("data/2019/day1")
-        .unwrap()
-        .lines()
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+    let data = std::fs::read_to_string("data/2019/day1").unwrap();
+    let numbers = parse(&data).unwrap();
 
     let total: i64 = numbers.iter().map(|n| n / 3 - 2).sum();
     println!("day1 part1: {total}");
@@ -20,3 +24,15 @@ pub fn main() {
     let total: i64 = numbers.iter().map(|&n| fuel(n)).sum();
     println!("day1 part2: {total}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "12\n14\n1969\n100756";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,7 +1,17 @@
+use adventofcode::math::gcd;
+use adventofcode::parsing::{self, ParseError};
 use std::f64::consts::{FRAC_PI_2, TAU};
 
-fn gcd(a: i64, b: i64) -> i64 {
-    if b == 0 { a } else { gcd(b, a % b) }
+const VAPORIZED: usize = 200;
+
+fn parse(data: &str) -> Result<Vec<&[u8]>, ParseError> {
+    let data = data.trim();
+    parsing::grid_of(data, ".#")?;
+    // the station and every asteroid part 2 vaporizes
+    if data.matches('#').count() <= VAPORIZED {
+        return Err(ParseError::at(data, data, "more than 200 asteroids"));
+    }
+    Ok(data.lines().map(str::as_bytes).collect())
 }
 
 fn line_of_sight(map: &[&[u8]], (x0, y0): (i64, i64), (x1, y1): (i64, i64)) -> bool {
@@ -11,7 +21,7 @@ fn line_of_sight(map: &[&[u8]], (x0, y0): (i64, i64), (x1, y1): (i64, i64)) -> b
         dx = dx.signum();
         dy = dy.signum();
     } else {
-        let divisor = gcd(dx.abs(), dy.abs());
+        let divisor = gcd(dx, dy);
         dx /= divisor;
         dy /= divisor;
     }
@@ -131,16 +141,17 @@ fn part2(map: &[&[u8]], (x0, y0): (usize, usize), n: usize) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day10").unwrap();
-    let map = data.lines().map(|row| row.as_bytes()).collect::<Vec<_>>();
+    let map = parse(&data).unwrap();
 
     let (num, pos) = part1(&map);
     println!("day10 part1: {num}");
-    println!("day10 part2: {}", part2(&map, pos, 200));
+    println!("day10 part2: {}", part2(&map, pos, VAPORIZED));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -177,4 +188,10 @@ mod tests {
         assert_eq!(802, part2(&map, pos, 200));
         assert_eq!(1009, part2(&map, pos, 201));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "####################\n####################\n####################\n####################\n####################\n####################\n####################\n####################\n####################\n####################\n.#.#.#.#.#.#.#.#.#.#";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
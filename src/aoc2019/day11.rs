@@ -1,6 +1,7 @@
+use adventofcode::ocr;
 use ahash::AHashMap as HashMap;
 
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 enum Direction {
     Up,
@@ -113,12 +114,12 @@ fn bounds(map: &HashMap<(i64, i64), bool>) -> (i64, i64, i64, i64) {
     (min_x, min_y, max_x, max_y)
 }
 
-fn paint(map: &HashMap<(i64, i64), bool>) {
+fn paint(map: &HashMap<(i64, i64), bool>) -> String {
     let bounds = bounds(map);
 
     let width = (bounds.2 - bounds.0 + 1) as usize;
     let height = (bounds.3 - bounds.1 + 1) as usize;
-    let mut img = vec![b' '; width * height];
+    let mut img = vec![b'.'; width * height];
     for (&(x, y), &v) in map {
         if v {
             let x = (bounds.2 - x) as usize;
@@ -127,22 +128,20 @@ fn paint(map: &HashMap<(i64, i64), bool>) {
         }
     }
 
-    img.chunks(width).for_each(|row| {
-        println!("{}", unsafe { std::str::from_utf8_unchecked(row) });
-    })
+    img.chunks(width)
+        .map(|row| unsafe { std::str::from_utf8_unchecked(row) })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day11")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.trim().parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day11").unwrap();
+    let codes = parse(&data).unwrap();
 
     println!("day11 part1: {}", part1(&codes, false).len());
-    println!("day11 part2:");
-    paint(&part1(&codes, true));
+    let img = paint(&part1(&codes, true));
+    match ocr::recognize_str(&img) {
+        Ok(text) => println!("day11 part2: {text}"),
+        Err(e) => println!("day11 part2: {e}\n{img}"),
+    }
 }
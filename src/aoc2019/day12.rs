@@ -1,3 +1,14 @@
+use adventofcode::cycle::brent;
+use adventofcode::math::lcm;
+use adventofcode::parsing::{self, ParseError, signed};
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::char,
+    combinator::verify,
+    error::context,
+    sequence::{delimited, preceded},
+};
 use std::ops::{Add, AddAssign, Sub};
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -95,16 +106,6 @@ impl NBody {
             .map(|p| p.p.energy() * p.v.energy())
             .sum()
     }
-
-    fn state<F>(&self, axis: &F) -> Vec<i64>
-    where
-        F: Fn(&Vec3) -> i64,
-    {
-        self.planets
-            .iter()
-            .flat_map(|p| [axis(&p.p), axis(&p.v)])
-            .collect()
-    }
 }
 
 fn part1(nbody: &mut NBody) -> i64 {
@@ -114,80 +115,79 @@ fn part1(nbody: &mut NBody) -> i64 {
     nbody.total_energy()
 }
 
-fn find_periods(nbody: &mut NBody) -> (usize, usize, usize) {
-    let axis_x = |v: &Vec3| v.x;
-    let axis_y = |v: &Vec3| v.y;
-    let axis_z = |v: &Vec3| v.z;
-
-    let state_x = nbody.state(&axis_x);
-    let state_y = nbody.state(&axis_y);
-    let state_z = nbody.state(&axis_z);
-
-    let mut result = (0, 0, 0);
-    let mut i = 0;
-    loop {
-        nbody.step();
-        i += 1;
-        if nbody.state(&axis_x) == state_x {
-            result.0 = i;
-        }
-        if nbody.state(&axis_y) == state_y {
-            result.1 = i;
-        }
-        if nbody.state(&axis_z) == state_z {
-            result.2 = i;
-        }
-        if result.0 > 0 && result.1 > 0 && result.2 > 0 {
-            return result;
-        }
-    }
+// the axes are independent, each one is a 1-D system of (position, velocity)
+fn step_axis(bodies: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    bodies
+        .iter()
+        .map(|&(p, v)| {
+            let v = v + bodies.iter().map(|&(q, _)| (q - p).signum()).sum::<i64>();
+            (p + v, v)
+        })
+        .collect()
 }
 
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
+fn find_periods(nbody: &NBody) -> [usize; 3] {
+    let axes: [fn(&Vec3) -> i64; 3] = [|v| v.x, |v| v.y, |v| v.z];
+    axes.map(|axis| {
+        let state = nbody
+            .planets
+            .iter()
+            .map(|p| (axis(&p.p), axis(&p.v)))
+            .collect::<Vec<_>>();
+        brent(state, |s| step_axis(s)).lambda
+    })
 }
 
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
+fn parse(data: &str) -> Result<Vec<Planet>, ParseError> {
+    // far from overflowing while the planets pull on each other
+    let coord = || {
+        context(
+            "a coordinate below 2^20",
+            verify(signed, |x: &i64| x.abs() < 1 << 20),
+        )
+    };
+    let position = (
+        preceded(tag("x="), coord()),
+        preceded(tag(", y="), coord()),
+        preceded(tag(", z="), coord()),
+    );
+    let planet = delimited(char('<'), position, char('>'));
+    let planet = planet.map(|(x, y, z)| Planet::new(Vec3 { x, y, z }));
+    parsing::lines(data.trim(), planet)
 }
 
-fn part2(nbody: &mut NBody) -> usize {
-    let (p1, p2, p3) = find_periods(nbody);
+fn part2(nbody: &NBody) -> usize {
+    let [p1, p2, p3] = find_periods(nbody);
     lcm(lcm(p1, p2), p3)
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day12").unwrap();
-    let planets = data
-        .lines()
-        .map(|t| {
-            let t = t.trim();
-            assert_eq!(b'<', t.as_bytes()[0]);
-            assert_eq!(b'>', *t.as_bytes().last().unwrap());
-            let p = t[1..t.len() - 1]
-                .split(',')
-                .map(|part| {
-                    part.split('=')
-                        .nth(1)
-                        .unwrap()
-                        .trim()
-                        .parse::<i64>()
-                        .unwrap()
-                })
-                .collect::<Vec<_>>();
-            Planet::new(Vec3 {
-                x: p[0],
-                y: p[1],
-                z: p[2],
-            })
-        })
-        .collect::<Vec<_>>();
+    let planets = parse(&data).unwrap();
 
     let mut nbody = NBody { planets };
     println!("day12 part1: {}", part1(&mut nbody));
-    println!("day12 part2: {}", part2(&mut nbody));
+    println!("day12 part2: {}", part2(&nbody));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn case1() {
+        let planets = [(-1, 0, 2), (2, -10, -7), (4, -8, 8), (3, 5, -1)]
+            .into_iter()
+            .map(|(x, y, z)| Planet::new(Vec3 { x, y, z }))
+            .collect();
+        let nbody = NBody { planets };
+        assert_eq!(2772, part2(&nbody));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "<x=-1, y=0, z=2>\n<x=2, y=-10, z=-7>\n<x=4, y=-8, z=8>\n<x=3, y=5, z=-1>";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,6 +1,6 @@
 use ahash::AHashMap as HashMap;
 
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 #[derive(PartialEq, Clone, Copy)]
 enum Tile {
@@ -163,14 +163,8 @@ fn part2(codes: &[i64], map: HashMap<(i64, i64), Tile>) -> i64 {
 }
 
 pub fn main() {
-    let mut codes = std::fs::read_to_string("data/2019/day13")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.trim().parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day13").unwrap();
+    let mut codes = parse(&data).unwrap();
 
     let map = part1(&codes);
     let blocks = map.values().filter(|t| **t == Tile::Block).count();
@@ -1,4 +1,13 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, char, space0},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+};
 
 type Graph<'a> = HashMap<&'a str, (usize, Vec<(&'a str, usize)>)>;
 
@@ -46,32 +55,80 @@ fn part1(g: &Graph, r: usize) -> usize {
     dfs(g, "FUEL", r, &mut remaining)
 }
 
-fn create_graph(data: &str) -> Graph<'_> {
+const TOTAL_ORE: usize = 1000000000000;
+
+fn parse(data: &str) -> Result<Graph<'_>, ParseError> {
+    let data = data.trim();
+    let term = || {
+        let amount = context("a positive amount", verify(unsigned, |&n: &usize| n > 0));
+        separated_pair(amount, char(' '), alpha1)
+    };
+    let inputs = separated_list1(tag(", "), term());
+    let reaction = preceded(space0, separated_pair(inputs, tag(" => "), term()));
     let mut g: Graph = HashMap::new();
-    data.lines().for_each(|s| {
-        let parts = s.trim().split("=>").collect::<Vec<_>>();
-        let output = parts[1].trim().split(' ').collect::<Vec<_>>();
-        let n = output[0].parse::<usize>().unwrap();
-        let output = output[1];
-        let e = g.entry(output).or_insert((n, Vec::new()));
-        parts[0].split(',').for_each(|p| {
-            let input = p.trim().split(' ').collect::<Vec<_>>();
-            let n = input[0].parse::<usize>().unwrap();
-            let input = input[1];
-            e.1.push((input, n));
-        });
-    });
-    g
+    for (inputs, (n, output)) in parsing::lines(data, reaction)? {
+        let inputs = inputs.into_iter().map(|(k, input)| (input, k)).collect();
+        if output == "ORE" || g.insert(output, (n, inputs)).is_some() {
+            return Err(ParseError::at(data, output, "one reaction per chemical"));
+        }
+    }
+    for (input, _) in g.values().flat_map(|(_, inputs)| inputs) {
+        if *input != "ORE" && !g.contains_key(input) {
+            return Err(ParseError::at(data, input, "a chemical with a reaction"));
+        }
+    }
+    if !g.contains_key("FUEL") {
+        return Err(ParseError::at(data, data, "a reaction for FUEL"));
+    }
+    // chemicals in the order FUEL needs them, outputs before their inputs
+    let mut order = vec![];
+    let mut done: HashMap<&str, bool> = HashMap::new();
+    let mut stack = vec![("FUEL", false)];
+    while let Some((chemical, explored)) = stack.pop() {
+        if explored {
+            done.insert(chemical, true);
+            order.push(chemical);
+            continue;
+        }
+        match done.get(chemical) {
+            Some(true) => continue,
+            Some(false) => return Err(ParseError::at(data, data, "reactions without a loop")),
+            None => {}
+        }
+        done.insert(chemical, false);
+        stack.push((chemical, true));
+        if let Some((_, inputs)) = g.get(chemical) {
+            stack.extend(inputs.iter().map(|&(input, _)| (input, false)));
+        }
+    }
+    // part 2 makes up to a trillion FUEL, which needs at most a trillion
+    // times what one FUEL does of anything
+    let mut need: HashMap<&str, usize> = HashMap::from([("FUEL", 1)]);
+    let mut fits = true;
+    for &chemical in order.iter().rev() {
+        let amount = need.get(chemical).copied().unwrap_or(0);
+        fits &= amount.checked_mul(TOTAL_ORE).is_some();
+        if let Some((n, inputs)) = g.get(chemical) {
+            for &(input, k) in inputs {
+                let e = need.entry(input).or_default();
+                let more = amount.div_ceil(*n).checked_mul(k);
+                *e = more.and_then(|m| e.checked_add(m)).unwrap_or(usize::MAX);
+            }
+        }
+    }
+    if !fits {
+        return Err(ParseError::at(data, data, "reactions that fit in a usize"));
+    }
+    Ok(g)
 }
 
 fn part2(g: &Graph) -> usize {
-    const TOTAL: usize = 1000000000000;
     let mut left = 0;
-    let mut right = TOTAL;
+    let mut right = TOTAL_ORE;
     while left < right {
         let mid = (left + right).div_ceil(2);
         let req = part1(g, mid);
-        if req > TOTAL {
+        if req > TOTAL_ORE {
             right = mid - 1;
         } else {
             left = mid;
@@ -82,7 +139,7 @@ fn part2(g: &Graph) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day14").unwrap();
-    let g = create_graph(&data);
+    let g = parse(&data).unwrap();
 
     println!("day14 part1: {}", part1(&g, 1));
     println!("day14 part2: {}", part2(&g));
@@ -91,6 +148,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -101,7 +159,7 @@ mod tests {
         5 B, 7 C => 1 BC
         4 C, 1 A => 1 CA
         2 AB, 3 BC, 4 CA => 1 FUEL";
-        let g = create_graph(data);
+        let g = parse(data).unwrap();
         assert_eq!(165, part1(&g, 1));
     }
 
@@ -113,7 +171,7 @@ mod tests {
         7 A, 1 C => 1 D
         7 A, 1 D => 1 E
         7 A, 1 E => 1 FUEL";
-        let g = create_graph(data);
+        let g = parse(data).unwrap();
         assert_eq!(31, part1(&g, 1));
     }
 
@@ -136,7 +194,13 @@ mod tests {
         121 ORE => 7 VRPVC
         7 XCVML => 6 RJRHP
         5 BHXH, 4 VRPVC => 5 LTCX";
-        let g = create_graph(data);
+        let g = parse(data).unwrap();
         assert_eq!(2210736, part1(&g, 1));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "10 ORE => 10 A\n1 ORE => 1 B\n7 A, 1 B => 1 C\n7 A, 1 C => 1 FUEL";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -2,7 +2,7 @@ use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
 use std::collections::{BinaryHeap, VecDeque};
 
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 #[derive(PartialEq, Clone, Copy)]
 enum Tile {
@@ -183,14 +183,8 @@ fn part2(map: &Map) -> i64 {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day15")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.trim().parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day15").unwrap();
+    let codes = parse(&data).unwrap();
 
     let map = Map::build(&codes);
     // println!("{}", map);
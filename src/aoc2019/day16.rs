@@ -1,3 +1,26 @@
+use adventofcode::parsing::{self, ParseError};
+use nom::{character::complete::digit1, error::context};
+
+const REPEAT: usize = 10000;
+
+fn parse(data: &str) -> Result<Vec<u8>, ParseError> {
+    let data = data.trim();
+    let signal = parsing::parse_all(data, context("a signal", digit1))?;
+    // part 2 repeats the signal and reads 8 digits at the offset in its first 7
+    if signal.len() < 7 || signal.len() > 10000 {
+        return Err(ParseError::at(data, data, "7 to 10000 digits"));
+    }
+    let offset: usize = signal[..7].parse().unwrap();
+    if offset + 8 > REPEAT * signal.len() {
+        return Err(ParseError::at(
+            data,
+            data,
+            "an offset within the repeated signal",
+        ));
+    }
+    Ok(signal.bytes().map(|b| b - b'0').collect())
+}
+
 fn phase(curr: &[u8], next: &mut [u8], prefix: &mut [i32]) {
     let n = curr.len();
     for i in 1..=n {
@@ -42,7 +65,7 @@ fn part2(signal: &[u8]) -> usize {
     let offset = signal[..7]
         .iter()
         .fold(0usize, |acc, i| acc * 10 + *i as usize);
-    let n = 10000 * signal.len() - offset;
+    let n = REPEAT * signal.len() - offset;
     let s = signal
         .iter()
         .cycle()
@@ -74,14 +97,7 @@ fn part2(signal: &[u8]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day16").unwrap();
-    let signal = data
-        .lines()
-        .next()
-        .unwrap()
-        .as_bytes()
-        .iter()
-        .map(|b| b - b'0')
-        .collect::<Vec<_>>();
+    let signal = parse(&data).unwrap();
 
     println!("day16 part1: {}", part1(&signal));
     println!("day16 part2: {}", part2(&signal));
@@ -90,6 +106,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -145,4 +162,10 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(84462026, part2(&signal));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "03036732577212944063491565474664";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
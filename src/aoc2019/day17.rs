@@ -1,4 +1,4 @@
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Movement {
@@ -285,14 +285,8 @@ fn part2(codes: &[i64], img: Vec<Vec<u8>>) -> i64 {
 }
 
 pub fn main() {
-    let mut codes = std::fs::read_to_string("data/2019/day17")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.trim().parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day17").unwrap();
+    let mut codes = parse(&data).unwrap();
 
     let (alignment, img) = part1(&codes);
     println!("day17 part1: {alignment}");
@@ -1,6 +1,8 @@
+use adventofcode::exhaustive::{Min, Problem, Search};
+use adventofcode::parsing::{self, ParseError};
+use adventofcode::search;
 use ahash::AHashMap as HashMap;
-use ahash::AHashSet as HashSet;
-use std::{collections::BinaryHeap, ops::BitOr};
+use std::ops::BitOr;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Position {
@@ -99,72 +101,103 @@ struct Route {
 }
 
 fn all_paths(vault: &Vault, from: Position) -> Vec<Route> {
-    let mut q = BinaryHeap::new();
-    let mut closed = HashSet::new();
+    let neighbors = |curr: &Position| {
+        curr.neighbors()
+            .into_iter()
+            .filter(|p| vault.grid[p.y][p.x] != b'#')
+    };
+    let reachable = search::bfs_all([from], neighbors);
     let mut result = vec![];
-    q.push((
-        0i64,
-        from,
-        KeyCollection::default(),
-        KeyCollection::default(),
-    ));
-    while let Some((dist, curr, mut keys, mut doors)) = q.pop() {
-        let dist = -dist;
-        closed.insert(curr);
-        for p in curr.neighbors() {
+    for (&dest, &pos) in &vault.key_pos {
+        let Some(path) = reachable.path(&pos) else {
+            continue;
+        };
+        let (mut keys, mut doors) = (KeyCollection::default(), KeyCollection::default());
+        for p in &path[1..] {
             let cell = vault.grid[p.y][p.x];
-            if cell != b'#' && !closed.contains(&p) {
-                if cell.is_ascii_lowercase() {
-                    keys.insert(cell);
-                    result.push(Route {
-                        dest: cell,
-                        dist: dist as usize + 1,
-                        keys,
-                        doors,
-                    });
-                } else if cell.is_ascii_uppercase() {
-                    doors.insert(cell);
-                }
-                q.push((-(dist + 1), p, keys, doors));
+            if cell.is_ascii_lowercase() {
+                keys.insert(cell);
+            } else if cell.is_ascii_uppercase() {
+                doors.insert(cell);
             }
         }
+        result.push(Route {
+            dest,
+            dist: path.len() - 1,
+            keys,
+            doors,
+        });
     }
     result
 }
 
-fn shortest_path(vault: &Vault) -> usize {
-    let mut paths = HashMap::new();
-    let mut cost: HashMap<(KeyCollection, Vec<Position>), i64> = HashMap::new();
-    let mut q = BinaryHeap::new();
-    q.push((0i64, (KeyCollection::default(), vault.entrances.clone())));
-
-    while let Some((dist, (collected, positions))) = q.pop() {
-        let dist = -dist;
-        if collected == vault.keys {
-            return dist as usize;
-        }
-        for (i, &curr) in positions.iter().enumerate() {
-            paths.entry(curr).or_insert_with(|| all_paths(vault, curr));
-            let ps = paths.get(&curr).unwrap().to_vec();
-            for r in &ps {
+/// State is (keys collected, position of every robot).
+struct Collect<'a> {
+    vault: &'a Vault,
+    routes: HashMap<Position, Vec<Route>>,
+}
+
+impl<'a> Collect<'a> {
+    fn new(vault: &'a Vault) -> Self {
+        let routes = vault
+            .entrances
+            .iter()
+            .chain(vault.key_pos.values())
+            .map(|&p| (p, all_paths(vault, p)))
+            .collect();
+        Self { vault, routes }
+    }
+}
+
+impl Problem for Collect<'_> {
+    type State = (KeyCollection, Vec<Position>);
+    type Key = Self::State;
+    type Value = usize;
+    type Objective = Min;
+
+    fn key(&self, state: &Self::State) -> Option<Self::Key> {
+        Some(state.clone())
+    }
+
+    fn stop(&self, (collected, _): &Self::State) -> Option<usize> {
+        (*collected == self.vault.keys).then_some(0)
+    }
+
+    fn children(&self, (collected, positions): &Self::State, out: &mut Vec<(usize, Self::State)>) {
+        for (i, curr) in positions.iter().enumerate() {
+            for r in &self.routes[curr] {
                 if collected.contains(&r.dest) || (r.doors.0 & collected.0) != r.doors.0 {
                     continue;
                 }
-
-                let collected = collected | r.keys;
-                let dest = *vault.key_pos.get(&r.dest).unwrap();
                 let mut positions = positions.clone();
-                positions[i] = dest;
-                let state = (collected, positions);
-                if (dist + r.dist as i64) < *cost.get(&state).unwrap_or(&i64::MAX) {
-                    cost.insert(state.clone(), dist + r.dist as i64);
-                    q.push((-(dist + r.dist as i64), state));
-                }
+                positions[i] = self.vault.key_pos[&r.dest];
+                out.push((r.dist, (*collected | r.keys, positions)));
             }
         }
     }
+}
+
+fn shortest_path(vault: &Vault) -> usize {
+    let start = (KeyCollection::default(), vault.entrances.clone());
+    Search::new(&Collect::new(vault)).solve(&start).unwrap_or(0)
+}
 
-    0
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(
+        data,
+        "#.@abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    )?;
+    if data.matches('@').count() != 1 {
+        return Err(ParseError::at(data, data, "exactly one entrance"));
+    }
+    // positions are never next to the edge, so neighbors stay in the vault
+    let (m, n) = (grid.len(), grid[0].len());
+    let edge = |i: usize, j: usize| i == 0 || j == 0 || i == m - 1 || j == n - 1;
+    if (0..m).any(|i| (0..n).any(|j| edge(i, j) && grid[i][j] != b'#')) {
+        return Err(ParseError::at(data, data, "a vault surrounded by walls"));
+    }
+    Ok(grid)
 }
 
 fn part1(grid: &[Vec<u8>]) -> (usize, Vault) {
@@ -192,11 +225,7 @@ fn part2(mut vault: Vault) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day18").unwrap();
-    let grid = data
-        .trim()
-        .lines()
-        .map(|row| row.trim().as_bytes().to_vec())
-        .collect::<Vec<_>>();
+    let grid = parse(&data).unwrap();
     let (p1, vault) = part1(&grid);
     println!("day 18 part1: {p1}");
     println!("day 18 part2: {}", part2(vault));
@@ -205,6 +234,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -252,4 +282,10 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(136, part1(&grid).0);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#########\n#b.A.@.a#\n#########";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
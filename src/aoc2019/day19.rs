@@ -1,4 +1,4 @@
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 fn solve(codes: &[i64]) -> (i64, i64) {
     fn is_pulled(codes: &[i64], x: i64, y: i64) -> bool {
@@ -99,14 +99,8 @@ fn solve(codes: &[i64]) -> (i64, i64) {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day19")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day19").unwrap();
+    let codes = parse(&data).unwrap();
 
     let (p1, p2) = solve(&codes);
     println!("day 19 part1: {p1}");
@@ -1,3 +1,17 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{character::complete::char, combinator::verify, error::context, multi::separated_list1};
+
+fn parse(data: &str) -> Result<Vec<usize>, ParseError> {
+    // both parts write a noun and a verb after the first opcode
+    let program = verify(separated_list1(char(','), unsigned), |codes: &[usize]| {
+        codes.len() >= 3
+    });
+    parsing::parse_all(
+        data.trim(),
+        context("a program of at least 3 codes", program),
+    )
+}
+
 fn part1(mut codes: Vec<usize>) -> usize {
     let mut i = 0;
     while i < codes.len() {
@@ -37,14 +51,8 @@ fn part2(codes: &[usize]) -> usize {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day2")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.parse::<usize>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day2").unwrap();
+    let codes = parse(&data).unwrap();
 
     let mut p1 = codes.clone();
     p1[1] = 12;
@@ -53,3 +61,15 @@ pub fn main() {
 
     println!("day2 part2: {}", part2(&codes));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "1,9,10,3,2,3,11,0,99,30,40,50";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,6 +1,7 @@
+use adventofcode::parsing::{self, ParseError};
+use adventofcode::search;
 use ahash::AHashMap as HashMap;
 use arrayvec::ArrayVec;
-use std::{cmp::Reverse, collections::BinaryHeap};
 
 type Position = (usize, usize);
 
@@ -102,142 +103,128 @@ impl Maze {
 }
 
 fn all_paths(maze: &Maze, start: Position) -> Vec<(Key, usize)> {
-    let mut result = vec![];
-    let mut q = BinaryHeap::new();
-    let mut costs = HashMap::new();
-    q.push((Reverse(0), start));
-    costs.insert(start, 0);
-    while let Some((c, pos)) = q.pop() {
-        if pos != start && maze.portal_keys.contains_key(&pos) {
-            result.push((*maze.portal_keys.get(&pos).unwrap(), c.0));
-            continue;
-        }
-
-        if let Some(&cost) = costs.get(&pos)
-            && c.0 > cost
-        {
-            continue;
+    let neighbors = |&(x, y): &Position| {
+        if (x, y) != start && maze.portal_keys.contains_key(&(x, y)) {
+            ArrayVec::new()
+        } else {
+            maze.neighbors(x, y)
         }
+    };
+    search::bfs_all([start], neighbors)
+        .iter()
+        .filter(|&(pos, _)| *pos != start)
+        .filter_map(|(pos, d)| maze.portal_keys.get(pos).map(|&key| (key, d)))
+        .collect()
+}
 
-        for next in maze.neighbors(pos.0, pos.1) {
-            let curr = costs.entry(next).or_insert(usize::MAX);
-            if c.0 + 1 < *curr {
-                *curr = c.0 + 1;
-                q.push((Reverse(c.0 + 1), next));
-            }
-        }
+fn other_side(key: Key) -> Key {
+    Key {
+        name: key.name,
+        ring: match key.ring {
+            Ring::Inner => Ring::Outer,
+            Ring::Outer => Ring::Inner,
+        },
     }
-    result
 }
 
-fn part1(maze: &Maze) -> usize {
-    let mut costs = HashMap::new();
-    let mut q = BinaryHeap::new();
-    let start = Key {
-        name: [b'A'; 2],
-        ring: Ring::Outer,
-    };
-    q.push((Reverse(0), start));
-    costs.insert(start, 0usize);
-    let end = Key {
-        name: [b'Z'; 2],
-        ring: Ring::Outer,
-    };
-    while let Some((c, key)) = q.pop() {
-        if key == end {
-            return c.0;
-        }
-        if let Some(&cost) = costs.get(&key)
-            && c.0 > cost
-        {
-            continue;
-        }
-        for &(next, dist) in maze.paths.get(&key).unwrap() {
-            let mut cn = c.0 + dist;
-
-            let other_side = if next != start && next != end {
-                cn += 1;
-                Key {
-                    name: next.name,
-                    ring: match next.ring {
-                        Ring::Inner => Ring::Outer,
-                        Ring::Outer => Ring::Inner,
-                    },
+fn parse(data: &str) -> Result<Maze, ParseError> {
+    let data = data.trim_matches('\n');
+    let grid = parsing::grid_of(data, " #.ABCDEFGHIJKLMNOPQRSTUVWXYZ")?;
+    let (m, n) = (grid.len(), grid[0].len());
+    // open tiles are inside the grid, with a whole label next to them
+    let edge = |y: usize, x: usize| y == 0 || x == 0 || y == m - 1 || x == n - 1;
+    for y in 0..m {
+        for x in 0..n {
+            if grid[y][x] != b'.' {
+                continue;
+            }
+            if m < 5 || n < 5 || edge(y, x) {
+                return Err(ParseError::at(data, data, "open tiles inside the maze"));
+            }
+            for (dy, dx) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (y1, x1) = (y.wrapping_add_signed(dy), x.wrapping_add_signed(dx));
+                let (y2, x2) = (y1.wrapping_add_signed(dy), x1.wrapping_add_signed(dx));
+                let letter = |y: usize, x: usize| {
+                    grid.get(y)
+                        .and_then(|r| r.get(x))
+                        .is_some_and(u8::is_ascii_uppercase)
+                };
+                if letter(y1, x1) && !letter(y2, x2) {
+                    return Err(ParseError::at(data, data, "two-letter portal labels"));
                 }
-            } else {
-                next
-            };
-            let curr = costs.entry(other_side).or_insert(usize::MAX);
-            if cn < *curr {
-                *curr = cn;
-                q.push((Reverse(cn), other_side));
             }
         }
     }
-    unreachable!()
+    let maze = Maze::from_lines(data);
+    // every portal has another side, and one label per tile
+    let paired = maze.portal_pos.keys().all(|&key| {
+        if key.name == START.name || key.name == END.name {
+            key.ring == Ring::Outer
+        } else {
+            maze.portal_pos.contains_key(&other_side(key))
+        }
+    });
+    let ends = maze.portal_pos.contains_key(&START) && maze.portal_pos.contains_key(&END);
+    if !paired || !ends || maze.portal_keys.len() != maze.portal_pos.len() {
+        return Err(ParseError::at(data, data, "AA, ZZ and pairs of portals"));
+    }
+    if search::dijkstra([START], |&key| portals(&maze, key), |&key| key == END).is_none() {
+        return Err(ParseError::at(data, data, "a path from AA to ZZ"));
+    }
+    Ok(maze)
 }
 
-fn part2(maze: &Maze) -> usize {
-    let mut costs = HashMap::new();
-    let mut q = BinaryHeap::new();
-    let start = Key {
-        name: [b'A'; 2],
-        ring: Ring::Outer,
-    };
-    q.push((Reverse(0), start, 0u32));
-    costs.insert((start, 0u32), 0);
-    let end = Key {
-        name: [b'Z'; 2],
-        ring: Ring::Outer,
-    };
-    while let Some((c, key, level)) = q.pop() {
-        if (key, level) == (end, 0) {
-            return c.0;
-        }
-        if let Some(&cost) = costs.get(&(key, level))
-            && c.0 > cost
-        {
-            continue;
+const START: Key = Key {
+    name: [b'A'; 2],
+    ring: Ring::Outer,
+};
+
+const END: Key = Key {
+    name: [b'Z'; 2],
+    ring: Ring::Outer,
+};
+
+/// Through a portal to the other side, or onto the start or end tile.
+fn portals(maze: &Maze, key: Key) -> impl Iterator<Item = (Key, usize)> {
+    maze.paths[&key].iter().map(move |&(next, dist)| {
+        if next != START && next != END {
+            (other_side(next), dist + 1)
+        } else {
+            (next, dist)
         }
-        for &(next, dist) in maze.paths.get(&key).unwrap() {
-            if next == start || (level == 0 && next.ring == Ring::Outer && next.name != end.name) {
-                continue;
-            }
+    })
+}
 
-            let mut cn = c.0 + dist;
-            let mut level = level;
+fn part1(maze: &Maze) -> usize {
+    search::dijkstra([START], |&key| portals(maze, key), |&key| key == END)
+        .unwrap()
+        .1
+}
 
-            let other_side = if next != end {
-                cn += 1;
-                Key {
-                    name: next.name,
-                    ring: match next.ring {
-                        Ring::Inner => {
-                            level += 1;
-                            Ring::Outer
-                        }
-                        Ring::Outer => {
-                            level -= 1;
-                            Ring::Inner
-                        }
-                    },
-                }
+fn part2(maze: &Maze) -> usize {
+    let neighbors = |&(key, level): &(Key, u32)| {
+        maze.paths[&key].iter().filter_map(move |&(next, dist)| {
+            if next == START || (level == 0 && next.ring == Ring::Outer && next != END) {
+                None
+            } else if next == END {
+                Some(((next, level), dist))
             } else {
-                next
-            };
-            let curr = costs.entry((other_side, level)).or_insert(usize::MAX);
-            if cn < *curr {
-                *curr = cn;
-                q.push((Reverse(cn), other_side, level));
+                let level = match next.ring {
+                    Ring::Inner => level + 1,
+                    Ring::Outer => level - 1,
+                };
+                Some(((other_side(next), level), dist + 1))
             }
-        }
-    }
-    unreachable!()
+        })
+    };
+    search::dijkstra([(START, 0)], neighbors, |&s| s == (END, 0))
+        .unwrap()
+        .1
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day20").unwrap();
-    let maze = Maze::from_lines(&data);
+    let maze = parse(&data).unwrap();
 
     println!("day 20 part1: {}", part1(&maze));
     println!("day 20 part2: {}", part2(&maze));
@@ -246,6 +233,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -268,7 +256,7 @@ FG..#########.....#
   ###########.#####  
              Z       
              Z       ";
-        let maze = Maze::from_lines(data);
+        let maze = parse(data).unwrap();
         assert_eq!(23, part1(&maze));
         assert_eq!(26, part2(&maze));
     }
@@ -312,7 +300,7 @@ YN......#               VT..#....QG
   #########.###.###.#############  
            B   J   C               
            U   P   P               ";
-        let maze = Maze::from_lines(data);
+        let maze = parse(data).unwrap();
         assert_eq!(58, part1(&maze));
     }
 
@@ -355,7 +343,13 @@ RE....#.#                           #......RF
   #############.#.#.###.###################  
                A O F   N                     
                A A D   M                     ";
-        let maze = Maze::from_lines(data);
+        let maze = parse(data).unwrap();
         assert_eq!(396, part2(&maze));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "       A     \n       A     \n  #####.###  \n  #.......#  \n  #.#####.#  \n  #.#   #.#  \n  #.#   #.#  \n  #.#   #.#  \n  #.#####.#  \n  #.......#  \n  ###.#####  \n     Z       \n     Z       ";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 fn execute(codes: &[i64], script: &str) -> usize {
     let mut p = Intcode::new(codes);
@@ -18,14 +18,8 @@ fn execute(codes: &[i64], script: &str) -> usize {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day21")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day21").unwrap();
+    let codes = parse(&data).unwrap();
 
     let p1 = "OR A T\nAND B T\nAND C T\nNOT T J\nAND D J\nWALK\n";
     let p2 = "OR A T\nAND B T\nAND C T\nNOT T T\nAND D T\nOR E J\nOR H J\nAND T J\nRUN\n";
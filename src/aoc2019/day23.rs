@@ -1,4 +1,4 @@
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 struct Nat {
     nics: Vec<Intcode>,
@@ -78,14 +78,8 @@ fn solve(codes: &[i64]) -> (i64, i64) {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day23")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day23").unwrap();
+    let codes = parse(&data).unwrap();
 
     let (p1, p2) = solve(&codes);
     println!("day 23 part1: {p1}");
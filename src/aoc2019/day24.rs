@@ -1,3 +1,4 @@
+use adventofcode::parsing::ParseError;
 use ahash::AHashSet as HashSet;
 
 const WIDTH: usize = 5;
@@ -133,16 +134,31 @@ impl Grid {
         result
     }
 
-    fn parse(data: &str) -> Self {
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let data = data.trim();
         let mut grid = 0u64;
-        for (y, line) in data.lines().enumerate() {
-            for (x, c) in line.trim().chars().enumerate() {
+        let mut lines = data.lines();
+        for y in 0..WIDTH {
+            let line = lines
+                .next()
+                .ok_or_else(|| ParseError::at(data, &data[data.len()..], "5 rows"))?
+                .trim();
+            if let Some(i) = line.find(|c| c != '.' && c != '#') {
+                return Err(ParseError::at(data, &line[i..], "'.' or '#'"));
+            }
+            if line.len() != WIDTH {
+                return Err(ParseError::at(data, line, "a row of 5"));
+            }
+            for (x, c) in line.chars().enumerate() {
                 if c == '#' {
                     grid |= Grid::mask(x + 1, y + 1);
                 }
             }
         }
-        Grid(grid)
+        if let Some(extra) = lines.next() {
+            return Err(ParseError::at(data, extra, "only 5 rows"));
+        }
+        Ok(Grid(grid))
     }
 }
 
@@ -242,7 +258,7 @@ fn part2(grid: Grid, miniutes: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day24").unwrap();
-    let grid = Grid::parse(&data);
+    let grid = Grid::parse(&data).unwrap();
 
     println!("day 24 part1: {}", part1(Grid(grid.0)));
     println!("day 24 part2: {}", part2(grid, 200));
@@ -251,6 +267,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -259,8 +276,14 @@ mod tests {
         #..##
         ..#..
         #....";
-        let grid = Grid::parse(data);
+        let grid = Grid::parse(data).unwrap();
         assert_eq!(2129920, part1(Grid(grid.0)));
         assert_eq!(99, part2(grid, 10));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "....#\n#..#.\n#..##\n..#..\n#....";
+        fuzz::check(&[seed], |s| Grid::parse(s).map(drop));
+    }
 }
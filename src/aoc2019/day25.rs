@@ -1,4 +1,4 @@
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
 use rayon::prelude::*;
@@ -252,14 +252,8 @@ fn guess_items(prog: &mut Intcode, collected: &HashSet<String>, direction: Direc
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day25")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day25").unwrap();
+    let codes = parse(&data).unwrap();
 
     let mut prog = Intcode::new(&codes);
 
@@ -1,5 +1,10 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
+use nom::{
+    Parser, branch::alt, character::complete::char, combinator::verify, error::context,
+    multi::separated_list1,
+};
 
 #[derive(Debug)]
 enum Direction {
@@ -26,18 +31,32 @@ struct Segment {
     dist: isize,
 }
 
-impl From<&str> for Segment {
-    fn from(s: &str) -> Self {
-        let dir = match s.as_bytes()[0] {
-            b'R' => Direction::Right,
-            b'L' => Direction::Left,
-            b'U' => Direction::Up,
-            b'D' => Direction::Down,
-            _ => panic!("invalid direction"),
-        };
-        let dist = s[1..].parse().unwrap();
-        Self { dir, dist }
+fn segment(input: &str) -> PResult<'_, Segment> {
+    let dir = alt((
+        char('U').map(|_| Direction::Up),
+        char('D').map(|_| Direction::Down),
+        char('R').map(|_| Direction::Right),
+        char('L').map(|_| Direction::Left),
+    ));
+    let dist = verify(unsigned, |&d| d <= 1 << 20);
+    (context("a direction", dir), dist)
+        .map(|(dir, dist)| Segment { dir, dist })
+        .parse(input)
+}
+
+fn parse(data: &str) -> Result<Vec<Vec<Segment>>, ParseError> {
+    let data = data.trim();
+    // every step is stored, so each wire is kept to 2^20 of them
+    let short = |w: &[Segment]| w.iter().map(|s| s.dist).sum::<isize>() <= 1 << 20;
+    let wire = context(
+        "a wire of at most 2^20 steps",
+        verify(separated_list1(char(','), segment), short),
+    );
+    let wires = parsing::lines(data, wire)?;
+    if wires.len() != 2 {
+        return Err(ParseError::at(data, data, "two wires"));
     }
+    Ok(wires)
 }
 
 fn part1(wires: &[Vec<Segment>]) -> i32 {
@@ -101,13 +120,22 @@ fn part2(wires: &[Vec<Segment>]) -> i32 {
 }
 
 pub fn main() {
-    let wires = std::fs::read_to_string("data/2019/day3")
-        .unwrap()
-        .lines()
-        .map(|s| s.split(',').map(|s| s.into()).collect::<Vec<Segment>>())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day3").unwrap();
+    let wires = parse(&data).unwrap();
 
     println!("day3 part1: {}", part1(&wires));
 
     println!("day3 part2: {}", part2(&wires));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "R8,U5,L5,D3\nU7,R6,D4,L4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
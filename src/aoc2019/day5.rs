@@ -1,5 +1,19 @@
 use std::collections::VecDeque;
 
+use adventofcode::parsing::{self, ParseError, int_list};
+use nom::{combinator::verify, error::context};
+
+/// An Intcode program, shared by every day that runs one.
+pub fn parse(data: &str) -> Result<Vec<i64>, ParseError> {
+    let program = verify(int_list(","), |codes: &[i64]| codes.len() <= MEMORY);
+    parsing::parse_all(
+        data.trim(),
+        context("a program that fits in memory", program),
+    )
+}
+
+const MEMORY: usize = 10000;
+
 fn parameter_modes(mut n: i64) -> [u8; 3] {
     let mut r = [0; 3];
     for m in &mut r {
@@ -22,7 +36,7 @@ pub struct Intcode {
 impl Intcode {
     pub fn new(codes: &[i64]) -> Self {
         let mut codes = codes.to_vec();
-        codes.resize(10000, 0);
+        codes.resize(MEMORY, 0);
         Self {
             codes: codes.to_vec(),
             ip: 0,
@@ -153,14 +167,8 @@ impl Intcode {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day5")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day5").unwrap();
+    let codes = parse(&data).unwrap();
 
     let mut code = Intcode::new(&codes);
     code.inputs.push_back(1);
@@ -176,13 +184,11 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
-        let codes = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99"
-            .split(',')
-            .map(|t| t.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+        let codes = parse("3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99").unwrap();
         let mut code = Intcode::new(&codes);
         code.inputs.push_back(7);
         code.run();
@@ -198,4 +204,10 @@ mod tests {
         code.run();
         assert_eq!(1001, code.outputs.pop_front().unwrap());
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "3,9,8,9,10,9,4,9,99,-1,8";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
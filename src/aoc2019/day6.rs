@@ -1,5 +1,44 @@
+use adventofcode::dot::{self, Dot};
+use adventofcode::parsing::{self, ParseError};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
+use nom::character::complete::{alphanumeric1, char};
+use nom::sequence::separated_pair;
+
+fn parse(data: &str) -> Result<HashMap<&str, HashSet<&str>>, ParseError> {
+    let orbits = parsing::lines(
+        data.trim(),
+        separated_pair(alphanumeric1, char(')'), alphanumeric1),
+    )?;
+    let mut g: HashMap<&str, HashSet<&str>> = HashMap::new();
+    let mut satellites = HashSet::new();
+    // a tree under COM, so the searches from it end
+    for (center, satellite) in orbits {
+        if satellite == "COM" || !satellites.insert(satellite) {
+            return Err(ParseError::at(
+                data,
+                satellite,
+                "an object orbiting only one other",
+            ));
+        }
+        g.entry(center).or_default().insert(satellite);
+    }
+    Ok(g)
+}
+
+/// Edges from every object to the ones orbiting it.
+fn to_dot(g: &HashMap<&str, HashSet<&str>>) -> Dot {
+    let mut dot = Dot::digraph();
+    for name in ["YOU", "SAN"] {
+        dot.node(name, &[("shape", "box")]);
+    }
+    for (center, satellites) in g {
+        for satellite in satellites {
+            dot.edge(center, satellite, None);
+        }
+    }
+    dot
+}
 
 fn part1(g: &HashMap<&str, HashSet<&str>>) -> usize {
     fn dfs(g: &HashMap<&str, HashSet<&str>>, s: &str, result: &mut usize) -> usize {
@@ -50,12 +89,9 @@ fn part2(g: &HashMap<&str, HashSet<&str>>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day6").unwrap();
-    let mut g: HashMap<&str, HashSet<&str>> = HashMap::new();
-    data.lines().for_each(|s| {
-        let parts = s.split(')').collect::<Vec<_>>();
-        g.entry(parts[0]).or_default().insert(parts[1]);
-    });
+    let g = parse(&data).unwrap();
 
+    dot::export(|| to_dot(&g));
     println!("day6 part1: {}", part1(&g));
 
     println!("day6 part2: {}", part2(&g));
@@ -64,6 +100,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -89,4 +126,10 @@ mod tests {
 
         assert_eq!(4, part2(&g));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "COM)B\nB)C\nC)D\nD)E\nK)YOU\nI)SAN";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 fn part1(codes: &[i64]) -> i64 {
     fn amp(codes: &[i64], signal: i64, phases: &mut [i64], result: &mut i64) {
@@ -72,14 +72,8 @@ fn part2(codes: &[i64]) -> i64 {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day7")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.trim().parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day7").unwrap();
+    let codes = parse(&data).unwrap();
 
     println!("day7 part1: {}", part1(&codes));
     println!("day7 part2: {}", part2(&codes));
@@ -91,31 +85,26 @@ mod tests {
 
     #[test]
     fn case1() {
-        let codes = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0"
-            .split(',')
-            .map(|t| t.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+        let codes = parse("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0").unwrap();
 
         assert_eq!(43210, part1(&codes));
     }
 
     #[test]
     fn case2() {
-        let codes = "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0"
-            .split(',')
-            .map(|t| t.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+        let codes =
+            parse("3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0")
+                .unwrap();
 
         assert_eq!(54321, part1(&codes));
     }
 
     #[test]
     fn case3() {
-        let codes =
-            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5"
-                .split(',')
-                .map(|t| t.parse::<i64>().unwrap())
-                .collect::<Vec<_>>();
+        let codes = parse(
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+        )
+        .unwrap();
 
         assert_eq!(139629729, part2(&codes));
     }
@@ -1,7 +1,17 @@
+use adventofcode::ocr;
+use adventofcode::parsing::{self, ParseError};
+use nom::{bytes::complete::take_while1, combinator::verify, error::context};
+
 const WIDTH: usize = 25;
 const HEIGHT: usize = 6;
 const LAYER_SIZE: usize = WIDTH * HEIGHT;
 
+fn parse(data: &str) -> Result<&str, ParseError> {
+    let pixels = take_while1(|c| matches!(c, '0'..='2'));
+    let layers = verify(pixels, |s: &str| s.len().is_multiple_of(LAYER_SIZE));
+    parsing::parse_all(data.trim(), context("whole layers of pixels 0-2", layers))
+}
+
 fn part1(image: &str) -> usize {
     let img = image.as_bytes();
     let zeros = img
@@ -20,33 +30,50 @@ fn part1(image: &str) -> usize {
     ones * (LAYER_SIZE - ones - zeros.1)
 }
 
-fn part2(image: &str) {
+fn part2(image: &str) -> String {
     let img = image.as_bytes();
     let layers = img.chunks_exact(LAYER_SIZE).collect::<Vec<_>>();
-    let mut rendered = [b' '; LAYER_SIZE];
+    let mut rendered = [b'.'; LAYER_SIZE];
     for i in 0..HEIGHT {
         for j in 0..WIDTH {
             for layer in &layers {
                 let p = layer[i * WIDTH + j];
                 if p != b'2' {
                     if p == b'1' {
-                        rendered[i * WIDTH + j] = p;
+                        rendered[i * WIDTH + j] = b'#';
                     }
                     break;
                 }
             }
         }
-        println!("{}", unsafe {
-            std::str::from_utf8_unchecked(&rendered[i * WIDTH..i * WIDTH + WIDTH])
-        });
     }
+    rendered
+        .chunks(WIDTH)
+        .map(|row| unsafe { std::str::from_utf8_unchecked(row) })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2019/day8").unwrap();
-    let image = data.lines().next().unwrap();
+    let image = parse(&data).unwrap();
 
     println!("day8 part1: {}", part1(image));
-    println!("day8 part2:");
-    part2(image);
+    let img = part2(image);
+    match ocr::recognize_str(&img) {
+        Ok(text) => println!("day8 part2: {text}"),
+        Err(e) => println!("day8 part2: {e}\n{img}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "000000000000000000000000011111111111111111111111112222222222222222222222222222222222222222222222222222222222222222222222222222222222222222222222222222";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
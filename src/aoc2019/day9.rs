@@ -1,4 +1,4 @@
-use crate::day5::Intcode;
+use crate::day5::{Intcode, parse};
 
 fn part1(codes: &[i64]) -> i64 {
     let mut prog = Intcode::new(codes);
@@ -15,14 +15,8 @@ fn part2(codes: &[i64]) -> i64 {
 }
 
 pub fn main() {
-    let codes = std::fs::read_to_string("data/2019/day9")
-        .unwrap()
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|t| t.trim().parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2019/day9").unwrap();
+    let codes = parse(&data).unwrap();
 
     println!("day9 part1: {}", part1(&codes));
     println!("day9 part2: {}", part2(&codes));
@@ -34,10 +28,7 @@ mod tests {
 
     #[test]
     fn case1() {
-        let codes = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99"
-            .split(',')
-            .map(|t| t.trim().parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+        let codes = parse("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99").unwrap();
 
         let mut prog = Intcode::new(&codes);
         prog.run_till_halt();
@@ -46,10 +37,7 @@ mod tests {
 
     #[test]
     fn case2() {
-        let codes = "1102,34915192,34915192,7,4,7,99,0"
-            .split(',')
-            .map(|t| t.trim().parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+        let codes = parse("1102,34915192,34915192,7,4,7,99,0").unwrap();
 
         let mut prog = Intcode::new(&codes);
         prog.run();
@@ -59,10 +47,7 @@ mod tests {
 
     #[test]
     fn case3() {
-        let codes = "104,1125899906842624,99"
-            .split(',')
-            .map(|t| t.trim().parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+        let codes = parse("104,1125899906842624,99").unwrap();
 
         let mut prog = Intcode::new(&codes);
         prog.run();
@@ -1,4 +1,11 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
+use nom::combinator::map;
+
+fn parse(data: &str) -> Result<Vec<i32>, ParseError> {
+    // small enough for the differences from 2020 to fit
+    parsing::lines(data.trim(), map(unsigned::<u16>, i32::from))
+}
 
 fn two_sum(target: i32, nums: &[i32]) -> Option<i32> {
     let mut numbers = HashMap::new();
@@ -22,11 +29,8 @@ fn three_sum(target: i32, nums: &[i32]) -> Option<i32> {
 }
 
 pub fn main() {
-    let numbers: Vec<i32> = std::fs::read_to_string("data/2020/day1")
-        .unwrap()
-        .lines()
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect();
+    let data = std::fs::read_to_string("data/2020/day1").unwrap();
+    let numbers = parse(&data).unwrap();
 
     if let Some(n) = two_sum(2020, &numbers) {
         println!("day1 part1: {n}");
@@ -36,3 +40,15 @@ pub fn main() {
         println!("day1 part2: {n}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "1721\n979\n366\n299\n675\n1456";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,11 +1,7 @@
-fn parse(content: &str) -> Vec<u64> {
-    content
-        .lines()
-        .map(|line| {
-            line.parse::<u64>()
-                .unwrap_or_else(|_| panic!("invalid line: {line}"))
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+
+fn parse(content: &str) -> Result<Vec<u64>, ParseError> {
+    parsing::lines(content.trim(), unsigned)
 }
 
 fn part1(adapters: &[u64]) -> u64 {
@@ -37,7 +33,7 @@ fn part2(jolts: &[u64]) -> u64 {
 }
 
 pub fn main() {
-    let mut adapters = parse(&std::fs::read_to_string("data/2020/day10").unwrap());
+    let mut adapters = parse(&std::fs::read_to_string("data/2020/day10").unwrap()).unwrap();
     adapters.sort_unstable();
 
     // part 1
@@ -50,10 +46,15 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_day10_1() {
-        let mut adapters = parse(&std::fs::read_to_string("data/2020/day10-1").unwrap());
+        let Some(input) = read_or_skip("data/2020/day10-1") else {
+            return;
+        };
+        let mut adapters = parse(&input).unwrap();
         adapters.sort_unstable();
 
         assert_eq!(35, part1(&adapters));
@@ -62,10 +63,19 @@ mod tests {
 
     #[test]
     fn test_day10_2() {
-        let mut adapters = parse(&std::fs::read_to_string("data/2020/day10-2").unwrap());
+        let Some(input) = read_or_skip("data/2020/day10-2") else {
+            return;
+        };
+        let mut adapters = parse(&input).unwrap();
         adapters.sort_unstable();
 
         assert_eq!(220, part1(&adapters));
         assert_eq!(19208, part2(&adapters));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "16\n10\n15\n5\n1";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
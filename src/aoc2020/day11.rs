@@ -1,3 +1,4 @@
+use adventofcode::parsing::{self, ParseError};
 use arrayvec::ArrayVec;
 use std::fmt;
 
@@ -35,11 +36,11 @@ impl fmt::Debug for Grid {
     }
 }
 
-fn parse(content: &str) -> Grid {
-    let mut r = content
-        .lines()
+fn parse(content: &str) -> Result<Grid, ParseError> {
+    let mut r = parsing::grid_of(content.trim(), "L.#")?
+        .into_iter()
         .map(|line| {
-            let mut c: Vec<char> = line.chars().collect();
+            let mut c: Vec<char> = line.into_iter().map(char::from).collect();
             c.insert(0, ' ');
             c.push(' ');
             c
@@ -47,11 +48,11 @@ fn parse(content: &str) -> Grid {
         .collect::<Vec<_>>();
     r.insert(0, vec![' '; r[0].len()]);
     r.push(vec![' '; r[0].len()]);
-    Grid {
+    Ok(Grid {
         num_rows: r.len(),
         num_columns: r[0].len(),
         array: r.into_iter().flatten().collect(),
-    }
+    })
 }
 
 fn neighbors_part1(_seats: &Grid, i: usize, j: usize) -> ArrayVec<(usize, usize), 8> {
@@ -144,7 +145,7 @@ fn part(
 }
 
 pub fn main() {
-    let mut seats = parse(&std::fs::read_to_string("data/2020/day11").unwrap());
+    let mut seats = parse(&std::fs::read_to_string("data/2020/day11").unwrap()).unwrap();
 
     // part 1
     println!(
@@ -159,25 +160,45 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_neightbors_part2() {
         // step 0
-        let mut seats = parse(&std::fs::read_to_string("data/2020/day11-0").unwrap());
+        let Some(input) = read_or_skip("data/2020/day11-0") else {
+            return;
+        };
+        let mut seats = parse(&input).unwrap();
 
         // step 1
         step(&mut seats, 5, neighbors_part2);
-        let expected = parse(&std::fs::read_to_string("data/2020/day11-1").unwrap());
+        let Some(input) = read_or_skip("data/2020/day11-1") else {
+            return;
+        };
+        let expected = parse(&input).unwrap();
         assert_eq!(seats, expected);
 
         // step 2
         step(&mut seats, 5, neighbors_part2);
-        let expected = parse(&std::fs::read_to_string("data/2020/day11-2").unwrap());
+        let Some(input) = read_or_skip("data/2020/day11-2") else {
+            return;
+        };
+        let expected = parse(&input).unwrap();
         assert_eq!(seats, expected);
 
         // step 3
         step(&mut seats, 5, neighbors_part2);
-        let expected = parse(&std::fs::read_to_string("data/2020/day11-3").unwrap());
+        let Some(input) = read_or_skip("data/2020/day11-3") else {
+            return;
+        };
+        let expected = parse(&input).unwrap();
         assert_eq!(seats, expected);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "L.LL\nLLL.\n#.#L";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,6 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::{Parser, character::complete::one_of, combinator::verify, error::context};
+
 enum Instruction {
     North(i32),
     East(i32),
@@ -53,30 +56,30 @@ impl Postion {
     }
 }
 
-fn parse(content: &str) -> Vec<Instruction> {
-    content
-        .lines()
-        .map(|line| {
-            let mut chars = line.chars();
-            let c = chars
-                .next()
-                .unwrap_or_else(|| panic!("invalid line: {line}"));
-            let value = chars.collect::<String>();
-            let v = value
-                .parse::<i32>()
-                .unwrap_or_else(|_| panic!("invalid line: {line}"));
-            match c {
-                'N' => Instruction::North(v),
-                'E' => Instruction::East(v),
-                'S' => Instruction::South(v),
-                'W' => Instruction::West(v),
-                'F' => Instruction::Forward(v),
-                'L' => Instruction::Left(v),
-                'R' => Instruction::Right(v),
-                _ => panic!("invalid line: {line}"),
-            }
-        })
-        .collect::<Vec<_>>()
+fn instruction(line: &str) -> PResult<'_, Instruction> {
+    let (rest, (c, v)) = (one_of("NESWFLR"), unsigned).parse(line)?;
+    let ins = match c {
+        'N' => Instruction::North(v),
+        'E' => Instruction::East(v),
+        'S' => Instruction::South(v),
+        'W' => Instruction::West(v),
+        'F' => Instruction::Forward(v),
+        'L' => Instruction::Left(v),
+        'R' => Instruction::Right(v),
+        _ => unreachable!(),
+    };
+    Ok((rest, ins))
+}
+
+fn parse(content: &str) -> Result<Vec<Instruction>, ParseError> {
+    let turn = |ins: &Instruction| match ins {
+        Instruction::Left(v) | Instruction::Right(v) => v % 90 == 0,
+        _ => true,
+    };
+    parsing::lines(
+        content.trim(),
+        context("turns by multiples of 90", verify(instruction, turn)),
+    )
 }
 
 fn part1(instructions: &[Instruction]) -> i32 {
@@ -119,7 +122,7 @@ fn part2(instructions: &[Instruction]) -> i32 {
 }
 
 pub fn main() {
-    let instructions = parse(&std::fs::read_to_string("data/2020/day12").unwrap());
+    let instructions = parse(&std::fs::read_to_string("data/2020/day12").unwrap()).unwrap();
 
     // part 1
     println!("day 12 part1: {}", part1(&instructions));
@@ -131,11 +134,22 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_small_input() {
-        let instructions = parse(&std::fs::read_to_string("data/2020/day12-1").unwrap());
+        let Some(input) = read_or_skip("data/2020/day12-1") else {
+            return;
+        };
+        let instructions = parse(&input).unwrap();
         assert_eq!(25, part1(&instructions));
         assert_eq!(286, part2(&instructions));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "F10\nN3\nF7\nR90\nF11";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
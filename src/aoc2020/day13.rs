@@ -1,13 +1,26 @@
-fn parse(content: &str) -> (usize, Vec<Option<usize>>) {
-    let mut lines = content.lines();
-    let timestamp = lines.next().unwrap().parse::<usize>().unwrap();
-    let buses = lines
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|e| e.parse::<usize>().ok())
-        .collect::<Vec<_>>();
-    (timestamp, buses)
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    Parser,
+    branch::alt,
+    character::complete::{char, line_ending},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+    sequence::separated_pair,
+};
+
+fn parse(content: &str) -> Result<(usize, Vec<Option<usize>>), ParseError> {
+    let bus = alt((
+        char('x').map(|_| None),
+        context("a bus id", verify(unsigned, |&b| b > 0)).map(Some),
+    ));
+    let buses = verify(separated_list1(char(','), bus), |b: &[_]| {
+        b.iter().any(Option::is_some)
+    });
+    parsing::parse_all(
+        content.trim(),
+        separated_pair(unsigned, line_ending, context("at least one bus", buses)),
+    )
 }
 
 fn part1(timestamp: usize, buses: &[Option<usize>]) -> usize {
@@ -47,7 +60,7 @@ fn part2(_timestamp: usize, buses: &[Option<usize>]) -> usize {
 }
 
 pub fn main() {
-    let (timestamp, buses) = parse(&std::fs::read_to_string("data/2020/day13").unwrap());
+    let (timestamp, buses) = parse(&std::fs::read_to_string("data/2020/day13").unwrap()).unwrap();
 
     // part 1
     println!("day 13 part1: {}", part1(timestamp, &buses));
@@ -59,13 +72,27 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_part2() {
-        let (timestamp, buses) = parse(&std::fs::read_to_string("data/2020/day13-1").unwrap());
+        let Some(input) = read_or_skip("data/2020/day13-1") else {
+            return;
+        };
+        let (timestamp, buses) = parse(&input).unwrap();
         assert_eq!(1068781, part2(timestamp, &buses));
 
-        let (timestamp, buses) = parse(&std::fs::read_to_string("data/2020/day13-2").unwrap());
+        let Some(input) = read_or_skip("data/2020/day13-2") else {
+            return;
+        };
+        let (timestamp, buses) = parse(&input).unwrap();
         assert_eq!(1202161486, part2(timestamp, &buses));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "939\n7,13,x,x,59,x,31,19";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,4 +1,12 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    error::context,
+    sequence::{delimited, preceded},
+};
 
 #[derive(Clone)]
 enum Instruction {
@@ -51,22 +59,31 @@ fn mask_part2(s: &str) -> (usize, usize, Vec<usize>) {
     (mask, value, addrs)
 }
 
-fn parse(content: &str) -> Vec<Instruction> {
-    content
-        .lines()
-        .map(|line| {
-            if &line[0..4] == "mask" {
-                Instruction::Mask(line[7..].to_string())
-            } else if &line[0..4] == "mem[" {
-                let mut tokens = line[4..].split("] = ");
-                let addr = tokens.next().unwrap().parse().unwrap();
-                let value = tokens.next().unwrap().parse().unwrap();
-                Instruction::Mem { addr, value }
-            } else {
-                panic!("invalid line: {line}");
-            }
-        })
-        .collect::<Vec<_>>()
+fn instruction(line: &str) -> PResult<'_, Instruction> {
+    let bits = take_while_m_n(36, 36, |c| "X01".contains(c));
+    let mask = preceded(tag("mask = "), context("36 mask bits", bits));
+    let mem = (
+        delimited(tag("mem["), unsigned, tag("]")),
+        preceded(tag(" = "), unsigned),
+    );
+    alt((
+        mask.map(|m: &str| Instruction::Mask(m.to_string())),
+        mem.map(|(addr, value)| Instruction::Mem { addr, value }),
+    ))
+    .parse(line)
+}
+
+fn parse(content: &str) -> Result<Vec<Instruction>, ParseError> {
+    let instructions = parsing::lines(content.trim(), instruction)?;
+    // part 2 can't write without a mask
+    if let Some(Instruction::Mem { .. }) = instructions.first() {
+        return Err(ParseError::at(
+            content,
+            content.trim_start(),
+            "a mask first",
+        ));
+    }
+    Ok(instructions)
 }
 
 fn part1(instructions: &[Instruction]) -> usize {
@@ -112,7 +129,7 @@ fn part2(instructions: &[Instruction]) -> usize {
 }
 
 pub fn main() {
-    let instructions = parse(&std::fs::read_to_string("data/2020/day14").unwrap());
+    let instructions = parse(&std::fs::read_to_string("data/2020/day14").unwrap()).unwrap();
 
     // part 1
     println!("day 14 part1: {}", part1(&instructions));
@@ -124,10 +141,15 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_part1() {
-        let instructions = parse(&std::fs::read_to_string("data/2020/day14-1").unwrap());
+        let Some(input) = read_or_skip("data/2020/day14-1") else {
+            return;
+        };
+        let instructions = parse(&input).unwrap();
         assert_eq!(165, part1(&instructions));
     }
 
@@ -145,7 +167,16 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let instructions = parse(&std::fs::read_to_string("data/2020/day14-2").unwrap());
+        let Some(input) = read_or_skip("data/2020/day14-2") else {
+            return;
+        };
+        let instructions = parse(&input).unwrap();
         assert_eq!(208, part2(&instructions));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8] = 11\nmem[7] = 101";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,11 +1,11 @@
-fn parse(content: &str) -> Vec<u32> {
-    content
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|token| token.parse().unwrap())
-        .collect::<Vec<_>>()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{character::complete::char, combinator::verify, error::context, multi::separated_list1};
+
+/// The numbers index a table as long as the game, so they stay below part 1's
+/// 2020 turns.
+fn parse(content: &str) -> Result<Vec<u32>, ParseError> {
+    let number = context("a number below 2020", verify(unsigned, |&n| n < 2020));
+    parsing::parse_all(content.trim(), separated_list1(char(','), number))
 }
 
 fn part(starting_nums: &[u32], target_pos: usize) -> u32 {
@@ -28,7 +28,7 @@ fn part(starting_nums: &[u32], target_pos: usize) -> u32 {
 }
 
 pub fn main() {
-    let starting_nums = parse(&std::fs::read_to_string("data/2020/day15").unwrap());
+    let starting_nums = parse(&std::fs::read_to_string("data/2020/day15").unwrap()).unwrap();
 
     // part 1
     println!("day 15 part1: {}", part(&starting_nums, 2020));
@@ -40,13 +40,27 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_part1() {
-        let starting_nums = parse(&std::fs::read_to_string("data/2020/day15-1").unwrap());
+        let Some(input) = read_or_skip("data/2020/day15-1") else {
+            return;
+        };
+        let starting_nums = parse(&input).unwrap();
         assert_eq!(436, part(&starting_nums, 2020));
 
-        let starting_nums = parse(&std::fs::read_to_string("data/2020/day15-2").unwrap());
+        let Some(input) = read_or_skip("data/2020/day15-2") else {
+            return;
+        };
+        let starting_nums = parse(&input).unwrap();
         assert_eq!(1836, part(&starting_nums, 2020));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "0,3,6";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,50 +1,60 @@
+use adventofcode::parsing::{self, Error, PResult, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
+use nom::{
+    Parser,
+    bytes::complete::{tag, take_till1},
+    character::complete::{char, line_ending},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+};
 
 type Ranges = HashMap<String, Vec<(usize, usize)>>;
+type Ticket = Vec<usize>;
 
-fn parse(content: &str) -> (Ranges, Vec<usize>, Vec<Vec<usize>>) {
-    let mut map = Ranges::new();
-    let mut lines = content.lines();
-    for line in &mut lines {
-        if line.is_empty() {
-            break;
-        }
+/// The ranges become lookup tables, so their ends are capped.
+fn range(input: &str) -> PResult<'_, (usize, usize)> {
+    let range = separated_pair(unsigned, char('-'), unsigned);
+    let valid = |&(a, b): &(usize, usize)| a <= b && b < 1 << 20;
+    context("a range below 2^20", verify(range, valid)).parse(input)
+}
 
-        let mut parts = line.split(": ");
-        let name = parts.next().unwrap();
-        let ranges = parts.next().unwrap();
-        let ranges = ranges
-            .split(" or ")
-            .map(|r| {
-                let endpoints = r
-                    .split('-')
-                    .map(|e| e.parse::<usize>().unwrap())
-                    .collect::<Vec<_>>();
-                (endpoints[0], endpoints[1])
-            })
-            .collect::<Vec<_>>();
-        map.insert(name.to_string(), ranges);
-    }
+fn rule(input: &str) -> PResult<'_, (&str, Vec<(usize, usize)>)> {
+    let name = take_till1(|c| c == ':');
+    separated_pair(name, tag(": "), separated_list1(tag(" or "), range)).parse(input)
+}
 
-    assert_eq!(lines.next().unwrap(), "your ticket:");
-    let my_ticket = lines
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect::<Vec<_>>();
+/// Part 2 matches every field of a ticket to a rule.
+fn ticket<'a>(fields: usize) -> impl Parser<&'a str, Output = Ticket, Error = Error<'a>> {
+    let list = separated_list1(char(','), unsigned);
+    context(
+        "a field for every rule",
+        verify(list, move |t: &[usize]| t.len() == fields),
+    )
+}
 
-    assert!(lines.next().unwrap().is_empty());
-    assert_eq!(lines.next().unwrap(), "nearby tickets:");
-    let nearby_tickets = lines
-        .map(|line| {
-            line.split(',')
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-    (map, my_ticket, nearby_tickets)
+fn parse(content: &str) -> Result<(Ranges, Ticket, Vec<Ticket>), ParseError> {
+    let [rules, mine, nearby] = parsing::split_sections::<3>(content)?;
+    let rules = parsing::lines(rules, rule)?;
+    let fields = rules.len();
+    let my_ticket = parsing::parse_all(
+        mine,
+        preceded((tag("your ticket:"), line_ending), ticket(fields)),
+    )?;
+    let nearby_tickets = parsing::parse_all(
+        nearby,
+        preceded(
+            (tag("nearby tickets:"), line_ending),
+            separated_list1(line_ending, ticket(fields)),
+        ),
+    )?;
+    let map = rules
+        .into_iter()
+        .map(|(name, ranges)| (name.to_string(), ranges))
+        .collect();
+    Ok((map, my_ticket, nearby_tickets))
 }
 
 fn part1(ranges: &Ranges, nearby_tickets: &[Vec<usize>]) -> (usize, Vec<Vec<usize>>) {
@@ -144,7 +154,7 @@ fn part2(ranges: &Ranges, valid_tickets: &[Vec<usize>]) -> usize {
 
 pub fn main() {
     let (ranges, my_ticket, nearby_tickets) =
-        parse(&std::fs::read_to_string("data/2020/day16").unwrap());
+        parse(&std::fs::read_to_string("data/2020/day16").unwrap()).unwrap();
 
     // part 1
     let (sum, mut valid_tickets) = part1(&ranges, &nearby_tickets);
@@ -154,3 +164,15 @@ pub fn main() {
     valid_tickets.push(my_ticket);
     println!("day 16 part2: {}", part2(&ranges, &valid_tickets));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "class: 1-3 or 5-7\nrow: 6-11 or 33-44\n\nyour ticket:\n7,1\n\nnearby tickets:\n7,3\n40,4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
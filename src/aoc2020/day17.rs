@@ -1,3 +1,4 @@
+use adventofcode::parsing::{self, ParseError};
 use arrayvec::ArrayVec;
 
 const CYCLES: i64 = 6;
@@ -268,11 +269,17 @@ impl Grid4 {
     }
 }
 
-fn parse(content: &str) -> (Grid, Grid4) {
-    let layer = content
-        .lines()
-        .map(|line| line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+fn parse(content: &str) -> Result<(Grid, Grid4), ParseError> {
+    let content = content.trim();
+    let layer = parsing::grid_of(content, ".#")?;
+    // the 4D grid grows with the square of the slice
+    if layer.len() > 64 || layer[0].len() > 64 {
+        return Err(ParseError::at(
+            content,
+            content,
+            "a slice of at most 64 by 64",
+        ));
+    }
     let m = layer.len() as i64;
     let n = layer[0].len() as i64;
     let mut grid = Grid::new(m + 2 + 2 * CYCLES, n + 2 + 2 * CYCLES, 1 + 2 + 2 * CYCLES);
@@ -284,18 +291,23 @@ fn parse(content: &str) -> (Grid, Grid4) {
     );
     for (i, row) in layer.iter().enumerate() {
         for (j, c) in row.iter().enumerate() {
-            grid.set(i as i64 + 1 + CYCLES, j as i64 + 1 + CYCLES, 1 + CYCLES, *c);
+            grid.set(
+                i as i64 + 1 + CYCLES,
+                j as i64 + 1 + CYCLES,
+                1 + CYCLES,
+                char::from(*c),
+            );
             grid4.set(
                 i as i64 + 1 + CYCLES,
                 j as i64 + 1 + CYCLES,
                 1 + CYCLES,
                 1 + CYCLES,
-                *c,
+                char::from(*c),
             );
         }
     }
 
-    (grid, grid4)
+    Ok((grid, grid4))
 }
 
 fn part1(grid: &mut Grid) -> usize {
@@ -329,7 +341,8 @@ fn part2(grid: &mut Grid4) -> usize {
 }
 
 pub fn main() {
-    let (mut grid, mut grid4) = parse(&std::fs::read_to_string("data/2020/day17").unwrap());
+    let (mut grid, mut grid4) =
+        parse(&std::fs::read_to_string("data/2020/day17").unwrap()).unwrap();
 
     // part 1
     println!("day 17 part1: {}", part1(&mut grid));
@@ -341,11 +354,22 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_small1() {
-        let (mut grid, mut grid4) = parse(&std::fs::read_to_string("data/2020/day17-1").unwrap());
+        let Some(input) = read_or_skip("data/2020/day17-1") else {
+            return;
+        };
+        let (mut grid, mut grid4) = parse(&input).unwrap();
         assert_eq!(112, part1(&mut grid));
         assert_eq!(848, part2(&mut grid4));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = ".#.\n..#\n###";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,160 +1,419 @@
-#[derive(PartialEq, Eq, Clone, Debug)]
+use adventofcode::parsing;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operator {
     Add,
+    Subtract,
     Multiply,
+    Divide,
+}
+
+impl Operator {
+    fn symbol(self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+        }
+    }
+
+    fn apply(self, a: i64, b: i64) -> Result<i64, EvalError> {
+        match self {
+            Operator::Add => a.checked_add(b).ok_or(EvalError::Overflow),
+            Operator::Subtract => a.checked_sub(b).ok_or(EvalError::Overflow),
+            Operator::Multiply => a.checked_mul(b).ok_or(EvalError::Overflow),
+            Operator::Divide if b == 0 => Err(EvalError::DivisionByZero),
+            Operator::Divide => a.checked_div(b).ok_or(EvalError::Overflow),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Assoc {
+    Left,
+    // neither part needs it
+    #[allow(dead_code)]
+    Right,
 }
 
-#[derive(PartialEq, Eq, Clone, Debug)]
-enum Node {
-    None,
-    Value(usize),
+/// Precedence (higher binds tighter) and associativity of each operator,
+/// in `Operator` order. `None` for operators the expressions may not use.
+#[derive(Debug, Clone, Copy)]
+struct OperatorTable([Option<(u8, Assoc)>; 4]);
+
+impl OperatorTable {
+    /// Left and right binding powers for the Pratt loop.
+    fn binding(&self, op: Operator) -> Option<(u32, u32)> {
+        let (p, assoc) = self.0[op as usize]?;
+        let p = 2 * p as u32;
+        Some(match assoc {
+            Assoc::Left => (p, p + 1),
+            Assoc::Right => (p + 1, p),
+        })
+    }
+}
+
+/// Everything evaluates left to right.
+const PART1: OperatorTable = OperatorTable([Some((1, Assoc::Left)); 4]);
+
+/// Addition and subtraction before multiplication and division.
+const PART2: OperatorTable = OperatorTable([
+    Some((2, Assoc::Left)),
+    Some((2, Assoc::Left)),
+    Some((1, Assoc::Left)),
+    Some((1, Assoc::Left)),
+]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Number(i64),
     Operator(Operator),
     LeftBracket,
     RightBracket,
 }
 
-impl Node {
-    fn value(&self) -> usize {
-        if let Node::Value(v) = self {
-            *v
-        } else {
-            panic!("node is not Node::Value: {self:?}")
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParseError {
+    position: usize,
+    expected: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} at column {}",
+            self.expected,
+            self.position + 1
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EvalError {
+    Overflow,
+    DivisionByZero,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow => write!(f, "arithmetic overflow"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
         }
     }
 }
 
-impl From<char> for Node {
-    fn from(c: char) -> Self {
-        match c {
-            ' ' => Node::None,
-            '+' => Node::Operator(Operator::Add),
-            '*' => Node::Operator(Operator::Multiply),
-            '(' => Node::LeftBracket,
-            ')' => Node::RightBracket,
-            n => {
-                if let Ok(op) = n.to_string().parse::<usize>() {
-                    Node::Value(op)
-                } else {
-                    panic!("invalid char in expression: {n}")
+impl std::error::Error for EvalError {}
+
+/// Tokens with the column each one starts at.
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let b = s.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < b.len() {
+        let token = match b[i] {
+            b' ' => {
+                i += 1;
+                continue;
+            }
+            b'0'..=b'9' => {
+                let start = i;
+                while i < b.len() && b[i].is_ascii_digit() {
+                    i += 1;
                 }
+                let n = s[start..i].parse().map_err(|_| ParseError {
+                    position: start,
+                    expected: "a number that fits in 64 bits",
+                })?;
+                tokens.push((start, Token::Number(n)));
+                continue;
             }
-        }
+            b'+' => Token::Operator(Operator::Add),
+            b'-' => Token::Operator(Operator::Subtract),
+            b'*' => Token::Operator(Operator::Multiply),
+            b'/' => Token::Operator(Operator::Divide),
+            b'(' => Token::LeftBracket,
+            b')' => Token::RightBracket,
+            _ => {
+                return Err(ParseError {
+                    position: i,
+                    expected: "a number, operator or bracket",
+                });
+            }
+        };
+        tokens.push((i, token));
+        i += 1;
     }
+    Ok(tokens)
 }
 
-fn parse(content: &str) -> Vec<&str> {
-    content.lines().collect()
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Number(i64),
+    Binary(Operator, Box<Expr>, Box<Expr>),
 }
 
-#[allow(clippy::ptr_arg)]
-fn reduce_part1(exp: &mut Vec<Node>) -> usize {
-    let mut it = exp.iter_mut();
-    let mut left = it.next().unwrap().value();
-    while let Some(Node::Operator(op)) = it.next() {
-        let right = it.next().unwrap().value();
-        match op {
-            Operator::Add => left += right,
-            Operator::Multiply => left *= right,
-        }
-    }
-    left
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    pos: usize,
+    end: usize,
+    table: &'a OperatorTable,
 }
 
-fn reduce_part2(exp: &mut Vec<Node>) -> usize {
-    while let Some((i, _)) = exp
-        .iter()
-        .enumerate()
-        .find(|&(_, n)| n == &Node::Operator(Operator::Add))
-    {
-        let mut left = exp[i - 1].value();
-        left += exp[i + 1].value();
-        exp.remove(i - 1);
-        exp.remove(i - 1);
-        exp.remove(i - 1);
-        exp.insert(i - 1, Node::Value(left));
-    }
-    reduce_part1(exp)
-}
-
-fn evaluate(exp: &str, reduce: fn(&mut Vec<Node>) -> usize) -> usize {
-    let exp = exp
-        .chars()
-        .map(Node::from)
-        .filter(|c| c != &Node::None)
-        .collect::<Vec<_>>();
-    let mut stack = Vec::with_capacity(exp.len());
-
-    for c in exp {
-        match c {
-            Node::RightBracket => {
-                let mut sub_exp = Vec::new();
-                while stack.last().unwrap() != &Node::LeftBracket {
-                    sub_exp.push(stack.pop().unwrap());
+impl Parser<'_> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).map(|&(_, t)| t)
+    }
+
+    fn error(&self, expected: &'static str) -> ParseError {
+        let position = self.tokens.get(self.pos).map_or(self.end, |&(p, _)| p);
+        ParseError { position, expected }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some(Token::Number(n)) => {
+                self.pos += 1;
+                Ok(Expr::Number(n))
+            }
+            Some(Token::LeftBracket) => {
+                self.pos += 1;
+                let e = self.expr(0)?;
+                if self.peek() != Some(Token::RightBracket) {
+                    return Err(self.error("')'"));
                 }
-                stack.pop();
-                sub_exp.reverse();
-                stack.push(Node::Value(reduce(&mut sub_exp)));
+                self.pos += 1;
+                Ok(e)
             }
-            n => {
-                stack.push(n);
+            _ => Err(self.error("a number or '('")),
+        }
+    }
+
+    fn expr(&mut self, min_power: u32) -> Result<Expr, ParseError> {
+        let mut lhs = self.primary()?;
+        while let Some(Token::Operator(op)) = self.peek() {
+            let (left, right) = self
+                .table
+                .binding(op)
+                .ok_or_else(|| self.error("an operator from the table"))?;
+            if left < min_power {
+                break;
             }
+            self.pos += 1;
+            let rhs = self.expr(right)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
         }
+        Ok(lhs)
     }
-    reduce(&mut stack)
 }
 
-fn part1(expressions: &[&str]) -> usize {
-    expressions
-        .iter()
-        .map(|exp| evaluate(exp, reduce_part1))
-        .sum()
+impl Expr {
+    fn parse(s: &str, table: &OperatorTable) -> Result<Self, ParseError> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            end: s.len(),
+            table,
+        };
+        let e = parser.expr(0)?;
+        if parser.pos < tokens.len() {
+            return Err(parser.error("an operator"));
+        }
+        Ok(e)
+    }
+
+    /// Division truncates towards zero.
+    fn evaluate(&self) -> Result<i64, EvalError> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Binary(op, a, b) => op.apply(a.evaluate()?, b.evaluate()?),
+        }
+    }
 }
 
-fn part2(expressions: &[&str]) -> usize {
-    expressions
-        .iter()
-        .map(|exp| evaluate(exp, reduce_part2))
-        .sum()
+/// Brackets around every operation, so it reads the same under any table.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Binary(op, a, b) => write!(f, "({a} {} {b})", op.symbol()),
+        }
+    }
+}
+
+/// Every line, under the tables of both parts.
+fn parse(content: &str) -> Result<Vec<[Expr; 2]>, parsing::ParseError> {
+    let content = content.trim_end();
+    if content.is_empty() {
+        return Err(parsing::ParseError::at(content, content, "an expression"));
+    }
+    let at = |line: &str, e: ParseError| {
+        let rest = &line[line.floor_char_boundary(e.position)..];
+        parsing::ParseError::at(content, rest, e.expected)
+    };
+    content
+        .lines()
+        .map(|line| {
+            let part1 = Expr::parse(line, &PART1).map_err(|e| at(line, e))?;
+            let part2 = Expr::parse(line, &PART2).map_err(|e| at(line, e))?;
+            Ok([part1, part2])
+        })
+        .collect()
+}
+
+fn total<'a>(mut expressions: impl Iterator<Item = &'a Expr>) -> Result<i64, EvalError> {
+    expressions.try_fold(0i64, |sum, e| {
+        sum.checked_add(e.evaluate()?).ok_or(EvalError::Overflow)
+    })
+}
+
+fn part1(expressions: &[[Expr; 2]]) -> Result<i64, EvalError> {
+    total(expressions.iter().map(|e| &e[0]))
+}
+
+fn part2(expressions: &[[Expr; 2]]) -> Result<i64, EvalError> {
+    total(expressions.iter().map(|e| &e[1]))
 }
 
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day18").unwrap();
-    let expressions = parse(&content);
+    let expressions = parse(&content).unwrap();
 
     // part 1
-    println!("day 18 part1: {}", part1(&expressions));
+    match part1(&expressions) {
+        Ok(n) => println!("day 18 part1: {n}"),
+        Err(e) => println!("day 18 part1: {e}"),
+    }
 
     // part 2
-    println!("day 18 part2: {}", part2(&expressions));
+    match part2(&expressions) {
+        Ok(n) => println!("day 18 part2: {n}"),
+        Err(e) => println!("day 18 part2: {e}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+
+    fn evaluate(exp: &str, table: &OperatorTable) -> i64 {
+        Expr::parse(exp, table).unwrap().evaluate().unwrap()
+    }
+
+    #[test]
+    fn homework() {
+        let content = "2 * 3 + (4 * 5)\n5 + (8 * 3 + 9 + 3 * 4 * 3)\n";
+        let expressions = parse(content).unwrap();
+        assert_eq!(Ok(26 + 437), part1(&expressions));
+        assert_eq!(Ok(46 + 1445), part2(&expressions));
+        let e = parse("1 + 2\n3 * (4").unwrap_err();
+        assert_eq!((2, 7, "')'"), (e.line, e.column, e.expected.as_str()));
+        let overflow = parse("9223372036854775807\n1").unwrap();
+        assert_eq!(Err(EvalError::Overflow), part1(&overflow));
+    }
 
     #[test]
     fn test_evaluate_part1() {
-        [("1 + (2 * 3) + (4 * (5 + 6))", 51),
+        [
+            ("1 + (2 * 3) + (4 * (5 + 6))", 51),
             ("2 * 3 + (4 * 5)", 26),
             ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 437),
             ("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 12240),
-            ("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 13632)]
+            ("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 13632),
+        ]
         .iter()
         .for_each(|&(exp, v)| {
-            assert_eq!(evaluate(exp, reduce_part1), v);
+            assert_eq!(evaluate(exp, &PART1), v);
         });
     }
 
     #[test]
     fn test_evaluate_part2() {
-        [("1 + (2 * 3) + (4 * (5 + 6))", 51),
+        [
+            ("1 + (2 * 3) + (4 * (5 + 6))", 51),
             ("2 * 3 + (4 * 5)", 46),
             ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 1445),
             ("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 669060),
-            ("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 23340)]
+            ("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 23340),
+        ]
         .iter()
         .for_each(|&(exp, v)| {
-            assert_eq!(evaluate(exp, reduce_part2), v);
+            assert_eq!(evaluate(exp, &PART2), v);
+        });
+    }
+
+    #[test]
+    fn printer() {
+        let exp = "2 * 3 + (4 * 5)";
+        let e = Expr::parse(exp, &PART1).unwrap();
+        assert_eq!("((2 * 3) + (4 * 5))", e.to_string());
+        let e = Expr::parse(exp, &PART2).unwrap();
+        assert_eq!("(2 * (3 + (4 * 5)))", e.to_string());
+        // the printed form means the same under any table
+        let again = Expr::parse(&e.to_string(), &PART1).unwrap();
+        assert_eq!(e, again);
+    }
+
+    #[test]
+    fn tables() {
+        let normal = OperatorTable([
+            Some((1, Assoc::Left)),
+            Some((1, Assoc::Left)),
+            Some((2, Assoc::Left)),
+            Some((2, Assoc::Left)),
+        ]);
+        assert_eq!(4, evaluate("10 - 4 - 2", &normal));
+        assert_eq!(-8, evaluate("100 - 12 * 9", &normal));
+        assert_eq!(5, evaluate("100 / 10 / 2", &normal));
+        let right = OperatorTable([Some((1, Assoc::Right)); 4]);
+        assert_eq!(8, evaluate("10 - 4 - 2", &right));
+        assert_eq!(20, evaluate("100 / 10 / 2", &right));
+        let e = Expr::parse("1 - 2 - 3", &right).unwrap();
+        assert_eq!("(1 - (2 - 3))", e.to_string());
+    }
+
+    #[test]
+    fn errors() {
+        let error = |exp, table| Expr::parse(exp, table).unwrap_err().to_string();
+        assert_eq!(
+            "expected a number or '(' at column 5",
+            error("1 + ", &PART1)
+        );
+        assert_eq!("expected ')' at column 7", error("(1 + 2", &PART1));
+        assert_eq!("expected an operator at column 3", error("1 2", &PART1));
+        assert_eq!(
+            "expected a number, operator or bracket at column 3",
+            error("1 % 2", &PART1)
+        );
+        let no_division = OperatorTable([Some((1, Assoc::Left)), None, None, None]);
+        assert_eq!(
+            "expected an operator from the table at column 3",
+            error("1 / 2", &no_division)
+        );
+        let e = Expr::parse("5 / (3 - 3)", &PART1).unwrap();
+        assert_eq!(Err(EvalError::DivisionByZero), e.evaluate());
+        let e = Expr::parse("9223372036854775807 + 1", &PART1).unwrap();
+        assert_eq!(Err(EvalError::Overflow), e.evaluate());
+    }
+
+    #[test]
+    fn fuzz() {
+        let seeds = ["5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", "100 / 10 - 2"];
+        fuzz::check(&seeds, |s| {
+            for exprs in parse(s)? {
+                for (e, table) in exprs.iter().zip([&PART1, &PART2]) {
+                    assert_eq!(Ok(e), Expr::parse(&e.to_string(), table).as_ref());
+                    let _ = e.evaluate();
+                }
+            }
+            Ok::<_, parsing::ParseError>(())
         });
     }
 }
@@ -1,4 +1,5 @@
-use ahash::AHashMap as HashMap;
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(Debug, Clone)]
@@ -7,118 +8,477 @@ enum Rule {
     Any(Vec<Vec<usize>>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 struct ParseRuleError;
 
 impl FromStr for Rule {
     type Err = ParseRuleError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.as_bytes()[0] == b'"' && s.as_bytes()[2] == b'"' {
-            Ok(Rule::Exact(s.as_bytes()[1] as char))
+        let mut chars = s.chars();
+        if let (Some('"'), Some(c), Some('"'), None) =
+            (chars.next(), chars.next(), chars.next(), chars.next())
+        {
+            Ok(Rule::Exact(c))
         } else {
             let rules = s
                 .split(" | ")
                 .map(|part| {
                     part.split(' ')
-                        .map(|n| n.parse().unwrap())
-                        .collect::<Vec<_>>()
+                        .map(|n| n.parse().map_err(|_| ParseRuleError))
+                        .collect::<Result<Vec<_>, _>>()
                 })
-                .collect::<Vec<_>>();
+                .collect::<Result<Vec<_>, _>>()?;
             Ok(Rule::Any(rules))
         }
     }
 }
 
-fn parse(content: &str) -> (Vec<Rule>, Vec<&str>) {
-    let mut lines = content.lines();
-    let mut map: HashMap<usize, Rule> = HashMap::new();
-    for line in &mut lines {
-        if line.is_empty() {
-            break;
+#[derive(Debug, PartialEq, Eq)]
+enum GrammarError {
+    Parse(usize),
+    Undefined { rule: usize, used_by: usize },
+    MissingRoot,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarError::Parse(line) => write!(f, "invalid rule on line {line}"),
+            GrammarError::Undefined { rule, used_by } => {
+                write!(f, "rule {rule} is used by {used_by} but never defined")
+            }
+            GrammarError::MissingRoot => write!(f, "rule 0 is never defined"),
         }
+    }
+}
 
-        let mut parts = line.split(": ");
-        let num = parts.next().unwrap().parse().unwrap();
-        let rule = parts.next().unwrap().parse().unwrap();
-        map.insert(num, rule);
+/// Any set of rules, recursive in any way, recognised with Earley's
+/// algorithm. The rule syntax can't say "empty", so nothing is nullable.
+#[derive(Debug, Clone)]
+struct Grammar {
+    rules: HashMap<usize, Rule>,
+}
+
+/// Where the Earley parser is within one alternative of a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    rule: usize,
+    alt: usize,
+    dot: usize,
+    origin: usize,
+}
+
+impl Item {
+    fn advance(self) -> Self {
+        Self {
+            dot: self.dot + 1,
+            ..self
+        }
     }
+}
 
-    let messages = lines.collect::<Vec<_>>();
-    let max_rules = map.keys().max().unwrap();
-    let mut rules = Vec::with_capacity(max_rules + 1);
-    for i in 0..=*max_rules {
-        if let Some(r) = map.get(&i) {
-            rules.push(r.clone());
-        } else {
-            rules.push(Rule::Exact('❓'));
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tree {
+    Leaf(usize, char),
+    Node(usize, Vec<Tree>),
+}
+
+impl Tree {
+    fn text(&self) -> String {
+        match self {
+            Tree::Leaf(_, c) => c.to_string(),
+            Tree::Node(_, children) => children.iter().map(Tree::text).collect(),
         }
     }
-    (rules, messages)
 }
 
-fn check_rule(rules: &[Rule], rule: usize, msg: &str, stack: &mut Vec<usize>) -> bool {
-    match &rules[rule] {
-        Rule::Exact(c) => {
-            if let Some(m) = msg.chars().next() {
-                if *c != m {
-                    return false;
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tree::Leaf(rule, c) => write!(f, "({rule} {c})"),
+            Tree::Node(rule, children) => {
+                write!(f, "({rule}")?;
+                for child in children {
+                    write!(f, " {child}")?;
                 }
-            } else {
-                return false;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Every `(rule, start, end)` such that `rule` derives `input[start..end]`,
+/// as far as Earley needs to find out whether the root derives all of it.
+type Spans = HashSet<(usize, usize, usize)>;
+
+impl Grammar {
+    fn new(rules: HashMap<usize, Rule>) -> Result<Self, GrammarError> {
+        for (&used_by, rule) in &rules {
+            if let Rule::Any(alts) = rule
+                && let Some(&r) = alts.iter().flatten().find(|r| !rules.contains_key(r))
+            {
+                return Err(GrammarError::Undefined { rule: r, used_by });
             }
+        }
+        Ok(Self { rules })
+    }
+
+    fn set(&mut self, n: usize, rule: Rule) -> Result<(), GrammarError> {
+        let mut rules = self.rules.clone();
+        rules.insert(n, rule);
+        *self = Self::new(rules)?;
+        Ok(())
+    }
 
-            if let Some(r) = stack.pop() {
-                check_rule(rules, r, &msg[1..], stack)
-            } else {
-                msg.len() == 1
+    fn next_symbol(&self, item: &Item) -> Option<usize> {
+        match &self.rules[&item.rule] {
+            Rule::Any(alts) => alts[item.alt].get(item.dot).copied(),
+            Rule::Exact(_) => unreachable!(),
+        }
+    }
+
+    fn spans(&self, root: usize, input: &[char]) -> Spans {
+        let n = input.len();
+        let mut spans = Spans::new();
+        let alts = match &self.rules[&root] {
+            Rule::Exact(c) => {
+                if input == [*c] {
+                    spans.insert((root, 0, 1));
+                }
+                return spans;
+            }
+            Rule::Any(alts) => alts,
+        };
+        let mut sets = vec![vec![]; n + 1];
+        let mut seen = vec![HashSet::new(); n + 1];
+        let mut add = |sets: &mut Vec<Vec<Item>>, k: usize, item: Item| {
+            if seen[k].insert(item) {
+                sets[k].push(item);
             }
+        };
+        for alt in 0..alts.len() {
+            let item = Item {
+                rule: root,
+                alt,
+                dot: 0,
+                origin: 0,
+            };
+            add(&mut sets, 0, item);
+        }
+        for k in 0..=n {
+            let mut i = 0;
+            while i < sets[k].len() {
+                let item = sets[k][i];
+                i += 1;
+                let Some(s) = self.next_symbol(&item) else {
+                    // complete: every item waiting on this rule moves on
+                    spans.insert((item.rule, item.origin, k));
+                    for j in 0..sets[item.origin].len() {
+                        let parent = sets[item.origin][j];
+                        if self.next_symbol(&parent) == Some(item.rule) {
+                            add(&mut sets, k, parent.advance());
+                        }
+                    }
+                    continue;
+                };
+                match &self.rules[&s] {
+                    Rule::Exact(c) => {
+                        if input.get(k) == Some(c) {
+                            spans.insert((s, k, k + 1));
+                            add(&mut sets, k + 1, item.advance());
+                        }
+                    }
+                    Rule::Any(alts) => {
+                        for alt in 0..alts.len() {
+                            let item = Item {
+                                rule: s,
+                                alt,
+                                dot: 0,
+                                origin: k,
+                            };
+                            add(&mut sets, k, item);
+                        }
+                    }
+                }
+            }
+        }
+        spans
+    }
+
+    fn matches(&self, root: usize, message: &str) -> bool {
+        let input = message.chars().collect::<Vec<_>>();
+        self.spans(root, &input).contains(&(root, 0, input.len()))
+    }
+
+    fn parse_tree(&self, root: usize, message: &str) -> Option<Tree> {
+        let input = message.chars().collect::<Vec<_>>();
+        let spans = self.spans(root, &input);
+        let mut builder = TreeBuilder {
+            grammar: self,
+            spans: &spans,
+            input: &input,
+            active: HashSet::new(),
+        };
+        builder.tree(root, 0, input.len())
+    }
+}
+
+/// Walks back down the spans Earley found. `active` stops unit cycles like
+/// `8: 8 | 42` from going round forever; a derivation without them exists.
+struct TreeBuilder<'a> {
+    grammar: &'a Grammar,
+    spans: &'a Spans,
+    input: &'a [char],
+    active: HashSet<(usize, usize, usize)>,
+}
+
+impl TreeBuilder<'_> {
+    fn tree(&mut self, rule: usize, start: usize, end: usize) -> Option<Tree> {
+        if !self.spans.contains(&(rule, start, end)) {
+            return None;
         }
-        Rule::Any(groups) => groups.iter().any(|group| {
-            let mut stack = stack.clone();
-            for &r in group.iter().rev() {
-                stack.push(r);
+        let alts = match &self.grammar.rules[&rule] {
+            Rule::Exact(_) => return Some(Tree::Leaf(rule, self.input[start])),
+            Rule::Any(alts) => alts,
+        };
+        if !self.active.insert((rule, start, end)) {
+            return None;
+        }
+        let children = alts.iter().find_map(|alt| self.sequence(alt, start, end));
+        self.active.remove(&(rule, start, end));
+        children.map(|children| Tree::Node(rule, children))
+    }
+
+    fn sequence(&mut self, symbols: &[usize], start: usize, end: usize) -> Option<Vec<Tree>> {
+        let Some((&first, rest)) = symbols.split_first() else {
+            return (start == end).then(Vec::new);
+        };
+        // every symbol covers at least one character
+        for mid in start + 1..=end - rest.len().min(end) {
+            if !self.spans.contains(&(first, start, mid)) {
+                continue;
             }
-            if let Some(r) = stack.pop() {
-                check_rule(rules, r, msg, &mut stack)
-            } else {
-                panic!("empty stack in Rule::Any");
+            if let Some(tail) = self.sequence(rest, mid, end)
+                && let Some(head) = self.tree(first, start, mid)
+            {
+                let mut trees = vec![head];
+                trees.extend(tail);
+                return Some(trees);
             }
-        }),
+        }
+        None
     }
 }
 
-fn part1(rules: &[Rule], messages: &[&str]) -> usize {
-    let mut stack = Vec::new();
+fn parse(content: &str) -> Result<(Grammar, Vec<&str>), GrammarError> {
+    let mut lines = content.lines().enumerate();
+    let mut rules = HashMap::new();
+    for (i, line) in &mut lines {
+        if line.is_empty() {
+            break;
+        }
+        let (num, rule) = line.split_once(": ").ok_or(GrammarError::Parse(i + 1))?;
+        let num = num.parse().map_err(|_| GrammarError::Parse(i + 1))?;
+        let rule = rule.parse().map_err(|_| GrammarError::Parse(i + 1))?;
+        rules.insert(num, rule);
+    }
+
+    if !rules.contains_key(&0) {
+        return Err(GrammarError::MissingRoot);
+    }
+    let messages = lines.map(|(_, l)| l).collect::<Vec<_>>();
+    Ok((Grammar::new(rules)?, messages))
+}
+
+fn part1(grammar: &Grammar, messages: &[&str]) -> usize {
     messages
         .iter()
-        .filter(|&&msg| check_rule(rules, 0, msg, &mut stack))
+        .filter(|&&msg| grammar.matches(0, msg))
         .count()
 }
 
+fn with_loops(grammar: &Grammar) -> Grammar {
+    let mut grammar = grammar.clone();
+    grammar.set(8, "42 | 42 8".parse().unwrap()).unwrap();
+    grammar
+        .set(11, "42 31 | 42 11 31".parse().unwrap())
+        .unwrap();
+    grammar
+}
+
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day19").unwrap();
-    let (mut rules, messages) = parse(&content);
+    let (grammar, messages) = parse(&content).unwrap();
 
     // part 1
-    println!("day 19 part1: {}", part1(&rules, &messages));
+    println!("day 19 part1: {}", part1(&grammar, &messages));
 
     // part 2
-    rules[8] = Rule::Any(vec![vec![42], vec![42, 8]]);
-    rules[11] = Rule::Any(vec![vec![42, 31], vec![42, 11, 31]]);
-    println!("day 19 part2: {}", part1(&rules, &messages));
+    let grammar = with_loops(&grammar);
+    println!("day 19 part2: {}", part1(&grammar, &messages));
+
+    if std::env::args().any(|a| a == "--tree")
+        && let Some(tree) = messages.iter().find_map(|m| grammar.parse_tree(0, m))
+    {
+        println!("{}: {tree}", tree.text());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
+
+    const EXAMPLE2: &str = r#"42: 9 14 | 10 1
+9: 14 27 | 1 26
+10: 23 14 | 28 1
+1: "a"
+11: 42 31
+5: 1 14 | 15 1
+19: 14 1 | 14 14
+12: 24 14 | 19 1
+16: 15 1 | 14 14
+31: 14 17 | 1 13
+6: 14 14 | 1 14
+2: 1 24 | 14 4
+0: 8 11
+13: 14 3 | 1 12
+15: 1 | 14
+17: 14 2 | 1 7
+23: 25 1 | 22 14
+28: 16 1
+4: 1 1
+20: 14 14 | 1 15
+3: 5 14 | 16 1
+27: 1 6 | 14 18
+14: "b"
+21: 14 1 | 1 14
+25: 1 1 | 1 14
+22: 14 14
+8: 42
+26: 14 22 | 1 20
+18: 15 15
+7: 14 5 | 1 21
+24: 14 1
+
+abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
+bbabbbbaabaabba
+babbbbaabbbbbabbbbbbaabaaabaaa
+aaabbbbbbaaaabaababaabababbabaaabbababababaaa
+bbbbbbbaaaabbbbaaabbabaaa
+bbbababbbbaaaaaaaabbababaaababaabab
+ababaaaaaabaaab
+ababaaaaabbbaba
+baabbaaaabbaaaababbaababb
+abbbbabbbbaaaababbbbbbaaaababb
+aaaaabbaabaaaaababaa
+aaaabbaaaabbaaa
+aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
+babaaabbbaaabaababbaabababaaab
+aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#;
 
     #[test]
     fn test_part1() {
-        let content = std::fs::read_to_string("data/2020/day19-1").unwrap();
-        let (rules, messages) = parse(&content);
+        let Some(content) = read_or_skip("data/2020/day19-1") else {
+            return;
+        };
+        let (grammar, messages) = parse(&content).unwrap();
 
         // part 1
-        assert_eq!(2, part1(&rules, &messages));
+        assert_eq!(2, part1(&grammar, &messages));
+    }
+
+    #[test]
+    fn case1() {
+        let content = r#"0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: "a"
+5: "b"
+
+ababbb
+bababa
+abbbab
+aaabbb
+aaaabbb"#;
+        let (grammar, messages) = parse(content).unwrap();
+        assert_eq!(2, part1(&grammar, &messages));
+
+        let tree = grammar.parse_tree(0, "ababbb").unwrap();
+        let expected = "(0 (4 a) (1 (3 (5 b) (4 a)) (2 (5 b) (5 b))) (5 b))";
+        assert_eq!(expected, tree.to_string());
+        assert_eq!(None, grammar.parse_tree(0, "bababa"));
+    }
+
+    #[test]
+    fn case2() {
+        let (grammar, messages) = parse(EXAMPLE2).unwrap();
+        assert_eq!(3, part1(&grammar, &messages));
+        let grammar = with_loops(&grammar);
+        assert_eq!(12, part1(&grammar, &messages));
+        for m in messages.iter().filter(|m| grammar.matches(0, m)) {
+            assert_eq!(*m, grammar.parse_tree(0, m).unwrap().text());
+        }
+    }
+
+    #[test]
+    fn recursion() {
+        // left recursive, ambiguous and with a unit cycle
+        let content = r#"0: 0 0 | 1 0 2 | 1 2 | 0
+1: "("
+2: ")"
+3: 3 4 | 4
+4: "x"
+"#;
+        let (grammar, _) = parse(content).unwrap();
+        let balanced = |s: &str| {
+            let mut depth = 0i32;
+            s.chars().all(|c| {
+                depth += if c == '(' { 1 } else { -1 };
+                depth >= 0
+            }) && depth == 0
+        };
+        for len in 1..=10 {
+            for bits in 0..1u32 << len {
+                let s = (0..len)
+                    .map(|i| if bits >> i & 1 == 1 { '(' } else { ')' })
+                    .collect::<String>();
+                assert_eq!(balanced(&s), grammar.matches(0, &s), "{s}");
+                if let Some(tree) = grammar.parse_tree(0, &s) {
+                    assert_eq!(s, tree.text());
+                }
+            }
+        }
+        assert!(grammar.matches(3, "xxxxx"));
+        assert!(!grammar.matches(3, ""));
+        assert_eq!("(4 x)", grammar.parse_tree(4, "x").unwrap().to_string());
+    }
+
+    #[test]
+    fn errors() {
+        let undefined = "0: 1 2\n1: \"a\"\n\naa";
+        let expected = GrammarError::Undefined {
+            rule: 2,
+            used_by: 0,
+        };
+        assert_eq!(Some(expected), parse(undefined).err());
+        assert_eq!(Some(GrammarError::Parse(2)), parse("0: 1\n1 \"a\"").err());
+        assert_eq!(
+            Some(GrammarError::MissingRoot),
+            parse("1: \"a\"\n\na").err()
+        );
+        assert_eq!(Some(ParseRuleError), "1 x".parse::<Rule>().err());
+    }
+
+    #[test]
+    fn fuzz() {
+        fuzz::check(&[EXAMPLE2], |s| {
+            let (grammar, messages) = parse(s)?;
+            part1(&grammar, &messages);
+            Ok::<_, GrammarError>(())
+        });
     }
 }
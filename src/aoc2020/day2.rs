@@ -1,26 +1,27 @@
+use adventofcode::parsing::{self, PResult, unsigned};
 use nom::{
+    Parser,
     bytes::complete::tag,
-    character::complete::{alpha1, anychar, char, digit1},
-    combinator::{eof, map_res, recognize},
-    IResult, Parser,
+    character::complete::{alpha1, anychar, char},
+    combinator::verify,
+    error::context,
+    sequence::preceded,
 };
 
-fn parse_nom(s: &str) -> IResult<&str, (usize, usize, char, String)> {
-    fn parse_usize(input: &str) -> IResult<&str, usize> {
-        map_res(recognize(digit1), str::parse).parse(input)
-    }
-    let (_, (min, _, max, _, c, _, pwd, _)) = (
-        parse_usize,
-        char('-'),
-        parse_usize,
-        char(' '),
-        anychar,
-        tag(": "),
-        alpha1,
-        eof,
-    )
-        .parse(s)?;
-    Ok(("", (min, max, c, pwd.to_string())))
+fn parse_nom(s: &str) -> PResult<'_, (usize, usize, char, String)> {
+    let entry = (
+        unsigned,
+        preceded(char('-'), unsigned),
+        preceded(char(' '), anychar),
+        preceded(tag(": "), alpha1),
+    );
+    // part 2 reads both positions, counting from 1
+    let in_password = |(min, max, _, pwd): &(usize, usize, char, &str)| {
+        1 <= *min && min <= max && *max <= pwd.len()
+    };
+    context("positions within the password", verify(entry, in_password))
+        .map(|(min, max, c, pwd)| (min, max, c, pwd.to_string()))
+        .parse(s)
 }
 
 fn is_valid(min: &usize, max: &usize, c: &char, pwd: &str) -> bool {
@@ -35,11 +36,8 @@ fn is_valid_part2(i1: &usize, i2: &usize, c: &char, pwd: &str) -> bool {
 }
 
 pub fn main() {
-    let passwords: Vec<_> = std::fs::read_to_string("data/2020/day2")
-        .unwrap()
-        .lines()
-        .map(|line| parse_nom(line).map(|r| r.1).unwrap())
-        .collect();
+    let data = std::fs::read_to_string("data/2020/day2").unwrap();
+    let passwords = parsing::lines(&data, parse_nom).unwrap();
     let n_valid = passwords
         .iter()
         .filter(|(min, max, c, pwd)| is_valid(min, max, c, pwd))
@@ -52,3 +50,15 @@ pub fn main() {
         .count();
     println!("day2 part2: {n_valid_part2}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc";
+        fuzz::check(&[seed], |s| parsing::lines(s, parse_nom).map(drop));
+    }
+}
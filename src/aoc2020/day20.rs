@@ -1,5 +1,15 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{char, line_ending, one_of},
+    combinator::verify,
+    error::context,
+    multi::{many1, separated_list1},
+    sequence::delimited,
+};
 use std::fmt;
 
 const SEA_MONSTER: &str =
@@ -221,24 +231,33 @@ impl PartialEq for Tile {
     }
 }
 
-fn parse(content: &str) -> Vec<Tile> {
-    let mut lines = content.lines();
-    let mut tiles = Vec::new();
-    let mut group = Vec::new();
-    for line in &mut lines {
-        if line.is_empty() {
-            tiles.push(Tile::from_lines(&group));
-            group.clear();
-        } else {
-            group.push(line);
-        }
-    }
+fn tile(input: &str) -> PResult<'_, Tile> {
+    let header = delimited(tag("Tile "), unsigned, (char(':'), line_ending));
+    let row = many1(one_of(".#").map(Color::from));
+    // edges are matched in every orientation, so tiles are square
+    let square =
+        |rows: &Vec<Vec<Color>>| rows.len() >= 3 && rows.iter().all(|r| r.len() == rows.len());
+    let rows = context(
+        "a square of at least 3",
+        verify(separated_list1(line_ending, row), square),
+    );
+    (header, rows)
+        .map(|(id, array)| Tile { id, array })
+        .parse(input)
+}
 
-    if !group.is_empty() {
-        tiles.push(Tile::from_lines(&group));
+fn parse(content: &str) -> Result<Vec<Tile>, ParseError> {
+    let tiles = parsing::sections(content, tile)?;
+    let size = tiles[0].array.len();
+    if let Some(t) = tiles.iter().find(|t| t.array.len() != size) {
+        let at = content.find(&format!("Tile {}:", t.id)).unwrap_or(0);
+        return Err(ParseError::at(
+            content,
+            &content[at..],
+            format!("a tile of {size} by {size}"),
+        ));
     }
-
-    tiles
+    Ok(tiles)
 }
 
 fn rearrange(tiles: &[Tile], edges_map: HashMap<Edge, Vec<&Tile>>, corners: &[&Tile]) -> Tile {
@@ -411,7 +430,7 @@ fn part2(image: &Tile) -> usize {
 
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day20").unwrap();
-    let tiles = parse(&content);
+    let tiles = parse(&content).unwrap();
     // part 1
     let (answer, image) = part1(&tiles);
     println!("day 20 part1: {answer}");
@@ -423,12 +442,18 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn small_input1() {
-        let content = std::fs::read_to_string("data/2020/day20-1").unwrap();
-        let image_str = std::fs::read_to_string("data/2020/day20-1-1").unwrap();
-        let tiles = parse(&content);
+        let Some(content) = read_or_skip("data/2020/day20-1") else {
+            return;
+        };
+        let Some(image_str) = read_or_skip("data/2020/day20-1-1") else {
+            return;
+        };
+        let tiles = parse(&content).unwrap();
         let expected_image = Tile::from_str(&image_str);
         let (answer, image) = part1(&tiles);
         assert_eq!(20899048083289, answer);
@@ -542,7 +567,7 @@ Tile 4:
 .###
 ##.#
 ####";
-        let tiles = parse(tiles_str);
+        let tiles = parse(tiles_str).unwrap();
         let n = (tiles.len() as f64).sqrt().round() as usize;
         let mut tiles = tiles.chunks(n).map(|c| c.to_vec()).collect::<Vec<_>>();
 
@@ -571,4 +596,10 @@ Tile 4:
 
         assert_eq!(image, expected.array);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Tile 1:\n#.#\n..#\n##.\n\nTile 2:\n.##\n#.#\n###";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,17 +1,31 @@
+use adventofcode::parsing::{self, PResult, ParseError};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{alpha1, char},
+    multi::separated_list1,
+    sequence::delimited,
+};
 use std::collections::BTreeMap;
 
-fn parse(content: &str) -> Vec<(HashSet<&str>, HashSet<&str>)> {
-    content
-        .lines()
-        .map(|line| {
-            let parts = line.split(" (contains ").collect::<Vec<_>>();
-            let ingredients = parts[0].split(' ').collect();
-            let allergens = parts[1][..parts[1].len() - 1].split(", ").collect();
-            (ingredients, allergens)
-        })
-        .collect()
+type Food<'a> = (HashSet<&'a str>, HashSet<&'a str>);
+
+fn food(line: &str) -> PResult<'_, Food<'_>> {
+    let ingredients = separated_list1(char(' '), alpha1);
+    let allergens = delimited(
+        tag(" (contains "),
+        separated_list1(tag(", "), alpha1),
+        char(')'),
+    );
+    (ingredients, allergens)
+        .map(|(i, a)| (i.into_iter().collect(), a.into_iter().collect()))
+        .parse(line)
+}
+
+fn parse(content: &str) -> Result<Vec<Food<'_>>, ParseError> {
+    parsing::lines(content.trim(), food)
 }
 
 fn part1<'a>(
@@ -66,7 +80,7 @@ fn part2(dangerous: &HashMap<&str, HashSet<&str>>) -> String {
 
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day21").unwrap();
-    let data = parse(&content);
+    let data = parse(&content).unwrap();
     // part 1
     let (n, dangerous) = part1(&data);
     println!("day 21 part1: {n}");
@@ -78,11 +92,21 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn test_part1() {
-        let content = std::fs::read_to_string("data/2020/day21-1").unwrap();
-        let data = parse(&content);
+        let Some(content) = read_or_skip("data/2020/day21-1") else {
+            return;
+        };
+        let data = parse(&content).unwrap();
         assert_eq!(5, part1(&data).0);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)\ntrh fvjkl sbzzf mxmxvkd (contains dairy)";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,26 +1,32 @@
+use adventofcode::parsing::{self, Error, ParseError, unsigned};
 use ahash::AHashSet as HashSet;
+use nom::{
+    Parser, bytes::complete::tag, character::complete::line_ending, multi::separated_list1,
+    sequence::preceded,
+};
 
 enum Player {
     P1,
     P2,
 }
 
-fn parse(content: &str) -> (Vec<u8>, Vec<u8>) {
-    let mut lines = content.lines();
-
-    assert_eq!("Player 1:", lines.next().unwrap());
-
-    let deck1: Vec<u8> = lines
-        .by_ref()
-        .take_while(|line| !line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect();
-
-    assert_eq!("Player 2:", lines.next().unwrap());
-
-    let deck2: Vec<u8> = lines.map(|line| line.parse().unwrap()).collect();
+fn deck<'a>(player: &'static str) -> impl Parser<&'a str, Output = Vec<u8>, Error = Error<'a>> {
+    preceded(
+        (tag(player), line_ending),
+        separated_list1(line_ending, unsigned),
+    )
+}
 
-    (deck1, deck2)
+fn parse(content: &str) -> Result<(Vec<u8>, Vec<u8>), ParseError> {
+    let [a, b] = parsing::split_sections::<2>(content)?;
+    let deck1 = parsing::parse_all(a, deck("Player 1:"))?;
+    let deck2 = parsing::parse_all(b, deck("Player 2:"))?;
+    // with a repeated card a round of part 1 can tie
+    let mut seen = HashSet::new();
+    if !deck1.iter().chain(&deck2).all(|&c| seen.insert(c)) {
+        return Err(ParseError::at(content, content, "distinct cards"));
+    }
+    Ok((deck1, deck2))
 }
 
 fn score(deck: &[u8]) -> usize {
@@ -105,7 +111,7 @@ fn part2(mut deck1: Vec<u8>, mut deck2: Vec<u8>) -> usize {
 
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day22").unwrap();
-    let (deck1, deck2) = parse(&content);
+    let (deck1, deck2) = parse(&content).unwrap();
     // part 1
     println!("day 22 part1: {}", part1(deck1.clone(), deck2.clone()));
 
@@ -116,13 +122,23 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use adventofcode::input::read_or_skip;
 
     #[test]
     fn small_input1() {
-        let content = std::fs::read_to_string("data/2020/day22-1").unwrap();
-        let (deck1, deck2) = parse(&content);
+        let Some(content) = read_or_skip("data/2020/day22-1") else {
+            return;
+        };
+        let (deck1, deck2) = parse(&content).unwrap();
         assert_eq!(306, part1(deck1.to_owned(), deck2.to_owned()));
-        let (deck1, deck2) = parse(&content);
+        let (deck1, deck2) = parse(&content).unwrap();
         assert_eq!(291, part2(deck1, deck2));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Player 1:\n9\n2\n6\n\nPlayer 2:\n5\n8\n4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
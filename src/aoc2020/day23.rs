@@ -1,8 +1,25 @@
-fn parse(content: &str) -> (Vec<u32>, Vec<u32>) {
+use adventofcode::parsing::ParseError;
+
+/// The cups are labelled 1 to n, and a move takes three of them and still
+/// needs a destination.
+fn parse(content: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    let content = content.trim();
+    if let Some(i) = content.find(|c: char| !c.is_ascii_digit()) {
+        return Err(ParseError::at(content, &content[i..], "a digit"));
+    }
     let nums = content
-        .chars()
-        .filter_map(|c| c.to_digit(10))
+        .bytes()
+        .map(|b| u32::from(b - b'0'))
         .collect::<Vec<_>>();
+    let mut labels = nums.clone();
+    labels.sort_unstable();
+    if labels.len() < 5 || labels.iter().zip(1..).any(|(&l, i)| l != i) {
+        return Err(ParseError::at(
+            content,
+            content,
+            "the cups 1 to n, with n from 5 to 9",
+        ));
+    }
     let mut r1 = vec![0; nums.len() + 1];
     let mut prev = 0;
     for &n in &nums {
@@ -20,7 +37,7 @@ fn parse(content: &str) -> (Vec<u32>, Vec<u32>) {
     }
     r2.push(r2[0]);
 
-    (r1, r2)
+    Ok((r1, r2))
 }
 
 // fn print_cups(c: &[u32]) {
@@ -90,7 +107,7 @@ fn part2(cups: &mut [u32]) -> usize {
 
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day23").unwrap();
-    let mut cups = parse(&content);
+    let mut cups = parse(&content).unwrap();
 
     // part 1
     println!("day 23 part1: {}", part1(&mut cups.0));
@@ -102,10 +119,11 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn input1() {
-        let mut cups = parse("389125467");
+        let mut cups = parse("389125467").unwrap();
 
         // part 1
         assert_eq!(67384529, part1(&mut cups.0));
@@ -113,4 +131,10 @@ mod tests {
         // part 2
         assert_eq!(149245887792, part2(&mut cups.1));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "389125467";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
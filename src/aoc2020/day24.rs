@@ -1,4 +1,6 @@
+use adventofcode::parsing::{self, PResult, ParseError};
 use ahash::AHashMap as HashMap;
+use nom::{Parser, branch::alt, bytes::complete::tag, error::context, multi::many1};
 
 const NEIGHBORS: [(i32, i32); 6] = [(1, 0), (-1, 0), (0, -1), (1, -1), (-1, 1), (0, 1)];
 
@@ -27,33 +29,20 @@ impl Face {
     }
 }
 
-fn parse(content: &str) -> Vec<Vec<Direction>> {
-    content
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let mut chars = line.chars();
-            let mut dirs = Vec::new();
-            while let Some(c) = chars.next() {
-                dirs.push(match c {
-                    's' => match chars.next().unwrap() {
-                        'w' => Direction::SW,
-                        'e' => Direction::SE,
-                        x => panic!("invalid direction symbol after s: {x}"),
-                    },
-                    'n' => match chars.next().unwrap() {
-                        'w' => Direction::NW,
-                        'e' => Direction::NE,
-                        x => panic!("invalid direction symbol after n: {x}"),
-                    },
-                    'e' => Direction::E,
-                    'w' => Direction::W,
-                    x => panic!("invalid direction symbol: {x}"),
-                });
-            }
-            dirs
-        })
-        .collect()
+fn direction(input: &str) -> PResult<'_, Direction> {
+    alt((
+        tag("se").map(|_| Direction::SE),
+        tag("sw").map(|_| Direction::SW),
+        tag("ne").map(|_| Direction::NE),
+        tag("nw").map(|_| Direction::NW),
+        tag("e").map(|_| Direction::E),
+        tag("w").map(|_| Direction::W),
+    ))
+    .parse(input)
+}
+
+fn parse(content: &str) -> Result<Vec<Vec<Direction>>, ParseError> {
+    parsing::lines(content.trim(), context("a direction", many1(direction)))
 }
 
 fn part1(directions: &[Vec<Direction>]) -> (usize, HashMap<(i32, i32), Face>) {
@@ -128,7 +117,7 @@ fn part2(tiles: &mut HashMap<(i32, i32), Face>) -> usize {
 
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day24").unwrap();
-    let directions = parse(&content);
+    let directions = parse(&content).unwrap();
 
     // part 1
     let (n_black, mut tiles) = part1(&directions);
@@ -141,6 +130,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn input1() {
@@ -165,7 +155,7 @@ eneswnwswnwsenenwnwnwwseeswneewsenese
 neswnwewnwnwseenwseesewsenwsweewe
 wseweeenwnesenwwwswnew";
 
-        let directions = parse(content);
+        let directions = parse(content).unwrap();
 
         // part 1
         let (n_black, mut tiles) = part1(&directions);
@@ -174,4 +164,10 @@ wseweeenwnesenwwwswnew";
         // part 2
         assert_eq!(2208, part2(&mut tiles));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "sesenwnenenewseeswwsw\nneeenesenwnwwswnenew";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,13 +1,20 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    character::complete::line_ending, combinator::verify, error::context, sequence::separated_pair,
+};
+
 const MODULO: usize = 20201227;
 const SUBJECT: usize = 7;
 
-fn parse(content: &str) -> (usize, usize) {
-    let nums = content
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<_>>();
-    (nums[0], nums[1])
+/// Every key in 1..MODULO is a power of 7, so the loop size search ends.
+fn parse(content: &str) -> Result<(usize, usize), ParseError> {
+    let key = || {
+        context(
+            "a key below 20201227",
+            verify(unsigned, |&k| (1..MODULO).contains(&k)),
+        )
+    };
+    parsing::parse_all(content.trim(), separated_pair(key(), line_ending, key()))
 }
 
 #[inline]
@@ -39,7 +46,7 @@ fn part1(card_pub: usize, door_pub: usize) -> usize {
 
 pub fn main() {
     let content = std::fs::read_to_string("data/2020/day25").unwrap();
-    let (card_pub, door_pub) = parse(&content);
+    let (card_pub, door_pub) = parse(&content).unwrap();
 
     // part 1
     println!("day 25 part1: {}", part1(card_pub, door_pub));
@@ -48,13 +55,20 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn input1() {
         let content = "5764801\n17807724";
-        let (card_pub, door_pub) = parse(content);
+        let (card_pub, door_pub) = parse(content).unwrap();
 
         // part 1
         assert_eq!(14897079, part1(card_pub, door_pub));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "5764801\n17807724";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
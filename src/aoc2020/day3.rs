@@ -1,3 +1,5 @@
+use adventofcode::parsing::{self, ParseError};
+
 struct Map {
     tile: Vec<Vec<char>>,
     tile_width: usize,
@@ -20,6 +22,14 @@ impl Map {
     }
 }
 
+fn parse(data: &str) -> Result<Map, ParseError> {
+    let grid = parsing::grid_of(data.trim(), ".#")?;
+    let tile = grid
+        .into_iter()
+        .map(|r| r.into_iter().map(char::from).collect());
+    Ok(Map::new(tile.collect()))
+}
+
 fn count_trees(map: &Map, dx: usize, dy: usize) -> usize {
     let mut x = 0;
     let mut y = 0;
@@ -35,12 +45,8 @@ fn count_trees(map: &Map, dx: usize, dy: usize) -> usize {
 }
 
 pub fn main() {
-    let tile: Vec<_> = std::fs::read_to_string("data/2020/day3")
-        .unwrap()
-        .lines()
-        .map(|s| s.chars().collect::<Vec<_>>())
-        .collect();
-    let map = Map::new(tile);
+    let data = std::fs::read_to_string("data/2020/day3").unwrap();
+    let map = parse(&data).unwrap();
     let slops = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
     let n_trees = slops
         .iter()
@@ -49,3 +55,15 @@ pub fn main() {
     println!("day3 part1: {}", n_trees[1]);
     println!("day3 part2: {}", n_trees.iter().product::<usize>());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
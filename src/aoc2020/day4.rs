@@ -1,3 +1,4 @@
+use adventofcode::parsing::{self, ParseError, key_values};
 use ahash::AHashMap as HashMap;
 use nom::{
     IResult, Parser,
@@ -39,18 +40,11 @@ struct Passport {
 }
 
 impl Passport {
-    fn from_lines(lines: &[&str]) -> Self {
-        let mut fields = HashMap::new();
-        for line in lines {
-            line.split(' ').for_each(|pattern| {
-                let mut tokens = pattern.split(':');
-                if let Some(key) = tokens.next()
-                    && let Some(value) = tokens.next()
-                {
-                    fields.insert(key.to_string(), value.to_string());
-                }
-            });
-        }
+    fn from_fields(fields: Vec<(&str, &str)>) -> Self {
+        let fields = fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
         Self { fields }
     }
 
@@ -120,23 +114,50 @@ impl Passport {
     }
 }
 
+fn parse(data: &str) -> Result<Vec<Passport>, ParseError> {
+    let blocks = parsing::sections(data, key_values(':'))?;
+    Ok(blocks.into_iter().map(Passport::from_fields).collect())
+}
+
 pub fn main() {
-    let mut lines = Vec::new();
-    let mut valid = 0;
-    let mut valid_part2 = 0;
-    for line in std::fs::read_to_string("data/2020/day4").unwrap().lines() {
-        if line.is_empty() {
-            let p = Passport::from_lines(&lines);
-            if p.is_valid() {
-                valid += 1;
-            }
-            if p.is_valid_part2() {
-                valid_part2 += 1;
-            }
-            lines.clear();
-        } else {
-            lines.push(line);
-        }
-    }
+    let data = std::fs::read_to_string("data/2020/day4").unwrap();
+    let passports = parse(&data).unwrap();
+    let valid = passports.iter().filter(|p| p.is_valid()).count();
+    let valid_part2 = passports.iter().filter(|p| p.is_valid_part2()).count();
     println!("day4 part1: {valid}\nday4 part2: {valid_part2}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn case1() {
+        let data = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in";
+        let passports = parse(data).unwrap();
+        assert_eq!(4, passports.len());
+        assert_eq!(2, passports.iter().filter(|p| p.is_valid()).count());
+
+        let e = parse("ecl:gry pid\n").err().unwrap();
+        assert_eq!((1, 12), (e.line, e.column));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm\n\nhcl:#cfa07d eyr:2025 pid:166559648\niyr:2011 ecl:brn hgt:59in";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,5 +1,8 @@
 use std::str::Chars;
 
+use adventofcode::parsing::{self, ParseError};
+use nom::{Parser, bytes::complete::take_while_m_n, error::context};
+
 fn code_to_row(s: &str) -> usize {
     binary(s.chars(), 'F', 'B', 0, 127)
 }
@@ -22,17 +25,16 @@ fn binary(mut s: Chars, left: char, right: char, low: usize, high: usize) -> usi
     }
 }
 
+fn parse(data: &str) -> Result<Vec<usize>, ParseError> {
+    let row = take_while_m_n(7, 7, |c| c == 'F' || c == 'B').map(code_to_row);
+    let col = take_while_m_n(3, 3, |c| c == 'L' || c == 'R').map(code_to_col);
+    let seat = (context("a row", row), context("a column", col));
+    parsing::lines(data.trim(), seat.map(|(row, col)| row * 8 + col))
+}
+
 pub fn main() {
-    let mut numbers = std::fs::read_to_string("data/2020/day5")
-        .unwrap()
-        .lines()
-        .map(|line| {
-            let (row, col) = line.split_at(7);
-            let row = code_to_row(row);
-            let col = code_to_col(col);
-            row * 8 + col
-        })
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2020/day5").unwrap();
+    let mut numbers = parse(&data).unwrap();
     numbers.sort_unstable();
     let max = numbers.iter().last().unwrap_or(&0);
     let mut id = 0;
@@ -44,3 +46,15 @@ pub fn main() {
     }
     println!("day5 part1: {max}\nday5 part2: {id}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "FBFBBFFRLR\nBFFFBBFRRR\nFFFBBBFRRR";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
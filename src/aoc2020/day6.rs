@@ -1,4 +1,15 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::AHashSet as HashSet;
+use nom::{
+    character::complete::{line_ending, satisfy},
+    combinator::recognize,
+    multi::{many1, separated_list1},
+};
+
+fn parse(data: &str) -> Result<Vec<Vec<&str>>, ParseError> {
+    let answers = recognize(many1(satisfy(|c| c.is_ascii_lowercase())));
+    parsing::sections(data, separated_list1(line_ending, answers))
+}
 
 fn count(lines: &[&str]) -> usize {
     let chars = lines
@@ -22,22 +33,21 @@ fn count_part2(lines: &[&str]) -> usize {
 }
 
 pub fn main() {
-    let mut lines = Vec::new();
-    let mut n = 0;
-    let mut n_part2 = 0;
     let content = std::fs::read_to_string("data/2020/day6").unwrap();
-    for line in content.lines() {
-        if line.is_empty() {
-            n += count(&lines);
-            n_part2 += count_part2(&lines);
-            lines.clear();
-        } else {
-            lines.push(line);
-        }
-    }
-    if !lines.is_empty() {
-        n += count(&lines);
-        n_part2 += count_part2(&lines);
-    }
+    let groups = parse(&content).unwrap();
+    let n: usize = groups.iter().map(|g| count(g)).sum();
+    let n_part2: usize = groups.iter().map(|g| count_part2(g)).sum();
     println!("day6 part1: {n}\nday6 part2: {n_part2}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
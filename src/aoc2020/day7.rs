@@ -1,40 +1,91 @@
+use adventofcode::dot::{self, Dot};
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
 use nom::{
+    Parser,
+    branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, char as ch, digit1, space1},
-    combinator::{map_res, opt, recognize, rest},
-    IResult, Parser,
+    character::complete::{alpha1, char as ch, space1},
+    combinator::opt,
+    multi::separated_list1,
+    sequence::terminated,
 };
 
-fn number(input: &str) -> IResult<&str, usize> {
-    map_res(recognize(digit1), str::parse).parse(input)
-}
+type Rules = HashMap<String, Vec<(usize, String)>>;
 
-fn color(input: &str) -> IResult<&str, String> {
+fn color(input: &str) -> PResult<'_, String> {
     let (r, (a, b, c)) = (alpha1, space1, alpha1).parse(input)?;
     Ok((r, format!("{a}{b}{c}")))
 }
 
-fn split(bags: &str) -> Vec<(usize, String)> {
-    if bags == "no other bags." {
-        return Vec::new();
+fn parse(data: &str) -> Result<Rules, ParseError> {
+    let data = data.trim();
+    let content = (unsigned, ch(' '), color, tag(" bag"), opt(ch('s')));
+    let contents = alt((
+        tag("no other bags.").map(|_| vec![]),
+        terminated(
+            separated_list1(tag(", "), content.map(|(n, _, c, _, _)| (n, c))),
+            ch('.'),
+        ),
+    ));
+    let rule = (color, tag(" bags contain "), contents).map(|(c, _, r)| (c, r));
+    let mut map: Rules = HashMap::new();
+    for ((c, r), line) in parsing::lines(data, rule)?.into_iter().zip(data.lines()) {
+        if map.insert(c, r).is_some() {
+            return Err(ParseError::at(data, line, "one rule per color"));
+        }
+    }
+    if let Some((_, c)) = map.values().flatten().find(|(_, c)| !map.contains_key(c)) {
+        return Err(ParseError::at(data, data, format!("a rule for {c}")));
     }
+    if !map.contains_key("shiny gold") {
+        return Err(ParseError::at(data, data, "a rule for shiny gold"));
+    }
+    // both parts recurse into the bags inside, and part 2 counts them all
+    let mut inside: HashMap<&str, Option<usize>> = HashMap::new();
+    for bag in map.keys() {
+        let mut stack = vec![(bag.as_str(), false)];
+        while let Some((bag, explored)) = stack.pop() {
+            if explored {
+                let total = map[bag].iter().try_fold(0usize, |sum, (n, c)| {
+                    n.checked_mul(inside[c.as_str()]? + 1)?.checked_add(sum)
+                });
+                let total = total.ok_or_else(|| ParseError::at(data, data, "fewer bags"))?;
+                inside.insert(bag, Some(total));
+                continue;
+            }
+            match inside.get(bag) {
+                Some(Some(_)) => continue,
+                Some(None) => {
+                    return Err(ParseError::at(
+                        data,
+                        data,
+                        "bags that don't hold themselves",
+                    ));
+                }
+                None => {}
+            }
+            inside.insert(bag, None);
+            stack.push((bag, true));
+            stack.extend(map[bag].iter().map(|(_, c)| (c.as_str(), false)));
+        }
+    }
+    Ok(map)
+}
 
-    bags.split(", ")
-        .map(|s| {
-            let (_, (num, _, c, _, _)) = (number, ch(' '), color, tag(" bag"), opt(ch('s')))
-                .parse(s)
-                .unwrap();
-            (num, c)
-        })
-        .collect()
+/// Edges from every bag to the bags it holds, labeled with how many.
+fn to_dot(map: &Rules) -> Dot {
+    let mut dot = Dot::digraph();
+    dot.node("shiny gold", &[("color", "gold")]);
+    for (bag, inner) in map {
+        for (n, other) in inner {
+            dot.edge(bag, other, Some(&n.to_string()));
+        }
+    }
+    dot
 }
 
-fn contains_shiny_gold(
-    bag: &str,
-    map: &HashMap<String, Vec<(usize, String)>>,
-    table: &mut HashMap<String, bool>,
-) -> bool {
+fn contains_shiny_gold(bag: &str, map: &Rules, table: &mut HashMap<String, bool>) -> bool {
     if bag == "shiny gold" {
         return true;
     }
@@ -59,11 +110,7 @@ fn contains_shiny_gold(
     false
 }
 
-fn count_contained_bags(
-    bag: &str,
-    map: &HashMap<String, Vec<(usize, String)>>,
-    table: &mut HashMap<String, usize>,
-) -> usize {
+fn count_contained_bags(bag: &str, map: &Rules, table: &mut HashMap<String, usize>) -> usize {
     let n = map
         .get(bag)
         .unwrap_or_else(|| panic!("map doesn't contain: {bag}"))
@@ -81,13 +128,10 @@ fn count_contained_bags(
 }
 
 pub fn main() {
-    let mut map = HashMap::new();
     let data = std::fs::read_to_string("data/2020/day7").unwrap();
-    for line in data.lines().filter(|line| !line.is_empty()) {
-        let (_, (c, _, r)) = (color, tag(" bags contain "), rest).parse(line).unwrap();
-        let others = split(r);
-        map.insert(c, others);
-    }
+    let map = parse(&data).unwrap();
+
+    dot::export(|| to_dot(&map));
 
     // part 1
     let mut table = HashMap::new();
@@ -102,3 +146,15 @@ pub fn main() {
     let n = count_contained_bags("shiny gold", &map, &mut table) - 1;
     println!("day7 part2: {n}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "light red bags contain 1 bright white bag, 2 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain 1 dark olive bag.\ndark olive bags contain no other bags.\nfaded blue bags contain no other bags.";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,9 +1,10 @@
+use adventofcode::parsing::{self, PResult, ParseError};
 use nom::{
+    Parser,
     branch::alt,
     bytes::complete::tag,
     character::complete::{char, digit1, one_of},
-    combinator::{eof, map_res, recognize},
-    IResult, Parser,
+    combinator::{map_res, recognize},
 };
 
 #[derive(Clone)]
@@ -13,20 +14,24 @@ enum Instruction {
     Nop(i32),
 }
 
-fn parse(line: &str) -> IResult<&str, Instruction> {
+fn instruction(line: &str) -> PResult<'_, Instruction> {
     let operator = alt((tag("jmp"), tag("acc"), tag("nop")));
-    fn number(input: &str) -> IResult<&str, i32> {
+    fn number(input: &str) -> PResult<'_, i32> {
         let num = (one_of("+-"), digit1);
         map_res(recognize(num), str::parse).parse(input)
     }
-    let (_, (op, _, num, _)) = (operator, char(' '), number, eof).parse(line)?;
+    let (rest, (op, _, num)) = (operator, char(' '), number).parse(line)?;
     let ins = match op {
         "jmp" => Instruction::Jmp(num),
         "acc" => Instruction::Acc(num),
         "nop" => Instruction::Nop(num),
-        _ => panic!("invalid op code: {op}"),
+        _ => unreachable!(),
     };
-    Ok(("", ins))
+    Ok((rest, ins))
+}
+
+fn parse(data: &str) -> Result<Vec<Instruction>, ParseError> {
+    parsing::lines(data.trim(), instruction)
 }
 
 fn run(instructions: &[Instruction], mut pos: usize) -> (i32, usize) {
@@ -75,11 +80,8 @@ fn find_bug(instructions: &mut [Instruction]) -> i32 {
 }
 
 pub fn main() {
-    let mut instructions = std::fs::read_to_string("data/2020/day8")
-        .unwrap()
-        .lines()
-        .map(|line| parse(line).map(|r| r.1).unwrap())
-        .collect::<Vec<_>>();
+    let data = std::fs::read_to_string("data/2020/day8").unwrap();
+    let mut instructions = parse(&data).unwrap();
 
     // part 1
     let acc = run(&instructions, 0).0;
@@ -89,3 +91,15 @@ pub fn main() {
     let acc = find_bug(&mut instructions);
     println!("day8 part2: {acc}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "nop +0\nacc +1\njmp -4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,14 +1,9 @@
 use std::{collections::BTreeSet, iter::FromIterator};
 
-fn parse() -> Vec<u64> {
-    std::fs::read_to_string("data/2020/day9")
-        .unwrap()
-        .lines()
-        .map(|line| {
-            line.parse::<u64>()
-                .unwrap_or_else(|_| panic!("invalid line: {line}"))
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+
+fn parse(data: &str) -> Result<Vec<u64>, ParseError> {
+    parsing::lines(data.trim(), unsigned)
 }
 
 fn part1(nums: &[u64], preamble: usize) -> u64 {
@@ -42,7 +37,8 @@ fn part2(nums: &[u64], target: u64) -> u64 {
 }
 
 pub fn main() {
-    let nums = parse();
+    let data = std::fs::read_to_string("data/2020/day9").unwrap();
+    let nums = parse(&data).unwrap();
 
     // part 1
     let target = part1(&nums, 25);
@@ -51,3 +47,15 @@ pub fn main() {
     // part 2
     println!("day9 part2: {}", part2(&nums, target));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "35\n20\n15\n25\n47";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,3 +1,10 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::Parser;
+
+fn parse(data: &str) -> Result<Vec<i64>, ParseError> {
+    parsing::lines(data.trim(), unsigned::<u32>.map(i64::from))
+}
+
 fn part1(measurements: &[i64]) -> usize {
     measurements.windows(2).filter(|w| w[1] > w[0]).count()
 }
@@ -7,12 +14,21 @@ fn part2(measurements: &[i64]) -> usize {
 }
 
 pub fn main() {
-    let measurements: Vec<i64> = std::fs::read_to_string("data/2021/day1")
-        .unwrap()
-        .lines()
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+    let data = std::fs::read_to_string("data/2021/day1").unwrap();
+    let measurements = parse(&data).unwrap();
 
     println!("day1 part1: {}", part1(&measurements));
     println!("day1 part2: {}", part2(&measurements));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
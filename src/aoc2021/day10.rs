@@ -1,3 +1,41 @@
+use adventofcode::parsing::{self, ParseError};
+use nom::bytes::complete::is_a;
+
+/// How many chunks are left open at the end of `line`, or `None` if it
+/// closes one with the wrong bracket.
+fn open_chunks(line: &str) -> Option<usize> {
+    let mut open = vec![];
+    for b in line.bytes() {
+        match b {
+            b'(' | b'[' | b'{' | b'<' => open.push(b),
+            _ => {
+                let o = open.pop()?;
+                if !matches!(
+                    (o, b),
+                    (b'(', b')') | (b'[', b']') | (b'{', b'}') | (b'<', b'>')
+                ) {
+                    return None;
+                }
+            }
+        }
+    }
+    Some(open.len())
+}
+
+fn parse(data: &str) -> Result<Vec<&str>, ParseError> {
+    let data = data.trim();
+    parsing::lines(data, is_a("()[]{}<>"))?;
+    let open = data.lines().filter_map(open_chunks).collect::<Vec<_>>();
+    if !open.iter().any(|&n| n > 0) {
+        return Err(ParseError::at(data, data, "an incomplete line"));
+    }
+    // part 2 scores each open chunk as a base 5 digit
+    if let Some(line) = data.lines().find(|&l| open_chunks(l) > Some(27)) {
+        return Err(ParseError::at(data, line, "at most 27 open chunks"));
+    }
+    Ok(data.lines().collect())
+}
+
 fn solve(lines: &[&str]) -> (usize, usize) {
     let mut p1 = 0;
     let mut p2 = vec![];
@@ -67,7 +105,7 @@ fn solve(lines: &[&str]) -> (usize, usize) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day10").unwrap();
-    let lines = data.lines().collect::<Vec<_>>();
+    let lines = parse(&data).unwrap();
     let (p1, p2) = solve(&lines);
     println!("day10 part1: {p1}");
     println!("day10 part2: {p2}");
@@ -76,6 +114,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -92,4 +131,10 @@ mod tests {
         let lines = data.lines().map(|s| s.trim()).collect::<Vec<_>>();
         assert_eq!((26397, 288957), solve(&lines));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "[({(<(())[]>[[{[]{<()<>>\n[(()[<>])]({[<{<<[]>>(\n{([(<{}[<>[]}>{[]{[(<()>\n(((({<>}<{<{<>}{[]{[]{}\n[[<[([]))<([[{}[[()]]]\n[{[{({}]{}}([{[{{{}}([]\n{<[[]]>}<{[{[{[]{()[[[]\n[<(<(<(<{}))><([]([]()\n<{([([[(<>()){}]>(<<{{\n<{([{{}}[<[[[<>{}]]]>[]]";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
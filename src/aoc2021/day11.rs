@@ -1,3 +1,18 @@
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(data, "0123456789")?;
+    // flashes spread recursively
+    if grid.len() > 100 || grid[0].len() > 100 {
+        return Err(ParseError::at(data, data, "at most 100 by 100 octopuses"));
+    }
+    Ok(grid
+        .into_iter()
+        .map(|row| row.iter().map(|e| e - b'0').collect())
+        .collect())
+}
+
 fn step(grid: &mut [Vec<u8>]) -> usize {
     fn flashing(grid: &mut [Vec<u8>], x: usize, y: usize) {
         if grid[y][x] == 10 {
@@ -63,17 +78,20 @@ fn part2(grid: &[Vec<u8>]) -> usize {
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day11").unwrap();
 
-    let grid = data
-        .lines()
-        .map(|s| {
-            s.trim()
-                .as_bytes()
-                .iter()
-                .map(|e| e - b'0')
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+    let grid = parse(&data).unwrap();
 
     println!("day11 part1: {}", part1(&grid));
     println!("day11 part2: {}", part2(&grid));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "5483143223\n2745854711\n5264556173\n6141336146\n6357385478\n4167524645\n2176841721\n6882881134\n4846848554\n5283751526";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,12 +1,21 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
+use nom::{
+    character::complete::{alpha1, char, space0},
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
+
+type Graph<'a> = HashMap<&'a str, Vec<&'a str>>;
 
 fn is_lowercase(s: &str) -> bool {
     s.as_bytes()[0].is_ascii_lowercase()
 }
 
 fn dfs<'a>(
-    g: &HashMap<&'a str, Vec<&'a str>>,
+    g: &Graph<'a>,
     visited: &mut HashSet<&'a str>,
     can_revisit: bool,
     curr: &'a str,
@@ -40,33 +49,39 @@ fn dfs<'a>(
     }
 }
 
-fn part1(g: &HashMap<&str, Vec<&str>>) -> usize {
+fn part1(g: &Graph) -> usize {
     let mut visited = HashSet::new();
     let mut result = 0;
     dfs(g, &mut visited, false, "start", &mut result);
     result
 }
 
-fn part2(g: &HashMap<&str, Vec<&str>>) -> usize {
+fn part2(g: &Graph) -> usize {
     let mut visited = HashSet::new();
     let mut result = 0;
     dfs(g, &mut visited, true, "start", &mut result);
     result
 }
 
+fn parse(data: &str) -> Result<Graph<'_>, ParseError> {
+    // paths could go back and forth between two big caves forever
+    let edge = context(
+        "a small cave at one end",
+        verify(separated_pair(alpha1, char('-'), alpha1), |&(a, b)| {
+            is_lowercase(a) || is_lowercase(b)
+        }),
+    );
+    let mut g: Graph = HashMap::new();
+    for (a, b) in parsing::lines(data.trim(), preceded(space0, edge))? {
+        g.entry(a).or_default().push(b);
+        g.entry(b).or_default().push(a);
+    }
+    Ok(g)
+}
+
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day12").unwrap();
-
-    let edges = data
-        .lines()
-        .map(|s| s.trim().split('-').collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    let mut g: HashMap<&str, Vec<&str>> = HashMap::new();
-    for e in edges {
-        g.entry(e[0]).or_default().push(e[1]);
-        g.entry(e[1]).or_default().push(e[0]);
-    }
+    let g = parse(&data).unwrap();
 
     println!("day12 part1: {}", part1(&g));
     println!("day12 part2: {}", part2(&g));
@@ -75,6 +90,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -85,18 +101,16 @@ mod tests {
         b-d
         A-end
         b-end";
-        let edges = data
-            .lines()
-            .map(|s| s.trim().split('-').collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-
-        let mut g: HashMap<&str, Vec<&str>> = HashMap::new();
-        for e in edges {
-            g.entry(e[0]).or_default().push(e[1]);
-            g.entry(e[1]).or_default().push(e[0]);
-        }
+        let g = parse(data).unwrap();
 
         assert_eq!(10, part1(&g));
         assert_eq!(36, part2(&g));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed =
+            "dc-end\nHN-start\nstart-kj\ndc-start\ndc-HN\nLN-dc\nHN-end\nkj-sa\nkj-HN\nkj-dc";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,4 +1,16 @@
+use adventofcode::{
+    ocr,
+    parsing::{self, ParseError, unsigned},
+};
 use ahash::AHashSet as HashSet;
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{char, one_of, space0},
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
 
 type Points = HashSet<(usize, usize)>;
 
@@ -16,7 +28,7 @@ fn fold(points: Points, (fold_dir, p): (u8, usize)) -> Points {
     }
 }
 
-fn print_grid(points: &Points) {
+fn render(points: &Points) -> String {
     let (width, height) = points
         .iter()
         .fold((0, 0), |(w, h), p| (w.max(p.0), h.max(p.1)));
@@ -24,59 +36,68 @@ fn print_grid(points: &Points) {
     for &(x, y) in points {
         paper[y][x] = true;
     }
-    for row in &paper {
-        for &cell in row {
-            print!("{}", if cell { '█' } else { ' ' });
-        }
-        println!();
-    }
+    paper
+        .iter()
+        .map(|row| row.iter().map(|&c| if c { '#' } else { '.' }).collect())
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
-fn parse(data: &str) -> (Points, Vec<(u8, usize)>) {
-    let mut lines = data.lines();
+fn parse(data: &str) -> Result<(Points, Vec<(u8, usize)>), ParseError> {
+    let [dots, folds] = parsing::split_sections::<2>(data)?;
+    // the folded paper gets rendered
+    let coordinate = || {
+        context(
+            "a coordinate below 4096",
+            verify(unsigned, |&c: &usize| c < 4096),
+        )
+    };
+    let dot = separated_pair(coordinate(), char(','), coordinate());
+    let points = parsing::lines(dots, preceded(space0, dot))?;
+    let axis = one_of("xy").map(|c| c as u8);
+    let fold = preceded(
+        (space0, tag("fold along ")),
+        separated_pair(axis, char('='), unsigned::<usize>),
+    );
+    let fold_lines = parsing::lines(folds, fold)?;
 
-    let mut points = HashSet::new();
-    let mut max_x = 0;
-    let mut max_y = 0;
-    let mut fold_lines = vec![];
-    loop {
-        let line = lines.next().unwrap().trim();
-        if line.is_empty() {
-            break;
+    // a fold has to land every dot on the paper
+    let mut max_x = points.iter().map(|p| p.0).max().unwrap_or(0);
+    let mut max_y = points.iter().map(|p| p.1).max().unwrap_or(0);
+    for (line, &(xy, p)) in folds.lines().zip(&fold_lines) {
+        let max = if xy == b'x' { &mut max_x } else { &mut max_y };
+        if *max > p.saturating_mul(2) {
+            return Err(ParseError::at(
+                data,
+                line.trim_start(),
+                "a fold at least halfway",
+            ));
         }
-        let mut xy = line.split(',');
-        let x = xy.next().unwrap().parse::<usize>().unwrap();
-        max_x = max_x.max(x);
-        let y = xy.next().unwrap().parse::<usize>().unwrap();
-        max_y = max_y.max(y);
-        points.insert((x, y));
+        *max = (*max).min(p);
     }
-    for line in lines {
-        let mut parts = line.trim().split('=');
-        let xy = *parts.next().unwrap().as_bytes().last().unwrap();
-        let pos = parts.next().unwrap().parse::<usize>().unwrap();
-        fold_lines.push((xy, pos));
-    }
-
-    (points, fold_lines)
+    Ok((points.into_iter().collect(), fold_lines))
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day13").unwrap();
-    let (mut points, fold_lines) = parse(&data);
+    let (mut points, fold_lines) = parse(&data).unwrap();
 
     points = fold(points, fold_lines[0]);
     println!("day13 part1: {}", points.len());
     for &line in &fold_lines[1..] {
         points = fold(points, line);
     }
-    println!("day13 part2:");
-    print_grid(&points);
+    let img = render(&points);
+    match ocr::recognize_str(&img) {
+        Ok(text) => println!("day13 part2: {text}"),
+        Err(e) => println!("day13 part2: {e}\n{img}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -101,13 +122,17 @@ mod tests {
 
         fold along y=7
         fold along x=5";
-        let (mut points, fold_lines) = parse(data);
-        print_grid(&points);
+        let (mut points, fold_lines) = parse(data).unwrap();
         points = fold(points, fold_lines[0]);
         assert_eq!(17, points.len());
-        print_grid(&points);
         points = fold(points, fold_lines[1]);
         assert_eq!(16, points.len());
-        print_grid(&points);
+        assert_eq!("#####\n#...#\n#...#\n#...#\n#####", render(&points));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "6,10\n0,14\n9,10\n\nfold along y=7\nfold along x=5";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,24 +1,41 @@
+use adventofcode::parsing::{self, PResult, ParseError};
 use ahash::AHashMap as HashMap;
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{satisfy, space0},
+    error::context,
+    multi::many1,
+    sequence::{preceded, separated_pair},
+};
 
 type Input = (u8, u8, HashMap<[u8; 2], usize>, HashMap<[u8; 2], u8>);
-fn parse(data: &str) -> Input {
-    let mut lines = data.lines();
-    let template = lines.next().unwrap().trim().as_bytes();
-    lines.next();
-    let mut rules = HashMap::new();
-    for line in lines {
-        let mut parts = line.trim().split(" -> ");
-        let ab = parts.next().unwrap().as_bytes();
-        let c = parts.next().unwrap().as_bytes()[0];
-        rules.insert([ab[0], ab[1]], c);
-    }
+
+/// Elements are counted in an array indexed by letter.
+fn element(input: &str) -> PResult<'_, u8> {
+    context(
+        "an element from A to Z",
+        satisfy(|c| c.is_ascii_uppercase()),
+    )
+    .map(|c| c as u8)
+    .parse(input)
+}
+
+fn parse(data: &str) -> Result<Input, ParseError> {
+    let [template, rules] = parsing::split_sections::<2>(data)?;
+    let template = parsing::parse_all(template.trim(), many1(element))?;
+    let rule = separated_pair((element, element), tag(" -> "), element);
+    let rules = parsing::lines(rules, preceded(space0, rule))?
+        .into_iter()
+        .map(|((a, b), c)| ([a, b], c))
+        .collect();
     let first = template[0];
     let last = *template.last().unwrap();
     let mut pairs: HashMap<[u8; 2], usize> = HashMap::new();
     for w in template.windows(2) {
         *pairs.entry([w[0], w[1]]).or_default() += 1;
     }
-    (first, last, pairs, rules)
+    Ok((first, last, pairs, rules))
 }
 
 fn polymerization(
@@ -61,7 +78,7 @@ fn polymerization(
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day14").unwrap();
-    let (first, last, pairs, rules) = parse(&data);
+    let (first, last, pairs, rules) = parse(&data).unwrap();
     println!(
         "day14 part1: {}",
         polymerization(first, last, pairs.clone(), &rules, 10)
@@ -75,6 +92,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -96,11 +114,17 @@ mod tests {
         BC -> B
         CC -> N
         CN -> C";
-        let (first, last, pairs, rules) = parse(data);
+        let (first, last, pairs, rules) = parse(data).unwrap();
         assert_eq!(1588, polymerization(first, last, pairs.clone(), &rules, 10));
         assert_eq!(
             2188189693529,
             polymerization(first, last, pairs, &rules, 40)
         );
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "NNCB\n\nCH -> B\nHH -> N\nNN -> C";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,79 +1,30 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use adventofcode::{
+    parsing::{self, ParseError},
+    search,
+};
 
-fn parse(data: &str) -> Vec<Vec<u8>> {
-    data.lines()
-        .map(|line| line.trim().as_bytes().iter().map(|b| b - b'0').collect())
-        .collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let map = parsing::grid_of(data.trim(), "123456789")?;
+    Ok(map
+        .into_iter()
+        .map(|row| row.iter().map(|b| b - b'0').collect())
+        .collect())
 }
 
-fn dijkstra(risk_map: &[Vec<u8>]) -> i32 {
+fn lowest_risk(risk_map: &[Vec<u8>]) -> i32 {
     let m = risk_map.len() as i32;
     let n = risk_map[0].len() as i32;
-    let target = (m - 1, n - 1);
-    let mut costs = vec![vec![i32::MAX; n as usize]; m as usize];
-    let mut q: BinaryHeap<(Reverse<i32>, (i32, i32))> = BinaryHeap::new();
-    q.push((Reverse(0), (0, 0)));
-    while let Some((c, pos)) = q.pop() {
-        if pos == target {
-            return c.0;
-        }
-        if c.0 > costs[pos.1 as usize][pos.0 as usize] {
-            continue;
-        }
-        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let (xn, yn) = (pos.0 + dx, pos.1 + dy);
-            if xn < 0 || yn < 0 || xn >= n || yn >= m {
-                continue;
-            }
-            let cn = c.0 + risk_map[yn as usize][xn as usize] as i32;
-            if cn < costs[yn as usize][xn as usize] {
-                costs[yn as usize][xn as usize] = cn;
-                q.push((Reverse(cn), (xn, yn)));
-            }
-        }
-    }
-    unreachable!()
-}
-
-#[allow(dead_code)]
-fn astar(risk_map: &[Vec<u8>]) -> i32 {
-    let m = risk_map.len() as i32;
-    let n = risk_map[0].len() as i32;
-    let target = (m - 1, n - 1);
-
-    let heuristic = |(x, y): (i32, i32)| (target.0 - x) + (target.1 - y);
-
-    let mut gs = vec![vec![i32::MAX; n as usize]; m as usize];
-    let mut fs = vec![vec![i32::MAX; n as usize]; m as usize];
-    let mut q: BinaryHeap<(Reverse<i32>, (i32, i32))> = BinaryHeap::new();
-
-    gs[0][0] = 0;
-    fs[0][0] = 0;
-    q.push((Reverse(0), (0, 0)));
-
-    while let Some((f, pos)) = q.pop() {
-        if pos == target {
-            return f.0;
-        }
-        if f.0 > fs[pos.1 as usize][pos.0 as usize] {
-            continue;
-        }
-        let g = gs[pos.1 as usize][pos.0 as usize];
-        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let (xn, yn) = (pos.0 + dx, pos.1 + dy);
-            if xn < 0 || yn < 0 || xn >= n || yn >= m {
-                continue;
-            }
-            let g_new = g + risk_map[yn as usize][xn as usize] as i32;
-            if g_new < gs[yn as usize][xn as usize] {
-                gs[yn as usize][xn as usize] = g_new;
-                let f_new = g_new + heuristic((xn, yn));
-                fs[yn as usize][xn as usize] = f_new;
-                q.push((Reverse(f_new), (xn, yn)));
-            }
-        }
-    }
-    unreachable!()
+    let target = (n - 1, m - 1);
+    let neighbors = |&(x, y): &(i32, i32)| {
+        [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(|&(xn, yn)| xn >= 0 && yn >= 0 && xn < n && yn < m)
+            .map(|(xn, yn)| ((xn, yn), risk_map[yn as usize][xn as usize] as i32))
+    };
+    let heuristic = |&(x, y): &(i32, i32)| (target.0 - x) + (target.1 - y);
+    search::astar((0, 0), neighbors, heuristic, |&pos| pos == target)
+        .unwrap()
+        .1
 }
 
 fn large_map(risk_map: &[Vec<u8>]) -> Vec<Vec<u8>> {
@@ -96,14 +47,15 @@ fn large_map(risk_map: &[Vec<u8>]) -> Vec<Vec<u8>> {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day15").unwrap();
-    let risk_map = parse(&data);
-    println!("day15 part1: {}", dijkstra(&risk_map));
-    println!("day15 part2: {}", dijkstra(&large_map(&risk_map)));
+    let risk_map = parse(&data).unwrap();
+    println!("day15 part1: {}", lowest_risk(&risk_map));
+    println!("day15 part2: {}", lowest_risk(&large_map(&risk_map)));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn generate_map() {
@@ -118,4 +70,27 @@ mod tests {
         ];
         assert_eq!(expected, large);
     }
+
+    #[test]
+    fn case1() {
+        let data = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+        let risk_map = parse(data).unwrap();
+        assert_eq!(40, lowest_risk(&risk_map));
+        assert_eq!(315, lowest_risk(&large_map(&risk_map)));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "116\n138\n213";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,16 +1,18 @@
-#[derive(Debug, PartialEq)]
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
 struct Header {
     version: u8,
     type_id: u8,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 enum Body {
     Literal(i64),
     Operator(Vec<Packet>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 struct Packet {
     header: Header,
     body: Body,
@@ -26,33 +28,162 @@ impl Packet {
         }
     }
 
-    fn value(&self) -> i64 {
+    /// The packet's value, or `None` if a sum or product overflows.
+    fn value(&self) -> Option<i64> {
+        let sub_packets = match &self.body {
+            Body::Literal(v) => return Some(*v),
+            Body::Operator(sub_packets) => sub_packets,
+        };
+        let values = sub_packets
+            .iter()
+            .map(|pkt| pkt.value())
+            .collect::<Option<Vec<_>>>()?;
+        Some(match self.header.type_id {
+            0 => values.iter().try_fold(0i64, |sum, &v| sum.checked_add(v))?,
+            1 => values
+                .iter()
+                .try_fold(1i64, |product, &v| product.checked_mul(v))?,
+            2 => *values.iter().min().expect("read_packet checks arity"),
+            3 => *values.iter().max().expect("read_packet checks arity"),
+            5 => (values[0] > values[1]).into(),
+            6 => (values[0] < values[1]).into(),
+            7 => (values[0] == values[1]).into(),
+            _ => unreachable!(),
+        })
+    }
+}
+
+/// How an operator packet says where its sub-packets end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LengthType {
+    /// Total length in bits, in 15 bits.
+    Bits,
+    /// Number of sub-packets, in 11 bits.
+    Count,
+}
+
+impl Packet {
+    fn name(&self) -> &'static str {
+        match self.header.type_id {
+            0 => "sum",
+            1 => "product",
+            2 => "min",
+            3 => "max",
+            5 => "gt",
+            6 => "lt",
+            7 => "eq",
+            _ => "lit",
+        }
+    }
+
+    /// Hex transmission of the packet, zero-padded to whole hex digits.
+    /// Operators use `length` where their sub-packets fit in it.
+    fn to_hex(&self, length: LengthType) -> String {
+        let mut bits = BitWriter::default();
+        self.write(&mut bits, length);
+        bits.to_hex()
+    }
+
+    fn write(&self, bits: &mut BitWriter, length: LengthType) {
+        bits.write(self.header.version as u64, 3);
+        bits.write(self.header.type_id as u64, 3);
         match &self.body {
-            Body::Literal(v) => *v,
-            Body::Operator(sub_packets) => match self.header.type_id {
-                0 => sub_packets.iter().map(|pkt| pkt.value()).sum(),
-                1 => sub_packets.iter().map(|pkt| pkt.value()).product(),
-                2 => sub_packets
-                    .iter()
-                    .map(|pkt| pkt.value())
-                    .min()
-                    .expect("empty sub-packets for Minimum operator"),
-                3 => sub_packets
-                    .iter()
-                    .map(|pkt| pkt.value())
-                    .max()
-                    .expect("empty sub-packets for Maximum operator"),
-                5 => (sub_packets[0].value() > sub_packets[1].value()).into(),
-                6 => (sub_packets[0].value() < sub_packets[1].value()).into(),
-                7 => (sub_packets[0].value() == sub_packets[1].value()).into(),
-                _ => unreachable!(),
-            },
+            Body::Literal(v) => {
+                let groups = (64 - v.leading_zeros()).div_ceil(4).max(1);
+                for g in (0..groups).rev() {
+                    bits.write((g > 0) as u64, 1);
+                    bits.write((*v as u64 >> (4 * g)) & 0xf, 4);
+                }
+            }
+            Body::Operator(sub_packets) => {
+                let mut inner = BitWriter::default();
+                for p in sub_packets {
+                    p.write(&mut inner, length);
+                }
+                let by_bits = inner.len < 1 << 15;
+                let by_count = sub_packets.len() < 1 << 11;
+                if by_bits && (length == LengthType::Bits || !by_count) {
+                    bits.write(0, 1);
+                    bits.write(inner.len as u64, 15);
+                } else {
+                    assert!(by_count, "too many sub-packets for either length type");
+                    bits.write(1, 1);
+                    bits.write(sub_packets.len() as u64, 11);
+                }
+                bits.append(&inner);
+            }
         }
     }
 }
 
+/// Literals as numbers, operators as `(name sub-packets...)`. The alternate
+/// form `{:#}` prefixes every packet with its version, like `v6:2021`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = if f.alternate() {
+            format!("v{}:", self.header.version)
+        } else {
+            String::new()
+        };
+        match &self.body {
+            Body::Literal(v) => write!(f, "{version}{v}"),
+            Body::Operator(sub_packets) => {
+                write!(f, "({version}{}", self.name())?;
+                for p in sub_packets {
+                    if f.alternate() {
+                        write!(f, " {p:#}")?;
+                    } else {
+                        write!(f, " {p}")?;
+                    }
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BitWriter {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitWriter {
+    /// Appends the low `n` bits of `value`, most significant first.
+    fn write(&mut self, value: u64, n: usize) {
+        for i in (0..n).rev() {
+            if self.len.is_multiple_of(64) {
+                self.words.push(0);
+            }
+            let bit = value >> i & 1;
+            *self.words.last_mut().unwrap() |= bit << (63 - self.len % 64);
+            self.len += 1;
+        }
+    }
+
+    fn append(&mut self, other: &BitWriter) {
+        for (i, &w) in other.words.iter().enumerate() {
+            let n = (other.len - i * 64).min(64);
+            self.write(w >> (64 - n), n);
+        }
+    }
+
+    fn to_hex(&self) -> String {
+        (0..self.len.div_ceil(4))
+            .map(|i| {
+                let nibble = self.words[i / 16] >> (60 - 4 * (i % 16)) & 0xf;
+                char::from_digit(nibble as u32, 16)
+                    .unwrap()
+                    .to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
+
+/// Bits packed into words, most significant first.
 struct BITSTransmission {
-    data: Vec<bool>,
+    words: Vec<u64>,
+    len: usize,
     idx: usize,
 }
 
@@ -60,35 +191,67 @@ struct BITSTransmission {
 enum BITSReadError {
     ExceededMaximumReadSize,
     NotEnoughBits,
+    InvalidHexDigit { position: usize, byte: u8 },
+    LiteralOverflow,
+    WrongArity { type_id: u8, sub_packets: usize },
+    ValueOverflow,
 }
 
-impl BITSTransmission {
-    fn new(bin: &[u8]) -> Self {
-        let mut data = Vec::with_capacity(bin.len() * 8);
-        for b in bin {
-            let b = (*b as char).to_digit(16).expect("invalid hexadecimal byte") as u8;
-            let mut mask = 1u8 << 3;
-            while mask > 0 {
-                data.push(mask & b > 0);
-                mask >>= 1;
+impl fmt::Display for BITSReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BITSReadError::ExceededMaximumReadSize => write!(f, "reads are 64 bits at most"),
+            BITSReadError::NotEnoughBits => write!(f, "transmission ends mid-packet"),
+            BITSReadError::InvalidHexDigit { position, byte } => {
+                write!(f, "invalid hex digit {:?} at {position}", *byte as char)
             }
+            BITSReadError::LiteralOverflow => write!(f, "literal doesn't fit in 63 bits"),
+            BITSReadError::WrongArity {
+                type_id,
+                sub_packets,
+            } => write!(f, "type {type_id} operator with {sub_packets} sub-packets"),
+            BITSReadError::ValueOverflow => write!(f, "value doesn't fit in 64 bits"),
         }
-        Self { data, idx: 0 }
     }
+}
 
-    fn read(&mut self, n: usize) -> Result<u16, BITSReadError> {
-        if n > 15 {
-            Err(BITSReadError::ExceededMaximumReadSize)
-        } else if n + self.idx <= self.data.len() {
-            let mut result = 0;
-            for &bit in &self.data[self.idx..self.idx + n] {
-                result = (result << 1) + u16::from(bit);
-            }
-            self.idx += n;
-            Ok(result)
-        } else {
-            Err(BITSReadError::NotEnoughBits)
+impl BITSTransmission {
+    fn new(bin: &[u8]) -> Result<Self, BITSReadError> {
+        let mut words = vec![0; bin.len().div_ceil(16)];
+        for (i, &b) in bin.iter().enumerate() {
+            let digit = (b as char)
+                .to_digit(16)
+                .ok_or(BITSReadError::InvalidHexDigit {
+                    position: i,
+                    byte: b,
+                })?;
+            words[i / 16] |= (digit as u64) << (60 - 4 * (i % 16));
         }
+        Ok(Self {
+            words,
+            len: bin.len() * 4,
+            idx: 0,
+        })
+    }
+
+    fn read(&mut self, n: usize) -> Result<u64, BITSReadError> {
+        if n > 64 {
+            return Err(BITSReadError::ExceededMaximumReadSize);
+        }
+        if n + self.idx > self.len {
+            return Err(BITSReadError::NotEnoughBits);
+        }
+        if n == 0 {
+            return Ok(0);
+        }
+        // the bits may straddle two words
+        let (w, offset) = (self.idx / 64, self.idx % 64);
+        let mut window = (self.words[w] as u128) << 64;
+        if let Some(&next) = self.words.get(w + 1) {
+            window |= next as u128;
+        }
+        self.idx += n;
+        Ok((window << offset >> (128 - n)) as u64)
     }
 
     fn read_packet(&mut self) -> Result<Packet, BITSReadError> {
@@ -101,6 +264,9 @@ impl BITSTransmission {
                 loop {
                     let prefix = self.read(1)?;
                     let group = self.read(4)? as i64;
+                    if value >> 59 != 0 {
+                        return Err(BITSReadError::LiteralOverflow);
+                    }
                     value = (value << 4) + group;
                     if prefix == 0 {
                         break;
@@ -115,12 +281,14 @@ impl BITSTransmission {
                 let len_type = self.read(1)?;
                 let sub_packets = match len_type {
                     0 => {
-                        let mut len = self.read(15)? as usize;
+                        let len = self.read(15)? as usize;
+                        let end = self.idx + len;
                         let mut sub_pkts = vec![];
-                        while len > 0 {
-                            let curr = self.idx;
+                        while self.idx < end {
                             sub_pkts.push(self.read_packet()?);
-                            len -= self.idx - curr;
+                        }
+                        if self.idx > end {
+                            return Err(BITSReadError::NotEnoughBits);
                         }
                         sub_pkts
                     }
@@ -131,6 +299,18 @@ impl BITSTransmission {
                         sub_pkts?
                     }
                 };
+                // min and max need an operand, comparisons exactly two
+                let arity_ok = match type_id {
+                    2 | 3 => !sub_packets.is_empty(),
+                    5..=7 => sub_packets.len() == 2,
+                    _ => true,
+                };
+                if !arity_ok {
+                    return Err(BITSReadError::WrongArity {
+                        type_id,
+                        sub_packets: sub_packets.len(),
+                    });
+                }
                 Ok(Packet {
                     header,
                     body: Body::Operator(sub_packets),
@@ -140,30 +320,65 @@ impl BITSTransmission {
     }
 }
 
-fn solve(data: &[u8]) -> (i64, i64) {
-    let mut bits = BITSTransmission::new(data);
-    if let Ok(pkt) = bits.read_packet() {
-        (pkt.versions(), pkt.value())
-    } else {
-        unreachable!()
-    }
+fn solve(data: &[u8]) -> Result<(i64, i64), BITSReadError> {
+    let pkt = BITSTransmission::new(data)?.read_packet()?;
+    let value = pkt.value().ok_or(BITSReadError::ValueOverflow)?;
+    Ok((pkt.versions(), value))
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day16").unwrap();
-    let (p1, p2) = solve(data.trim().as_bytes());
-    println!("day16 part1: {p1}");
-    println!("day16 part2: {p2}");
+    // `2021 16 --tree` shows the packets, and their shortest encoding
+    if std::env::args().any(|a| a == "--tree") {
+        match BITSTransmission::new(data.trim().as_bytes()).and_then(|mut b| b.read_packet()) {
+            Ok(pkt) => println!("{pkt:#}\n{}", pkt.to_hex(LengthType::Count)),
+            Err(e) => println!("day16: {e}"),
+        }
+    }
+    match solve(data.trim().as_bytes()) {
+        Ok((p1, p2)) => {
+            println!("day16 part1: {p1}");
+            println!("day16 part2: {p2}");
+        }
+        Err(e) => println!("day16: {e}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use rand::prelude::*;
+
+    fn random_packet(rng: &mut StdRng, depth: usize) -> Packet {
+        let version = rng.random_range(0..8);
+        let type_id = if depth == 0 {
+            4
+        } else {
+            rng.random_range(0..8)
+        };
+        let body = match type_id {
+            4 => Body::Literal(match rng.random_range(0..3) {
+                0 => rng.random_range(0..16),
+                1 => rng.random_range(0..1 << 20),
+                _ => rng.random_range(0..i64::MAX),
+            }),
+            5..=7 => Body::Operator((0..2).map(|_| random_packet(rng, depth - 1)).collect()),
+            _ => {
+                let n = rng.random_range(1..5);
+                Body::Operator((0..n).map(|_| random_packet(rng, depth - 1)).collect())
+            }
+        };
+        Packet {
+            header: Header { version, type_id },
+            body,
+        }
+    }
 
     #[test]
     fn case1() {
         let data = "D2FE28";
-        let mut bits = BITSTransmission::new(data.as_bytes());
+        let mut bits = BITSTransmission::new(data.as_bytes()).unwrap();
         let pkt = bits.read_packet();
         assert_eq!(
             Ok(Packet {
@@ -180,7 +395,7 @@ mod tests {
     #[test]
     fn case2() {
         let data = "38006F45291200";
-        let mut bits = BITSTransmission::new(data.as_bytes());
+        let mut bits = BITSTransmission::new(data.as_bytes()).unwrap();
         let pkt = bits.read_packet();
         assert_eq!(
             Ok(Packet {
@@ -212,72 +427,178 @@ mod tests {
     #[test]
     fn case3() {
         let data = "8A004A801A8002F478";
-        assert_eq!(16, solve(data.as_bytes()).0);
+        assert_eq!(16, solve(data.as_bytes()).unwrap().0);
     }
 
     #[test]
     fn case4() {
         let data = "620080001611562C8802118E34";
-        assert_eq!(12, solve(data.as_bytes()).0);
+        assert_eq!(12, solve(data.as_bytes()).unwrap().0);
     }
 
     #[test]
     fn case5() {
         let data = "C0015000016115A2E0802F182340";
-        assert_eq!(23, solve(data.as_bytes()).0);
+        assert_eq!(23, solve(data.as_bytes()).unwrap().0);
     }
 
     #[test]
     fn case6() {
         let data = "A0016C880162017C3686B18A3D4780";
-        assert_eq!(31, solve(data.as_bytes()).0);
+        assert_eq!(31, solve(data.as_bytes()).unwrap().0);
     }
 
     #[test]
     fn case7() {
         let data = "C200B40A82";
-        assert_eq!(3, solve(data.as_bytes()).1);
+        assert_eq!(3, solve(data.as_bytes()).unwrap().1);
     }
 
     #[test]
     fn case8() {
         let data = "04005AC33890";
-        assert_eq!(54, solve(data.as_bytes()).1);
+        assert_eq!(54, solve(data.as_bytes()).unwrap().1);
     }
 
     #[test]
     fn case9() {
         let data = "880086C3E88112";
-        assert_eq!(7, solve(data.as_bytes()).1);
+        assert_eq!(7, solve(data.as_bytes()).unwrap().1);
     }
 
     #[test]
     fn case10() {
         let data = "CE00C43D881120";
-        assert_eq!(9, solve(data.as_bytes()).1);
+        assert_eq!(9, solve(data.as_bytes()).unwrap().1);
     }
 
     #[test]
     fn case11() {
         let data = "D8005AC2A8F0";
-        assert_eq!(1, solve(data.as_bytes()).1);
+        assert_eq!(1, solve(data.as_bytes()).unwrap().1);
     }
 
     #[test]
     fn case12() {
         let data = "F600BC2D8F";
-        assert_eq!(0, solve(data.as_bytes()).1);
+        assert_eq!(0, solve(data.as_bytes()).unwrap().1);
     }
 
     #[test]
     fn case13() {
         let data = "9C005AC2F8F0";
-        assert_eq!(0, solve(data.as_bytes()).1);
+        assert_eq!(0, solve(data.as_bytes()).unwrap().1);
     }
 
     #[test]
     fn case14() {
         let data = "9C0141080250320F1802104A08";
-        assert_eq!(1, solve(data.as_bytes()).1);
+        assert_eq!(1, solve(data.as_bytes()).unwrap().1);
+    }
+
+    #[test]
+    fn encode() {
+        for data in ["D2FE28", "38006F45291200", "EE00D40C823060"] {
+            let pkt = BITSTransmission::new(data.as_bytes())
+                .unwrap()
+                .read_packet()
+                .unwrap();
+            let length = if data.starts_with("EE") {
+                LengthType::Count
+            } else {
+                LengthType::Bits
+            };
+            // the examples pad to whole bytes
+            assert!(data.starts_with(&pkt.to_hex(length)));
+        }
+    }
+
+    #[test]
+    fn s_expression() {
+        let pkt = BITSTransmission::new(b"9C0141080250320F1802104A08")
+            .unwrap()
+            .read_packet()
+            .unwrap();
+        assert_eq!("(eq (sum 1 3) (product 2 2))", pkt.to_string());
+        let pkt = BITSTransmission::new(b"D2FE28")
+            .unwrap()
+            .read_packet()
+            .unwrap();
+        assert_eq!("v6:2021", format!("{pkt:#}"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Some(BITSReadError::InvalidHexDigit {
+                position: 2,
+                byte: b'x'
+            }),
+            BITSTransmission::new(b"D2xE28").err()
+        );
+        assert_eq!(Err(BITSReadError::NotEnoughBits), solve(b"D2FE"));
+        let mut bits = BITSTransmission::new(b"FFFFFFFFFFFFFFFFFF").unwrap();
+        assert_eq!(Err(BITSReadError::ExceededMaximumReadSize), bits.read(65));
+        assert_eq!(Ok(1), bits.read(1));
+        assert_eq!(Ok(u64::MAX), bits.read(64));
+
+        let literal = Packet {
+            header: Header {
+                version: 0,
+                type_id: 4,
+            },
+            body: Body::Literal(1),
+        };
+        for (type_id, sub_packets) in [(5, 1), (7, 3), (2, 0), (3, 0)] {
+            let pkt = Packet {
+                header: Header {
+                    version: 0,
+                    type_id,
+                },
+                body: Body::Operator(vec![literal.clone(); sub_packets]),
+            };
+            let hex = pkt.to_hex(LengthType::Count);
+            assert_eq!(
+                Err(BITSReadError::WrongArity {
+                    type_id,
+                    sub_packets
+                }),
+                solve(hex.as_bytes())
+            );
+        }
+
+        let big = Packet {
+            body: Body::Literal(i64::MAX),
+            ..literal.clone()
+        };
+        let product = Packet {
+            header: Header {
+                version: 0,
+                type_id: 1,
+            },
+            body: Body::Operator(vec![big.clone(), big]),
+        };
+        let hex = product.to_hex(LengthType::Count);
+        assert_eq!(Err(BITSReadError::ValueOverflow), solve(hex.as_bytes()));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "9C0141080250320F1802104A08";
+        fuzz::check(&[seed], |s| solve(s.trim().as_bytes()).map(drop));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..300 {
+            let depth = rng.random_range(0..5);
+            let pkt = random_packet(&mut rng, depth);
+            for length in [LengthType::Bits, LengthType::Count] {
+                let hex = pkt.to_hex(length);
+                let mut bits = BITSTransmission::new(hex.as_bytes()).unwrap();
+                assert_eq!(Ok(&pkt), bits.read_packet().as_ref(), "{pkt}");
+            }
+        }
     }
 }
@@ -1,14 +1,29 @@
-fn parse(data: &str) -> Vec<i64> {
-    let (_, coords) = data.trim().split_once(": ").unwrap();
-    let (x, y) = coords.split_once(", ").unwrap();
-    [x, y]
-        .iter()
-        .flat_map(|s| {
-            let (_, range) = s.split_once('=').unwrap();
-            let (low, high) = range.split_once("..").unwrap();
-            [low.parse().unwrap(), high.parse().unwrap()]
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, signed};
+use nom::{
+    bytes::complete::tag,
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
+
+/// The search tries every velocity that reaches the target in one step, so
+/// the target has to be right of and below the probe, and not too far.
+fn parse(data: &str) -> Result<Vec<i64>, ParseError> {
+    let range = || separated_pair(signed::<i64>, tag(".."), signed::<i64>);
+    let x = verify(range(), |&(lo, hi)| 0 < lo && lo <= hi && hi <= 1000);
+    let y = verify(range(), |&(lo, hi)| -1000 <= lo && lo <= hi && hi < 0);
+    let ((x1, x2), (y1, y2)) = parsing::parse_all(
+        data.trim(),
+        preceded(
+            tag("target area: "),
+            separated_pair(
+                preceded(tag("x="), context("x from 1 to 1000", x)),
+                tag(", "),
+                preceded(tag("y="), context("y from -1000 to -1", y)),
+            ),
+        ),
+    )?;
+    Ok(vec![x1, x2, y1, y2])
 }
 
 fn shoot(mut vx: i64, mut vy: i64, limits: &[i64]) -> (bool, i64) {
@@ -50,7 +65,7 @@ fn solve(limits: &[i64]) -> (i64, usize) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day17").unwrap();
-    let limits = parse(&data);
+    let limits = parse(&data).unwrap();
     let (p1, p2) = solve(&limits);
     println!("day17 part1: {p1}");
     println!("day17 part2: {p2}");
@@ -59,13 +74,20 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "target area: x=20..30, y=-10..-5";
-        let limits = parse(data);
+        let limits = parse(data).unwrap();
         let (p1, p2) = solve(&limits);
         assert_eq!(45, p1);
         assert_eq!(112, p2);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "target area: x=20..30, y=-10..-5";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
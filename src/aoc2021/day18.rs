@@ -1,238 +1,612 @@
-use std::{fmt::Display, ops::Add, str::FromStr};
+use std::{fmt, ops::Add, str::FromStr};
 
-#[derive(Debug, Clone, PartialEq)]
-enum Number {
-    Value(u8),
-    Nested(Box<Pair>),
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Leaf {
+    value: u16,
+    depth: u8,
+}
+
+/// Regular numbers in order with their depths, which pins down the tree.
+/// Reduction never leaves a pair nested deeper than five, so there are at
+/// most 32 of them.
+#[derive(Clone, Copy)]
+struct Number {
+    leaves: [Leaf; 32],
+    len: usize,
 }
 
 impl Number {
+    fn leaves(&self) -> &[Leaf] {
+        &self.leaves[..self.len]
+    }
+
+    fn push(&mut self, leaf: Leaf) {
+        self.leaves[self.len] = leaf;
+        self.len += 1;
+    }
+
     fn magnitude(&self) -> i64 {
-        match self {
-            Number::Value(v) => *v as i64,
-            Number::Nested(p) => 3 * p.left.magnitude() + 2 * p.right.magnitude(),
+        // fold sibling leaves from the left, like a shift-reduce parser
+        let mut stack: Vec<(i64, u8)> = Vec::with_capacity(6);
+        for leaf in self.leaves() {
+            let mut top = (leaf.value as i64, leaf.depth);
+            while let Some(&(left, depth)) = stack.last()
+                && depth == top.1
+            {
+                stack.pop();
+                top = (3 * left + 2 * top.0, depth - 1);
+            }
+            stack.push(top);
         }
+        stack[0].0
     }
 
     fn reduce(&mut self) {
-        loop {
-            if self.explode(0).is_some() || self.split() {
-                continue;
-            } else {
-                break;
-            }
-        }
+        while self.explode() || self.split() {}
     }
 
-    fn explode(&mut self, level: usize) -> Option<(Option<u8>, Option<u8>)> {
-        match self {
-            Number::Value(_) => None,
-            Number::Nested(p) => p.explode(level),
+    /// With nothing deeper than five, the first leaf at that depth starts a
+    /// pair of two regular numbers.
+    fn explode(&mut self) -> bool {
+        let Some(i) = self.leaves().iter().position(|l| l.depth > 4) else {
+            return false;
+        };
+        let (a, b) = (self.leaves[i].value, self.leaves[i + 1].value);
+        if i > 0 {
+            self.leaves[i - 1].value += a;
+        }
+        if i + 2 < self.len {
+            self.leaves[i + 2].value += b;
         }
+        self.leaves[i] = Leaf {
+            value: 0,
+            depth: self.leaves[i].depth - 1,
+        };
+        self.leaves.copy_within(i + 2..self.len, i + 1);
+        self.len -= 1;
+        true
     }
 
     fn split(&mut self) -> bool {
-        match self {
-            Number::Value(_) => false,
-            Number::Nested(p) => p.split(),
-        }
+        let Some(i) = self.leaves().iter().position(|l| l.value >= 10) else {
+            return false;
+        };
+        let Leaf { value, depth } = self.leaves[i];
+        self.leaves.copy_within(i + 1..self.len, i + 2);
+        self.len += 1;
+        self.leaves[i] = Leaf {
+            value: value / 2,
+            depth: depth + 1,
+        };
+        self.leaves[i + 1] = Leaf {
+            value: value - value / 2,
+            depth: depth + 1,
+        };
+        true
     }
 }
 
 impl Add for Number {
     type Output = Self;
 
+    /// Both sides must be reduced, so they have 16 leaves at most.
     fn add(self, rhs: Self) -> Self::Output {
-        let mut n = Number::Nested(Box::new(Pair {
-            left: self,
-            right: rhs,
-        }));
+        let mut n = Number {
+            leaves: [Leaf::default(); 32],
+            len: 0,
+        };
+        for &leaf in self.leaves().iter().chain(rhs.leaves()) {
+            n.push(Leaf {
+                depth: leaf.depth + 1,
+                ..leaf
+            });
+        }
         n.reduce();
         n
     }
 }
 
-#[derive(Debug)]
-struct InvalidSnailfishNumber;
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParseError {
+    position: usize,
+    expected: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} at column {}",
+            self.expected,
+            self.position + 1
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
 
 impl FromStr for Number {
-    type Err = InvalidSnailfishNumber;
+    type Err = ParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        fn recursive(line: &str) -> (Number, usize) {
-            let s = line.as_bytes();
-            let (left, size_l) = if s[1] == b'[' {
-                recursive(&line[1..])
-            } else {
-                (Number::Value(s[1] - b'0'), 1)
-            };
-            if s[size_l + 1] == b']' {
-                (left, size_l)
-            } else {
-                let (right, size_r) = recursive(&line[size_l + 1..]);
-                (
-                    Number::Nested(Box::new(Pair { left, right })),
-                    size_l + size_r + 3,
-                )
-            }
-        }
-        Ok(recursive(line).0)
+        fn element(s: &[u8], pos: &mut usize, depth: u8, n: &mut Number) -> Result<(), ParseError> {
+            let error = |position, expected| Err(ParseError { position, expected });
+            match s.get(*pos) {
+                Some(b'[') if depth == 5 => error(*pos, "a regular number at depth 5"),
+                Some(b'[') => {
+                    *pos += 1;
+                    element(s, pos, depth + 1, n)?;
+                    if s.get(*pos) != Some(&b',') {
+                        return error(*pos, "','");
+                    }
+                    *pos += 1;
+                    element(s, pos, depth + 1, n)?;
+                    if s.get(*pos) != Some(&b']') {
+                        return error(*pos, "']'");
+                    }
+                    *pos += 1;
+                    Ok(())
+                }
+                Some(c) if c.is_ascii_digit() => {
+                    let start = *pos;
+                    while s.get(*pos).is_some_and(u8::is_ascii_digit) {
+                        *pos += 1;
+                    }
+                    let digits = std::str::from_utf8(&s[start..*pos]).unwrap();
+                    let Ok(value) = digits.parse::<u8>() else {
+                        return error(start, "a regular number below 256");
+                    };
+                    n.push(Leaf {
+                        value: value.into(),
+                        depth,
+                    });
+                    Ok(())
+                }
+                _ => error(*pos, "'[' or a digit"),
+            }
+        }
+
+        let mut n = Number {
+            leaves: [Leaf::default(); 32],
+            len: 0,
+        };
+        let mut pos = 0;
+        element(line.as_bytes(), &mut pos, 0, &mut n)?;
+        if pos < line.len() {
+            return Err(ParseError {
+                position: pos,
+                expected: "end of line",
+            });
+        }
+        Ok(n)
     }
 }
 
-impl Display for Number {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Number::Value(n) => write!(f, "{n}"),
-            Number::Nested(p) => write!(f, "[{},{}]", p.left, p.right),
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn element(
+            leaves: &[Leaf],
+            i: &mut usize,
+            depth: u8,
+            f: &mut fmt::Formatter<'_>,
+        ) -> fmt::Result {
+            let leaf = leaves[*i];
+            if leaf.depth == depth {
+                *i += 1;
+                return write!(f, "{}", leaf.value);
+            }
+            write!(f, "[")?;
+            element(leaves, i, depth + 1, f)?;
+            write!(f, ",")?;
+            element(leaves, i, depth + 1, f)?;
+            write!(f, "]")
         }
+        element(self.leaves(), &mut 0, 0, f)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct Pair {
-    left: Number,
-    right: Number,
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.leaves() == other.leaves()
+    }
 }
 
-impl Pair {
-    fn distribute_left(&mut self, v: u8) {
-        match &mut self.left {
-            Number::Value(left) => *left += v,
-            Number::Nested(p) => p.distribute_left(v),
-        }
+impl Eq for Number {}
+
+impl fmt::Debug for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
     }
+}
 
-    fn distribute_right(&mut self, v: u8) {
-        match &mut self.right {
-            Number::Value(right) => *right += v,
-            Number::Nested(p) => p.distribute_right(v),
+/// Reduces every number, as `+` expects. Reduction keeps the sum of the
+/// values, so they stay far below `u16::MAX`.
+fn parse(data: &str) -> Result<Vec<Number>, ParseError> {
+    data.lines()
+        .map(|line| {
+            let mut n: Number = line.parse()?;
+            n.reduce();
+            Ok(n)
+        })
+        .collect()
+}
+
+pub fn main() {
+    let data = std::fs::read_to_string("data/2021/day18").unwrap();
+    let numbers = parse(&data).unwrap();
+    let n = numbers[1..].iter().fold(numbers[0], |n, &rhs| n + rhs);
+    println!("day18 part1: {}", n.magnitude());
+
+    let p2 = numbers
+        .iter()
+        .flat_map(|&a| numbers.iter().map(move |&b| (a + b).magnitude()))
+        .max()
+        .unwrap();
+    println!("day18 part2: {p2}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+    use rand::prelude::*;
+
+    /// The original boxed tree, kept to check the flat version against.
+    mod tree {
+        use std::{fmt::Display, ops::Add, str::FromStr};
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub(super) enum Number {
+            Value(u8),
+            Nested(Box<Pair>),
         }
-    }
 
-    fn explode(&mut self, level: usize) -> Option<(Option<u8>, Option<u8>)> {
-        match (&mut self.left, &mut self.right) {
-            (Number::Value(a), Number::Value(b)) => {
-                if level >= 4 {
-                    Some((Some(*a), Some(*b)))
-                } else {
-                    None
+        impl Number {
+            pub(super) fn magnitude(&self) -> i64 {
+                match self {
+                    Number::Value(v) => *v as i64,
+                    Number::Nested(p) => 3 * p.left.magnitude() + 2 * p.right.magnitude(),
                 }
             }
-            (Number::Value(v), Number::Nested(right)) => match right.explode(level + 1) {
-                Some((Some(frag), b)) => {
-                    *v += frag;
-                    if b.is_some() {
-                        self.right = Number::Value(0);
+
+            pub(super) fn reduce(&mut self) {
+                loop {
+                    if self.explode(0).is_some() || self.split() {
+                        continue;
+                    } else {
+                        break;
                     }
-                    Some((None, b))
                 }
-                x => x,
-            },
-            (Number::Nested(left), Number::Value(v)) => match left.explode(level + 1) {
-                Some((b, Some(frag))) => {
-                    *v += frag;
-                    if b.is_some() {
-                        self.left = Number::Value(0);
-                    }
-                    Some((b, None))
+            }
+
+            fn explode(&mut self, level: usize) -> Option<(Option<u8>, Option<u8>)> {
+                match self {
+                    Number::Value(_) => None,
+                    Number::Nested(p) => p.explode(level),
                 }
-                x => x,
-            },
-            (Number::Nested(left), Number::Nested(right)) => {
-                if let Some((a, b)) = left.explode(level + 1) {
-                    if let Some(b) = b {
-                        right.distribute_left(b);
-                        if a.is_some() {
-                            self.left = Number::Value(0);
-                        }
-                    }
-                    return Some((a, None));
+            }
+
+            fn split(&mut self) -> bool {
+                match self {
+                    Number::Value(_) => false,
+                    Number::Nested(p) => p.split(),
                 }
-                if let Some((a, b)) = right.explode(level + 1) {
-                    if let Some(a) = a {
-                        left.distribute_right(a);
-                        if b.is_some() {
-                            self.right = Number::Value(0);
-                        }
+            }
+        }
+
+        impl Add for Number {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                let mut n = Number::Nested(Box::new(Pair {
+                    left: self,
+                    right: rhs,
+                }));
+                n.reduce();
+                n
+            }
+        }
+
+        #[derive(Debug)]
+        pub(super) struct InvalidSnailfishNumber;
+
+        impl FromStr for Number {
+            type Err = InvalidSnailfishNumber;
+
+            fn from_str(line: &str) -> Result<Self, Self::Err> {
+                fn recursive(line: &str) -> (Number, usize) {
+                    let s = line.as_bytes();
+                    let (left, size_l) = if s[1] == b'[' {
+                        recursive(&line[1..])
+                    } else {
+                        (Number::Value(s[1] - b'0'), 1)
+                    };
+                    if s[size_l + 1] == b']' {
+                        (left, size_l)
+                    } else {
+                        let (right, size_r) = recursive(&line[size_l + 1..]);
+                        (
+                            Number::Nested(Box::new(Pair { left, right })),
+                            size_l + size_r + 3,
+                        )
                     }
-                    Some((None, b))
-                } else {
-                    None
                 }
+                Ok(recursive(line).0)
             }
         }
-    }
 
-    fn split(&mut self) -> bool {
-        match &mut self.left {
-            Number::Value(v) => {
-                if *v >= 10 {
-                    self.left = Number::Nested(Box::new(Pair {
-                        left: Number::Value(*v / 2),
-                        right: Number::Value(*v - *v / 2),
-                    }));
-                    return true;
+        impl Display for Number {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Number::Value(n) => write!(f, "{n}"),
+                    Number::Nested(p) => write!(f, "[{},{}]", p.left, p.right),
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub(super) struct Pair {
+            left: Number,
+            right: Number,
+        }
+
+        impl Pair {
+            fn distribute_left(&mut self, v: u8) {
+                match &mut self.left {
+                    Number::Value(left) => *left += v,
+                    Number::Nested(p) => p.distribute_left(v),
                 }
             }
-            Number::Nested(p) => {
-                if p.split() {
-                    return true;
+
+            fn distribute_right(&mut self, v: u8) {
+                match &mut self.right {
+                    Number::Value(right) => *right += v,
+                    Number::Nested(p) => p.distribute_right(v),
                 }
             }
-        }
 
-        match &mut self.right {
-            Number::Value(v) => {
-                if *v >= 10 {
-                    self.right = Number::Nested(Box::new(Pair {
-                        left: Number::Value(*v / 2),
-                        right: Number::Value(*v - *v / 2),
-                    }));
-                    true
-                } else {
-                    false
+            fn explode(&mut self, level: usize) -> Option<(Option<u8>, Option<u8>)> {
+                match (&mut self.left, &mut self.right) {
+                    (Number::Value(a), Number::Value(b)) => {
+                        if level >= 4 {
+                            Some((Some(*a), Some(*b)))
+                        } else {
+                            None
+                        }
+                    }
+                    (Number::Value(v), Number::Nested(right)) => match right.explode(level + 1) {
+                        Some((Some(frag), b)) => {
+                            *v += frag;
+                            if b.is_some() {
+                                self.right = Number::Value(0);
+                            }
+                            Some((None, b))
+                        }
+                        x => x,
+                    },
+                    (Number::Nested(left), Number::Value(v)) => match left.explode(level + 1) {
+                        Some((b, Some(frag))) => {
+                            *v += frag;
+                            if b.is_some() {
+                                self.left = Number::Value(0);
+                            }
+                            Some((b, None))
+                        }
+                        x => x,
+                    },
+                    (Number::Nested(left), Number::Nested(right)) => {
+                        if let Some((a, b)) = left.explode(level + 1) {
+                            if let Some(b) = b {
+                                right.distribute_left(b);
+                                if a.is_some() {
+                                    self.left = Number::Value(0);
+                                }
+                            }
+                            return Some((a, None));
+                        }
+                        if let Some((a, b)) = right.explode(level + 1) {
+                            if let Some(a) = a {
+                                left.distribute_right(a);
+                                if b.is_some() {
+                                    self.right = Number::Value(0);
+                                }
+                            }
+                            Some((None, b))
+                        } else {
+                            None
+                        }
+                    }
                 }
             }
-            Number::Nested(p) => p.split(),
-        }
-    }
-}
 
-fn parse(data: &str) -> Vec<Number> {
-    data.lines().map(|line| line.parse().unwrap()).collect()
-}
+            fn split(&mut self) -> bool {
+                match &mut self.left {
+                    Number::Value(v) => {
+                        if *v >= 10 {
+                            self.left = Number::Nested(Box::new(Pair {
+                                left: Number::Value(*v / 2),
+                                right: Number::Value(*v - *v / 2),
+                            }));
+                            return true;
+                        }
+                    }
+                    Number::Nested(p) => {
+                        if p.split() {
+                            return true;
+                        }
+                    }
+                }
 
-pub fn main() {
-    let data = std::fs::read_to_string("data/2021/day18").unwrap();
-    let numbers = parse(&data);
-    let mut n = numbers[0].clone();
-    for rhs in &numbers[1..] {
-        n = n + rhs.clone();
+                match &mut self.right {
+                    Number::Value(v) => {
+                        if *v >= 10 {
+                            self.right = Number::Nested(Box::new(Pair {
+                                left: Number::Value(*v / 2),
+                                right: Number::Value(*v - *v / 2),
+                            }));
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    Number::Nested(p) => p.split(),
+                }
+            }
+        }
     }
-    println!("day18 part1: {}", n.magnitude());
 
-    let p2 = numbers
-        .iter()
-        .flat_map(|a| numbers.iter().map(|b| (a.clone() + b.clone()).magnitude()))
-        .max()
-        .unwrap();
-    println!("day18 part2: {p2}");
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// A random reduced number, or up to five deep with values up to 15
+    /// when `wild`.
+    fn random(rng: &mut StdRng, depth: u8, wild: bool) -> String {
+        let max_depth = if wild { 5 } else { 4 };
+        if depth == max_depth || (depth > 0 && rng.random_bool(0.3)) {
+            let max = if wild { 16 } else { 10 };
+            return rng.random_range(0..max).to_string();
+        }
+        format!(
+            "[{},{}]",
+            random(rng, depth + 1, wild),
+            random(rng, depth + 1, wild)
+        )
+    }
 
     #[test]
     fn case1() {
         let data = "[[[[4,3],4],4],[7,[[8,4],9]]]\n[1,1]";
-        let mut numbers = parse(data);
-        let mut n = numbers.remove(0);
-        for rhs in numbers {
-            n = n + rhs;
-        }
+        let numbers = parse(data).unwrap();
+        let n = numbers[0] + numbers[1];
         let expected = Number::from_str("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").unwrap();
         assert_eq!(expected, n);
     }
+
+    #[test]
+    fn unreduced_input() {
+        let data = "[[[[[255,255],255],255],255],255]\n[255,[[[[99,0],1],2],3]]";
+        let numbers = parse(data).unwrap();
+        let sum = numbers[0] + numbers[1];
+        for n in numbers.iter().chain([&sum]) {
+            assert!(n.leaves().iter().all(|l| l.depth <= 4 && l.value < 10));
+        }
+    }
+
+    #[test]
+    fn magnitude() {
+        let n: Number = "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]"
+            .parse()
+            .unwrap();
+        assert_eq!(3488, n.magnitude());
+        assert_eq!(29, "[9,1]".parse::<Number>().unwrap().magnitude());
+    }
+
+    #[test]
+    fn steps() {
+        let cases = [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+        ];
+        for (before, after) in cases {
+            let mut n: Number = before.parse().unwrap();
+            assert!(n.explode());
+            assert_eq!(after, n.to_string());
+        }
+        let mut n: Number = "[[[[0,7],4],[15,[0,13]]],[1,1]]".parse().unwrap();
+        assert!(n.split());
+        assert_eq!("[[[[0,7],4],[[7,8],[0,13]]],[1,1]]", n.to_string());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |line: &str| line.parse::<Number>().unwrap_err();
+        assert_eq!(
+            ParseError {
+                position: 0,
+                expected: "'[' or a digit"
+            },
+            error("")
+        );
+        assert_eq!(
+            ParseError {
+                position: 2,
+                expected: "','"
+            },
+            error("[1]")
+        );
+        assert_eq!(
+            ParseError {
+                position: 4,
+                expected: "']'"
+            },
+            error("[1,2,3]")
+        );
+        assert_eq!(
+            ParseError {
+                position: 3,
+                expected: "'[' or a digit"
+            },
+            error("[1,x]")
+        );
+        assert_eq!(
+            ParseError {
+                position: 5,
+                expected: "end of line"
+            },
+            error("[1,2]]")
+        );
+        assert_eq!(5, error("[[[[[[1,2],3],4],5],6],7]").position);
+        assert_eq!(1, error("[300,2]").position);
+        assert_eq!("expected ',' at column 3", error("[1]").to_string());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = StdRng::seed_from_u64(40);
+        for _ in 0..500 {
+            let s = random(&mut rng, 0, true);
+            assert_eq!(s, s.parse::<Number>().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn same_as_tree() {
+        let mut rng = StdRng::seed_from_u64(18);
+        for _ in 0..500 {
+            let (a, b) = (random(&mut rng, 0, false), random(&mut rng, 0, false));
+            let flat = a.parse::<Number>().unwrap() + b.parse::<Number>().unwrap();
+            let boxed = tree::Number::from_str(&a).unwrap() + tree::Number::from_str(&b).unwrap();
+            assert_eq!(boxed.to_string(), flat.to_string(), "{a} + {b}");
+            assert_eq!(boxed.magnitude(), flat.magnitude());
+
+            // a single leftover explosion or split, which the tree parser
+            // can't read when it has two digit values
+            let wild = random(&mut rng, 0, true);
+            let mut flat = wild.parse::<Number>().unwrap();
+            flat.reduce();
+            if wild
+                .split(|c: char| !c.is_ascii_digit())
+                .all(|d| d.len() < 2)
+            {
+                let mut boxed = tree::Number::from_str(&wild).unwrap();
+                boxed.reduce();
+                assert_eq!(boxed.to_string(), flat.to_string(), "{wild}");
+            }
+        }
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]\n[9,1]";
+        fuzz::check(&[seed], |s| {
+            let numbers = parse(s)?;
+            for &n in &numbers {
+                assert_eq!(Ok(n), n.to_string().parse());
+            }
+            numbers.into_iter().reduce(|a, b| a + b);
+            Ok::<_, ParseError>(())
+        });
+    }
 }
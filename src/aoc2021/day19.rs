@@ -1,6 +1,16 @@
+use adventofcode::parsing::{self, PResult, ParseError, signed, unsigned};
 use ahash::AHashMap as HashMap;
 use ahash::AHashSet as HashSet;
 // use lazy_static::lazy_static;
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{char, line_ending, space0},
+    combinator::verify,
+    error::context,
+    multi::many1,
+    sequence::{delimited, preceded},
+};
 use std::{
     fmt::Display,
     ops::{Add, Div, Mul, Sub},
@@ -340,32 +350,36 @@ impl Scanner {
     }
 }
 
-fn parse(data: &str) -> Vec<Scanner> {
-    let lines = data.lines().collect::<Vec<_>>();
-    lines
-        .split(|line| line.is_empty())
-        .map(|g| {
-            let id = g[0]
-                .trim()
-                .split_ascii_whitespace()
-                .nth(2)
-                .unwrap()
-                .parse::<usize>()
-                .unwrap();
-            let beacons = g[1..]
-                .iter()
-                .map(|p| {
-                    let v = p
-                        .trim()
-                        .split(',')
-                        .map(|e| e.parse::<i32>().unwrap())
-                        .collect::<Vec<_>>();
-                    Vec3::new(v[0], v[1], v[2])
-                })
-                .collect();
-            Scanner::new(id, beacons)
-        })
-        .collect()
+/// Beacons are within 1000 of their scanner, so squared distances fit in i32.
+fn coordinate(input: &str) -> PResult<'_, i32> {
+    context(
+        "a coordinate within 1000",
+        verify(signed, |c: &i32| c.abs() <= 1000),
+    )
+    .parse(input)
+}
+
+fn scanner(input: &str) -> PResult<'_, (usize, Vec<Vec3>)> {
+    let header = delimited((space0, tag("--- scanner ")), unsigned, tag(" ---"));
+    let beacon = (coordinate, char(','), coordinate, char(','), coordinate)
+        .map(|(x, _, y, _, z)| Vec3::new(x, y, z));
+    (header, many1(preceded((line_ending, space0), beacon))).parse(input)
+}
+
+fn parse(data: &str) -> Result<Vec<Scanner>, ParseError> {
+    let scanners = parsing::sections(data, scanner)?;
+    // scanner 0 comes first and stays put, the others get rotated
+    for (i, &(id, _)) in scanners.iter().enumerate() {
+        if id != i {
+            let header = format!("--- scanner {id} ---");
+            let at = data.find(&header).unwrap_or(0);
+            return Err(ParseError::at(data, &data[at..], format!("scanner {i}")));
+        }
+    }
+    Ok(scanners
+        .into_iter()
+        .map(|(id, beacons)| Scanner::new(id, beacons))
+        .collect())
 }
 
 fn solve(mut scanners: Vec<Scanner>) -> (usize, i32) {
@@ -402,7 +416,7 @@ fn solve(mut scanners: Vec<Scanner>) -> (usize, i32) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day19").unwrap();
-    let scanners = parse(&data);
+    let scanners = parse(&data).unwrap();
     let (p1, p2) = solve(scanners);
     println!("day19 part1: {p1}");
     println!("day19 part2: {p2}");
@@ -411,6 +425,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -551,7 +566,13 @@ mod tests {
         -652,-548,-490
         30,-46,-14";
 
-        let scanners = parse(data);
+        let scanners = parse(data).unwrap();
         assert_eq!((79, 3621), solve(scanners));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "--- scanner 0 ---\n404,-588,-901\n528,-643,409\n\n--- scanner 1 ---\n686,422,578\n-336,658,858";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
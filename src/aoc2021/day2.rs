@@ -1,4 +1,13 @@
-use std::{fmt::Display, str::FromStr};
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{space0, space1},
+    combinator::verify,
+    error::context,
+    sequence::preceded,
+};
 
 #[derive(Debug)]
 enum Command {
@@ -7,32 +16,19 @@ enum Command {
     Forward(i64),
 }
 
-struct InvalidCommand(String);
-
-impl Display for InvalidCommand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "invalid command, source: {}", self.0)
-    }
-}
-
-impl FromStr for Command {
-    type Err = InvalidCommand;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.trim().split(' ');
-        let dir = parts.next().ok_or_else(|| InvalidCommand(s.to_string()))?;
-        let n = parts
-            .next()
-            .ok_or_else(|| InvalidCommand(s.to_string()))?
-            .parse::<i64>()
-            .map_err(|_| InvalidCommand(s.to_string()))?;
-        match dir {
-            "up" => Ok(Command::Up(n)),
-            "down" => Ok(Command::Down(n)),
-            "forward" => Ok(Command::Forward(n)),
-            _ => Err(InvalidCommand(s.to_string())),
-        }
+fn parse(data: &str) -> Result<Vec<Command>, ParseError> {
+    let data = data.trim();
+    // small enough that part 2 can't overflow
+    if data.lines().count() > 10000 {
+        return Err(ParseError::at(data, data, "at most 10000 commands"));
     }
+    let n = || context("a step below 100", verify(unsigned, |&n: &i64| n < 100));
+    let command = alt((
+        preceded((tag("up"), space1), n()).map(Command::Up),
+        preceded((tag("down"), space1), n()).map(Command::Down),
+        preceded((tag("forward"), space1), n()).map(Command::Forward),
+    ));
+    parsing::lines(data, preceded(space0, command))
 }
 
 fn part1(cmds: &[Command]) -> i64 {
@@ -66,17 +62,21 @@ fn part2(cmds: &[Command]) -> i64 {
 }
 
 pub fn main() {
-    let cmds: Result<Vec<Command>, _> = std::fs::read_to_string("data/2021/day2")
-        .unwrap()
-        .lines()
-        .map(|s| s.parse())
-        .collect();
+    let data = std::fs::read_to_string("data/2021/day2").unwrap();
+    let cmds = parse(&data).unwrap();
 
-    match cmds {
-        Ok(cmds) => {
-            println!("day2 part1: {}", part1(&cmds));
-            println!("day2 part2: {}", part2(&cmds));
-        }
-        Err(e) => println!("failed to parse input, error: {e}"),
+    println!("day2 part1: {}", part1(&cmds));
+    println!("day2 part2: {}", part2(&cmds));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
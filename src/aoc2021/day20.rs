@@ -1,4 +1,6 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::AHashSet as HashSet;
+use nom::{bytes::complete::take_while_m_n, error::context};
 
 struct Image {
     img: Vec<bool>,
@@ -14,20 +16,20 @@ impl Image {
         bounds.3 = bounds.3.max(y);
     }
 
-    fn from_lines(s: &str) -> Self {
+    fn from_grid(grid: &[Vec<u8>]) -> Self {
         let mut pixels = HashSet::new();
         let mut bounds = (usize::MAX, usize::MAX, usize::MIN, usize::MIN);
-        s.lines().enumerate().for_each(|(y, line)| {
-            line.as_bytes().iter().enumerate().for_each(|(x, &pixel)| {
+        grid.iter().enumerate().for_each(|(y, line)| {
+            line.iter().enumerate().for_each(|(x, &pixel)| {
                 if pixel == b'#' {
                     pixels.insert((x, y));
                     Self::update_bounds(&mut bounds, x, y);
                 }
             })
         });
-        let height = bounds.2 - bounds.0 + 4 * 50;
-        let width = bounds.3 - bounds.1 + 4 * 50;
-        let mut img = vec![false; width * height];
+        // room for 50 steps, each growing the image by a pixel on every side
+        let width = grid.len().max(grid[0].len()) + 4 * 50;
+        let mut img = vec![false; width * width];
         for (x, y) in pixels {
             img[(y + 2 * 50) * width + x + 2 * 50] = true;
         }
@@ -80,18 +82,17 @@ impl Image {
     }
 }
 
-fn parse(data: &str) -> (Vec<bool>, Image) {
-    let mut parts = data.split("\n\n");
-    let algo = parts
-        .next()
-        .unwrap()
-        .as_bytes()
-        .iter()
-        .map(|p| *p == b'#')
-        .collect::<Vec<_>>();
-    assert_eq!(512, algo.len());
-    let img = Image::from_lines(parts.next().unwrap());
-    (algo, img)
+fn parse(data: &str) -> Result<(Vec<bool>, Image), ParseError> {
+    let [algo, image] = parsing::split_sections::<2>(data)?;
+    let pixels = take_while_m_n(512, 512, |c| c == '.' || c == '#');
+    let algo = parsing::parse_all(algo, context("512 pixels", pixels))?;
+    let algo = algo.bytes().map(|p| p == b'#').collect();
+    let grid = parsing::grid_of(image, ".#")?;
+    // the image is tracked by the bounds of its lit pixels
+    if !grid.iter().flatten().any(|&p| p == b'#') {
+        return Err(ParseError::at(data, image, "a lit pixel"));
+    }
+    Ok((algo, Image::from_grid(&grid)))
 }
 
 fn enhace(algo: &[bool], img: &mut Image, times: usize) -> usize {
@@ -103,7 +104,19 @@ fn enhace(algo: &[bool], img: &mut Image, times: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day20").unwrap();
-    let (algo, mut img) = parse(&data);
+    let (algo, mut img) = parse(&data).unwrap();
     println!("day20 part1: {}", enhace(&algo, &mut img, 2));
     println!("day20 part2: {}", enhace(&algo, &mut img, 48));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = format!("{}\n\n#..#.\n#....\n##..#", "..#.#..###.#.#..".repeat(32));
+        fuzz::check(&[seed.as_str()], |s| parse(s).map(drop));
+    }
+}
@@ -1,12 +1,28 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
+use nom::{
+    bytes::complete::tag,
+    character::complete::line_ending,
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
 
-fn parse(data: &str) -> (usize, usize) {
-    let pos = data
-        .lines()
-        .map(|line| line.split(": ").last().unwrap().parse::<usize>().unwrap())
-        .take(2)
-        .collect::<Vec<_>>();
-    (pos[0], pos[1])
+fn parse(data: &str) -> Result<(usize, usize), ParseError> {
+    let position = || {
+        context(
+            "a position from 1 to 10",
+            verify(unsigned, |p| (1..=10).contains(p)),
+        )
+    };
+    parsing::parse_all(
+        data.trim(),
+        separated_pair(
+            preceded(tag("Player 1 starting position: "), position()),
+            line_ending,
+            preceded(tag("Player 2 starting position: "), position()),
+        ),
+    )
 }
 
 fn part1(mut p1: usize, mut p2: usize) -> usize {
@@ -108,7 +124,19 @@ fn part2(p1: usize, p2: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day21").unwrap();
-    let (p1, p2) = parse(&data);
+    let (p1, p2) = parse(&data).unwrap();
     println!("day21 part1: {}", part1(p1, p2));
     println!("day21 part2: {}", part2(p1, p2));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "Player 1 starting position: 4\nPlayer 2 starting position: 8";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,3 +1,14 @@
+use adventofcode::parsing::{self, PResult, ParseError, signed};
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::space0,
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
+
 #[derive(Debug, Clone, PartialEq)]
 struct LineSegment {
     start: i32,
@@ -95,22 +106,27 @@ impl Reactor {
     }
 }
 
-fn parse(data: &str) -> Vec<(bool, Cuboid)> {
-    data.lines()
-        .map(|line| {
-            let p = line.trim().split_once(' ').unwrap();
-            let on = p.0 == "on";
-            let cuboid =
-                p.1.split(',')
-                    .flat_map(|s| {
-                        let (_, range) = s.split_once('=').unwrap();
-                        let (low, high) = range.split_once("..").unwrap();
-                        [low.parse().unwrap(), high.parse().unwrap()]
-                    })
-                    .collect::<Vec<_>>();
-            (on, Cuboid::new(&cuboid))
-        })
-        .collect()
+/// Small enough that the volume of any cuboid fits in usize.
+fn range(input: &str) -> PResult<'_, (i32, i32)> {
+    let small = |&(lo, hi): &(i32, i32)| -1_000_000 <= lo && lo <= hi && hi <= 1_000_000;
+    let range = separated_pair(signed, tag(".."), signed);
+    context("a range within a million", verify(range, small)).parse(input)
+}
+
+fn step(line: &str) -> PResult<'_, (bool, Cuboid)> {
+    let on = alt((tag("on").map(|_| true), tag("off").map(|_| false)));
+    let cuboid = (
+        preceded(tag(" x="), range),
+        preceded(tag(",y="), range),
+        preceded(tag(",z="), range),
+    );
+    (on, cuboid)
+        .map(|(on, (x, y, z))| (on, Cuboid::new(&[x.0, x.1, y.0, y.1, z.0, z.1])))
+        .parse(line)
+}
+
+fn parse(data: &str) -> Result<Vec<(bool, Cuboid)>, ParseError> {
+    parsing::lines(data.trim(), preceded(space0, step))
 }
 
 fn solve(steps: Vec<(bool, Cuboid)>) -> usize {
@@ -131,7 +147,7 @@ fn solve(steps: Vec<(bool, Cuboid)>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day22").unwrap();
-    let steps = parse(&data);
+    let steps = parse(&data).unwrap();
     let p1 = steps
         .iter()
         .filter(|(_, c)| c.is_within(50))
@@ -144,6 +160,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -169,7 +186,7 @@ mod tests {
         on x=-41..9,y=-7..43,z=-33..15
         on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
         on x=967..23432,y=45373..81175,z=27513..53682";
-        let steps = parse(data);
+        let steps = parse(data).unwrap();
         let p1 = steps
             .iter()
             .filter(|(_, c)| c.is_within(50))
@@ -240,7 +257,7 @@ mod tests {
         off x=-70369..-16548,y=22648..78696,z=-1892..86821
         on x=-53470..21291,y=-120233..-33476,z=-44150..38147
         off x=-93533..-4276,y=-16170..68771,z=-104985..-24507";
-        let steps = parse(data);
+        let steps = parse(data).unwrap();
         let p1 = steps
             .iter()
             .filter(|(_, c)| c.is_within(50))
@@ -263,4 +280,10 @@ mod tests {
             a.intersection(&LineSegment::new(1, 5))
         );
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "on x=10..12,y=10..12,z=10..12\noff x=9..11,y=-9..11,z=9..11";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
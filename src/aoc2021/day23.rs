@@ -1,6 +1,15 @@
-use ahash::AHashMap as HashMap;
+use adventofcode::{
+    exhaustive::{Min, Problem, Search},
+    parsing::{self, Error, ParseError},
+};
 use arrayvec::ArrayVec;
-use std::{cmp::Reverse, collections::BinaryHeap, fmt::Display};
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{char, line_ending, one_of, space0},
+    sequence::{delimited, preceded},
+};
+use std::fmt::Display;
 
 fn energy(amphipod: u8) -> usize {
     match amphipod {
@@ -253,11 +262,41 @@ impl<const D: usize> Display for Grid<D> {
     }
 }
 
-fn parse<const D: usize>(data: &str) -> Grid<D> {
-    let cells: Vec<Vec<char>> = data
-        .lines()
-        .map(|line| line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+fn side_rooms<'a>(
+    left: &'static str,
+    right: &'static str,
+) -> impl Parser<&'a str, Output = [u8; 4], Error = Error<'a>> {
+    let amphipod = || one_of("ABCD").map(|c| c as u8 - b'A' + 1);
+    let rooms = (
+        amphipod(),
+        preceded(char('#'), amphipod()),
+        preceded(char('#'), amphipod()),
+        preceded(char('#'), amphipod()),
+    );
+    delimited(tag(left), rooms, (tag(right), space0)).map(|(a, b, c, d)| [a, b, c, d])
+}
+
+fn parse<const D: usize>(data: &str) -> Result<Grid<D>, ParseError> {
+    let (_, _, _, _, top, _, bottom, _, _) = parsing::parse_all(
+        data.trim_end(),
+        (
+            tag("#############"),
+            line_ending,
+            tag("#...........#"),
+            line_ending,
+            side_rooms("###", "###"),
+            line_ending,
+            side_rooms("  #", "#"),
+            line_ending,
+            tag("  #########"),
+        ),
+    )?;
+    // the search only ends once every room is full
+    for kind in 1..=4 {
+        if top.iter().chain(&bottom).filter(|&&c| c == kind).count() != 2 {
+            return Err(ParseError::at(data, data, "two amphipods of each kind"));
+        }
+    }
     let mut grid = Grid {
         hallway: [0; 12],
         rooms: [[0; D]; 4],
@@ -276,57 +315,74 @@ fn parse<const D: usize>(data: &str) -> Grid<D> {
         grid.rooms[3][2] = 3;
     }
     for i in 0..4 {
-        let x = 1 + 2 * (i + 1);
-        for (j, y) in [(0, 2), (D - 1, 3)] {
-            grid.rooms[i][j] = match cells[y][x] {
-                'A' => 1,
-                'B' => 2,
-                'C' => 3,
-                'D' => 4,
-                x => panic!("invalid amphipod {x}"),
-            };
-        }
+        grid.rooms[i][0] = top[i];
+        grid.rooms[i][D - 1] = bottom[i];
     }
-    grid
+    Ok(grid)
 }
 
-fn solve<const D: usize, const E: usize>(g: Grid<D>) -> usize {
-    let mut costs = HashMap::new();
-    let mut q = BinaryHeap::new();
-    q.push((Reverse(0), g));
-    while let Some((cost, g)) = q.pop() {
-        if g.is_done() {
-            return cost.0;
+struct Burrow<const D: usize>;
+
+impl<const D: usize> Problem for Burrow<D> {
+    type State = Grid<D>;
+    type Key = Grid<D>;
+    type Value = usize;
+    type Objective = Min;
+
+    fn key(&self, g: &Grid<D>) -> Option<Grid<D>> {
+        Some(g.clone())
+    }
+
+    fn stop(&self, g: &Grid<D>) -> Option<usize> {
+        g.is_done().then_some(0)
+    }
+
+    fn children(&self, g: &Grid<D>, out: &mut Vec<(usize, Grid<D>)>) {
+        for (from, to, cost) in g.all_moves() {
+            out.push((cost, g.make_move((from, to))));
         }
-        let key: &[u32; E] = unsafe { std::mem::transmute(&g) };
-        if let Some(c) = costs.get(key)
-            && cost.0 > *c
-        {
-            continue;
+    }
+
+    /// Energy to walk every amphipod outside its room to the door of its
+    /// room, ignoring the others.
+    fn bound(&self, g: &Grid<D>) -> Option<usize> {
+        let mut result = 0;
+        for (i, &c) in g.hallway.iter().enumerate() {
+            if c != 0 {
+                let exit = Grid::<D>::room_exit(Grid::<D>::amphipod_room(c));
+                result += (i.abs_diff(exit) + 1) * energy(c);
+            }
         }
-        for (from, to, delta_cost) in g.all_moves() {
-            let next = g.make_move((from, to));
-            let key: &[u32; E] = unsafe { std::mem::transmute(&next) };
-            let old = costs.get(key).cloned().unwrap_or(usize::MAX);
-            let new = cost.0 + delta_cost;
-            if new < old {
-                costs.insert(*key, new);
-                q.push((Reverse(new), next));
+        for (room, cells) in g.rooms.iter().enumerate() {
+            for (depth, &c) in cells.iter().enumerate() {
+                if c == 0 {
+                    continue;
+                }
+                let target = Grid::<D>::amphipod_room(c);
+                if target != room {
+                    let dist = Grid::<D>::room_exit(room).abs_diff(Grid::<D>::room_exit(target));
+                    result += (depth + 1 + dist + 1) * energy(c);
+                }
             }
         }
+        Some(result)
     }
-    unreachable!()
+}
+
+fn solve<const D: usize>(g: Grid<D>) -> usize {
+    Search::new(&Burrow).solve(&g).unwrap()
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day23").unwrap();
-    println!("day23 part1: {}", solve::<2, 6>(parse::<2>(&data)));
-    println!("day23 part2: {}", solve::<4, 8>(parse::<4>(&data)));
+    println!("day23 part1: {}", solve::<2>(parse::<2>(&data).unwrap()));
+    println!("day23 part2: {}", solve::<4>(parse::<4>(&data).unwrap()));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -335,7 +391,13 @@ mod tests {
 ###B#C#B#D###
   #A#D#C#A#  
   #########  ";
-        assert_eq!(12521, solve::<2, 6>(parse::<2>(data)));
-        assert_eq!(44169, solve::<4, 8>(parse::<4>(data)));
+        assert_eq!(12521, solve::<2>(parse::<2>(data).unwrap()));
+        assert_eq!(44169, solve::<4>(parse::<4>(data).unwrap()));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########";
+        fuzz::check(&[seed], |s| parse::<2>(s).map(drop));
     }
 }
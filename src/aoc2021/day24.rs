@@ -1,3 +1,4 @@
+use adventofcode::parsing::ParseError;
 use ahash::AHashSet as HashSet;
 
 fn alu(
@@ -61,22 +62,60 @@ fn alu(
     -1
 }
 
-fn parse(data: &str) -> Vec<(i64, i64, i64)> {
-    let mut result = vec![];
-    let mut a = 0;
-    let mut b = 0;
-    let mut c = 0;
-    data.lines().enumerate().for_each(|(i, line)| {
-        if i % 18 == 4 {
-            a = line.trim().trim_start_matches("div z ").parse().unwrap();
-        } else if i % 18 == 5 {
-            b = line.trim().trim_start_matches("add x ").parse().unwrap();
-        } else if i % 18 == 15 {
-            c = line.trim().trim_start_matches("add y ").parse().unwrap();
-            result.push((a, b, c));
+/// Each of the 14 digits is checked by the same block of instructions, with
+/// the parameters `a`, `b` and `c` marked by `_`.
+const BLOCK: [&str; 18] = [
+    "inp w", "mul x 0", "add x z", "mod x 26", "div z _", "add x _", "eql x w", "eql x 0",
+    "mul y 0", "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y _",
+    "mul y x", "add z y",
+];
+
+fn parse(data: &str) -> Result<Vec<(i64, i64, i64)>, ParseError> {
+    let data = data.trim();
+    let mut params = vec![];
+    let mut lines = data.lines().map(str::trim);
+    for _ in 0..14 {
+        for expected in BLOCK {
+            let end = &data[data.len()..];
+            let line = lines
+                .next()
+                .ok_or_else(|| ParseError::at(data, end, expected))?;
+            let Some(prefix) = expected.strip_suffix('_') else {
+                if line != expected {
+                    return Err(ParseError::at(data, line, expected));
+                }
+                continue;
+            };
+            let n = line
+                .strip_prefix(prefix)
+                .and_then(|n| n.parse::<i64>().ok())
+                .filter(|n| n.abs() <= 26)
+                .ok_or_else(|| ParseError::at(data, line, format!("{prefix}n with |n| <= 26")))?;
+            params.push((n, line));
         }
-    });
-    result
+    }
+    if let Some(extra) = lines.next() {
+        return Err(ParseError::at(data, extra, "only 14 blocks"));
+    }
+    // the solver pairs every `div z 26` with an earlier `div z 1`
+    let mut depth = 0;
+    let mut result = vec![];
+    for p in params.chunks(3) {
+        let (a, line) = p[0];
+        depth = match a {
+            1 => depth + 1,
+            26 if depth > 0 => depth - 1,
+            _ => {
+                return Err(ParseError::at(
+                    data,
+                    line,
+                    "div z 1, or div z 26 after more 1s",
+                ));
+            }
+        };
+        result.push((a, p[1].0, p[2].0));
+    }
+    Ok(result)
 }
 
 fn solve(insturctions: &[(i64, i64, i64)], part2: bool) -> i64 {
@@ -87,7 +126,42 @@ fn solve(insturctions: &[(i64, i64, i64)], part2: bool) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day24").unwrap();
-    let instructions = parse(&data);
+    let instructions = parse(&data).unwrap();
     println!("day24 part1: {}", solve(&instructions, false));
     println!("day24 part2: {}", solve(&instructions, true));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    fn program(params: &[(i64, i64, i64)]) -> String {
+        let mut lines = vec![];
+        for &(a, b, c) in params {
+            let mut params = [a, b, c].into_iter();
+            for line in BLOCK {
+                lines.push(match line.strip_suffix('_') {
+                    Some(prefix) => format!("{prefix}{}", params.next().unwrap()),
+                    None => line.to_string(),
+                });
+            }
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn case1() {
+        // each pair of digits has the second one 3 less than the first
+        let data = program(&[(1, 12, 4), (26, -7, 11)].repeat(7));
+        let instructions = parse(&data).unwrap();
+        assert_eq!(96969696969696, solve(&instructions, false));
+        assert_eq!(41414141414141, solve(&instructions, true));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = program(&[(1, 12, 4), (26, -7, 11)].repeat(7));
+        fuzz::check(&[seed.as_str()], |s| parse(s).map(drop));
+    }
+}
@@ -1,3 +1,4 @@
+use adventofcode::parsing::ParseError;
 use rayon::prelude::*;
 
 #[derive(Clone, PartialEq)]
@@ -63,25 +64,30 @@ impl Grid {
     }
 }
 
-fn parse(data: &str) -> Grid {
-    let mut height = 0;
-    let grid = data
-        .lines()
-        .flat_map(|line| {
-            height += 1;
-            line.trim().as_bytes().iter().map(|b| match b {
-                b'.' => Cell::Empty,
-                b'>' => Cell::East,
-                b'v' => Cell::South,
-                _ => panic!("invalid cell"),
-            })
-        })
-        .collect::<Vec<_>>();
-    Grid {
-        width: grid.len() / height,
-        height,
-        grid,
+fn parse(data: &str) -> Result<Grid, ParseError> {
+    let mut width = None;
+    let mut grid = vec![];
+    for line in data.trim().lines().map(str::trim) {
+        if let Some(i) = line.find(|c| !".>v".contains(c)) {
+            return Err(ParseError::at(data, &line[i..], "one of \".>v\""));
+        }
+        if line.is_empty() || *width.get_or_insert(line.len()) != line.len() {
+            return Err(ParseError::at(data, line, "a rectangle of cells"));
+        }
+        grid.extend(line.bytes().map(|b| match b {
+            b'.' => Cell::Empty,
+            b'>' => Cell::East,
+            _ => Cell::South,
+        }));
     }
+    let Some(width) = width else {
+        return Err(ParseError::at(data, data, "a row"));
+    };
+    Ok(Grid {
+        width,
+        height: grid.len() / width,
+        grid,
+    })
 }
 
 fn part1(grid: &mut Grid) -> usize {
@@ -94,13 +100,14 @@ fn part1(grid: &mut Grid) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day25").unwrap();
-    let mut grid = parse(&data);
+    let mut grid = parse(&data).unwrap();
     println!("day25 part1: {}", part1(&mut grid));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -113,7 +120,13 @@ mod tests {
         .vv..>.>v.
         v.v..>>v.v
         ....v..v.>";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         assert_eq!(58, part1(&mut grid));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "v...>>.vv>\n.vv>>.vv..\n>>.>v>...v";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
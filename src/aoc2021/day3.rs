@@ -1,3 +1,22 @@
+use adventofcode::parsing::{self, ParseError};
+use ahash::AHashSet as HashSet;
+
+fn parse(data: &str) -> Result<Vec<&[u8]>, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(data, "01")?;
+    if grid[0].len() > 63 {
+        return Err(ParseError::at(data, data, "at most 63 bits"));
+    }
+    // part 2 narrows the candidates down to a single number
+    let mut seen = HashSet::new();
+    for line in data.lines() {
+        if !seen.insert(line) {
+            return Err(ParseError::at(data, line, "a number not seen before"));
+        }
+    }
+    Ok(data.lines().map(str::as_bytes).collect())
+}
+
 fn part1(nums: &[&[u8]]) -> i64 {
     let n = nums.len();
     let mut gamma = 0;
@@ -39,10 +58,7 @@ fn part2(nums: &[&[u8]]) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day3").unwrap();
-    let nums = data
-        .lines()
-        .map(|s| s.trim().as_bytes())
-        .collect::<Vec<_>>();
+    let nums = parse(&data).unwrap();
 
     println!("day3 part1: {}", part1(&nums));
     println!("day3 part2: {}", part2(&nums));
@@ -51,6 +67,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -74,4 +91,11 @@ mod tests {
         assert_eq!(198, part1(&nums));
         assert_eq!(230, part2(&nums));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
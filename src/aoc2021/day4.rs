@@ -1,3 +1,13 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    Parser,
+    character::complete::{char, line_ending, space0, space1},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+    sequence::preceded,
+};
+
 const LENGTH: usize = 5;
 
 #[derive(Debug)]
@@ -6,18 +16,6 @@ struct Board {
 }
 
 impl Board {
-    fn from(lines: &[&str]) -> Self {
-        let nums = lines
-            .iter()
-            .flat_map(|r| {
-                r.split_ascii_whitespace()
-                    .map(|n| n.parse::<i64>().unwrap())
-            })
-            .collect::<Vec<_>>();
-        assert_eq!(LENGTH * LENGTH, nums.len());
-        Self { nums }
-    }
-
     fn is_row_marked(&self, r: usize) -> bool {
         for c in 0..LENGTH {
             if self.nums[r * LENGTH + c] != i64::MAX {
@@ -97,33 +95,47 @@ fn part2(nums: &[i64], boards: &mut [Board]) -> i64 {
     0
 }
 
+fn parse(data: &str) -> Result<(Vec<i64>, Vec<Board>), ParseError> {
+    let data = data.trim();
+    let Some((draws, boards)) = data.split_once("\n\n") else {
+        return Err(ParseError::at(data, &data[data.len()..], "a board"));
+    };
+    // marked numbers become i64::MAX, and a score is a sum times a number
+    let n = || context("a number below 1000", verify(unsigned, |&n: &i64| n < 1000));
+    let nums = parsing::parse_all(draws, separated_list1(char(','), n()))?;
+    let row = context(
+        "a row of 5",
+        verify(
+            preceded(space0, separated_list1(space1, n())),
+            |r: &Vec<_>| r.len() == LENGTH,
+        ),
+    );
+    let board = context(
+        "5 rows",
+        verify(separated_list1(line_ending, row), |b: &Vec<_>| {
+            b.len() == LENGTH
+        }),
+    );
+    let boards = parsing::sections(boards, board.map(|b| Board { nums: b.concat() }))?;
+    Ok((nums, boards))
+}
+
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day4").unwrap();
-    let mut lines = data.lines();
-    let nums = lines
-        .next()
-        .unwrap()
-        .trim()
-        .split(',')
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
-
-    let mut curr = vec![];
-    let mut boards = vec![];
-    for line in lines {
-        if line.is_empty() {
-            if !curr.is_empty() {
-                boards.push(Board::from(&curr));
-                curr.clear();
-            }
-        } else {
-            curr.push(line);
-        }
-    }
-    if !curr.is_empty() {
-        boards.push(Board::from(&curr));
-    }
+    let (nums, mut boards) = parse(&data).unwrap();
 
     println!("day4 part1: {}", part1(&nums, &mut boards));
     println!("day4 part2: {}", part2(&nums, &mut boards));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\n\n22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16  7\n 6 10  3 18  5\n 1 12 20 15 19\n\n 3 15  0  2 22\n 9 18 13 17  5\n19  8  7 25 23\n20 11 10 24  4\n14 21 16 12  6";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
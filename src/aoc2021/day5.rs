@@ -1,6 +1,35 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::AHashMap as HashMap;
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{char, space0},
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
 
-fn part1(lines: &[Vec<i64>]) -> usize {
+fn parse(data: &str) -> Result<Vec<[i64; 4]>, ParseError> {
+    // every point of a line goes into the map
+    let n = || {
+        context(
+            "a coordinate below 1000",
+            verify(unsigned, |&n: &i64| n < 1000),
+        )
+    };
+    let point = || separated_pair(n(), char(','), n());
+    let line =
+        separated_pair(point(), tag(" -> "), point()).map(|((x1, y1), (x2, y2))| [x1, y1, x2, y2]);
+    let line = context(
+        "a horizontal, vertical or diagonal line",
+        verify(line, |l: &[i64; 4]| {
+            l[0] == l[2] || l[1] == l[3] || (l[2] - l[0]).abs() == (l[3] - l[1]).abs()
+        }),
+    );
+    parsing::lines(data.trim(), preceded(space0, line))
+}
+
+fn part1(lines: &[[i64; 4]]) -> usize {
     let mut map: HashMap<(i64, i64), usize> = HashMap::new();
     for line in lines {
         let dx = (line[2] - line[0]).signum();
@@ -20,7 +49,7 @@ fn part1(lines: &[Vec<i64>]) -> usize {
     map.values().filter(|p| **p >= 2).count()
 }
 
-fn part2(lines: &[Vec<i64>]) -> usize {
+fn part2(lines: &[[i64; 4]]) -> usize {
     let mut map: HashMap<(i64, i64), usize> = HashMap::new();
     for line in lines {
         let dx = (line[2] - line[0]).signum();
@@ -39,14 +68,7 @@ fn part2(lines: &[Vec<i64>]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day5").unwrap();
-    let lines = data
-        .lines()
-        .map(|r| {
-            r.split("->")
-                .flat_map(|p| p.split(',').map(|n| n.trim().parse::<i64>().unwrap()))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+    let lines = parse(&data).unwrap();
 
     println!("day5 part1: {}", part1(&lines));
     println!("day5 part2: {}", part2(&lines));
@@ -55,6 +77,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -68,19 +91,14 @@ mod tests {
         3,4 -> 1,4
         0,0 -> 8,8
         5,5 -> 8,2";
-        let lines = data
-            .lines()
-            .map(|r| {
-                r.split("->")
-                    .flat_map(|p| {
-                        p.split(",")
-                            .map(|n| n.trim().parse::<i64>().unwrap())
-                            .collect::<Vec<_>>()
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+        let lines = parse(data).unwrap();
 
         assert_eq!(5, part1(&lines));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "0,9 -> 5,9\n8,0 -> 0,8\n9,4 -> 3,4\n2,2 -> 2,1\n7,0 -> 7,4\n6,4 -> 2,0\n0,9 -> 2,9\n3,4 -> 1,4\n0,0 -> 8,8\n5,5 -> 8,2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,16 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{character::complete::char, combinator::verify, error::context, multi::separated_list1};
+
+fn parse(data: &str) -> Result<[usize; 9], ParseError> {
+    let timer = context("a timer below 9", verify(unsigned::<usize>, |&t| t < 9));
+    let timers = parsing::parse_all(data.trim(), separated_list1(char(','), timer))?;
+    let mut count = [0usize; 9];
+    for t in timers {
+        count[t] += 1;
+    }
+    Ok(count)
+}
+
 fn part1(count: &mut [usize], days: usize) -> usize {
     for _ in 0..days {
         count.rotate_left(1);
@@ -8,11 +21,7 @@ fn part1(count: &mut [usize], days: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day6").unwrap();
-    let mut count = [0usize; 9];
-    data.split(',').for_each(|s| {
-        let f = s.trim().parse::<usize>().unwrap();
-        count[f] += 1;
-    });
+    let mut count = parse(&data).unwrap();
 
     println!("day6 part1: {}", part1(&mut count.clone(), 80));
     println!("day6 part2: {}", part1(&mut count, 256));
@@ -21,17 +30,20 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "3,4,3,1,2";
-        let mut count = [0usize; 9];
-        data.split(',').for_each(|s| {
-            let f = s.trim().parse::<usize>().unwrap();
-            count[f] += 1;
-        });
+        let mut count = parse(data).unwrap();
 
         assert_eq!(26, part1(&mut count.clone(), 18));
         assert_eq!(5934, part1(&mut count, 80));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "3,4,3,1,2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,12 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{Parser, character::complete::char, multi::separated_list1};
+
+fn parse(data: &str) -> Result<Vec<i64>, ParseError> {
+    // small enough for part 2's triangular fuel costs
+    let position = unsigned::<u16>.map(i64::from);
+    parsing::parse_all(data.trim(), separated_list1(char(','), position))
+}
+
 fn part1(pos: &[i64]) -> i64 {
     let n = pos.len();
     let m = pos[n / 2];
@@ -23,13 +32,21 @@ fn part2(pos: &[i64]) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day7").unwrap();
-    let mut pos = data
-        .trim()
-        .split(',')
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+    let mut pos = parse(&data).unwrap();
 
     pos.sort_unstable();
     println!("day7 part1: {}", part1(&pos));
     println!("day7 part2: {}", part2(&pos));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "16,1,2,0,4,2,7,1,2,14";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,3 +1,20 @@
+use adventofcode::parsing::{self, ParseError};
+use nom::{
+    Parser,
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, space0},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+};
+
+/// Segments of each digit, from `a` in bit 0 to `g` in bit 6.
+const DIGITS: [u8; 10] = [119, 36, 93, 109, 46, 107, 123, 37, 127, 111];
+
+/// The ten patterns seen, and the four output digits.
+type Note = (Vec<u8>, Vec<u8>);
+
 fn encode_digit(d: &str) -> u8 {
     let mut result = 0;
     for d in d.as_bytes() {
@@ -6,7 +23,7 @@ fn encode_digit(d: &str) -> u8 {
     result
 }
 
-fn part1(notes: &[(Vec<u8>, Vec<u8>)]) -> usize {
+fn part1(notes: &[Note]) -> usize {
     notes
         .iter()
         .map(|n| {
@@ -78,34 +95,84 @@ fn get_output(patterns: &[u8], outputs: &[u8]) -> usize {
     result
 }
 
-fn part2(notes: &[(Vec<u8>, Vec<u8>)]) -> usize {
+fn part2(notes: &[Note]) -> usize {
     notes.iter().map(|n| get_output(&n.0, &n.1)).sum()
 }
 
+/// Whether the patterns are the ten digits with their wires swapped, which
+/// the wires' counts give away: only `b`, `e` and `f` are lit in 6, 4 and 9
+/// digits, `c` is lit in 8 like `a` but is part of the 1, and `d` in 7 like
+/// `g` but is part of the 4.
+fn is_wiring(patterns: &[u8]) -> bool {
+    let with_len = |n| patterns.iter().find(|p| p.count_ones() == n).copied();
+    let (Some(one), Some(four)) = (with_len(2), with_len(4)) else {
+        return false;
+    };
+    let mut segment = [0; 7];
+    for (wire, segment) in segment.iter_mut().enumerate() {
+        let bit = 1 << wire;
+        *segment = match patterns.iter().filter(|&&p| p & bit != 0).count() {
+            8 if one & bit != 0 => 2,
+            8 => 0,
+            6 => 1,
+            7 if four & bit != 0 => 3,
+            7 => 6,
+            4 => 4,
+            9 => 5,
+            _ => return false,
+        };
+    }
+    let mut digits = patterns
+        .iter()
+        .map(|&p| {
+            (0..7)
+                .filter(|w| p & 1 << w != 0)
+                .fold(0, |d, w| d | 1 << segment[w])
+        })
+        .collect::<Vec<u8>>();
+    digits.sort_unstable();
+    let mut expected = DIGITS;
+    expected.sort_unstable();
+    digits == expected
+}
+
+fn parse(data: &str) -> Result<Vec<Note>, ParseError> {
+    let digit = || take_while1(|c| ('a'..='g').contains(&c)).map(encode_digit);
+    let digits = |n: usize| {
+        verify(separated_list1(char(' '), digit()), move |d: &Vec<_>| {
+            d.len() == n
+        })
+    };
+    let note = separated_pair(
+        context("10 patterns", digits(10)),
+        tag(" | "),
+        context("4 digits", digits(4)),
+    );
+    let note = context(
+        "the digits of a display",
+        verify(note, |(patterns, outputs)| {
+            is_wiring(patterns) && outputs.iter().all(|o| patterns.contains(o))
+        }),
+    );
+    parsing::lines(data.trim(), preceded(space0, note))
+}
+
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day8").unwrap();
-    let notes = data
-        .lines()
-        .map(|line| {
-            let mut parts = line.split('|');
-            let patterns = parts
-                .next()
-                .unwrap()
-                .trim()
-                .split(' ')
-                .map(encode_digit)
-                .collect::<Vec<_>>();
-            let output = parts
-                .next()
-                .unwrap()
-                .trim()
-                .split(' ')
-                .map(encode_digit)
-                .collect::<Vec<_>>();
-            (patterns, output)
-        })
-        .collect::<Vec<_>>();
+    let notes = parse(&data).unwrap();
 
     println!("day8 part1: {}", part1(&notes));
     println!("day8 part2: {}", part2(&notes));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe\nedbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc\nfgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,5 +1,21 @@
+use adventofcode::parsing::{self, ParseError};
 use std::collections::VecDeque;
 
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let grid = parsing::grid_of(data.trim(), "0123456789")?;
+    // walled in by 10s, above any height
+    let w = grid[0].len() + 2;
+    let mut heightmap = vec![vec![10; w]];
+    for row in grid {
+        let mut row = row.iter().map(|e| e - b'0').collect::<Vec<_>>();
+        row.push(10);
+        row.insert(0, 10);
+        heightmap.push(row);
+    }
+    heightmap.push(vec![10; w]);
+    Ok(heightmap)
+}
+
 fn part1(heightmap: &[Vec<u8>]) -> (usize, Vec<(usize, usize)>) {
     let h = heightmap.len();
     let w = heightmap[0].len();
@@ -57,18 +73,7 @@ fn part2(heightmap: &mut [Vec<u8>], basins: &[(usize, usize)]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2021/day9").unwrap();
-    let mut heightmap = data
-        .lines()
-        .map(|line| {
-            let mut row = line.as_bytes().iter().map(|e| e - b'0').collect::<Vec<_>>();
-            row.push(10);
-            row.insert(0, 10);
-            row
-        })
-        .collect::<Vec<_>>();
-    let w = heightmap[0].len();
-    heightmap.insert(0, vec![10; w]);
-    heightmap.push(vec![10; w]);
+    let mut heightmap = parse(&data).unwrap();
 
     let (p1, basins) = part1(&heightmap);
     println!("day9 part1: {p1}");
@@ -78,6 +83,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -109,4 +115,10 @@ mod tests {
         assert_eq!(15, p1);
         assert_eq!(1134, part2(&mut heightmap, &basins));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "2199943210\n3987894921\n9856789892\n8767896789\n9899965678";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
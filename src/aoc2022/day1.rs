@@ -1,12 +1,14 @@
-fn parse(data: &str) -> Vec<i32> {
-    data.split("\n\n")
-        .map(|e| {
-            e.trim()
-                .lines()
-                .map(|line| line.parse::<i32>().unwrap())
-                .sum()
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    character::complete::newline, combinator::map_opt, error::context, multi::separated_list1,
+};
+
+fn parse(data: &str) -> Result<Vec<i32>, ParseError> {
+    let items = separated_list1(newline, unsigned::<i32>);
+    let total = map_opt(items, |items| {
+        items.into_iter().try_fold(0, i32::checked_add)
+    });
+    parsing::sections(data, context("calories that add up", total))
 }
 
 fn part1(calories: &[i32]) -> i32 {
@@ -19,7 +21,7 @@ fn part2(calories: &[i32]) -> i32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day1").unwrap();
-    let mut calories = parse(&data);
+    let mut calories = parse(&data).unwrap();
     calories.sort_unstable();
     println!("part1: {}", part1(&calories));
     println!("part2: {}", part2(&calories));
@@ -28,6 +30,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -46,9 +49,15 @@ mod tests {
 9000
 
 10000";
-        let mut calories = parse(data);
+        let mut calories = parse(data).unwrap();
         calories.sort_unstable();
         assert_eq!(24000, part1(&calories));
         assert_eq!(45000, part2(&calories));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "1000\n2000\n\n4000\n\n5000\n6000";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
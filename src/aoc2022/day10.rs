@@ -1,13 +1,6 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{self as cc, line_ending},
-    combinator::all_consuming,
-    multi::separated_list1,
-    sequence::preceded,
-    IResult,
-    Parser,
-};
+use adventofcode::ocr;
+use adventofcode::parsing::{self, PResult, ParseError, signed};
+use nom::{Parser, branch::alt, bytes::complete::tag, sequence::preceded};
 
 #[derive(Debug)]
 enum Instruction {
@@ -15,22 +8,28 @@ enum Instruction {
     Noop,
 }
 
-fn parse_addx(s: &str) -> IResult<&str, Instruction> {
-    let (r, arg) = preceded(tag("addx "), cc::i64).parse(s)?;
-    Ok((r, Instruction::Addx(arg)))
+fn parse_addx(s: &str) -> PResult<'_, Instruction> {
+    let (r, arg) = preceded(tag("addx "), signed::<i32>).parse(s)?;
+    Ok((r, Instruction::Addx(arg.into())))
 }
 
-fn parse_noop(s: &str) -> IResult<&str, Instruction> {
-    let (r, _) = tag("noop")(s)?;
+fn parse_noop(s: &str) -> PResult<'_, Instruction> {
+    let (r, _) = tag("noop").parse(s)?;
     Ok((r, Instruction::Noop))
 }
 
-fn parse_instructions(s: &str) -> IResult<&str, Vec<Instruction>> {
-    separated_list1(line_ending, alt((parse_addx, parse_noop))).parse(s)
-}
-
-fn parse(data: &str) -> Vec<Instruction> {
-    all_consuming(parse_instructions).parse(data.trim()).unwrap().1
+fn parse(data: &str) -> Result<Vec<Instruction>, ParseError> {
+    let data = data.trim();
+    let instructions = parsing::lines(data, alt((parse_addx, parse_noop)))?;
+    let cycles = instructions.iter().map(|ins| match ins {
+        Instruction::Addx(_) => 2,
+        Instruction::Noop => 1,
+    });
+    // one cycle per pixel on the screen
+    if cycles.sum::<usize>() > 240 {
+        return Err(ParseError::at(data, data, "at most 240 cycles"));
+    }
+    Ok(instructions)
 }
 
 fn part1(instructions: &[Instruction]) -> i64 {
@@ -91,15 +90,19 @@ fn part2(instructions: &[Instruction]) -> String {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day10").unwrap();
-    let program = parse(&data);
+    let program = parse(&data).unwrap();
     println!("part1: {}", part1(&program));
-    println!("part2:");
-    println!("{}", part2(&program));
+    let img = part2(&program);
+    match ocr::recognize_str(&img) {
+        Ok(text) => println!("part2: {text}"),
+        Err(e) => println!("part2: {e}\n{img}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -250,7 +253,7 @@ addx -11
 noop
 noop
 noop";
-        let program = parse(data);
+        let program = parse(data).unwrap();
         assert_eq!(13140, part1(&program));
 
         let p2 = "##..##..##..##..##..##..##..##..##..##..
@@ -261,4 +264,10 @@ noop";
 #######.......#######.......#######.....";
         assert_eq!(p2, part2(&program));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "noop\naddx 3\naddx -5";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
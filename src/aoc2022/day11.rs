@@ -1,3 +1,15 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::char,
+    combinator::{consumed, verify},
+    error::context,
+    multi::separated_list1,
+    sequence::{delimited, preceded},
+};
+
 #[derive(Debug, Clone, Copy)]
 enum Arg {
     Old,
@@ -25,63 +37,71 @@ struct Monkey {
     inspected: u64,
 }
 
-fn parse(data: &str) -> Vec<Monkey> {
-    data.trim()
-        .split("\n\n")
-        .map(|m| {
-            let mut lines = m.lines();
-            lines.next();
-            let items = lines
-                .next()
-                .unwrap()
-                .trim_start_matches("  Starting items: ")
-                .split(", ")
-                .map(|i| i.parse().unwrap())
-                .collect();
-            let op = lines
-                .next()
-                .unwrap()
-                .trim_start_matches("  Operation: new = old ");
-            let arg = if &op[2..] == "old" {
-                Arg::Old
-            } else {
-                Arg::Num(op[2..].parse().unwrap())
-            };
-            let op = match op.as_bytes()[0] {
-                b'+' => Operation::Add(arg),
-                b'*' => Operation::Mul(arg),
-                _ => unreachable!(),
-            };
-            let divisible = lines
-                .next()
-                .unwrap()
-                .trim_start_matches("  Test: divisible by ")
-                .parse()
-                .unwrap();
-            let if_true = lines
-                .next()
-                .unwrap()
-                .trim_start_matches("    If true: throw to monkey ")
-                .parse()
-                .unwrap();
-            let if_false = lines
-                .next()
-                .unwrap()
-                .trim_start_matches("    If false: throw to monkey ")
-                .parse()
-                .unwrap();
-            Monkey {
-                items,
-                op,
-                test: Test {
-                    divisible,
-                    if_true,
-                    if_false,
-                },
-                inspected: 0,
-            }
-        })
-        .collect()
+fn arg(input: &str) -> PResult<'_, Arg> {
+    let num = unsigned::<u32>.map(|n| Arg::Num(n.into()));
+    alt((tag("old").map(|_| Arg::Old), num)).parse(input)
+}
+
+fn monkey(input: &str) -> PResult<'_, (usize, Monkey)> {
+    let id = delimited(tag("Monkey "), unsigned, char(':'));
+    // part 2 squares worry levels below the product of the divisors
+    let item = unsigned::<u32>.map(u64::from);
+    let items = preceded(
+        tag("\n  Starting items: "),
+        separated_list1(tag(", "), item),
+    );
+    let op = alt((
+        preceded(tag("+ "), arg).map(Operation::Add),
+        preceded(tag("* "), arg).map(Operation::Mul),
+    ));
+    let op = preceded(tag("\n  Operation: new = old "), op);
+    let divisible = context("a divisor", verify(unsigned, |&d: &u64| d > 0));
+    let test = (
+        preceded(tag("\n  Test: divisible by "), divisible),
+        preceded(tag("\n    If true: throw to monkey "), unsigned),
+        preceded(tag("\n    If false: throw to monkey "), unsigned),
+    );
+    let (rest, (id, items, op, (divisible, if_true, if_false))) =
+        (id, items, op, test).parse(input)?;
+    let test = Test {
+        divisible,
+        if_true,
+        if_false,
+    };
+    let monkey = Monkey {
+        items,
+        op,
+        test,
+        inspected: 0,
+    };
+    Ok((rest, (id, monkey)))
+}
+
+fn parse(data: &str) -> Result<Vec<Monkey>, ParseError> {
+    let monkeys = parsing::sections(data, consumed(monkey))?;
+    let n = monkeys.len();
+    let mut modulus = 1u64;
+    for (i, (block, (id, m))) in monkeys.iter().enumerate() {
+        if *id != i {
+            return Err(ParseError::at(data, block, format!("monkey {i}")));
+        }
+        if m.test.if_true >= n || m.test.if_false >= n {
+            return Err(ParseError::at(
+                data,
+                block,
+                format!("throws to monkeys below {n}"),
+            ));
+        }
+        modulus = modulus.saturating_mul(m.test.divisible);
+    }
+    if modulus > u32::MAX.into() {
+        return Err(ParseError::at(
+            data,
+            data,
+            "divisors with a product below 2^32",
+        ));
+    }
+    Ok(monkeys.into_iter().map(|(_, (_, m))| m).collect())
 }
 
 fn apply_operation(worry: u64, op: &Operation) -> u64 {
@@ -166,7 +186,7 @@ fn part2(mut monkeys: Vec<Monkey>) -> u64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day11").unwrap();
-    let monkeys = parse(&data);
+    let monkeys = parse(&data).unwrap();
     println!("part1: {}", part1(monkeys.clone()));
     println!("part2: {}", part2(monkeys));
 }
@@ -174,6 +194,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -205,8 +226,14 @@ Monkey 3:
   Test: divisible by 17
     If true: throw to monkey 0
     If false: throw to monkey 1";
-        let monkeys = parse(data);
+        let monkeys = parse(data).unwrap();
         assert_eq!(10605, part1(monkeys.clone()));
         assert_eq!(2713310158, part2(monkeys));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = old * 19\n  Test: divisible by 23\n    If true: throw to monkey 1\n    If false: throw to monkey 1\n\nMonkey 1:\n  Starting items: 54\n  Operation: new = old + 6\n  Test: divisible by 19\n    If true: throw to monkey 0\n    If false: throw to monkey 0";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,7 +1,20 @@
 use std::collections::VecDeque;
 
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|line| line.as_bytes()).collect()
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(data: &str) -> Result<Vec<&[u8]>, ParseError> {
+    let data = data.trim();
+    let map = parsing::grid_of(data, "abcdefghijklmnopqrstuvwxyzSE")?;
+    // positions are i16, and both searches need a start and an end
+    if map.len() > i16::MAX as usize || map[0].len() > i16::MAX as usize {
+        return Err(ParseError::at(data, data, "a smaller map"));
+    }
+    for p in ['S', 'E'] {
+        if data.matches(p).count() != 1 {
+            return Err(ParseError::at(data, data, format!("exactly one {p}")));
+        }
+    }
+    Ok(data.lines().map(str::as_bytes).collect())
 }
 
 fn find_pos(map: &[&[u8]], p: u8) -> (i16, i16) {
@@ -93,7 +106,7 @@ fn part2(map: &[&[u8]]) -> u32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day12").unwrap();
-    let map = parse(&data);
+    let map = parse(&data).unwrap();
     println!("part1: {}", part1(&map));
     println!("part2: {}", part2(&map));
 }
@@ -101,6 +114,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -110,8 +124,14 @@ abcryxxl
 accszExk
 acctuvwj
 abdefghi";
-        let map = parse(data);
+        let map = parse(data).unwrap();
         assert_eq!(31, part1(&map));
         assert_eq!(29, part2(&map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Sabqponm\nabcryxxl\naccszExk";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
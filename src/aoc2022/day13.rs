@@ -1,13 +1,19 @@
 use std::cmp::Ordering;
 
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
 use nom::{
-    character::complete,
-    combinator::map,
+    Parser,
+    branch::alt,
+    character::complete::{char, newline},
+    combinator::{cut, fail},
+    error::context,
     multi::separated_list0,
-    sequence::{preceded, terminated},
-    IResult, Parser,
+    sequence::{delimited, separated_pair},
 };
 
+/// Deep enough for any packet, shallow enough for the stack.
+const MAX_DEPTH: usize = 32;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Item {
     Value(u8),
@@ -36,31 +42,23 @@ fn compare_list(a: &[Item], b: &[Item]) -> Option<Ordering> {
     a.partial_cmp(b)
 }
 
-fn parse_item(s: &str) -> IResult<&str, Item> {
-    if s.as_bytes()[0] == b'[' {
-        map(parse_list, Item::List).parse(s)
-    } else {
-        map(complete::u8, Item::Value).parse(s)
-    }
+fn parse_item(s: &str, depth: usize) -> PResult<'_, Item> {
+    let list = |s| parse_list(s, depth + 1);
+    alt((unsigned.map(Item::Value), list.map(Item::List))).parse(s)
 }
 
-fn parse_list(s: &str) -> IResult<&str, Vec<Item>> {
-    preceded(
-        complete::char('['),
-        terminated(
-            separated_list0(complete::char(','), parse_item),
-            complete::char(']'),
-        ),
-    )
-    .parse(s)
+fn parse_list(s: &str, depth: usize) -> PResult<'_, Vec<Item>> {
+    if depth > MAX_DEPTH {
+        return cut(context("a shallower list", fail())).parse(s);
+    }
+    let items = separated_list0(char(','), |s| parse_item(s, depth));
+    delimited(char('['), items, char(']')).parse(s)
 }
 
-fn parse(data: &str) -> Vec<Vec<Item>> {
-    data.trim()
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| parse_list(line).unwrap().1)
-        .collect()
+fn parse(data: &str) -> Result<Vec<Vec<Item>>, ParseError> {
+    let packet = |s| parse_list(s, 1);
+    let pairs = parsing::sections(data, separated_pair(packet, newline, packet))?;
+    Ok(pairs.into_iter().flat_map(|(a, b)| [a, b]).collect())
 }
 
 fn part1(signals: &[Vec<Item>]) -> usize {
@@ -72,7 +70,7 @@ fn part1(signals: &[Vec<Item>]) -> usize {
 }
 
 fn part2(mut signals: Vec<Vec<Item>>) -> usize {
-    let dividers = parse("[[2]]\n[[6]]");
+    let dividers = parse("[[2]]\n[[6]]").unwrap();
     signals.extend(dividers.clone());
     signals.sort_unstable();
     dividers
@@ -83,7 +81,7 @@ fn part2(mut signals: Vec<Vec<Item>>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day13").unwrap();
-    let signals = parse(&data);
+    let signals = parse(&data).unwrap();
     println!("part1: {}", part1(&signals));
     println!("part2: {}", part2(signals));
 }
@@ -91,6 +89,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -118,8 +117,14 @@ mod tests {
 
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]";
-        let signals = parse(data);
+        let signals = parse(data).unwrap();
         assert_eq!(13, part1(&signals));
         assert_eq!(140, part2(signals));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "[1,1,3,1,1]\n[1,1,5,1,1]\n\n[[1],[2,3,4]]\n[[1],4]\n\n[]\n[3]";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,31 +1,35 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::{
+    Parser, bytes::complete::tag, character::complete::char, combinator::verify, error::context,
+    multi::separated_list1, sequence::separated_pair,
+};
+
 struct Scan {
     rocks: Vec<Vec<(i16, i16)>>,
     min: (i16, i16),
     max: (i16, i16),
 }
 
-fn parse(data: &str) -> Scan {
+fn point(input: &str) -> PResult<'_, (i16, i16)> {
+    let coordinate = || verify(unsigned, |&c: &i16| c <= 1000);
+    let point = separated_pair(coordinate(), char(','), coordinate());
+    context("a point within 0 to 1000", point).parse(input)
+}
+
+fn parse(data: &str) -> Result<Scan, ParseError> {
+    let data = data.trim();
+    let rocks = parsing::lines(data, separated_list1(tag(" -> "), point))?;
     let mut min = (i16::MAX, i16::MAX);
     let mut max = (i16::MIN, i16::MIN);
-    let rocks = data
-        .trim()
-        .lines()
-        .map(|line| {
-            line.split(" -> ")
-                .map(|p| {
-                    let (a, b) = p.split_once(',').unwrap();
-                    let x = a.parse().unwrap();
-                    let y = b.parse().unwrap();
-                    min.0 = min.0.min(x);
-                    min.1 = min.1.min(y);
-                    max.0 = max.0.max(x);
-                    max.1 = max.1.max(y);
-                    (x, y)
-                })
-                .collect()
-        })
-        .collect();
-    Scan { rocks, min, max }
+    for &(x, y) in rocks.iter().flatten() {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    // the first map is only as wide as the rocks, and the sand falls from x=500
+    if !(min.0..=max.0).contains(&500) {
+        return Err(ParseError::at(data, data, "rocks left and right of x=500"));
+    }
+    Ok(Scan { rocks, min, max })
 }
 
 fn fill_rocks<F: Fn(i16) -> usize>(
@@ -129,7 +133,7 @@ fn part2(s: &Scan) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day14").unwrap();
-    let scan = parse(&data);
+    let scan = parse(&data).unwrap();
     println!("part1: {}", part1(&scan));
     println!("part2: {}", part2(&scan));
 }
@@ -137,14 +141,21 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "
 498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9";
-        let scan = parse(data);
+        let scan = parse(data).unwrap();
         assert_eq!(24, part1(&scan));
         assert_eq!(93, part2(&scan));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
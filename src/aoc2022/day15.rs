@@ -1,28 +1,26 @@
 use std::collections::BTreeMap;
 
+use adventofcode::parsing::{self, ParseError, fixed_ints};
 use ahash::HashSet;
+use nom::{combinator::verify, error::context};
 use rayon::prelude::*;
 
 fn manhattan_distance(x1: i32, y1: i32, x2: i32, y2: i32) -> i32 {
     (x1 - x2).abs() + (y1 - y2).abs()
 }
 
-fn parse(data: &str) -> Vec<Vec<i32>> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let mut p = line
-                .split_ascii_whitespace()
-                .filter(|p| p.contains('='))
-                .map(|p| {
-                    let p = p.trim_end_matches([',', ':']);
-                    p[2..].parse().unwrap()
-                })
-                .collect::<Vec<i32>>();
-            p.push(manhattan_distance(p[0], p[1], p[2], p[3]));
-            p
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    // distances and the edges of the covered ranges have to fit in an i32
+    let bounded = |p: &[i32; 4]| p.iter().all(|c| c.abs() <= 100_000_000);
+    let sensor = context(
+        "coordinates within 100000000",
+        verify(fixed_ints::<4, i32>, bounded),
+    );
+    let sensors = parsing::lines(data.trim(), sensor)?;
+    Ok(sensors
+        .into_iter()
+        .map(|[x1, y1, x2, y2]| vec![x1, y1, x2, y2, manhattan_distance(x1, y1, x2, y2)])
+        .collect())
 }
 
 fn part1(sensors: &[Vec<i32>], y0: i32) -> usize {
@@ -158,7 +156,7 @@ fn part2(sensors: &[Vec<i32>], max: i32) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day15").unwrap();
-    let sensors = parse(&data);
+    let sensors = parse(&data).unwrap();
     println!("part1: {}", part1(&sensors, 200_0000));
     println!("part2: {}", part2(&sensors, 400_0000));
 }
@@ -166,6 +164,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -184,9 +183,18 @@ Sensor at x=17, y=20: closest beacon is at x=21, y=22
 Sensor at x=16, y=7: closest beacon is at x=15, y=3
 Sensor at x=14, y=3: closest beacon is at x=15, y=3
 Sensor at x=20, y=1: closest beacon is at x=15, y=3";
-        let sensors = parse(data);
+        let sensors = parse(data).unwrap();
         assert_eq!(26, part1(&sensors, 10));
         assert_eq!(56000011, part2_scanline(&sensors, 20));
         assert_eq!(56000011, part2(&sensors, 20));
+
+        let e = parse("Sensor at x=2, y=18: closest beacon is at x=-2").unwrap_err();
+        assert_eq!("another integer", e.expected);
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\nSensor at x=9, y=16: closest beacon is at x=10, y=16";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
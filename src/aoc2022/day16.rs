@@ -4,10 +4,23 @@ use std::{
     sync::atomic::{AtomicU16, Ordering},
 };
 
+use adventofcode::dot::{self, Dot};
+use adventofcode::exhaustive::{Max, Memo, Problem, Search};
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
 use ahash::HashMap;
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+    sequence::preceded,
+};
 use rayon::prelude::*;
 
 struct Valve {
+    name: String,
     flow_rate: u16,
     connected: Vec<u8>,
 }
@@ -17,16 +30,32 @@ struct Map {
     valves: Vec<Valve>,
     paths: Vec<Vec<i8>>,
     m: usize,
-    cache: Vec<AtomicU16>,
+    cache: Cache,
+}
+
+/// Dense table indexed by `Map::key`, shared by all threads.
+struct Cache(Vec<AtomicU16>);
+
+impl Memo<usize, u16> for &Cache {
+    fn get(&mut self, &key: &usize) -> Option<Option<u16>> {
+        let p = self.0[key].load(Ordering::Relaxed);
+        (p < u16::MAX).then_some(Some(p))
+    }
+
+    fn insert(&mut self, key: usize, value: Option<u16>) {
+        self.0[key].store(value.unwrap(), Ordering::Relaxed);
+    }
 }
 
 impl Map {
     fn new(start: u8, valves: Vec<Valve>) -> Self {
         let m = valves.iter().filter(|v| v.flow_rate > 0).count();
         let paths = shortest_paths(&valves);
-        let cache = (0..30 * (m + 1) * (1 << m))
-            .map(|_| AtomicU16::new(u16::MAX))
-            .collect();
+        let cache = Cache(
+            (0..30 * (m + 1) * (1 << m))
+                .map(|_| AtomicU16::new(u16::MAX))
+                .collect(),
+        );
         Self {
             start,
             valves,
@@ -37,42 +66,87 @@ impl Map {
     }
 }
 
-fn parse(data: &str) -> Map {
-    let mut valves = data
-        .trim()
-        .lines()
-        .map(|line| {
-            let (p1, p2) = line.split_once("; ").unwrap();
-            let (id, flow_rate) = p1.split_once('=').unwrap();
-            let id = &id[6..8];
-            let flow_rate = flow_rate.parse().unwrap();
-            let connected = p2
-                .trim_start_matches(|c: char| c.is_ascii_lowercase() || c.is_ascii_whitespace())
-                .split(", ")
-                .collect::<Vec<_>>();
-            (id, flow_rate, connected)
-        })
-        .collect::<Vec<_>>();
+fn valve(input: &str) -> PResult<'_, (&str, u16, Vec<&str>)> {
+    let name = || {
+        context(
+            "a valve name",
+            take_while_m_n(2, 2, |c: char| c.is_ascii_uppercase()),
+        )
+    };
+    // rates stay small so that 30 minutes of all of them fit in a u16
+    let rate = context("a rate below 100", verify(unsigned, |&r: &u16| r < 100));
+    let tunnels = alt((
+        tag("; tunnels lead to valves "),
+        tag("; tunnel leads to valve "),
+    ));
+    (
+        preceded(tag("Valve "), name()),
+        preceded(tag(" has flow rate="), rate),
+        preceded(tunnels, separated_list1(tag(", "), name())),
+    )
+        .parse(input)
+}
+
+fn parse(data: &str) -> Result<Map, ParseError> {
+    let data = data.trim();
+    let mut valves = parsing::lines(data, valve)?;
+    // valve sets are bitmasks, and the cache doubles with each working valve
+    if valves.len() > 64 {
+        return Err(ParseError::at(data, valves[64].0, "at most 64 valves"));
+    }
+    if let Some(v) = valves.iter().filter(|v| v.1 > 0).nth(15) {
+        return Err(ParseError::at(data, v.0, "at most 15 valves with a flow"));
+    }
     valves.sort_unstable_by_key(|v| (Reverse(v.1), v.0));
 
-    let ids = valves
-        .iter()
-        .enumerate()
-        .map(|(i, v)| (v.0, i as u8))
-        .collect::<HashMap<_, _>>();
-    let start = *ids.get("AA").unwrap();
-
-    let valves = valves
-        .into_iter()
-        .map(|(_id, flow_rate, conn)| {
-            let connected = conn.iter().map(|c| *ids.get(c).unwrap()).collect();
-            Valve {
-                flow_rate,
-                connected,
+    let mut ids = HashMap::default();
+    for (i, v) in valves.iter().enumerate() {
+        if ids.insert(v.0, i as u8).is_some() {
+            return Err(ParseError::at(data, v.0, "a new valve name"));
+        }
+    }
+    let start = *ids
+        .get("AA")
+        .ok_or_else(|| ParseError::at(data, data, "a valve AA"))?;
+
+    let mut map = vec![];
+    for (id, flow_rate, conn) in valves {
+        let mut connected = vec![];
+        for c in conn {
+            let &c = ids
+                .get(c)
+                .ok_or_else(|| ParseError::at(data, c, "a known valve"))?;
+            connected.push(c);
+        }
+        map.push(Valve {
+            name: id.to_string(),
+            flow_rate,
+            connected,
+        });
+    }
+    Ok(Map::new(start, map))
+}
+
+/// Tunnels between valves, the ones worth opening labeled with their rate.
+fn to_dot(map: &Map) -> Dot {
+    let mut dot = Dot::graph();
+    for (i, v) in map.valves.iter().enumerate() {
+        let label = format!("{} ({})", v.name, v.flow_rate);
+        let shape = if i == map.start as usize {
+            "doublecircle"
+        } else {
+            "ellipse"
+        };
+        if v.flow_rate > 0 || i == map.start as usize {
+            dot.node(&v.name, &[("label", &label), ("shape", shape)]);
+        }
+        for &c in &v.connected {
+            if (c as usize) > i {
+                dot.edge(&v.name, &map.valves[c as usize].name, None);
             }
-        })
-        .collect::<Vec<_>>();
-    Map::new(start, valves)
+        }
+    }
+    dot
 }
 
 fn shortest_paths_from(start: u8, valves: &[Valve]) -> Vec<i8> {
@@ -97,32 +171,49 @@ fn shortest_paths(valves: &[Valve]) -> Vec<Vec<i8>> {
         .collect()
 }
 
-fn dfs(t: i8, curr: u8, valves_to_open: u16, map: &Map) -> u16 {
-    if t <= 1 {
-        return 0;
+/// State is (minutes left, current valve, valves still to open).
+impl Problem for Map {
+    type State = (i8, u8, u16);
+    type Key = usize;
+    type Value = u16;
+    type Objective = Max;
+
+    fn key(&self, &(t, curr, valves_to_open): &Self::State) -> Option<usize> {
+        (t > 1).then(|| {
+            (t - 1) as usize * (self.m + 1) * (1 << self.m)
+                + curr as usize * (1 << self.m)
+                + valves_to_open as usize
+        })
     }
-    let idx = (t - 1) as usize * (map.m + 1) * (1 << map.m)
-        + curr as usize * (1 << map.m)
-        + valves_to_open as usize;
-    let p = map.cache[idx].load(Ordering::Relaxed);
-    if p < u16::MAX {
-        return p;
+
+    fn stop(&self, _: &Self::State) -> Option<u16> {
+        Some(0)
     }
-    let mut result = 0;
-    for next in 0..map.m as u8 {
-        if valves_to_open & (1 << next) == 0 {
-            continue;
-        }
-        let dist = map.paths[curr as usize][next as usize];
-        let t1 = t - (dist + 1);
-        if t1 <= 0 {
-            continue;
+
+    fn children(
+        &self,
+        &(t, curr, valves_to_open): &Self::State,
+        out: &mut Vec<(u16, Self::State)>,
+    ) {
+        for next in 0..self.m as u8 {
+            if valves_to_open & (1 << next) == 0 {
+                continue;
+            }
+            let dist = self.paths[curr as usize][next as usize];
+            let t1 = t - (dist + 1);
+            if t1 <= 0 {
+                continue;
+            }
+            let p = self.valves[next as usize].flow_rate * t1 as u16;
+            out.push((p, (t1, next, valves_to_open - (1 << next))));
         }
-        let p = map.valves[next as usize].flow_rate * t1 as u16;
-        result = result.max(p + dfs(t1, next, valves_to_open - (1 << next), map));
     }
-    map.cache[idx].store(result, Ordering::Relaxed);
-    result
+}
+
+fn dfs(t: i8, curr: u8, valves_to_open: u16, map: &Map) -> u16 {
+    Search::with_memo(map, &map.cache)
+        .solve(&(t, curr, valves_to_open))
+        .unwrap()
 }
 
 fn part1(t: i8, start: u8, valves_to_open: u16, map: &Map) -> u16 {
@@ -150,7 +241,8 @@ fn part2(map: &Map) -> u16 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day16").unwrap();
-    let map = parse(&data);
+    let map = parse(&data).unwrap();
+    dot::export(|| to_dot(&map));
     println!("part1: {}", part1(30, map.start, (1 << map.m) - 1, &map));
     println!("part2: {}", part2(&map));
 }
@@ -158,6 +250,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -172,8 +265,14 @@ Valve GG has flow rate=0; tunnels lead to valves FF, HH
 Valve HH has flow rate=22; tunnel leads to valve GG
 Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II";
-        let map = parse(data);
+        let map = parse(data).unwrap();
         assert_eq!(1651, part1(30, map.start, (1 << map.m) - 1, &map));
         assert_eq!(1707, part2(&map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Valve AA has flow rate=0; tunnels lead to valves DD, BB\nValve BB has flow rate=13; tunnels lead to valves CC, AA\nValve CC has flow rate=2; tunnel leads to valve BB\nValve DD has flow rate=20; tunnel leads to valve AA";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,5 +1,7 @@
-use ahash::HashMap;
+use adventofcode::cycle::Detector;
+use adventofcode::parsing::{self, ParseError};
 use arrayvec::ArrayVec;
+use nom::{bytes::complete::take_while1, error::context};
 
 macro_rules! avec {
     ($($x:expr),+ $(,)?) => {
@@ -13,8 +15,9 @@ macro_rules! avec {
     };
 }
 
-fn parse(data: &str) -> &[u8] {
-    data.trim().as_bytes()
+fn parse(data: &str) -> Result<&[u8], ParseError> {
+    let jets = take_while1(|c| c == '<' || c == '>');
+    parsing::parse_all(data.trim(), context("jets", jets)).map(str::as_bytes)
 }
 
 fn rock_height(rock: u8) -> usize {
@@ -188,32 +191,24 @@ fn part2(tower: &mut Tower) -> usize {
     const N: usize = 1000000000000;
     // height of the top of the tower for hash
     const M: usize = 100;
-    let mut seen = HashMap::default();
-    let c = &tower.chamber;
-    seen.insert(
-        (c[c.len() - M..].to_vec(), tower.next_rock, tower.next_jet),
-        (2022, c.len()),
-    );
-    for i in 2023.. {
-        tower.fall();
+    let start = 2022;
+    let mut detector = Detector::new();
+    let mut heights = vec![];
+    loop {
         let c = &tower.chamber;
-        if let Some((i1, h1)) = seen.insert(
-            (c[c.len() - M..].to_vec(), tower.next_rock, tower.next_jet),
-            (i, c.len()),
-        ) {
-            let period = i - i1;
-            let delta_h = c.len() - h1;
-            let rem = (N - i) % period;
-            let dh = seen.values().find(|&&(k, _)| k == i1 + rem).unwrap().1 - h1;
-            return c.len() + ((N - i) / period) * delta_h + dh;
+        heights.push(c.len() as i64);
+        if let Some(cycle) =
+            detector.push((c[c.len() - M..].to_vec(), tower.next_rock, tower.next_jet))
+        {
+            return cycle.extrapolate(&heights, N - start) as usize;
         }
+        tower.fall();
     }
-    unreachable!()
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day17").unwrap();
-    let jets = parse(&data);
+    let jets = parse(&data).unwrap();
     let mut tower = Tower::new(jets);
     println!("part1: {}", part1(&mut tower));
     println!("part2: {}", part2(&mut tower));
@@ -222,13 +217,20 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-        let jets = parse(data);
+        let jets = parse(data).unwrap();
         let mut tower = Tower::new(jets);
         assert_eq!(3068, part1(&mut tower));
         assert_eq!(1514285714288, part2(&mut tower));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,16 +1,17 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::HashSet;
+use nom::{character::complete::char, combinator::verify, error::context, sequence::preceded};
 
-fn parse(data: &str) -> HashSet<(i8, i8, i8)> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let mut p = line.split(',');
-            let a = p.next().unwrap().parse().unwrap();
-            let b = p.next().unwrap().parse().unwrap();
-            let c = p.next().unwrap().parse().unwrap();
-            (a, b, c)
-        })
-        .collect()
+fn parse(data: &str) -> Result<HashSet<(i8, i8, i8)>, ParseError> {
+    // the flood fill recurses once per cube of air around the droplet
+    let coordinate = || verify(unsigned, |&c: &i8| c <= 24);
+    let cube = (
+        coordinate(),
+        preceded(char(','), coordinate()),
+        preceded(char(','), coordinate()),
+    );
+    let cubes = parsing::lines(data.trim(), context("coordinates from 0 to 24", cube))?;
+    Ok(cubes.into_iter().collect())
 }
 
 fn neighbors((x, y, z): (i8, i8, i8)) -> [(i8, i8, i8); 6] {
@@ -94,7 +95,7 @@ fn part2(scan: &HashSet<(i8, i8, i8)>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day18").unwrap();
-    let scan = parse(&data);
+    let scan = parse(&data).unwrap();
     println!("part1: {}", part1(&scan));
     println!("part2: {}", part2(&scan));
 }
@@ -102,6 +103,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -119,7 +121,7 @@ mod tests {
 3,2,5
 2,1,5
 2,3,5";
-        let scan = parse(data);
+        let scan = parse(data).unwrap();
         assert_eq!(64, part1(&scan));
         assert_eq!(58, part2(&scan));
     }
@@ -129,7 +131,13 @@ mod tests {
         let data = "
 1,1,1
 2,1,1";
-        let scan = parse(data);
+        let scan = parse(data).unwrap();
         assert_eq!(10, part1(&scan));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "2,2,2\n1,2,2\n3,2,2\n2,1,2\n2,3,2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,5 +1,5 @@
-use ahash::HashMap;
-use rayon::prelude::*;
+use adventofcode::exhaustive::{self, Max, Problem};
+use adventofcode::parsing::{self, ParseError, fixed_ints};
 
 type Int = u16;
 
@@ -70,18 +70,16 @@ impl Factory {
     }
 }
 
-fn parse(data: &str) -> Vec<Blueprint> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let mut p = line
-                .split_ascii_whitespace()
-                .filter_map(|p| p.parse::<Int>().ok());
+fn parse(data: &str) -> Result<Vec<Blueprint>, ParseError> {
+    let blueprints = parsing::lines(data.trim(), fixed_ints::<7, Int>)?;
+    Ok(blueprints
+        .into_iter()
+        .map(|[_, ore, clay, obs_ore, obs_clay, geo_ore, geo_obs]| {
             let resources = [
-                [p.next().unwrap(), 0, 0],
-                [p.next().unwrap(), 0, 0],
-                [p.next().unwrap(), p.next().unwrap(), 0],
-                [p.next().unwrap(), 0, p.next().unwrap()],
+                [ore, 0, 0],
+                [clay, 0, 0],
+                [obs_ore, obs_clay, 0],
+                [geo_ore, 0, geo_obs],
             ];
             let mut max = [0; 3];
             max.iter_mut().enumerate().for_each(|(i, e)| {
@@ -89,37 +87,27 @@ fn parse(data: &str) -> Vec<Blueprint> {
             });
             Blueprint { resources, max }
         })
-        .collect()
+        .collect())
 }
 
-fn dfs(
-    t: u8,
-    f: Factory,
-    bp: &Blueprint,
-    best: &mut Int,
-    cache: &mut HashMap<(u8, Factory), Int>,
-) -> Int {
-    if t <= 1 {
-        let geodes = if t == 1 {
-            f.resources[3] + f.robots[3]
-        } else {
-            f.resources[3]
-        };
-        *best = (*best).max(geodes);
-        return geodes;
-    }
+/// State is (blueprint, minutes left, factory), the geodes are only counted
+/// when the search stops building robots.
+struct Geodes<'a> {
+    blueprints: &'a [Blueprint],
+}
 
-    let key = (t, f);
-    if let Some(&r) = cache.get(&key) {
-        return r;
-    }
+impl Problem for Geodes<'_> {
+    type State = (usize, u8, Factory);
+    type Key = Self::State;
+    type Value = Int;
+    type Objective = Max;
 
-    let mut max = f.resources[3] + f.robots[3] * t as Int;
+    fn key(&self, &state: &Self::State) -> Option<Self::Key> {
+        Some(state)
+    }
 
-    // maximum possible geodes we can get assuming unlimited intermediate resources
-    let max_possible = max + t as Int * (t as Int - 1) / 2;
-    if max_possible <= *best {
-        return 0;
+    fn stop(&self, &(_, t, f): &Self::State) -> Option<Int> {
+        Some(f.resources[3] + f.robots[3] * t as Int)
     }
 
     // The assumption "If we can build a geode robot, choose to build it rather
@@ -127,37 +115,43 @@ fn dfs(
 
     // Fast forward to the minute we can build a type of robot.
     // This cut the branches significantly!!!
-    for i in 0..4 {
-        if let Some((t1, f1)) = f.try_build_robot(t, i, bp) {
-            max = max.max(dfs(t1, f1, bp, best, cache));
+    fn children(&self, &(i, t, f): &Self::State, out: &mut Vec<(Int, Self::State)>) {
+        for robot in 0..4 {
+            if let Some((t1, f1)) = f.try_build_robot(t, robot, &self.blueprints[i]) {
+                out.push((0, (i, t1, f1)));
+            }
         }
     }
 
-    cache.insert(key, max);
-    max
+    /// Maximum possible geodes we can get assuming unlimited intermediate
+    /// resources, a geode robot built every minute.
+    fn bound(&self, state: &Self::State) -> Option<Int> {
+        let t = state.1 as Int;
+        Some(self.stop(state)? + t * t.saturating_sub(1) / 2)
+    }
 }
 
 fn solve(blueprints: &[Blueprint]) -> (usize, usize) {
-    blueprints
-        .par_iter()
-        .enumerate()
-        .map(|(i, bp)| {
-            let mut cache = HashMap::default();
-            let f = Factory::default();
-            let p1 = (i + 1) * dfs(24, f, bp, &mut 0, &mut cache) as usize;
-            let p2 = if i < 3 {
-                dfs(32, f, bp, &mut 0, &mut cache) as usize
-            } else {
-                1
-            };
-            (p1, p2)
-        })
-        .reduce(|| (0, 1), |r, e| (r.0 + e.0, r.1 * e.1))
+    let f = Factory::default();
+    let mut roots = (0..blueprints.len())
+        .map(|i| (i, 24, f))
+        .collect::<Vec<_>>();
+    roots.extend((0..blueprints.len().min(3)).map(|i| (i, 32, f)));
+    let (geodes, _) = exhaustive::par_solve(&Geodes { blueprints }, &roots);
+    let geodes = geodes.into_iter().map(|g| g.unwrap() as usize);
+    let (p1, p2) = geodes.enumerate().fold((0, 1), |(p1, p2), (i, g)| {
+        if i < blueprints.len() {
+            (p1 + (i + 1) * g, p2)
+        } else {
+            (p1, p2 * g)
+        }
+    });
+    (p1, p2)
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day19").unwrap();
-    let blueprints = parse(&data);
+    let blueprints = parse(&data).unwrap();
     let (p1, p2) = solve(&blueprints);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -166,15 +160,22 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "
 Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
 Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
-        let blueprints = parse(data);
+        let blueprints = parse(data).unwrap();
         let (p1, p2) = solve(&blueprints);
         assert_eq!(33, p1);
         assert_eq!(56 * 62, p2);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
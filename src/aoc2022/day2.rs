@@ -1,11 +1,13 @@
-fn parse(data: &str) -> Vec<(u8, u8)> {
-    data.trim()
-        .lines()
-        .map(|e| {
-            let e = e.as_bytes();
-            (e[0] - b'A', e[2] - b'X')
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError};
+use nom::{
+    Parser,
+    character::complete::{char, one_of},
+};
+
+fn parse(data: &str) -> Result<Vec<(u8, u8)>, ParseError> {
+    let round = (one_of("ABC"), char(' '), one_of("XYZ"));
+    let round = round.map(|(o, _, m)| (o as u8 - b'A', m as u8 - b'X'));
+    parsing::lines(data.trim(), round)
 }
 
 fn part1(strategy: &[(u8, u8)]) -> u32 {
@@ -30,7 +32,7 @@ fn part2(strategy: &[(u8, u8)]) -> u32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day2").unwrap();
-    let strategy = parse(&data);
+    let strategy = parse(&data).unwrap();
     println!("part1: {}", part1(&strategy));
     println!("part2: {}", part2(&strategy));
 }
@@ -38,6 +40,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -45,8 +48,14 @@ mod tests {
 A Y
 B X
 C Z";
-        let strategy = parse(data);
+        let strategy = parse(data).unwrap();
         assert_eq!(15, part1(&strategy));
         assert_eq!(12, part2(&strategy));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "A Y\nB X\nC Z";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
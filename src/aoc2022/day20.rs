@@ -1,10 +1,19 @@
 use std::collections::VecDeque;
 
-fn parse(data: &str) -> Vec<i64> {
-    data.trim()
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect()
+use adventofcode::parsing::{self, ParseError, signed};
+use nom::Parser;
+
+fn parse(data: &str) -> Result<Vec<i64>, ParseError> {
+    let data = data.trim();
+    // part 2 multiplies each number by a 30-bit key
+    let nums = parsing::lines(data, signed::<i32>.map(i64::from))?;
+    if nums.len() < 2 {
+        return Err(ParseError::at(data, data, "at least 2 numbers"));
+    }
+    if nums.iter().filter(|&&e| e == 0).count() != 1 {
+        return Err(ParseError::at(data, data, "exactly one 0"));
+    }
+    Ok(nums)
 }
 
 fn mixing(nums: &mut [i64], key: i64, repeat: usize) -> i64 {
@@ -39,7 +48,7 @@ fn mixing(nums: &mut [i64], key: i64, repeat: usize) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day20").unwrap();
-    let mut nums = parse(&data);
+    let mut nums = parse(&data).unwrap();
     println!("part1: {}", mixing(&mut nums, 1, 1));
     println!("part2: {}", mixing(&mut nums, 811589153, 10));
 }
@@ -47,6 +56,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -58,8 +68,14 @@ mod tests {
 -2
 0
 4";
-        let mut nums = parse(data);
+        let mut nums = parse(data).unwrap();
         assert_eq!(3, mixing(&mut nums, 1, 1));
         assert_eq!(1623178306, mixing(&mut nums, 811589153, 10));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "1\n2\n-3\n3\n-2\n0\n4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,27 +1,58 @@
+use adventofcode::parsing::{self, PResult, ParseError, signed};
 use ahash::HashMap;
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, one_of},
+    error::context,
+    sequence::{delimited, separated_pair},
+};
 
 enum Job<'a> {
     Num(i64),
     Op((&'a str, u8, &'a str)),
 }
 
-fn parse(data: &str) -> HashMap<&str, Job<'_>> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (id, job) = line.split_once(": ").unwrap();
-            let job = if let Ok(n) = job.parse::<i64>() {
-                Job::Num(n)
-            } else {
-                let mut job = job.split_ascii_whitespace();
-                let a = job.next().unwrap();
-                let b = job.next().unwrap().as_bytes()[0];
-                let c = job.next().unwrap();
-                Job::Op((a, b, c))
-            };
-            (id, job)
-        })
-        .collect()
+fn name(input: &str) -> PResult<'_, &str> {
+    context(
+        "a monkey name",
+        take_while_m_n(4, 4, |c: char| c.is_ascii_lowercase()),
+    )
+    .parse(input)
+}
+
+fn job(input: &str) -> PResult<'_, Job<'_>> {
+    let op = (name, delimited(char(' '), one_of("+-*/"), char(' ')), name);
+    let op = op.map(|(a, b, c)| Job::Op((a, b as u8, c)));
+    alt((signed.map(Job::Num), op)).parse(input)
+}
+
+fn parse(data: &str) -> Result<HashMap<&str, Job<'_>>, ParseError> {
+    let data = data.trim();
+    let mut jobs = HashMap::default();
+    for (id, job) in parsing::lines(data, separated_pair(name, tag(": "), job))? {
+        if jobs.insert(id, job).is_some() {
+            return Err(ParseError::at(data, id, "a new monkey"));
+        }
+    }
+    for job in jobs.values() {
+        if let Job::Op((a, _, c)) = job {
+            for id in [a, c] {
+                if !jobs.contains_key(id) {
+                    return Err(ParseError::at(data, id, "a known monkey"));
+                }
+            }
+        }
+    }
+    if !matches!(jobs.get("root"), Some(Job::Op(_))) || !jobs.contains_key("humn") {
+        return Err(ParseError::at(
+            data,
+            data,
+            "an operation for root and a humn",
+        ));
+    }
+    Ok(jobs)
 }
 
 fn dfs<'a>(jobs: &'a HashMap<&str, Job>, m: &mut HashMap<&'a str, i64>, id: &'a str) -> i64 {
@@ -165,7 +196,7 @@ fn part2(jobs: &HashMap<&str, Job>) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day21").unwrap();
-    let jobs = parse(&data);
+    let jobs = parse(&data).unwrap();
     println!("part1: {}", part1(&jobs));
     println!("part2: {}", part2(&jobs));
 }
@@ -173,6 +204,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -192,8 +224,14 @@ pppw: cczh / lfqf
 lgvd: ljgn * ptdq
 drzm: hmdt - zczc
 hmdt: 32";
-        let jobs = parse(data);
+        let jobs = parse(data).unwrap();
         assert_eq!(152, part1(&jobs));
         assert_eq!(301, part2(&jobs));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "root: pppw + sjmn\ndbpl: 5\npppw: humn / dbpl\nsjmn: dbpl * dbpl\nhumn: 5";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,6 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{Parser, branch::alt, character::complete::one_of, multi::many1};
+
 const DIR: [(i64, i64); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
 
 enum Directive {
@@ -5,31 +8,28 @@ enum Directive {
     Turn(u8),
 }
 
-fn parse(data: &str) -> (Vec<Vec<u8>>, Vec<Directive>) {
-    let (map, p) = data.split_once("\n\n").unwrap();
+fn parse(data: &str) -> Result<(Vec<Vec<u8>>, Vec<Directive>), ParseError> {
+    let [map, p] = parsing::split_sections(data)?;
     let mut width = 0;
-    let mut map = map
-        .lines()
-        .map(|line| {
-            let r = line.as_bytes().to_vec();
-            width = width.max(r.len());
-            r
-        })
-        .collect::<Vec<_>>();
-    map.iter_mut().for_each(|r| r.resize(width, b' '));
-    let mut path = vec![];
-    for e in p.trim().split_inclusive(|c: char| c.is_alphabetic()) {
-        let n = e.len();
-        if e.as_bytes()[n - 1].is_ascii_alphabetic() {
-            path.extend([
-                Directive::Forward(e[..n - 1].parse().unwrap()),
-                Directive::Turn(e.as_bytes()[n - 1]),
-            ]);
-        } else {
-            path.push(Directive::Forward(e.parse().unwrap()));
+    let mut rows = vec![];
+    for line in map.lines() {
+        if let Some(j) = line.find(|c| !" .#".contains(c)) {
+            return Err(ParseError::at(data, &line[j..], "one of \" .#\""));
         }
+        width = width.max(line.len());
+        rows.push(line.as_bytes().to_vec());
+    }
+    // both parts start on the first open tile of the top row
+    if !rows[0].contains(&b'.') {
+        return Err(ParseError::at(data, map, "an open tile in the top row"));
     }
-    (map, path)
+    rows.iter_mut().for_each(|r| r.resize(width, b' '));
+    let directive = alt((
+        unsigned.map(Directive::Forward),
+        one_of("LR").map(|t| Directive::Turn(t as u8)),
+    ));
+    let path = parsing::parse_all(p.trim_end(), many1(directive))?;
+    Ok((rows, path))
 }
 
 fn part1(map: &[Vec<u8>], path: &[Directive]) -> usize {
@@ -120,7 +120,7 @@ fn part2(map: &[Vec<u8>], path: &[Directive]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day22").unwrap();
-    let (map, path) = parse(&data);
+    let (map, path) = parse(&data).unwrap();
     println!("part1: {}", part1(&map, &path));
     println!("part2: {}", part2(&map, &path));
 }
@@ -128,6 +128,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -145,16 +146,14 @@ mod tests {
         ......#.
 
 10R5L5R10L4R5L5";
-        let (map, path) = parse(data);
+        let (map, path) = parse(data).unwrap();
         assert_eq!(6032, part1(&map, &path));
         // assert_eq!(5031, part2(&map, &path));
     }
 
     #[test]
-    fn case2() {
-        let data = std::fs::read_to_string("data/2022/day22").unwrap();
-        let (map, path) = parse(&data);
-        assert_eq!(67390, part1(&map, &path));
-        assert_eq!(95291, part2(&map, &path));
+    fn fuzz() {
+        let seed = "        ...#\n        .#..\n...#.......#\n........#...\n        ...#....\n\n10R5L5R10L4R5L5";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,3 +1,5 @@
+use adventofcode::parsing::{self, ParseError};
+
 struct Map {
     width: usize,
     height: usize,
@@ -116,11 +118,11 @@ impl Map {
     }
 }
 
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|line| line.as_bytes()).collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    parsing::grid_of(data.trim(), ".#")
 }
 
-fn solve(map: &[&[u8]]) -> (usize, usize) {
+fn solve(map: &[Vec<u8>]) -> (usize, usize) {
     let height = map.len();
     let width = map[0].len();
     let mut m = Map::new(width, height);
@@ -156,7 +158,7 @@ fn solve(map: &[&[u8]]) -> (usize, usize) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day23").unwrap();
-    let map = parse(&data);
+    let map = parse(&data).unwrap();
     let (p1, p2) = solve(&map);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -165,6 +167,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -176,7 +179,7 @@ mod tests {
 #.###..
 ##.#.##
 .#..#..";
-        let map = parse(data);
+        let map = parse(data).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(110, p1);
         assert_eq!(20, p2);
@@ -191,9 +194,15 @@ mod tests {
 .....
 ..##.
 .....";
-        let map = parse(data);
+        let map = parse(data).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(25, p1);
         assert_eq!(4, p2);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "....#..\n..###.#\n#...#.#";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,18 +1,5 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
-
-use ahash::HashMap;
-
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
-}
+use adventofcode::parsing::{self, ParseError};
+use adventofcode::{math::lcm, search};
 
 struct Map {
     width: i16,
@@ -22,18 +9,47 @@ struct Map {
     blizzards: Vec<Vec<bool>>,
 }
 
-fn parse(data: &str) -> Map {
-    let g = data
-        .trim()
-        .lines()
-        .map(|line| line.as_bytes())
-        .collect::<Vec<_>>();
+fn parse(data: &str) -> Result<Map, ParseError> {
+    let data = data.trim();
+    let g = parsing::grid_of(data, "#.<>v^")?;
     let height = g.len();
     let width = g[0].len();
-    let entry = g[0].iter().position(|c| *c == b'.').unwrap() as i16;
-    let exit = g[height - 1].iter().position(|c| *c == b'.').unwrap() as i16;
+    if height < 3 || width < 3 {
+        return Err(ParseError::at(data, data, "a valley inside walls"));
+    }
+    let line = |i: usize| data.lines().nth(i).unwrap();
+    let gap = |row: &[u8]| {
+        let mut gaps = (0..width).filter(|&j| row[j] == b'.');
+        gaps.next().filter(|_| gaps.next().is_none())
+    };
+    let walls = |row: &[u8]| row.iter().filter(|c| **c != b'.').all(|c| *c == b'#');
+    let (Some(entry), Some(exit)) = (gap(&g[0]), gap(&g[height - 1])) else {
+        return Err(ParseError::at(
+            data,
+            data,
+            "one gap in the top and bottom walls",
+        ));
+    };
+    if !walls(&g[0]) || !walls(&g[height - 1]) {
+        return Err(ParseError::at(data, data, "walls at the top and bottom"));
+    }
+    for (i, row) in g.iter().enumerate().skip(1).take(height - 2) {
+        let inside = &row[1..width - 1];
+        if row[0] != b'#' || row[width - 1] != b'#' || inside.contains(&b'#') {
+            return Err(ParseError::at(
+                data,
+                line(i),
+                "a row of the valley between walls",
+            ));
+        }
+    }
     let (h, w) = (height - 2, width - 2);
     let period = lcm(h, w);
+    // one map of the blizzards per minute until they repeat
+    if period.saturating_mul(height * width) > 1 << 24 {
+        return Err(ParseError::at(data, data, "a smaller valley"));
+    }
+    let (entry, exit) = (entry as i16, exit as i16);
     let blizzards = (0..period)
         .map(|t| {
             let mut b = vec![false; height * width];
@@ -55,13 +71,13 @@ fn parse(data: &str) -> Map {
             b
         })
         .collect();
-    Map {
+    Ok(Map {
         width: width as i16,
         height: height as i16,
         entry,
         exit,
         blizzards,
-    }
+    })
 }
 
 fn manhattan((i1, j1): (i16, i16), (i2, j2): (i16, i16)) -> i16 {
@@ -69,36 +85,27 @@ fn manhattan((i1, j1): (i16, i16), (i2, j2): (i16, i16)) -> i16 {
 }
 
 fn shortest_path(map: &Map, (i0, j0): (i16, i16), end: (i16, i16), t0: usize) -> usize {
-    let mut q = BinaryHeap::new();
-    q.push((Reverse(0), (i0, j0, t0)));
-    let mut dist = HashMap::default();
-    dist.insert((i0, j0, t0), 0);
-    while let Some((Reverse(_), (i, j, t))) = q.pop() {
-        if (i, j) == end {
-            return t;
-        }
-        let k = (t + 1) % map.blizzards.len();
-        let blizz = &map.blizzards[k];
-        for (i1, j1) in [(i, j), (i + 1, j), (i - 1, j), (i, j + 1), (i, j - 1)] {
-            if i1 < 0
-                || j1 <= 0
-                || i1 >= map.height
-                || j1 >= map.width - 1
-                || (i1 == 0 && j1 != map.entry)
-                || (i1 == map.height - 1 && j1 != map.exit)
-                || blizz[(map.width as usize) * i1 as usize + j1 as usize]
-            {
-                continue;
-            }
-            let d = dist.entry((i1, j1, t + 1)).or_insert(usize::MAX);
-            if t + 1 < *d {
-                *d = t + 1;
-                let h = manhattan((i1, j1), end) as usize;
-                q.push((Reverse(t + 1 + h), (i1, j1, t + 1)));
-            }
-        }
-    }
-    unreachable!()
+    let neighbors = |&(i, j, t): &(i16, i16, usize)| {
+        let blizz = &map.blizzards[(t + 1) % map.blizzards.len()];
+        [(i, j), (i + 1, j), (i - 1, j), (i, j + 1), (i, j - 1)]
+            .into_iter()
+            .filter(move |&(i1, j1)| {
+                !(i1 < 0
+                    || j1 <= 0
+                    || i1 >= map.height
+                    || j1 >= map.width - 1
+                    || (i1 == 0 && j1 != map.entry)
+                    || (i1 == map.height - 1 && j1 != map.exit)
+                    || blizz[(map.width as usize) * i1 as usize + j1 as usize])
+            })
+            .map(move |(i1, j1)| ((i1, j1, t + 1), 1))
+    };
+    let heuristic = |&(i, j, _): &(i16, i16, usize)| manhattan((i, j), end) as usize;
+    let ((_, _, t), _) = search::astar((i0, j0, t0), neighbors, heuristic, |&(i, j, _)| {
+        (i, j) == end
+    })
+    .unwrap();
+    t
 }
 
 fn solve(map: &Map) -> (usize, usize) {
@@ -111,7 +118,7 @@ fn solve(map: &Map) -> (usize, usize) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day24").unwrap();
-    let map = parse(&data);
+    let map = parse(&data).unwrap();
     let (p1, p2) = solve(&map);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -120,6 +127,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -130,9 +138,15 @@ mod tests {
 #>v.><>#
 #<^v^^>#
 ######.#";
-        let map = parse(data);
+        let map = parse(data).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(18, p1);
         assert_eq!(54, p2);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#.#####\n#.....#\n#>....#\n#.....#\n#...v.#\n#.....#\n#####.#";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
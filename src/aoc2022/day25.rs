@@ -1,74 +1,256 @@
-fn parse(data: &str) -> Vec<&str> {
-    data.trim().lines().collect::<Vec<_>>()
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// Balanced base 5 of any length: digits in `-2..=2`, least significant
+/// first, with no zeros at the top. Zero has no digits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct Snafu(Vec<i8>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SnafuError {
+    Empty,
+    InvalidDigit { position: usize, byte: u8 },
 }
 
-fn snafu_digit_to_dec(d: u8) -> i64 {
-    match d {
-        b'2' => 2,
-        b'1' => 1,
-        b'0' => 0,
-        b'-' => -1,
-        b'=' => -2,
-        _ => unreachable!(),
+impl fmt::Display for SnafuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnafuError::Empty => write!(f, "empty SNAFU number"),
+            SnafuError::InvalidDigit { position, byte } => {
+                write!(f, "invalid SNAFU digit {:?} at {position}", *byte as char)
+            }
+        }
     }
 }
 
-fn dec_digit_to_snafu(d: i64) -> (u8, bool) {
-    match d {
-        2 => (b'2', false),
-        1 => (b'1', false),
-        0 => (b'0', false),
-        4 => (b'-', true),
-        3 => (b'=', true),
-        _ => unreachable!(),
+impl std::error::Error for SnafuError {}
+
+impl Snafu {
+    /// Carries any digit sums, least significant first, back into `-2..=2`.
+    fn normalize(digits: impl IntoIterator<Item = i64>) -> Self {
+        let mut result = vec![];
+        let mut carry = 0;
+        for d in digits {
+            let d = d + carry;
+            let r = (d + 2).rem_euclid(5) - 2;
+            carry = (d - r) / 5;
+            result.push(r as i8);
+        }
+        while carry != 0 {
+            let r = (carry + 2).rem_euclid(5) - 2;
+            carry = (carry - r) / 5;
+            result.push(r as i8);
+        }
+        while result.last() == Some(&0) {
+            result.pop();
+        }
+        Self(result)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `None` if it doesn't fit.
+    fn to_i128(&self) -> Option<i128> {
+        self.0
+            .iter()
+            .rev()
+            // 4n + (n + d) stays in range whenever 5n + d does
+            .try_fold(0i128, |n, &d| {
+                n.checked_mul(4)?.checked_add(n.checked_add(d as i128)?)
+            })
     }
 }
 
-fn snafu_to_dec(s: &str) -> i64 {
-    let mut result = 0;
-    let mut base = 1;
-    for &d in s.as_bytes().iter().rev() {
-        result += snafu_digit_to_dec(d) * base;
-        base *= 5;
+impl From<i128> for Snafu {
+    fn from(mut n: i128) -> Self {
+        let mut digits = vec![];
+        while n != 0 {
+            // n + 2 and n - r overflow at the ends of the range, the
+            // euclidean parts don't
+            let m = n.rem_euclid(5);
+            let r = if m > 2 { m - 5 } else { m };
+            digits.push(r as i8);
+            n = n.div_euclid(5) + (m > 2) as i128;
+        }
+        Self(digits)
     }
-    result
 }
 
-fn dec_to_snafu(mut n: i64) -> String {
-    let mut result = vec![];
-    let mut carry = false;
-    while n > 0 {
-        let d = i64::from(carry) + n % 5;
-        let (sd, ca) = dec_digit_to_snafu(d);
-        carry = ca;
-        result.push(sd);
-        n /= 5;
+impl FromStr for Snafu {
+    type Err = SnafuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(SnafuError::Empty);
+        }
+        let digits = s
+            .bytes()
+            .enumerate()
+            .map(|(position, byte)| match byte {
+                b'2' => Ok(2),
+                b'1' => Ok(1),
+                b'0' => Ok(0),
+                b'-' => Ok(-1),
+                b'=' => Ok(-2),
+                _ => Err(SnafuError::InvalidDigit { position, byte }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::normalize(digits.into_iter().rev()))
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let s = self
+            .0
+            .iter()
+            .rev()
+            .map(|d| match d {
+                2 => '2',
+                1 => '1',
+                0 => '0',
+                -1 => '-',
+                _ => '=',
+            })
+            .collect::<String>();
+        f.pad(&s)
+    }
+}
+
+impl Add for &Snafu {
+    type Output = Snafu;
+
+    fn add(self, rhs: Self) -> Snafu {
+        let n = self.0.len().max(rhs.0.len());
+        let digit = |s: &Snafu, i: usize| s.0.get(i).copied().unwrap_or(0) as i64;
+        Snafu::normalize((0..n).map(|i| digit(self, i) + digit(rhs, i)))
     }
-    result.reverse();
-    String::from_utf8(result).unwrap()
 }
 
-fn part1(nums: &[&str]) -> String {
-    let sum = nums.iter().map(|n| snafu_to_dec(n)).sum();
-    dec_to_snafu(sum)
+impl Add for Snafu {
+    type Output = Snafu;
+
+    fn add(self, rhs: Self) -> Snafu {
+        &self + &rhs
+    }
+}
+
+impl Neg for Snafu {
+    type Output = Snafu;
+
+    fn neg(self) -> Snafu {
+        Snafu(self.0.into_iter().map(|d| -d).collect())
+    }
+}
+
+impl Sub for Snafu {
+    type Output = Snafu;
+
+    fn sub(self, rhs: Self) -> Snafu {
+        self + -rhs
+    }
+}
+
+impl Mul for &Snafu {
+    type Output = Snafu;
+
+    /// Schoolbook, each column summed before carrying.
+    fn mul(self, rhs: Self) -> Snafu {
+        if self.is_zero() || rhs.is_zero() {
+            return Snafu::default();
+        }
+        let mut columns = vec![0i64; self.0.len() + rhs.0.len()];
+        for (i, &a) in self.0.iter().enumerate() {
+            for (j, &b) in rhs.0.iter().enumerate() {
+                columns[i + j] += a as i64 * b as i64;
+            }
+        }
+        Snafu::normalize(columns)
+    }
+}
+
+impl Mul for Snafu {
+    type Output = Snafu;
+
+    fn mul(self, rhs: Self) -> Snafu {
+        &self * &rhs
+    }
+}
+
+impl Sum for Snafu {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |a, b| a + b)
+    }
+}
+
+fn parse(data: &str) -> Result<Vec<Snafu>, SnafuError> {
+    data.trim().lines().map(str::parse).collect()
+}
+
+fn part1(nums: &[Snafu]) -> Snafu {
+    nums.iter().cloned().sum()
+}
+
+/// `None` when some number or the total doesn't fit.
+fn decimal_sum(nums: &[Snafu]) -> Option<i128> {
+    nums.iter()
+        .try_fold(0i128, |total, n| total.checked_add(n.to_i128()?))
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day25").unwrap();
-    let nums = parse(&data);
-    println!("part1: {}", part1(&nums));
+    let nums = parse(&data).unwrap();
+    let total = part1(&nums);
+    // cross-check against the decimal route whenever it fits
+    if let Some(decimal) = decimal_sum(&nums) {
+        assert_eq!(Snafu::from(decimal), total);
+    }
+    println!("part1: {total}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use rand::prelude::*;
+
+    fn snafu(s: &str) -> Snafu {
+        s.parse().unwrap()
+    }
 
     #[test]
     fn case1() {
-        let s = "2=-01";
-        let n = 976;
-        assert_eq!(n, snafu_to_dec(s));
-        assert_eq!(s, dec_to_snafu(n));
+        let table = [
+            (1, "1"),
+            (2, "2"),
+            (3, "1="),
+            (4, "1-"),
+            (5, "10"),
+            (8, "2="),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+            (976, "2=-01"),
+            (0, "0"),
+        ];
+        for (n, s) in table {
+            assert_eq!(Some(n), snafu(s).to_i128());
+            assert_eq!(s, Snafu::from(n).to_string());
+        }
+        for n in [i128::MIN, i128::MAX] {
+            let x = Snafu::from(n);
+            assert_eq!(Some(n), x.to_i128());
+            assert_eq!(x, snafu(&x.to_string()));
+        }
+        assert_eq!(None, (Snafu::from(i128::MAX) + snafu("1")).to_i128());
+        assert_eq!(None, (Snafu::from(i128::MIN) - snafu("1")).to_i128());
     }
 
     #[test]
@@ -87,7 +269,68 @@ mod tests {
 12
 1=
 122";
-        let nums = parse(data);
-        assert_eq!("2=-1=0", part1(&nums));
+        let nums = parse(data).unwrap();
+        assert_eq!("2=-1=0", part1(&nums).to_string());
+        // the decimal route
+        let total = nums.iter().map(|n| n.to_i128().unwrap()).sum::<i128>();
+        assert_eq!(part1(&nums), Snafu::from(total));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(SnafuError::Empty), "".parse::<Snafu>());
+        assert_eq!(
+            Err(SnafuError::InvalidDigit {
+                position: 2,
+                byte: b'3'
+            }),
+            "1=3".parse::<Snafu>()
+        );
+        // leading zeros are fine
+        assert_eq!(snafu("2="), snafu("002="));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut rng = StdRng::seed_from_u64(25);
+        for _ in 0..1000 {
+            let a = rng.random_range(-1i128 << 60..1 << 60);
+            let b = rng.random_range(-1i128 << 60..1 << 60);
+            let (x, y) = (Snafu::from(a), Snafu::from(b));
+            assert_eq!(Snafu::from(a + b), x.clone() + y.clone());
+            assert_eq!(Snafu::from(a - b), x.clone() - y.clone());
+            assert_eq!(Snafu::from(a * b), x.clone() * y.clone());
+            assert_eq!(Snafu::from(-a), -x.clone());
+            assert_eq!(Some(a), x.to_i128());
+            assert_eq!(x, snafu(&x.to_string()));
+        }
+    }
+
+    #[test]
+    fn arbitrary_length() {
+        // (5^200 - 1) / 4 and friends are far beyond i128
+        let big = Snafu(vec![1; 200]);
+        assert_eq!(None, big.to_i128());
+        let square = &big * &big;
+        assert_eq!(big.clone() * big.clone() - square.clone(), Snafu::default());
+        assert_eq!(
+            square.clone() - big.clone(),
+            &big * &(big.clone() - snafu("1"))
+        );
+        let total = [big.clone(), -big.clone(), snafu("2=")]
+            .into_iter()
+            .sum::<Snafu>();
+        assert_eq!(Some(8), total.to_i128());
+    }
+
+    #[test]
+    fn fuzz() {
+        fuzz::check(&["1=-0-2\n12111\n2=0=\n1-12"], |s| {
+            let nums = parse(s)?;
+            if let Some(decimal) = decimal_sum(&nums) {
+                assert_eq!(Snafu::from(decimal), part1(&nums));
+            }
+            Ok::<_, SnafuError>(())
+        });
     }
 }
@@ -1,5 +1,14 @@
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|line| line.as_bytes()).collect()
+use adventofcode::parsing::{self, ParseError};
+use nom::{Parser, bytes::complete::take_while1, combinator::verify, error::context};
+
+fn parse(data: &str) -> Result<Vec<&[u8]>, ParseError> {
+    let items = take_while1(|c: char| c.is_ascii_alphabetic());
+    // part 1 splits each rucksack into two halves
+    let rucksack = context(
+        "an even number of items",
+        verify(items, |s: &str| s.len().is_multiple_of(2)),
+    );
+    parsing::lines(data.trim(), rucksack.map(str::as_bytes))
 }
 
 fn priority(item: u8) -> u8 {
@@ -37,7 +46,7 @@ fn part2(rucksacks: &[&[u8]]) -> u32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day3").unwrap();
-    let rucksacks = parse(&data);
+    let rucksacks = parse(&data).unwrap();
     println!("part1: {}", part1(&rucksacks));
     println!("part2: {}", part2(&rucksacks));
 }
@@ -45,6 +54,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -56,8 +66,14 @@ wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw"
             .to_string();
-        let rucksacks = parse(&data);
+        let rucksacks = parse(&data).unwrap();
         assert_eq!(157, part1(&rucksacks));
         assert_eq!(70, part2(&rucksacks));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
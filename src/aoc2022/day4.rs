@@ -1,22 +1,14 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::{Parser, character::complete::char, sequence::separated_pair};
+
 type Range = (u8, u8);
 
-fn parse_range(input: &str) -> Range {
-    let (left, right) = input.split_once('-').unwrap();
-    let left = left.parse().unwrap();
-    let right = right.parse().unwrap();
-    (left, right)
+fn range(input: &str) -> PResult<'_, Range> {
+    separated_pair(unsigned, char('-'), unsigned).parse(input)
 }
 
-fn parse(data: &str) -> Vec<(Range, Range)> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once(',').unwrap();
-            let a = parse_range(a);
-            let b = parse_range(b);
-            (a, b)
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<(Range, Range)>, ParseError> {
+    parsing::lines(data.trim(), separated_pair(range, char(','), range))
 }
 
 fn part1(assignments: &[(Range, Range)]) -> usize {
@@ -35,7 +27,7 @@ fn part2(assignments: &[(Range, Range)]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day4").unwrap();
-    let assignments = parse(&data);
+    let assignments = parse(&data).unwrap();
     println!("part1: {}", part1(&assignments));
     println!("part2: {}", part2(&assignments));
 }
@@ -43,6 +35,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -53,8 +46,14 @@ mod tests {
 2-8,3-7
 6-6,4-6
 2-6,4-8";
-        let assignments = parse(data);
+        let assignments = parse(data).unwrap();
         assert_eq!(2, part1(&assignments));
         assert_eq!(4, part2(&assignments));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "2-4,6-8\n2-3,4-5\n5-7,7-9";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,41 +1,90 @@
-fn parse_stacks(data: &str) -> Vec<Vec<u8>> {
-    let lines = data.lines().collect::<Vec<_>>();
-    let m = lines.len() - 1;
-    let n = lines.last().unwrap().split_ascii_whitespace().count();
-    (0..n)
-        .map(|j| {
-            (0..m)
-                .rev()
-                .map_while(|i| {
-                    let b = lines[i].as_bytes()[4 * j + 1];
-                    if b == b' ' {
-                        None
-                    } else {
-                        Some(b)
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::{Parser, bytes::complete::tag, sequence::preceded};
+
+type Stacks = Vec<Vec<u8>>;
+
+fn parse_stacks(data: &str) -> Result<Stacks, ParseError> {
+    let (rows, labels) = data.rsplit_once('\n').unwrap_or(("", data));
+    let mut n = 0;
+    for (j, label) in labels.split_ascii_whitespace().enumerate() {
+        // the drawing has room for one digit per stack
+        if j == 9 || label != (j + 1).to_string() {
+            return Err(ParseError::at(
+                data,
+                label,
+                "stacks numbered 1 to 9 in order",
+            ));
+        }
+        n += 1;
+    }
+    if n == 0 {
+        return Err(ParseError::at(data, labels, "stack numbers"));
+    }
+    let mut stacks = vec![vec![]; n];
+    let mut open = vec![true; n];
+    for row in rows.lines().rev() {
+        for (j, cell) in row.as_bytes().chunks(4).enumerate() {
+            let rest = &row[row.floor_char_boundary(4 * j)..];
+            match cell {
+                [b' ', b' ', b' '] | [b' ', b' ', b' ', b' '] if j < n => open[j] = false,
+                [b'[', c, b']'] | [b'[', c, b']', b' '] if j < n && c.is_ascii_uppercase() => {
+                    if !open[j] {
+                        return Err(ParseError::at(data, rest, "a crate on top of another"));
                     }
-                })
-                .collect()
-        })
-        .collect()
+                    stacks[j].push(*c);
+                }
+                _ => {
+                    return Err(ParseError::at(
+                        data,
+                        rest,
+                        format!("a crate in one of {n} stacks"),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(stacks)
 }
 
-fn parse_procedure(data: &str) -> Vec<[u8; 3]> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
-            [
-                parts[1].parse().unwrap(),
-                parts[3].parse().unwrap(),
-                parts[5].parse().unwrap(),
-            ]
-        })
-        .collect()
+fn step(input: &str) -> PResult<'_, [u8; 3]> {
+    let step = (
+        preceded(tag("move "), unsigned),
+        preceded(tag(" from "), unsigned),
+        preceded(tag(" to "), unsigned),
+    );
+    step.map(|(n, from, to)| [n, from, to]).parse(input)
 }
 
-fn parse(data: &str) -> (Vec<Vec<u8>>, Vec<[u8; 3]>) {
-    let (stacks, procedure) = data.split_once("\n\n").unwrap();
-    (parse_stacks(stacks), parse_procedure(procedure))
+fn parse(data: &str) -> Result<(Stacks, Vec<[u8; 3]>), ParseError> {
+    let [stacks, procedure] = parsing::split_sections(data)?;
+    let stacks = parse_stacks(stacks)?;
+    let steps = parsing::lines(procedure, step)?;
+    // both parts move crates one stack at a time, so the heights are the same
+    let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+    for (&[n, from, to], line) in steps.iter().zip(procedure.lines()) {
+        let (from, to) = (from as usize, to as usize);
+        let stack = 1..=heights.len();
+        if !stack.contains(&from) || !stack.contains(&to) || from == to {
+            return Err(ParseError::at(data, line, "two different stacks"));
+        }
+        if heights[from - 1] < n as usize {
+            return Err(ParseError::at(
+                data,
+                line,
+                format!("at most {} crates", heights[from - 1]),
+            ));
+        }
+        heights[from - 1] -= n as usize;
+        heights[to - 1] += n as usize;
+    }
+    if heights.contains(&0) {
+        return Err(ParseError::at(
+            data,
+            &data[data.len()..],
+            "a crate left on every stack",
+        ));
+    }
+    Ok((stacks, steps))
 }
 
 fn part1(mut stacks: Vec<Vec<u8>>, procedure: &[[u8; 3]]) -> String {
@@ -61,7 +110,7 @@ fn part2(mut stacks: Vec<Vec<u8>>, procedure: &[[u8; 3]]) -> String {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day5").unwrap();
-    let (stacks, procedure) = parse(&data);
+    let (stacks, procedure) = parse(&data).unwrap();
     println!("part1: {}", part1(stacks.clone(), &procedure));
     println!("part2: {}", part2(stacks, &procedure));
 }
@@ -69,6 +118,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -81,8 +131,14 @@ move 1 from 2 to 1
 move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2";
-        let (stacks, procedure) = parse(data);
+        let (stacks, procedure) = parse(data).unwrap();
         assert_eq!("CMZ", part1(stacks.clone(), &procedure));
         assert_eq!("MCD", part2(stacks, &procedure));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
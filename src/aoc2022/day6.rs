@@ -1,5 +1,9 @@
-fn parse(data: &str) -> &[u8] {
-    data.trim().as_bytes()
+use adventofcode::parsing::{self, ParseError};
+use nom::{bytes::complete::take_while1, error::context};
+
+fn parse(data: &str) -> Result<&[u8], ParseError> {
+    let letters = take_while1(|c: char| c.is_ascii_lowercase());
+    parsing::parse_all(data.trim(), context("lowercase letters", letters)).map(str::as_bytes)
 }
 
 fn marker(signal: &[u8], len: usize) -> usize {
@@ -24,7 +28,7 @@ fn marker(signal: &[u8], len: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day6").unwrap();
-    let signal = parse(&data);
+    let signal = parse(&data).unwrap();
     println!("part1: {}", marker(signal, 4));
     println!("part2: {}", marker(signal, 14));
 }
@@ -32,11 +36,12 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-        let signal = parse(data);
+        let signal = parse(data).unwrap();
         assert_eq!(7, marker(signal, 4));
         assert_eq!(19, marker(signal, 14));
     }
@@ -44,7 +49,7 @@ mod tests {
     #[test]
     fn case2() {
         let data = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        let signal = parse(data);
+        let signal = parse(data).unwrap();
         assert_eq!(5, marker(signal, 4));
         assert_eq!(23, marker(signal, 14));
     }
@@ -52,7 +57,7 @@ mod tests {
     #[test]
     fn case3() {
         let data = "nppdvjthqldpwncqszvftbrmjlhg";
-        let signal = parse(data);
+        let signal = parse(data).unwrap();
         assert_eq!(6, marker(signal, 4));
         assert_eq!(23, marker(signal, 14));
     }
@@ -60,7 +65,7 @@ mod tests {
     #[test]
     fn case4() {
         let data = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        let signal = parse(data);
+        let signal = parse(data).unwrap();
         assert_eq!(10, marker(signal, 4));
         assert_eq!(29, marker(signal, 14));
     }
@@ -68,8 +73,14 @@ mod tests {
     #[test]
     fn case5() {
         let data = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        let signal = parse(data);
+        let signal = parse(data).unwrap();
         assert_eq!(11, marker(signal, 4));
         assert_eq!(26, marker(signal, 14));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,14 +1,16 @@
 use std::{cell::RefCell, rc::Rc};
 
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
 use ahash::HashMap;
 use nom::{
+    Parser,
     branch::alt,
     bytes::complete::{tag, take_while},
     character::complete::{self, line_ending},
-    combinator::all_consuming,
+    combinator::verify,
+    error::context,
     multi::separated_list0,
     sequence::{preceded, separated_pair},
-    IResult, Parser,
 };
 
 enum Entry<'a> {
@@ -26,49 +28,49 @@ enum FSEntry<'a> {
     File(usize),
 }
 
-fn parse_file(input: &str) -> IResult<&str, Entry<'_>> {
+fn parse_file(input: &str) -> PResult<'_, Entry<'_>> {
+    // no file can be bigger than the disk
+    let size = context("a file size", verify(unsigned, |&s: &usize| s <= 7000_0000));
     let (r, (size, name)) = separated_pair(
-        complete::u64,
+        size,
         complete::char(' '),
         take_while(|c: char| c != '\r' && c != '\n'),
     )
     .parse(input)?;
-    Ok((r, Entry::File(name, size as usize)))
+    Ok((r, Entry::File(name, size)))
 }
 
-fn parse_dir(input: &str) -> IResult<&str, Entry<'_>> {
+fn parse_dir(input: &str) -> PResult<'_, Entry<'_>> {
     let (r, name) =
         preceded(tag("dir "), take_while(|c: char| c != '\r' && c != '\n')).parse(input)?;
     Ok((r, Entry::Dir(name)))
 }
 
-fn parse_entry(input: &str) -> IResult<&str, Entry<'_>> {
+fn parse_entry(input: &str) -> PResult<'_, Entry<'_>> {
     alt((parse_file, parse_dir)).parse(input)
 }
 
-fn parse_ls_output(input: &str) -> IResult<&str, Vec<Entry<'_>>> {
+fn parse_ls_output(input: &str) -> PResult<'_, Vec<Entry<'_>>> {
     separated_list0(line_ending, parse_entry).parse(input)
 }
 
-fn parse_ls(input: &str) -> IResult<&str, Cmd<'_>> {
+fn parse_ls(input: &str) -> PResult<'_, Cmd<'_>> {
     let (r, entries) = preceded((tag("ls"), line_ending), parse_ls_output).parse(input)?;
     Ok((r, Cmd::Ls(entries)))
 }
 
-fn parse_cd(input: &str) -> IResult<&str, Cmd<'_>> {
+fn parse_cd(input: &str) -> PResult<'_, Cmd<'_>> {
     let (r, arg) =
         preceded(tag("cd "), take_while(|c: char| c != '\r' && c != '\n')).parse(input)?;
     Ok((r, Cmd::Cd(arg)))
 }
 
-fn parse_cmd(input: &str) -> IResult<&str, Cmd<'_>> {
+fn parse_cmd(input: &str) -> PResult<'_, Cmd<'_>> {
     preceded(tag("$ "), alt((parse_cd, parse_ls))).parse(input)
 }
 
-fn parse(data: &str) -> Rc<RefCell<FSEntry<'_>>> {
-    let (_, cmds) = all_consuming(separated_list0(line_ending, parse_cmd))
-        .parse(data.trim())
-        .unwrap();
+fn parse(data: &str) -> Result<Rc<RefCell<FSEntry<'_>>>, ParseError> {
+    let cmds = parsing::parse_all(data.trim(), separated_list0(line_ending, parse_cmd))?;
 
     let root = Rc::new(RefCell::new(FSEntry::Dir(HashMap::default())));
     let mut stack = vec![root.clone()];
@@ -76,6 +78,9 @@ fn parse(data: &str) -> Rc<RefCell<FSEntry<'_>>> {
         match cmd {
             Cmd::Cd(arg) => match arg {
                 ".." => {
+                    if stack.len() == 1 {
+                        return Err(ParseError::at(data, arg, "a directory below /"));
+                    }
                     stack.pop();
                 }
                 "/" => {
@@ -95,6 +100,9 @@ fn parse(data: &str) -> Rc<RefCell<FSEntry<'_>>> {
                             _ => unreachable!(),
                         }
                     };
+                    if matches!(*sub.borrow(), FSEntry::File(_)) {
+                        return Err(ParseError::at(data, arg, "a directory"));
+                    }
                     stack.push(sub);
                 }
             },
@@ -122,7 +130,7 @@ fn parse(data: &str) -> Rc<RefCell<FSEntry<'_>>> {
             }
         }
     }
-    root
+    Ok(root)
 }
 
 fn part1(root: &Rc<RefCell<FSEntry>>) -> (usize, usize) {
@@ -173,7 +181,7 @@ fn part2(root: &Rc<RefCell<FSEntry>>, total: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day7").unwrap();
-    let root = parse(&data);
+    let root = parse(&data).unwrap();
     let (p1, total) = part1(&root);
     println!("part1: {p1}");
     println!("part2: {}", part2(&root, total));
@@ -182,6 +190,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -209,9 +218,15 @@ $ ls
 8033020 d.log
 5626152 d.ext
 7214296 k";
-        let root = parse(data);
+        let root = parse(data).unwrap();
         let (p1, total) = part1(&root);
         assert_eq!(95437, p1);
         assert_eq!(24933642, part2(&root, total));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ cd a\n$ ls\n29116 f\n$ cd ..\n$ cd a";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,8 +1,20 @@
-fn parse(data: &str) -> Vec<Vec<i8>> {
-    data.trim()
-        .lines()
-        .map(|line| line.as_bytes().iter().map(|b| (b - b'0') as i8).collect())
-        .collect()
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(data: &str) -> Result<Vec<Vec<i8>>, ParseError> {
+    let data = data.trim();
+    let map = parsing::grid_of(data, "0123456789")?;
+    // part 2 looks for the best tree away from the edges
+    if map.len() < 3 || map[0].len() < 3 {
+        return Err(ParseError::at(
+            data,
+            data,
+            "at least 3 rows and columns of trees",
+        ));
+    }
+    Ok(map
+        .into_iter()
+        .map(|row| row.into_iter().map(|b| (b - b'0') as i8).collect())
+        .collect())
 }
 
 fn part1(map: &[Vec<i8>]) -> usize {
@@ -117,7 +129,7 @@ fn part2(map: &[Vec<i8>]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day8").unwrap();
-    let map = parse(&data);
+    let map = parse(&data).unwrap();
     println!("part1: {}", part1(&map));
     println!("part2: {}", part2(&map));
 }
@@ -125,6 +137,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -134,8 +147,14 @@ mod tests {
 65332
 33549
 35390";
-        let map = parse(data);
+        let map = parse(data).unwrap();
         assert_eq!(21, part1(&map));
         assert_eq!(8, part2(&map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "30373\n25512\n65332\n33549\n35390";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,14 +1,14 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::HashSet;
+use nom::{
+    Parser,
+    character::complete::{char, one_of},
+    sequence::separated_pair,
+};
 
-fn parse(data: &str) -> Vec<(u8, u32)> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let d = line.as_bytes()[0];
-            let n = line[2..].parse().unwrap();
-            (d, n)
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<(u8, u32)>, ParseError> {
+    let motion = separated_pair(one_of("RLUD"), char(' '), unsigned);
+    parsing::lines(data.trim(), motion.map(|(d, n)| (d as u8, n)))
 }
 
 fn dir(d: u8) -> (i32, i32) {
@@ -65,7 +65,7 @@ fn part2(motions: &[(u8, u32)]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2022/day9").unwrap();
-    let motions = parse(&data);
+    let motions = parse(&data).unwrap();
     println!("part1: {}", part1(&motions));
     println!("part2: {}", part2(&motions));
 }
@@ -73,6 +73,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -85,7 +86,7 @@ R 4
 D 1
 L 5
 R 2";
-        let motions = parse(data);
+        let motions = parse(data).unwrap();
         assert_eq!(13, part1(&motions));
         assert_eq!(1, part2(&motions));
     }
@@ -101,8 +102,14 @@ R 17
 D 10
 L 25
 U 20";
-        let motions = parse(data);
+        let motions = parse(data).unwrap();
         assert_eq!(88, part1(&motions));
         assert_eq!(36, part2(&motions));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "R 4\nU 4\nL 3\nD 1";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
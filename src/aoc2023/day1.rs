@@ -1,5 +1,9 @@
-fn parse(data: &str) -> Vec<&str> {
-    data.lines().filter(|line| !line.is_empty()).collect()
+use adventofcode::parsing::{self, ParseError};
+use nom::{bytes::complete::take_while1, error::context};
+
+fn parse(data: &str) -> Result<Vec<&str>, ParseError> {
+    let line = take_while1(|c: char| c.is_ascii_alphanumeric());
+    parsing::lines(data.trim(), context("letters and digits", line))
 }
 
 fn part1(document: &[&str]) -> i32 {
@@ -56,7 +60,7 @@ fn part2(document: &[&str]) -> i32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day1").unwrap();
-    let document = parse(&data);
+    let document = parse(&data).unwrap();
     println!("part1: {}", part1(&document));
     println!("part2: {}", part2(&document));
 }
@@ -64,6 +68,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -72,7 +77,7 @@ mod tests {
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet";
-        let document = parse(data);
+        let document = parse(data).unwrap();
         assert_eq!(142, part1(&document));
     }
 
@@ -86,7 +91,13 @@ xtwone3four
 4nineeightseven2
 zoneight234
 7pqrstsixteen";
-        let document = parse(data);
+        let document = parse(data).unwrap();
         assert_eq!(281, part2(&document));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "1abc2\npqr3stu8vwx\ntwo1nine";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
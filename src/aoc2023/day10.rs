@@ -1,10 +1,14 @@
 use std::collections::VecDeque;
 
-fn parse(data: &str) -> Vec<Vec<u8>> {
-    data.trim()
-        .lines()
-        .map(|line| line.as_bytes().to_vec())
-        .collect()
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(data, "|-LJ7F.S")?;
+    if data.matches('S').count() != 1 {
+        return Err(ParseError::at(data, data, "exactly one S"));
+    }
+    Ok(grid)
 }
 
 fn connected(grid: &[Vec<u8>], (i, j): (usize, usize)) -> Vec<(i32, i32)> {
@@ -93,7 +97,7 @@ fn part2(grid: &[Vec<u8>], dist: &[Vec<u32>]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day10").unwrap();
-    let mut grid = parse(&data);
+    let mut grid = parse(&data).unwrap();
     let mut dist = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
     println!("part1: {}", part1(&mut grid, &mut dist));
     println!("part2: {}", part2(&grid, &dist));
@@ -102,6 +106,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -112,7 +117,7 @@ mod tests {
 .L-J.
 .....
 ";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         let mut dist = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
         assert_eq!(4, part1(&mut grid, &mut dist));
     }
@@ -126,7 +131,7 @@ SJ.L7
 |F--J
 LJ...
 ";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         let mut dist = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
         assert_eq!(8, part1(&mut grid, &mut dist));
     }
@@ -144,7 +149,7 @@ LJ...
 .L--J.L--J.
 ...........
 ";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         let mut dist = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
         part1(&mut grid, &mut dist);
         assert_eq!(4, part2(&grid, &dist));
@@ -163,7 +168,7 @@ LJ...
 .L--JL--J.
 ..........
 ";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         let mut dist = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
         part1(&mut grid, &mut dist);
         assert_eq!(4, part2(&grid, &dist));
@@ -183,7 +188,7 @@ L--J.L7...LJS7F-7L7.
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ...
 ";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         let mut dist = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
         part1(&mut grid, &mut dist);
         assert_eq!(8, part2(&grid, &dist));
@@ -203,9 +208,15 @@ L---JF-JLJ.||-FJLJJ7
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L
 ";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         let mut dist = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
         part1(&mut grid, &mut dist);
         assert_eq!(10, part2(&grid, &dist));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
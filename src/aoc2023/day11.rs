@@ -1,8 +1,10 @@
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|line| line.as_bytes()).collect()
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    parsing::grid_of(data.trim(), ".#")
 }
 
-fn expand(image: &[&[u8]], empty_line_width: usize) -> usize {
+fn expand(image: &[Vec<u8>], empty_line_width: usize) -> usize {
     let mut result = 0;
     let mut stars = vec![];
     for (i, r) in image.iter().enumerate() {
@@ -48,7 +50,7 @@ fn expand(image: &[&[u8]], empty_line_width: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day11").unwrap();
-    let image = parse(&data);
+    let image = parse(&data).unwrap();
     println!("part1: {}", expand(&image, 2));
     println!("part2: {}", expand(&image, 100_0000));
 }
@@ -56,6 +58,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -71,9 +74,15 @@ mod tests {
 .......#..
 #...#.....
 ";
-        let image = parse(data);
+        let image = parse(data).unwrap();
         assert_eq!(374, expand(&image, 2));
         assert_eq!(1030, expand(&image, 10));
         assert_eq!(8410, expand(&image, 100));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "...#......\n.......#..\n#.........\n..........";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,89 +1,87 @@
-use ahash::HashMap;
-use rayon::prelude::*;
+use adventofcode::exhaustive::{self, Count, Problem};
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    bytes::complete::take_while1, character::complete::char, multi::separated_list1,
+    sequence::separated_pair,
+};
 
-fn parse(data: &str) -> Vec<(&str, Vec<usize>)> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (springs, sizes) = line.split_once(' ').unwrap();
-            let sizes: Vec<usize> = sizes.split(',').map(|s| s.parse().unwrap()).collect();
-            (springs, sizes)
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<(&str, Vec<usize>)>, ParseError> {
+    let springs = take_while1(|c| ".#?".contains(c));
+    let sizes = separated_list1(char(','), unsigned);
+    parsing::lines(data.trim(), separated_pair(springs, char(' '), sizes))
 }
 
-fn split(springs: &[u8], sizes: &[usize]) -> usize {
-    fn dfs<'a>(
-        springs: &'a [u8],
-        sizes: &'a [usize],
-        curr: usize,
-        cache: &mut HashMap<(&'a [u8], &'a [usize], usize), usize>,
-    ) -> usize {
-        if let Some(&r) = cache.get(&(springs, sizes, curr)) {
-            return r;
-        }
-        let r = match springs.first() {
-            Some(&c) => {
-                let arrangements = match c {
-                    b'.' | b'?' => {
-                        if curr > 0 {
-                            if let Some(&s) = sizes.first() {
-                                if curr == s {
-                                    dfs(&springs[1..], &sizes[1..], 0, cache)
-                                } else {
-                                    0
-                                }
-                            } else {
-                                0
-                            }
-                        } else {
-                            dfs(&springs[1..], sizes, 0, cache)
-                        }
-                    }
-                    b'#' => dfs(&springs[1..], sizes, curr + 1, cache),
-                    _ => unreachable!(),
-                };
-                if c == b'?' {
-                    arrangements + dfs(&springs[1..], sizes, curr + 1, cache)
-                } else {
-                    arrangements
-                }
-            }
-            None => {
-                if curr == 0 {
-                    usize::from(sizes.is_empty())
-                } else {
-                    usize::from(sizes == [curr])
-                }
-            }
+/// State is (record, spring, group, length of the current run of damaged springs).
+struct Arrangements<'a> {
+    records: &'a [(Vec<u8>, Vec<usize>)],
+}
+
+impl Problem for Arrangements<'_> {
+    type State = (usize, usize, usize, usize);
+    type Key = Self::State;
+    type Value = usize;
+    type Objective = Count;
+
+    fn key(&self, &state: &Self::State) -> Option<Self::Key> {
+        Some(state)
+    }
+
+    fn stop(&self, &(r, i, group, run): &Self::State) -> Option<usize> {
+        let (springs, sizes) = &self.records[r];
+        let done = if run == 0 {
+            group == sizes.len()
+        } else {
+            group + 1 == sizes.len() && sizes[group] == run
         };
-        cache.insert((springs, sizes, curr), r);
-        r
+        (i == springs.len() && done).then_some(1)
     }
-    dfs(springs, sizes, 0, &mut HashMap::default())
+
+    fn children(&self, &(r, i, group, run): &Self::State, out: &mut Vec<(usize, Self::State)>) {
+        let (springs, sizes) = &self.records[r];
+        let Some(&c) = springs.get(i) else {
+            return;
+        };
+        if c != b'#' {
+            if run == 0 {
+                out.push((1, (r, i + 1, group, 0)));
+            } else if sizes.get(group) == Some(&run) {
+                out.push((1, (r, i + 1, group + 1, 0)));
+            }
+        }
+        if c != b'.' && sizes.get(group).is_some_and(|&s| run < s) {
+            out.push((1, (r, i + 1, group, run + 1)));
+        }
+    }
+}
+
+fn arrangements(records: &[(Vec<u8>, Vec<usize>)]) -> usize {
+    let roots = (0..records.len()).map(|r| (r, 0, 0, 0)).collect::<Vec<_>>();
+    let (counts, _) = exhaustive::par_solve(&Arrangements { records }, &roots);
+    counts.into_iter().flatten().sum()
 }
 
 fn part1(records: &[(&str, Vec<usize>)]) -> usize {
-    records
+    let records = records
         .iter()
-        .map(|(springs, sizes)| split(springs.as_bytes(), sizes))
-        .sum()
+        .map(|(springs, sizes)| (springs.as_bytes().to_vec(), sizes.clone()))
+        .collect::<Vec<_>>();
+    arrangements(&records)
 }
 
 fn part2(records: &[(&str, Vec<usize>)]) -> usize {
-    records
-        .par_iter()
+    let records = records
+        .iter()
         .map(|(springs, sizes)| {
             let springs: String = [springs, "?"].into_iter().cycle().take(9).collect();
-            let sizes = sizes.repeat(5);
-            split(springs.as_bytes(), &sizes)
+            (springs.into_bytes(), sizes.repeat(5))
         })
-        .sum()
+        .collect::<Vec<_>>();
+    arrangements(&records)
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day12").unwrap();
-    let records = parse(&data);
+    let records = parse(&data).unwrap();
     println!("part1: {}", part1(&records));
     println!("part2: {}", part2(&records));
 }
@@ -91,6 +89,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -102,8 +101,14 @@ mod tests {
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1
 ";
-        let records = parse(data);
+        let records = parse(data).unwrap();
         assert_eq!(21, part1(&records));
         assert_eq!(525152, part2(&records));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "???.### 1,1,3\n.??..??...?##. 1,1,3";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,10 +1,16 @@
-fn parse(data: &str) -> Vec<Vec<Vec<u8>>> {
-    data.trim()
-        .lines()
-        .collect::<Vec<_>>()
-        .split(|l| l.is_empty())
-        .map(|lines| lines.iter().map(|l| l.as_bytes().to_vec()).collect())
-        .collect()
+use adventofcode::parsing::{self, ParseError};
+use nom::{
+    Parser, bytes::complete::take_while1, character::complete::newline, combinator::verify,
+    error::context, multi::separated_list1,
+};
+
+fn parse(data: &str) -> Result<Vec<Vec<Vec<u8>>>, ParseError> {
+    let row = take_while1(|c| c == '.' || c == '#').map(|r: &str| r.as_bytes().to_vec());
+    let rows = separated_list1(newline, row);
+    let pattern = verify(rows, |rows: &Vec<Vec<u8>>| {
+        rows.iter().all(|r| r.len() == rows[0].len())
+    });
+    parsing::sections(data, context("rows of the same length", pattern))
 }
 
 fn find_horizontal_line_reflection(pattern: &[Vec<u8>], exclude: usize) -> Option<usize> {
@@ -96,7 +102,7 @@ fn part2(reflection_lines: Vec<usize>, patterns: Vec<Vec<Vec<u8>>>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day13").unwrap();
-    let patterns = parse(&data);
+    let patterns = parse(&data).unwrap();
     let reflection_lines = part1(&patterns);
     println!("part1: {}", reflection_lines.iter().sum::<usize>());
     println!("part2: {}", part2(reflection_lines, patterns));
@@ -105,6 +111,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -125,9 +132,15 @@ mod tests {
 ..##..###
 #....#..#
 ";
-        let patterns = parse(data);
+        let patterns = parse(data).unwrap();
         let reflection_lines = part1(&patterns);
         assert_eq!(405, reflection_lines.iter().sum::<usize>());
         assert_eq!(400, part2(reflection_lines, patterns));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#.##..##.\n..#.##.#.\n##......#\n\n#...##..#\n#....#..#\n..##..###";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
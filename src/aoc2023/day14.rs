@@ -1,10 +1,14 @@
-use ahash::HashMap;
+use adventofcode::cycle;
+use adventofcode::parsing::{self, ParseError};
 
-fn parse(data: &str) -> Vec<Vec<u8>> {
-    data.trim()
-        .lines()
-        .map(|line| line.as_bytes().to_vec())
-        .collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(data, "O.#")?;
+    // the spin cycle rotates the platform in place
+    if grid.len() != grid[0].len() {
+        return Err(ParseError::at(data, data, "a square platform"));
+    }
+    Ok(grid)
 }
 
 fn load_on_north_support_beams(rotated_grid: &[Vec<u8>]) -> usize {
@@ -61,28 +65,21 @@ fn rotate_90_clockwise(grid: &mut [Vec<u8>]) {
     transpose(grid);
 }
 
-fn part2(mut grid: Vec<Vec<u8>>) -> usize {
-    let mut seen = HashMap::default();
-    for i in 1.. {
+fn part2(grid: Vec<Vec<u8>>) -> usize {
+    let (cycle, grids) = cycle::find_cycle(grid, |grid| {
+        let mut grid = grid.clone();
         for _ in 0..4 {
             tilt(&mut grid);
             rotate_90_clockwise(&mut grid);
         }
-        let load = load_on_north_support_beams(&grid);
-
-        if let Some(&(j, _)) = seen.get(&grid) {
-            let period = i - j;
-            let k = j + (10_0000_0000 - i) % period;
-            return seen.values().find(|e| e.0 == k).map(|e| e.1).unwrap();
-        }
-        seen.insert(grid.clone(), (i, load));
-    }
-    unreachable!()
+        grid
+    });
+    load_on_north_support_beams(&grids[cycle.index(10_0000_0000)])
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day14").unwrap();
-    let mut grid = parse(&data);
+    let mut grid = parse(&data).unwrap();
     rotate_90_clockwise(&mut grid);
     println!("part1: {}", part1(grid.clone()));
     println!("part2: {}", part2(grid));
@@ -91,6 +88,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -106,9 +104,15 @@ O.#..O.#.#
 #....###..
 #OO..#....
 ";
-        let mut grid = parse(data);
+        let mut grid = parse(data).unwrap();
         rotate_90_clockwise(&mut grid);
         assert_eq!(136, part1(grid.clone()));
         assert_eq!(64, part2(grid));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "O....#....\nO.OO#....#\n.....##...\nOO.#O....O\n.O.....O#.\nO.#..O.#.#\n..O..#O..O\n.......O..\n#....###..\n#OO..#....";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,7 +1,23 @@
 use std::collections::VecDeque;
 
-fn parse(data: &str) -> &str {
-    data.trim()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alpha1, char},
+    combinator::{recognize, verify},
+    error::context,
+    multi::separated_list1,
+    sequence::preceded,
+};
+
+fn parse(data: &str) -> Result<&str, ParseError> {
+    let data = data.trim();
+    let focal_length = verify(unsigned::<u8>, |f| (1..=9).contains(f));
+    let operation = alt((tag("-"), preceded(char('='), recognize(focal_length))));
+    let step = (alpha1, context("an operation", operation));
+    parsing::parse_all(data, separated_list1(char(','), step))?;
+    Ok(data)
 }
 
 fn hash(s: &str) -> usize {
@@ -47,7 +63,7 @@ fn part2(sequence: &str) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day15").unwrap();
-    let sequence = parse(&data);
+    let sequence = parse(&data).unwrap();
     println!("part1: {}", part1(sequence));
     println!("part2: {}", part2(sequence));
 }
@@ -55,12 +71,19 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-        let sequence = parse(data);
+        let sequence = parse(data).unwrap();
         assert_eq!(1320, part1(sequence));
         assert_eq!(145, part2(sequence));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "rn=1,cm-,qp=3,cm=2,qp-,pc=4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,12 +1,13 @@
 use std::collections::VecDeque;
 
+use adventofcode::parsing::{self, ParseError};
 use rayon::prelude::*;
 
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|l| l.as_bytes()).collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    parsing::grid_of(data.trim(), ".|-/\\")
 }
 
-fn bounce(grid: &[&[u8]], start: (i32, i32, i32)) -> usize {
+fn bounce(grid: &[Vec<u8>], start: (i32, i32, i32)) -> usize {
     let (m, n) = (grid.len(), grid[0].len());
     let mut visited = vec![vec![[false; 4]; n]; m];
     let mut q = VecDeque::from([start]);
@@ -67,11 +68,11 @@ fn bounce(grid: &[&[u8]], start: (i32, i32, i32)) -> usize {
         .count()
 }
 
-fn part1(grid: &[&[u8]]) -> usize {
+fn part1(grid: &[Vec<u8>]) -> usize {
     bounce(grid, (0, 0, 0))
 }
 
-fn part2(grid: &[&[u8]]) -> usize {
+fn part2(grid: &[Vec<u8>]) -> usize {
     let (m, n) = (grid.len() as i32, grid[0].len() as i32);
     let m1 = (0..m)
         .into_par_iter()
@@ -88,7 +89,7 @@ fn part2(grid: &[&[u8]]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day16").unwrap();
-    let grid = parse(&data);
+    let grid = parse(&data).unwrap();
     println!("part1: {}", part1(&grid));
     println!("part2: {}", part2(&grid));
 }
@@ -96,6 +97,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -110,8 +112,14 @@ mod tests {
 .-.-/..|..
 .|....-|.\
 ..//.|....";
-        let grid = parse(data);
+        let grid = parse(data).unwrap();
         assert_eq!(46, part1(&grid));
         assert_eq!(51, part2(&grid));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = ".|...\\....\n|.-.\\.....\n.....|-...";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,23 +1,20 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use adventofcode::parsing::{self, ParseError};
+use adventofcode::search;
 
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|l| l.as_bytes()).collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(data, "0123456789")?;
+    // positions are i16
+    if grid.len() > i16::MAX as usize || grid[0].len() > i16::MAX as usize {
+        return Err(ParseError::at(data, data, "a smaller map"));
+    }
+    Ok(grid)
 }
 
-fn mininum_loss(grid: &[&[u8]], min_straight: usize, max_straight: usize) -> u32 {
+fn mininum_loss(grid: &[Vec<u8>], min_straight: usize, max_straight: usize) -> u32 {
     let (m, n) = (grid.len() as i16, grid[0].len() as i16);
-    let mut losses = vec![vec![[u32::MAX; 4]; n as usize]; m as usize];
-    let mut q = BinaryHeap::from([(Reverse(0), 0, 0, usize::MAX)]);
-    while let Some((Reverse(loss), i, j, rev)) = q.pop() {
-        if (i, j) == (m - 1, n - 1) {
-            return loss;
-        }
-        if rev < 4 {
-            if losses[i as usize][j as usize][rev] < u32::MAX {
-                continue;
-            }
-            losses[i as usize][j as usize][rev] = loss;
-        }
+    let neighbors = |&(i, j, rev): &(i16, i16, usize)| {
+        let mut next = vec![];
         for (dir1, (di, dj)) in [(0, 1), (1, 0), (0, -1), (-1, 0)].into_iter().enumerate() {
             if dir1 == rev || (dir1 + 2) % 4 == rev {
                 continue;
@@ -29,22 +26,23 @@ fn mininum_loss(grid: &[&[u8]], min_straight: usize, max_straight: usize) -> u32
                     break;
                 }
                 straight_loss += (grid[i1 as usize][j1 as usize] - b'0') as u32;
-                if k < min_straight {
-                    continue;
-                }
-                let loss1 = loss + straight_loss;
-                if loss1 < losses[i1 as usize][j1 as usize][dir1] {
-                    q.push((Reverse(loss1), i1, j1, dir1));
+                if k >= min_straight {
+                    next.push(((i1, j1, dir1), straight_loss));
                 }
             }
         }
-    }
-    unreachable!()
+        next
+    };
+    search::dijkstra([(0, 0, usize::MAX)], neighbors, |&(i, j, _)| {
+        (i, j) == (m - 1, n - 1)
+    })
+    .unwrap()
+    .1
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day17").unwrap();
-    let grid = parse(&data);
+    let grid = parse(&data).unwrap();
     println!("part1: {}", mininum_loss(&grid, 0, 3));
     println!("part2: {}", mininum_loss(&grid, 4, 10));
 }
@@ -52,6 +50,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -69,8 +68,14 @@ mod tests {
 1224686865563
 2546548887735
 4322674655533";
-        let grid = parse(data);
+        let grid = parse(data).unwrap();
         assert_eq!(102, mininum_loss(&grid, 0, 3));
         assert_eq!(94, mininum_loss(&grid, 4, 10));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "2413432311323\n3215453535623\n3255245654254";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,16 +1,28 @@
-fn parse(data: &str) -> Vec<(u8, i64, u32)> {
-    data.trim()
-        .lines()
-        .map(|l| {
-            let parts = l.split_ascii_whitespace().collect::<Vec<_>>();
-            (
-                parts[0].as_bytes()[0],
-                parts[1].parse().unwrap(),
-                u32::from_str_radix(parts[2].trim_start_matches("(#").trim_end_matches(')'), 16)
-                    .unwrap(),
-            )
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    Parser,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, one_of},
+    combinator::{map_res, verify},
+    error::context,
+    sequence::{delimited, preceded},
+};
+
+fn parse(data: &str) -> Result<Vec<(u8, i64, u32)>, ParseError> {
+    // lengths stay within the 20 bits of part 2 so the area fits in an i64
+    let len = context(
+        "a length below 2^20",
+        verify(unsigned::<i64>, |&n| n < 1 << 20),
+    );
+    let hex = take_while_m_n(6, 6, |c: char| c.is_ascii_hexdigit());
+    let color = map_res(hex, |h| u32::from_str_radix(h, 16));
+    let color = context("a direction from 0 to 3", verify(color, |c| c & 0xf < 4));
+    let step = (
+        one_of("RDLU").map(|d| d as u8),
+        preceded(char(' '), len),
+        delimited(tag(" (#"), color, char(')')),
+    );
+    parsing::lines(data.trim(), step)
 }
 
 fn points(instructions: &[(u8, i64)]) -> usize {
@@ -58,7 +70,7 @@ fn part2(plan: &[(u8, i64, u32)]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day18").unwrap();
-    let plan = parse(&data);
+    let plan = parse(&data).unwrap();
     println!("part1: {}", part1(&plan));
     println!("part2: {}", part2(&plan));
 }
@@ -66,6 +78,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -84,8 +97,14 @@ R 2 (#7807d2)
 U 3 (#a77fa3)
 L 2 (#015232)
 U 2 (#7a21e3)";
-        let plan = parse(data);
+        let plan = parse(data).unwrap();
         assert_eq!(62, part1(&plan));
         assert_eq!(952408144115, part2(&plan));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "R 6 (#70c710)\nD 5 (#0dc571)\nL 2 (#5713f0)\nD 2 (#d2c081)";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,209 +1,391 @@
-use ahash::HashMap;
-use arrayvec::ArrayVec;
+use adventofcode::hyperbox::HyperBox;
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use ahash::{HashMap, HashSet};
+use nom::Parser;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{alphanumeric1, char, newline, one_of};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, separated_pair};
+use std::fmt;
+
+type Region = HyperBox<4>;
+
+/// Every part has each rating in `1..=4000`.
+const RATINGS: Region = HyperBox {
+    lo: [1; 4],
+    hi: [4001; 4],
+};
+
+/// `rating[axis] < at` when `below`, `rating[axis] >= at` otherwise.
+#[derive(Debug, Clone, Copy)]
+struct Condition {
+    axis: usize,
+    at: i64,
+    below: bool,
+}
+
+impl Condition {
+    fn new(axis: usize, cmp: char, n: i64) -> Self {
+        match cmp {
+            '<' => Self {
+                axis,
+                at: n,
+                below: true,
+            },
+            '>' => Self {
+                axis,
+                at: n + 1,
+                below: false,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// The parts of `region` that pass and fail.
+    fn split(&self, region: &Region) -> (Option<Region>, Option<Region>) {
+        let (below, above) = region.split(self.axis, self.at);
+        if self.below {
+            (below, above)
+        } else {
+            (above, below)
+        }
+    }
+}
 
 enum Rule<'a> {
     Accept,
     Reject,
-    Test(u8, u8, u32, &'a str),
+    Test(Condition, &'a str),
     Workflow(&'a str),
 }
 
-fn parse_rule(s: &str) -> Rule<'_> {
-    match s {
+/// The index of an `x`, `m`, `a` or `s` rating.
+fn category(input: &str) -> PResult<'_, usize> {
+    one_of("xmas").map(|c| "xmas".find(c).unwrap()).parse(input)
+}
+
+fn rule(input: &str) -> PResult<'_, Rule<'_>> {
+    let condition = (category, one_of("<>"), unsigned::<u16>)
+        .map(|(axis, cmp, n)| Condition::new(axis, cmp, n.into()));
+    let test = separated_pair(condition, char(':'), alphanumeric1)
+        .map(|(condition, target)| Rule::Test(condition, target));
+    let jump = alphanumeric1.map(|target| match target {
         "A" => Rule::Accept,
         "R" => Rule::Reject,
-        x if x.contains(':') => {
-            let t = s.as_bytes();
-            let i = t.iter().position(|&c| c == b':').unwrap();
-            let p = part_number(t[0]);
-            let n = s[2..i].parse().unwrap();
-            Rule::Test(p, t[1], n, &s[i + 1..])
-        }
         x => Rule::Workflow(x),
-    }
+    });
+    alt((test, jump)).parse(input)
 }
 
-fn part_number(part: u8) -> u8 {
-    match part {
-        b'x' => 0,
-        b'm' => 1,
-        b'a' => 2,
-        b's' => 3,
-        _ => unreachable!(),
-    }
+fn workflow(input: &str) -> PResult<'_, (&str, Vec<Rule<'_>>)> {
+    let rules = delimited(char('{'), separated_list1(char(','), rule), char('}'));
+    (alphanumeric1, rules).parse(input)
 }
 
-fn parse(data: &str) -> (HashMap<&str, Vec<Rule<'_>>>, Vec<[u32; 4]>) {
-    let lines = data.trim().lines().collect::<Vec<_>>();
-    let i = lines.iter().position(|l| l.is_empty()).unwrap();
-    let workflows = lines[..i]
-        .iter()
-        .map(|l| {
-            let (k, v) = l.split_once('{').unwrap();
-            let rules = v.trim_end_matches('}').split(',').map(parse_rule).collect();
-            (k, rules)
-        })
-        .collect();
-    let ratings = lines[i + 1..]
-        .iter()
-        .map(|l| {
-            let l = l.trim_matches(|c| c == '{' || c == '}');
+/// A part's ratings, like `{x=787,m=2655,a=1222,s=2876}`.
+fn part(input: &str) -> PResult<'_, [u32; 4]> {
+    let rating = separated_pair(category, char('='), unsigned::<u32>);
+    let ratings = delimited(char('{'), separated_list1(char(','), rating), char('}'));
+    ratings
+        .map(|ratings| {
             let mut r = [0; 4];
-            for p in l.split(',') {
-                let (part, rating) = p.split_once('=').unwrap();
-                let rating = rating.parse().unwrap();
-                let i = part_number(part.as_bytes()[0]);
-                r[i as usize] = rating;
+            for (i, rating) in ratings {
+                r[i] = rating;
             }
             r
         })
-        .collect();
-    (workflows, ratings)
+        .parse(input)
 }
 
-fn part1(workflows: &HashMap<&str, Vec<Rule>>, ratings: &[[u32; 4]]) -> u32 {
-    ratings
-        .iter()
-        .map(|rating| {
-            let mut w = "in";
-            loop {
-                match w {
-                    "A" => return rating.iter().sum(),
-                    "R" => return 0,
-                    _ => {}
-                }
-                for rule in &workflows[w] {
-                    match rule {
-                        Rule::Accept => return rating.iter().sum(),
-                        Rule::Reject => return 0,
-                        Rule::Test(p, c, n, next) => {
-                            let test = match c {
-                                b'>' => rating[*p as usize] > *n,
-                                b'<' => rating[*p as usize] < *n,
-                                _ => unreachable!(),
-                            };
-                            if test {
-                                w = next;
-                                break;
-                            }
-                        }
-                        Rule::Workflow(next) => w = next,
-                    }
-                }
-            }
-        })
-        .sum()
+type Workflows<'a> = HashMap<&'a str, Vec<Rule<'a>>>;
+
+fn parse(data: &str) -> Result<(Workflows<'_>, Vec<[u32; 4]>), ParseError> {
+    let workflows = separated_list1(newline, workflow);
+    let ratings = separated_list1(newline, part);
+    let (workflows, ratings) =
+        parsing::parse_all(data.trim(), separated_pair(workflows, tag("\n\n"), ratings))?;
+    Ok((workflows.into_iter().collect(), ratings))
 }
 
-#[derive(Clone, Copy)]
-struct Range {
-    start: i16,
-    end: i16,
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CompileError {
+    UnknownWorkflow(String),
+    Cycle(String),
+    NoDefault(String),
 }
 
-impl Range {
-    const fn new(start: i16, end: i16) -> Self {
-        Self { start, end }
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::UnknownWorkflow(w) => write!(f, "unknown workflow {w}"),
+            CompileError::Cycle(w) => write!(f, "workflow {w} can send a part back to itself"),
+            CompileError::NoDefault(w) => write!(f, "workflow {w} has no final rule"),
+        }
     }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+    Accept,
+    Reject,
+    /// Goes to `below` when `rating[axis] < at`, to `above` otherwise.
+    Split {
+        axis: usize,
+        at: i64,
+        below: usize,
+        above: usize,
+    },
+}
 
-    fn negate(&self) -> ArrayVec<Range, 2> {
-        let mut r = ArrayVec::new();
-        if self.start > 1 {
-            r.push(Range::new(1, self.start));
+const ACCEPT: usize = 0;
+const REJECT: usize = 1;
+
+/// All the workflows flattened into one DAG of single comparisons, with
+/// identical subtrees shared and tests whose branches agree dropped.
+#[derive(Debug, Clone)]
+struct DecisionTree {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+struct Compiler<'a, 'w> {
+    workflows: &'w Workflows<'a>,
+    nodes: Vec<Node>,
+    interned: HashMap<Node, usize>,
+    compiled: HashMap<&'a str, usize>,
+    visiting: HashSet<&'a str>,
+}
+
+impl<'a> Compiler<'a, '_> {
+    fn intern(&mut self, node: Node) -> usize {
+        if let Node::Split { below, above, .. } = node
+            && below == above
+        {
+            return below;
         }
-        if self.end < 4001 {
-            r.push(Range::new(self.end, 4001));
+        *self.interned.entry(node).or_insert_with(|| {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        })
+    }
+
+    fn workflow(&mut self, name: &'a str) -> Result<usize, CompileError> {
+        match name {
+            "A" => return Ok(ACCEPT),
+            "R" => return Ok(REJECT),
+            _ => {}
+        }
+        if let Some(&i) = self.compiled.get(name) {
+            return Ok(i);
+        }
+        if !self.visiting.insert(name) {
+            return Err(CompileError::Cycle(name.to_string()));
+        }
+        let rules = self
+            .workflows
+            .get(name)
+            .ok_or_else(|| CompileError::UnknownWorkflow(name.to_string()))?;
+        // anything after the first unconditional rule is dead
+        let end = rules
+            .iter()
+            .position(|r| !matches!(r, Rule::Test(..)))
+            .ok_or_else(|| CompileError::NoDefault(name.to_string()))?;
+        let mut next = match rules[end] {
+            Rule::Accept => ACCEPT,
+            Rule::Reject => REJECT,
+            Rule::Workflow(w) => self.workflow(w)?,
+            Rule::Test(..) => unreachable!(),
+        };
+        for rule in rules[..end].iter().rev() {
+            let Rule::Test(c, w) = *rule else {
+                unreachable!()
+            };
+            let pass = self.workflow(w)?;
+            let (below, above) = if c.below { (pass, next) } else { (next, pass) };
+            next = self.intern(Node::Split {
+                axis: c.axis,
+                at: c.at,
+                below,
+                above,
+            });
         }
-        r
+        self.visiting.remove(name);
+        self.compiled.insert(name, next);
+        Ok(next)
     }
+}
 
-    fn intersect(&self, other: &Range) -> Option<Self> {
-        let start = self.start.max(other.start);
-        let end = self.end.min(other.end);
-        if start < end {
-            Some(Range::new(start, end))
-        } else {
-            None
+impl DecisionTree {
+    fn compile(workflows: &Workflows) -> Result<Self, CompileError> {
+        let mut compiler = Compiler {
+            workflows,
+            nodes: vec![Node::Accept, Node::Reject],
+            interned: HashMap::default(),
+            compiled: HashMap::default(),
+            visiting: HashSet::default(),
+        };
+        compiler.interned.insert(Node::Accept, ACCEPT);
+        compiler.interned.insert(Node::Reject, REJECT);
+        let root = compiler.workflow("in")?;
+        Ok(Self {
+            nodes: compiler.nodes,
+            root,
+        })
+    }
+
+    fn accepts(&self, rating: &[u32; 4]) -> bool {
+        let mut i = self.root;
+        loop {
+            match self.nodes[i] {
+                Node::Accept => return true,
+                Node::Reject => return false,
+                Node::Split {
+                    axis,
+                    at,
+                    below,
+                    above,
+                } => {
+                    i = if (rating[axis] as i64) < at {
+                        below
+                    } else {
+                        above
+                    }
+                }
+            }
         }
     }
 
-    fn count(&self) -> usize {
-        (self.end - self.start) as _
+    /// Disjoint boxes covering exactly the accepted points of `region`.
+    fn accepted(&self, region: Region) -> Vec<Region> {
+        let mut result = vec![];
+        let mut stack = vec![(self.root, region)];
+        while let Some((i, region)) = stack.pop() {
+            match self.nodes[i] {
+                Node::Accept => result.push(region),
+                Node::Reject => {}
+                Node::Split {
+                    axis,
+                    at,
+                    below,
+                    above,
+                } => {
+                    let (lo, hi) = region.split(axis, at);
+                    stack.extend(lo.map(|r| (below, r)));
+                    stack.extend(hi.map(|r| (above, r)));
+                }
+            }
+        }
+        result
     }
 }
 
-const FULL: Range = Range::new(1, 4001);
+/// Workflows no part can reach, and reachable ones that send every part
+/// reaching them the same way, so could be replaced by `A` or `R`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Report<'a> {
+    unreachable: Vec<&'a str>,
+    redundant: Vec<(&'a str, bool)>,
+}
 
-fn combinations(parts: &[Vec<Range>]) -> usize {
-    parts
-        .iter()
-        .map(|p| p.iter().map(|r| r.count()).sum::<usize>())
-        .product()
-}
-
-fn dfs(workflows: &HashMap<&str, Vec<Rule>>, w: &str, mut parts: [Vec<Range>; 4]) -> usize {
-    match w {
-        "A" => return combinations(&parts),
-        "R" => return 0,
-        _ => {}
-    }
-
-    let mut result = 0;
-    for rule in &workflows[w] {
-        match rule {
-            Rule::Accept => return result + combinations(&parts),
-            Rule::Reject => return result,
-            &Rule::Test(p, cmp, n, w) => {
-                let range = if cmp == b'>' {
-                    Range::new(n as i16 + 1, 4001)
-                } else {
-                    Range::new(1, n as i16)
-                };
-                let mut next = parts.clone();
-                next[p as usize].iter_mut().for_each(|a| {
-                    if let Some(r) = a.intersect(&range) {
-                        *a = r;
+/// Volume of parts reaching and accepted by each workflow.
+struct Analysis<'a, 'w> {
+    workflows: &'w Workflows<'a>,
+    flow: HashMap<&'a str, (u64, u64)>,
+}
+
+impl<'a> Analysis<'a, '_> {
+    fn visit(&mut self, name: &'a str, region: Region) -> u64 {
+        match name {
+            "A" => return region.volume(),
+            "R" => return 0,
+            _ => {}
+        }
+        let mut accepted = 0;
+        let mut rest = Some(region);
+        for rule in &self.workflows[name] {
+            let Some(r) = rest else { break };
+            rest = None;
+            match *rule {
+                Rule::Accept => accepted += r.volume(),
+                Rule::Reject => {}
+                Rule::Workflow(w) => accepted += self.visit(w, r),
+                Rule::Test(c, w) => {
+                    let (pass, fail) = c.split(&r);
+                    if let Some(pass) = pass {
+                        accepted += self.visit(w, pass);
                     }
-                });
-                result += dfs(workflows, w, next);
-
-                // proceed to next rule
-                for r in range.negate() {
-                    parts[p as usize].iter_mut().for_each(|a| {
-                        if let Some(b) = a.intersect(&r) {
-                            *a = b;
-                        }
-                    });
+                    rest = fail;
                 }
             }
-            Rule::Workflow(w) => return result + dfs(workflows, w, parts.clone()),
         }
+        let e = self.flow.entry(name).or_default();
+        e.0 += region.volume();
+        e.1 += accepted;
+        accepted
     }
-    result
 }
 
-fn part2(workflows: &HashMap<&str, Vec<Rule>>) -> usize {
-    dfs(
+/// Needs workflows that compile, so that there are no cycles.
+fn analyze<'a>(workflows: &Workflows<'a>) -> Report<'a> {
+    let mut analysis = Analysis {
         workflows,
-        "in",
-        [vec![FULL], vec![FULL], vec![FULL], vec![FULL]],
-    )
+        flow: HashMap::default(),
+    };
+    analysis.visit("in", RATINGS);
+    let mut report = Report::default();
+    for &name in workflows.keys() {
+        match analysis.flow.get(name) {
+            None => report.unreachable.push(name),
+            Some(&(_, 0)) => report.redundant.push((name, false)),
+            Some(&(reached, accepted)) if reached == accepted => {
+                report.redundant.push((name, true))
+            }
+            _ => {}
+        }
+    }
+    report.unreachable.sort_unstable();
+    report.redundant.sort_unstable();
+    report
+}
+
+fn part1(tree: &DecisionTree, ratings: &[[u32; 4]]) -> u32 {
+    ratings
+        .iter()
+        .filter(|r| tree.accepts(r))
+        .map(|r| r.iter().sum::<u32>())
+        .sum()
+}
+
+fn part2(tree: &DecisionTree) -> u64 {
+    tree.accepted(RATINGS).iter().map(|r| r.volume()).sum()
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day19").unwrap();
-    let (workflows, ratings) = parse(&data);
-    println!("part1: {}", part1(&workflows, &ratings));
-    println!("part2: {}", part2(&workflows));
+    let (workflows, ratings) = parse(&data).unwrap();
+    let tree = DecisionTree::compile(&workflows).unwrap();
+    println!("part1: {}", part1(&tree, &ratings));
+    println!("part2: {}", part2(&tree));
+    if std::env::args().any(|a| a == "--report") {
+        let report = analyze(&workflows);
+        println!("unreachable: {}", report.unreachable.join(" "));
+        for (name, accepts) in report.redundant {
+            println!(
+                "redundant: {name} always {}",
+                if accepts { "A" } else { "R" }
+            );
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use rand::prelude::*;
 
-    #[test]
-    fn case1() {
-        let data = r"
+    const EXAMPLE: &str = r"
 px{a<2006:qkq,m>2090:A,rfg}
 pv{a>1716:R,A}
 lnx{m>1548:A,A}
@@ -221,8 +403,158 @@ hdj{m>838:A,pv}
 {x=2036,m=264,a=79,s=2244}
 {x=2461,m=1339,a=466,s=291}
 {x=2127,m=1623,a=2188,s=1013}";
-        let (workflows, ratings) = parse(data);
-        assert_eq!(19114, part1(&workflows, &ratings));
-        assert_eq!(167409079868000, part2(&workflows));
+
+    /// Follows the rules one at a time, as the puzzle describes.
+    fn interpret(workflows: &Workflows, rating: &[u32; 4]) -> bool {
+        let mut w = "in";
+        loop {
+            match w {
+                "A" => return true,
+                "R" => return false,
+                _ => {}
+            }
+            for rule in &workflows[w] {
+                match *rule {
+                    Rule::Accept => return true,
+                    Rule::Reject => return false,
+                    Rule::Test(c, next) => {
+                        let v = rating[c.axis] as i64;
+                        if (v < c.at) == c.below {
+                            w = next;
+                            break;
+                        }
+                    }
+                    Rule::Workflow(next) => {
+                        w = next;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn case1() {
+        let (workflows, ratings) = parse(EXAMPLE).unwrap();
+        let tree = DecisionTree::compile(&workflows).unwrap();
+        assert_eq!(19114, part1(&tree, &ratings));
+        assert_eq!(167409079868000, part2(&tree));
+    }
+
+    #[test]
+    fn report() {
+        let (workflows, _) = parse(EXAMPLE).unwrap();
+        let report = analyze(&workflows);
+        assert!(report.unreachable.is_empty());
+        // qs only ever falls through to lnx
+        let redundant = vec![("gd", false), ("lnx", true), ("qs", true)];
+        assert_eq!(redundant, report.redundant);
+
+        let data = "
+in{x<100:a,b}
+a{x>200:c,R}
+b{m<10:R,A}
+c{A}
+d{R}
+
+{x=1,m=1,a=1,s=1}";
+        let (workflows, _) = parse(data).unwrap();
+        let report = analyze(&workflows);
+        assert_eq!(vec!["c", "d"], report.unreachable);
+        assert_eq!(vec![("a", false)], report.redundant);
+    }
+
+    #[test]
+    fn compile_errors() {
+        let compile = |data| {
+            let (workflows, _) = parse(data).unwrap();
+            DecisionTree::compile(&workflows).map(|_| ())
+        };
+        let unknown = "in{x<10:A,foo}\n\n{x=1,m=1,a=1,s=1}";
+        assert_eq!(
+            Err(CompileError::UnknownWorkflow("foo".into())),
+            compile(unknown)
+        );
+        let cycle = "in{x<10:a,R}\na{m>5:in,A}\n\n{x=1,m=1,a=1,s=1}";
+        assert_eq!(Err(CompileError::Cycle("in".into())), compile(cycle));
+        let open = "in{x<10:A}\n\n{x=1,m=1,a=1,s=1}";
+        assert_eq!(Err(CompileError::NoDefault("in".into())), compile(open));
+    }
+
+    #[test]
+    fn shared_subtrees() {
+        // a and b share a node, and then so do both branches of in
+        let data = "
+in{x<10:a,b}
+a{m>5:A,R}
+b{m>5:A,R}
+
+{x=1,m=1,a=1,s=1}";
+        let (workflows, _) = parse(data).unwrap();
+        let tree = DecisionTree::compile(&workflows).unwrap();
+        assert_eq!(3, tree.nodes.len());
+        assert_eq!(4000 * 3995 * 4000 * 4000, part2(&tree));
+    }
+
+    #[test]
+    fn random_workflows() {
+        let mut rng = StdRng::seed_from_u64(19);
+        for _ in 0..50 {
+            let n = rng.random_range(1..12);
+            let names = (0..n).map(|i| format!("w{i}")).collect::<Vec<_>>();
+            let mut lines = vec![];
+            for i in 0..n {
+                let target = |rng: &mut StdRng| match rng.random_range(0..4) {
+                    0 => "A".to_string(),
+                    1 => "R".to_string(),
+                    _ if i + 1 < n => names[rng.random_range(i + 1..n)].clone(),
+                    _ => "A".to_string(),
+                };
+                let mut rules = vec![];
+                for _ in 0..rng.random_range(0..4) {
+                    let part = ['x', 'm', 'a', 's'][rng.random_range(0..4)];
+                    let cmp = if rng.random_bool(0.5) { '<' } else { '>' };
+                    let v = rng.random_range(1..4001);
+                    rules.push(format!("{part}{cmp}{v}:{}", target(&mut rng)));
+                }
+                rules.push(target(&mut rng));
+                let name = if i == 0 { "in" } else { names[i].as_str() };
+                lines.push(format!("{name}{{{}}}", rules.join(",")));
+            }
+            let data = lines.join("\n") + "\n\n{x=1,m=1,a=1,s=1}";
+            let (workflows, _) = parse(&data).unwrap();
+            let tree = DecisionTree::compile(&workflows).unwrap();
+            let boxes = tree.accepted(RATINGS);
+            let mut accepted = 0;
+            for _ in 0..200 {
+                let rating = std::array::from_fn(|_| rng.random_range(1..4001));
+                let expected = interpret(&workflows, &rating);
+                assert_eq!(expected, tree.accepts(&rating));
+                let point = rating.map(|v| v as i64);
+                let hits = boxes.iter().filter(|b| b.contains(&point)).count();
+                assert_eq!(expected as usize, hits);
+                accepted += expected as u64;
+            }
+            let volume = part2(&tree);
+            assert!(accepted == 0 || volume > 0);
+            let mut analysis = Analysis {
+                workflows: &workflows,
+                flow: HashMap::default(),
+            };
+            assert_eq!(volume, analysis.visit("in", RATINGS));
+        }
+    }
+
+    #[test]
+    fn fuzz() {
+        fuzz::check(&[EXAMPLE], |s| {
+            let (workflows, ratings) = parse(s)?;
+            if let Ok(tree) = DecisionTree::compile(&workflows) {
+                part1(&tree, &ratings);
+                part2(&tree);
+                analyze(&workflows);
+            }
+            Ok::<_, ParseError>(())
+        });
     }
 }
@@ -1,34 +1,40 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::char,
+    multi::separated_list1,
+    sequence::{delimited, separated_pair},
+};
+
 struct Game {
     id: u32,
     subsets: Vec<[u32; 3]>,
 }
 
-fn parse(data: &str) -> Vec<Game> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (p1, p2) = line.split_once(": ").unwrap();
-            let id: u32 = p1.strip_prefix("Game ").unwrap().parse().unwrap();
-            let subsets = p2
-                .split("; ")
-                .map(|parts| {
-                    let mut set = [0; 3];
-                    parts.split(", ").for_each(|s| {
-                        let (quantity, color) = s.split_once(' ').unwrap();
-                        let quantity: u32 = quantity.parse().unwrap();
-                        match color {
-                            "red" => set[0] = quantity,
-                            "green" => set[1] = quantity,
-                            "blue" => set[2] = quantity,
-                            c => panic!("unknown color {c}"),
-                        };
-                    });
-                    set
-                })
-                .collect();
-            Game { id, subsets }
-        })
-        .collect()
+fn cubes(input: &str) -> PResult<'_, [u32; 3]> {
+    let color = alt((tag("red"), tag("green"), tag("blue")));
+    let cube = separated_pair(unsigned::<u32>, char(' '), color);
+    let (rest, cubes) = separated_list1(tag(", "), cube).parse(input)?;
+    let mut set = [0; 3];
+    for (quantity, color) in cubes {
+        match color {
+            "red" => set[0] = quantity,
+            "green" => set[1] = quantity,
+            _ => set[2] = quantity,
+        }
+    }
+    Ok((rest, set))
+}
+
+fn parse(data: &str) -> Result<Vec<Game>, ParseError> {
+    let game = (
+        delimited(tag("Game "), unsigned, tag(": ")),
+        separated_list1(tag("; "), cubes),
+    );
+    let game = game.map(|(id, subsets)| Game { id, subsets });
+    parsing::lines(data.trim(), game)
 }
 
 fn part1(input: &[Game]) -> u32 {
@@ -64,7 +70,7 @@ fn part2(input: &[Game]) -> u32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day2").unwrap();
-    let input = parse(&data);
+    let input = parse(&data).unwrap();
     println!("part1: {}", part1(&input));
     println!("part2: {}", part2(&input));
 }
@@ -72,6 +78,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -81,8 +88,15 @@ Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        let input = parse(data);
+        let input = parse(data).unwrap();
         assert_eq!(8, part1(&input));
         assert_eq!(2286, part2(&input));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed =
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\nGame 2: 1 blue, 2 green";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
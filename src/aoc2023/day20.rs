@@ -1,205 +1,371 @@
-use std::collections::{hash_map::Entry, VecDeque};
+use std::collections::VecDeque;
+use std::fmt::Write;
 
+use adventofcode::dot::{self, Dot};
+use adventofcode::math::lcm;
+use adventofcode::parsing::{self, PResult, ParseError};
 use ahash::HashMap;
+use nom::{
+    Parser,
+    bytes::complete::{tag, take_while1},
+    character::complete::one_of,
+    combinator::opt,
+    multi::separated_list1,
+    sequence::preceded,
+};
 
-#[derive(Debug, Clone, Copy, Default)]
-enum ModuleType {
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Kind {
     #[default]
     Untyped,
     Broadcaster,
     FlipFlop,
-    Conjuction,
+    Conjunction,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 struct Module {
-    kind: ModuleType,
-    // when module type is FlipFlop, state represents the on/off state,
-    // when module type is Conjuction, state represents it's input pulses,
-    // (since total number of modules is less than 64, for the examples and personal input)
-    state: usize,
-    // bit mask of the inputs
-    inputs: usize,
-    // bit mask of the outputs
-    outputs: usize,
+    kind: Kind,
+    inputs: Vec<usize>,
+    /// Target and the slot of this module among the target's inputs.
+    outputs: Vec<(usize, usize)>,
 }
 
-impl Module {
-    fn new(kind: ModuleType) -> Self {
-        Self {
-            kind,
-            ..Default::default()
+#[derive(Debug, Clone)]
+struct Network<'a> {
+    names: Vec<&'a str>,
+    modules: Vec<Module>,
+}
+
+impl<'a> Network<'a> {
+    fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|&n| n == name)
+    }
+
+    /// The module graph, flip-flops as boxes and conjunctions as diamonds.
+    fn to_dot(&self) -> Dot {
+        let mut dot = Dot::digraph();
+        for (name, m) in self.names.iter().zip(&self.modules) {
+            let (label, shape) = match m.kind {
+                Kind::Untyped => (name.to_string(), "plaintext"),
+                Kind::Broadcaster => (name.to_string(), "doublecircle"),
+                Kind::FlipFlop => (format!("%{name}"), "box"),
+                Kind::Conjunction => (format!("&{name}"), "diamond"),
+            };
+            dot.node(name, &[("label", &label), ("shape", shape)]);
         }
+        for (name, m) in self.names.iter().zip(&self.modules) {
+            for &(j, _) in &m.outputs {
+                dot.edge(name, self.names[j], None);
+            }
+        }
+        dot
     }
 }
 
-fn get_index<'a>(map: &mut HashMap<&'a str, usize>, name: &'a str, idx: &mut usize) -> usize {
-    match name {
-        "broadcaster" => 0,
-        "rx" => 1,
-        name => match map.entry(name) {
-            Entry::Occupied(e) => *e.get(),
-            Entry::Vacant(e) => {
-                *idx += 1;
-                e.insert(*idx);
-                *idx
-            }
-        },
+fn module(input: &str) -> PResult<'_, (Option<char>, &str, Vec<&str>)> {
+    let name = || take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_');
+    let outputs = separated_list1(tag(", "), name());
+    (opt(one_of("%&")), name(), preceded(tag(" -> "), outputs)).parse(input)
+}
+
+fn parse(data: &str) -> Result<Network<'_>, ParseError> {
+    let data = data.trim();
+    let mut names = vec![];
+    let mut ids = HashMap::<&str, usize>::default();
+    let mut modules: Vec<Module> = vec![];
+    let mut id = |name| {
+        *ids.entry(name).or_insert_with(|| {
+            names.push(name);
+            modules.push(Module::default());
+            names.len() - 1
+        })
+    };
+    let mut kinds = vec![];
+    let mut edges = vec![];
+    for (prefix, name, outputs) in parsing::lines(data, module)? {
+        let kind = match prefix {
+            Some('%') => Kind::FlipFlop,
+            Some('&') => Kind::Conjunction,
+            _ => Kind::Broadcaster,
+        };
+        let i = id(name);
+        kinds.push((i, name, kind));
+        for t in outputs {
+            edges.push((i, id(t)));
+        }
+    }
+    for (i, name, kind) in kinds {
+        if modules[i].kind != Kind::Untyped {
+            return Err(ParseError::at(data, name, "a new module"));
+        }
+        modules[i].kind = kind;
+    }
+    for (i, j) in edges {
+        let slot = modules[j].inputs.len();
+        modules[j].inputs.push(i);
+        modules[i].outputs.push((j, slot));
     }
+    Ok(Network { names, modules })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pulse {
+    /// `None` for the button.
+    from: Option<usize>,
+    to: usize,
+    slot: usize,
+    high: bool,
+}
+
+/// State of every module between button presses.
+#[derive(Debug, Clone)]
+struct Simulator<'n> {
+    network: &'n Network<'n>,
+    on: Vec<bool>,
+    /// Last pulse from every input of a conjunction, and how many were high.
+    memory: Vec<Vec<bool>>,
+    high_inputs: Vec<usize>,
+    queue: VecDeque<Pulse>,
 }
 
-fn parse(data: &str) -> (HashMap<&str, usize>, Vec<Module>) {
-    let lines = data.trim().lines().collect::<Vec<_>>();
-    let mut map = HashMap::<&str, usize>::default();
-    let mut idx = 1;
-    let mut modules = vec![Module::new(ModuleType::Broadcaster), Module::default()];
-    for line in lines {
-        let (m, outpus) = line.split_once("->").unwrap();
-        let m = m.trim();
-        let i = match m.as_bytes()[0] {
-            b'%' => {
-                let i = get_index(&mut map, &m[1..], &mut idx);
-                if i >= modules.len() {
-                    modules.resize_with(i + 1, Module::default);
+impl<'n> Simulator<'n> {
+    fn new(network: &'n Network<'n>) -> Self {
+        let modules = &network.modules;
+        Self {
+            network,
+            on: vec![false; modules.len()],
+            memory: modules
+                .iter()
+                .map(|m| vec![false; m.inputs.len()])
+                .collect(),
+            high_inputs: vec![0; modules.len()],
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Pushes the button, calling `observe` with every pulse in the order
+    /// they are delivered.
+    fn press(&mut self, mut observe: impl FnMut(&Pulse)) {
+        let Some(broadcaster) = self.network.index("broadcaster") else {
+            return;
+        };
+        self.queue.push_back(Pulse {
+            from: None,
+            to: broadcaster,
+            slot: 0,
+            high: false,
+        });
+        while let Some(pulse) = self.queue.pop_front() {
+            observe(&pulse);
+            let j = pulse.to;
+            let module = &self.network.modules[j];
+            let high = match module.kind {
+                Kind::Untyped => continue,
+                Kind::Broadcaster => pulse.high,
+                Kind::FlipFlop if pulse.high => continue,
+                Kind::FlipFlop => {
+                    self.on[j] = !self.on[j];
+                    self.on[j]
                 }
-                modules[i].kind = ModuleType::FlipFlop;
-                i
-            }
-            b'&' => {
-                let i = get_index(&mut map, &m[1..], &mut idx);
-                if i >= modules.len() {
-                    modules.resize_with(i + 1, Module::default);
+                Kind::Conjunction => {
+                    let last = &mut self.memory[j][pulse.slot];
+                    if *last != pulse.high {
+                        *last = pulse.high;
+                        if pulse.high {
+                            self.high_inputs[j] += 1;
+                        } else {
+                            self.high_inputs[j] -= 1;
+                        }
+                    }
+                    self.high_inputs[j] != module.inputs.len()
                 }
-                modules[i].kind = ModuleType::Conjuction;
-                i
+            };
+            for &(to, slot) in &module.outputs {
+                self.queue.push_back(Pulse {
+                    from: Some(j),
+                    to,
+                    slot,
+                    high,
+                });
             }
-            b'b' => 0,
-            _ => unreachable!(),
-        };
-        for t in outpus.split(',') {
-            let t = t.trim();
-            let j = get_index(&mut map, t, &mut idx);
-            if j >= modules.len() {
-                modules.resize_with(j + 1, Module::default);
-            }
-            modules[j].inputs |= 1 << i;
-            modules[i].outputs |= 1 << j;
         }
     }
-    (map, modules)
+
+    /// Presses the button `n` times and returns every pulse in the puzzle's
+    /// notation, one per line.
+    fn trace(&mut self, n: usize) -> String {
+        let names = &self.network.names;
+        let mut lines = String::new();
+        for _ in 0..n {
+            self.press(|p| {
+                let from = p.from.map_or("button", |i| names[i]);
+                let level = if p.high { "high" } else { "low" };
+                writeln!(lines, "{from} -{level}-> {}", names[p.to]).unwrap();
+            });
+        }
+        lines
+    }
 }
 
-fn process(
-    modules: &mut [Module],
-    q: &mut VecDeque<(usize, usize, usize)>,
-    p: usize,
-    i: usize,
-    j: usize,
-) {
-    match modules[j].kind {
-        ModuleType::Untyped => {}
-        ModuleType::Broadcaster => {
-            for k in 0..usize::BITS {
-                if (1 << k) & modules[j].outputs > 0 {
-                    q.push_back((p, j, k as usize));
-                }
+/// A chain of flip-flops started by the broadcaster that counts button
+/// presses in binary. The flip-flops whose bit is set feed the hub
+/// conjunction, which fires low and resets the chain on press `period`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Counter {
+    flip_flops: Vec<usize>,
+    hub: usize,
+    period: u64,
+}
+
+/// The counters hanging off the broadcaster, `None` unless every output of
+/// the broadcaster starts one and every hub leads to `rx`.
+fn counters(network: &Network) -> Option<Vec<Counter>> {
+    let modules = &network.modules;
+    let kind = |i: usize| modules[i].kind;
+    let broadcaster = network.index("broadcaster")?;
+    let rx = network.index("rx")?;
+    let mut result = vec![];
+    for &(start, _) in &modules[broadcaster].outputs {
+        let mut flip_flops = vec![];
+        let mut hub = None;
+        let mut period = 0u64;
+        let mut cur = Some(start);
+        while let Some(i) = cur {
+            if kind(i) != Kind::FlipFlop || flip_flops.len() >= 63 {
+                return None;
             }
-        }
-        ModuleType::FlipFlop => {
-            if p == 0 {
-                modules[j].state = 1 - modules[j].state;
-                let p = modules[j].state;
-                for k in 0..usize::BITS {
-                    if (1 << k) & modules[j].outputs > 0 {
-                        q.push_back((p, j, k as usize));
+            let mut next = None;
+            for &(j, _) in &modules[i].outputs {
+                match kind(j) {
+                    Kind::FlipFlop if next.is_none() => next = Some(j),
+                    Kind::Conjunction if hub.is_none_or(|h| h == j) => {
+                        hub = Some(j);
+                        period |= 1 << flip_flops.len();
                     }
+                    _ => return None,
                 }
             }
+            flip_flops.push(i);
+            cur = next;
         }
-        ModuleType::Conjuction => {
-            if p == 0 {
-                modules[j].state &= !(1 << i);
-            } else {
-                modules[j].state |= 1 << i;
-            }
-            let p = usize::from(modules[j].state != modules[j].inputs);
-            for k in 0..usize::BITS {
-                if (1 << k) & modules[j].outputs > 0 {
-                    q.push_back((p, j, k as usize));
-                }
+        let hub = hub?;
+        if modules[hub].inputs.len() != period.count_ones() as usize {
+            return None;
+        }
+        result.push(Counter {
+            flip_flops,
+            hub,
+            period,
+        });
+    }
+
+    // every hub must reach rx
+    for c in &result {
+        let mut seen = vec![false; modules.len()];
+        let mut stack = vec![c.hub];
+        while let Some(i) = stack.pop() {
+            if !std::mem::replace(&mut seen[i], true) {
+                stack.extend(modules[i].outputs.iter().map(|o| o.0));
             }
         }
+        if !seen[rx] {
+            return None;
+        }
     }
+    (!result.is_empty()).then_some(result)
 }
 
-fn part1(mut modules: Vec<Module>) -> usize {
+fn part1(network: &Network) -> usize {
+    let mut sim = Simulator::new(network);
     let mut pulses = [0; 2];
     for _ in 0..1000 {
-        let mut q = VecDeque::with_capacity(256);
-        q.push_back((0, 0, 0));
-        while let Some((p, i, j)) = q.pop_front() {
-            pulses[p] += 1;
-            process(&mut modules, &mut q, p, i, j);
-        }
+        sim.press(|p| pulses[p.high as usize] += 1);
     }
     pulses[0] * pulses[1]
 }
 
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    while b != 0 {
-        (a, b) = (b, a % b);
+/// The counters all fire on the same press for the first time at the lcm of
+/// their periods. Without counters, simulates up to `limit` presses.
+fn part2(network: &Network, limit: usize) -> Option<u64> {
+    if let Some(counters) = counters(network) {
+        return Some(counters.iter().map(|c| c.period).fold(1, lcm));
     }
-    a
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    a / gcd(a, b) * b
-}
-
-// this is not a generalized solution
-fn part2(mut modules: Vec<Module>) -> usize {
-    let i = modules[1].inputs.trailing_zeros();
-    let m = &modules[i as usize];
-    let mut inputs = m.inputs;
-    let mut cycles = vec![];
-    for k in 1.. {
-        let mut q = VecDeque::with_capacity(256);
-        q.push_back((0, 0, 0));
-        while let Some((p, i, j)) = q.pop_front() {
-            process(&mut modules, &mut q, p, i, j);
-            if (1 << j) & inputs > 0 && modules[j].state != modules[j].inputs {
-                cycles.push(k);
-                inputs &= !(1 << j);
-                if inputs == 0 {
-                    return cycles.into_iter().fold(1, lcm);
-                }
-            }
+    let rx = network.index("rx")?;
+    let mut sim = Simulator::new(network);
+    for n in 1..=limit {
+        let mut low = false;
+        sim.press(|p| low |= p.to == rx && !p.high);
+        if low {
+            return Some(n as u64);
         }
     }
-    unreachable!()
+    None
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day20").unwrap();
-    let (_, modules) = parse(&data);
-    println!("part1: {}", part1(modules.clone()));
-    println!("part2: {}", part2(modules));
+    let network = parse(&data).unwrap();
+    dot::export(|| network.to_dot());
+    // `2023 20 --trace 1` prints the pulses of the first press
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(i) = args.iter().position(|a| a == "--trace") {
+        let n = args.get(i + 1).map_or(1, |n| n.parse().unwrap());
+        print!("{}", Simulator::new(&network).trace(n));
+    }
+    println!("part1: {}", part1(&network));
+    match part2(&network, 1_000_000) {
+        Some(answer) => println!("part2: {answer}"),
+        None => println!("part2: rx gets no low pulse in the first million presses"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
-    #[test]
-    fn case1() {
-        let data = r"
+    const EXAMPLE1: &str = r"
 broadcaster -> a, b, c
 %a -> b
 %b -> c
 %c -> inv
 &inv -> a";
-        let (_, modules) = parse(data);
-        assert_eq!(32000000, part1(modules));
+
+    /// Broadcaster into one counter per period, hubs joined through
+    /// inverters into a conjunction feeding `rx`, like the real inputs.
+    fn counter_network(periods: &[u64]) -> String {
+        let mut lines = vec![];
+        let starts = (0..periods.len()).map(|c| format!("f{c}_0"));
+        lines.push(format!(
+            "broadcaster -> {}",
+            starts.collect::<Vec<_>>().join(", ")
+        ));
+        for (c, &period) in periods.iter().enumerate() {
+            let bits = 64 - period.leading_zeros() as usize;
+            let mut resets = vec![format!("inv{c}"), format!("f{c}_0")];
+            for b in 0..bits {
+                let mut outputs = vec![];
+                if b + 1 < bits {
+                    outputs.push(format!("f{c}_{}", b + 1));
+                }
+                if period >> b & 1 == 1 {
+                    outputs.push(format!("hub{c}"));
+                } else {
+                    resets.push(format!("f{c}_{b}"));
+                }
+                lines.push(format!("%f{c}_{b} -> {}", outputs.join(", ")));
+            }
+            lines.push(format!("&hub{c} -> {}", resets.join(", ")));
+            lines.push(format!("&inv{c} -> join"));
+        }
+        lines.push("&join -> rx".to_string());
+        lines.join("\n")
+    }
+
+    #[test]
+    fn case1() {
+        assert_eq!(32000000, part1(&parse(EXAMPLE1).unwrap()));
     }
 
     #[test]
@@ -210,15 +376,80 @@ broadcaster -> a
 &inv -> b
 %b -> con
 &con -> output";
-        let (_, modules) = parse(data);
-        assert_eq!(11687500, part1(modules));
+        let network = parse(data).unwrap();
+        assert_eq!(11687500, part1(&network));
+        assert_eq!(None, counters(&network));
+        assert_eq!(None, part2(&network, 10));
+    }
+
+    #[test]
+    fn trace() {
+        let network = parse(EXAMPLE1).unwrap();
+        let expected = "
+button -low-> broadcaster
+broadcaster -low-> a
+broadcaster -low-> b
+broadcaster -low-> c
+a -high-> b
+b -high-> c
+c -high-> inv
+inv -low-> a
+a -low-> b
+b -low-> c
+c -low-> inv
+inv -high-> a
+";
+        let mut sim = Simulator::new(&network);
+        assert_eq!(expected.trim_start(), sim.trace(1));
+        // back where it started
+        assert_eq!(expected.trim_start(), sim.trace(1));
+    }
+
+    #[test]
+    fn counter_detection() {
+        let data = counter_network(&[11, 13, 7]);
+        let network = parse(&data).unwrap();
+        let counters = counters(&network).unwrap();
+        let periods = counters.iter().map(|c| c.period).collect::<Vec<_>>();
+        assert_eq!(vec![11, 13, 7], periods);
+        assert_eq!(4, counters[1].flip_flops.len());
+        assert_eq!(Some(1001), part2(&network, 0));
+
+        // plain simulation agrees once the counters are hidden
+        let mut hidden = network.clone();
+        let broadcaster = hidden.index("broadcaster").unwrap();
+        let extra = hidden.modules.len();
+        hidden.names.push("extra");
+        hidden.modules.push(Module::default());
+        hidden.modules[extra].inputs.push(broadcaster);
+        hidden.modules[broadcaster].outputs.push((extra, 0));
+        assert_eq!(None, super::counters(&hidden));
+        assert_eq!(Some(1001), part2(&hidden, 2000));
+    }
+
+    #[test]
+    fn many_modules() {
+        // well past the old limit of 64 modules
+        let data = counter_network(&[4093, 4091, 4079, 4057, 4051]);
+        let network = parse(&data).unwrap();
+        assert!(network.modules.len() > 64);
+        let expected = [4093u64, 4091, 4079, 4057, 4051].into_iter().fold(1, lcm);
+        assert_eq!(Some(expected), part2(&network, 0));
+        assert!(part1(&network) > 0);
+    }
+
+    #[test]
+    fn dot() {
+        let dot = parse(EXAMPLE1).unwrap().to_dot().to_string();
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains("  \"inv\" [label=\"&inv\", shape=\"diamond\"];\n"));
+        assert!(dot.contains("  \"a\" [label=\"%a\", shape=\"box\"];\n"));
+        assert!(dot.contains("  \"c\" -> \"inv\";\n"));
+        assert_eq!(7, dot.matches(" -> ").count());
     }
 
     #[test]
-    fn case3() {
-        let data = std::fs::read_to_string("data/2023/day20").unwrap();
-        let (_, modules) = parse(&data);
-        assert_eq!(731517480, part1(modules.clone()));
-        assert_eq!(244178746156661, part2(modules));
+    fn fuzz() {
+        fuzz::check(&[EXAMPLE1], |s| parse(s).map(drop));
     }
 }
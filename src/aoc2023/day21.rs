@@ -1,10 +1,17 @@
 use std::collections::VecDeque;
 
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|l| l.as_bytes()).collect()
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let garden = parsing::grid_of(data, ".#S")?;
+    if data.matches('S').count() != 1 {
+        return Err(ParseError::at(data, data, "exactly one S"));
+    }
+    Ok(garden)
 }
 
-fn find_start(garden: &[&[u8]]) -> (usize, usize) {
+fn find_start(garden: &[Vec<u8>]) -> (usize, usize) {
     garden
         .iter()
         .enumerate()
@@ -17,7 +24,7 @@ fn find_start(garden: &[&[u8]]) -> (usize, usize) {
         .unwrap()
 }
 
-fn bfs(garden: &[&[u8]], (i0, j0): (usize, usize), steps: usize) -> Vec<usize> {
+fn bfs(garden: &[Vec<u8>], (i0, j0): (usize, usize), steps: usize) -> Vec<usize> {
     let (m, n) = (garden.len(), garden[0].len());
     let mut q = VecDeque::default();
     let mut dist = vec![usize::MAX; m * n];
@@ -42,7 +49,7 @@ fn bfs(garden: &[&[u8]], (i0, j0): (usize, usize), steps: usize) -> Vec<usize> {
     dist
 }
 
-fn part1(garden: &[&[u8]], steps: usize) -> usize {
+fn part1(garden: &[Vec<u8>], steps: usize) -> usize {
     let (i0, j0) = find_start(garden);
     let dist = bfs(garden, (i0, j0), steps);
     dist.into_iter()
@@ -55,7 +62,7 @@ fn part1(garden: &[&[u8]], steps: usize) -> usize {
 // but the calculation for even_corners are wrong (for most inputs, like the author's, it happens to be the right answer)
 // even/odd corners have to be calculated from the side of the center, not the other side
 // correct methods for even_corners can be found from https://www.youtube.com/watch?v=9UOMZSL0JTg
-fn part2(garden: &[&[u8]], steps: usize) -> usize {
+fn part2(garden: &[Vec<u8>], steps: usize) -> usize {
     let m = garden.len();
     let (mut odd, mut even, mut odd_corners, mut even_corners) = (0, 0, 0, 0);
     let (i0, j0) = find_start(garden);
@@ -88,7 +95,7 @@ fn part2(garden: &[&[u8]], steps: usize) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day21").unwrap();
-    let garden = parse(&data);
+    let garden = parse(&data).unwrap();
     println!("part1: {}", part1(&garden, 64));
     println!("part2: {}", part2(&garden, 26501365));
 }
@@ -96,6 +103,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -111,7 +119,7 @@ mod tests {
 .##.#.####.
 .##..##.##.
 ...........";
-        let garden = parse(data);
+        let garden = parse(data).unwrap();
         assert_eq!(16, part1(&garden, 6));
         // assert_eq!(16, part2(&plan, 6));
         // assert_eq!(50, part2(&plan, 10));
@@ -123,10 +131,8 @@ mod tests {
     }
 
     #[test]
-    fn case2() {
-        let data = std::fs::read_to_string("data/2023/day21").unwrap();
-        let garden = parse(&data);
-        assert_eq!(3605, part1(&garden, 64));
-        assert_eq!(596734624269210, part2(&garden, 26501365));
+    fn fuzz() {
+        let seed = "...........\n.....###.#.\n.###.##..#.\n..#.#...#..\n....#.#....\n.##..S####.";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,22 +1,35 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::HashSet;
 use arrayvec::ArrayVec;
+use nom::{
+    Parser,
+    character::complete::char,
+    combinator::verify,
+    error::context,
+    sequence::{preceded, separated_pair},
+};
 
-fn parse(data: &str) -> Vec<[ArrayVec<u16, 3>; 2]> {
-    let mut r: Vec<[ArrayVec<u16, 3>; 2]> = data
-        .trim()
-        .lines()
-        .map(|l| {
-            let (a, b) = l.split_once('~').unwrap();
-            let a: ArrayVec<u16, 3> = a.split(',').map(|t| t.parse().unwrap()).collect();
-            let b: ArrayVec<u16, 3> = b.split(',').map(|t| t.parse().unwrap()).collect();
-            assert!(a[0] <= b[0]);
-            assert!(a[1] <= b[1]);
-            assert!(a[2] <= b[2]);
-            [a, b]
-        })
-        .collect();
+type Brick = [ArrayVec<u16, 3>; 2];
+
+fn parse(data: &str) -> Result<Vec<Brick>, ParseError> {
+    let end = || {
+        let end = (
+            unsigned,
+            preceded(char(','), unsigned),
+            preceded(char(','), unsigned),
+        );
+        end.map(|(x, y, z)| ArrayVec::from([x, y, z]))
+    };
+    let brick = separated_pair(end(), char('~'), end()).map(|(a, b)| [a, b]);
+    // the height map is 10 by 10
+    let fits = |[a, b]: &Brick| (0..3).all(|k| a[k] <= b[k]) && b[0] < 10 && b[1] < 10;
+    let brick = context(
+        "a brick from low to high corner within x, y < 10",
+        verify(brick, fits),
+    );
+    let mut r = parsing::lines(data.trim(), brick)?;
     r.sort_unstable_by_key(|e| (e[0][2], e[1][2]));
-    r
+    Ok(r)
 }
 
 fn fall(mut bricks: Vec<[ArrayVec<u16, 3>; 2]>) -> (Vec<HashSet<i32>>, Vec<HashSet<i32>>) {
@@ -92,7 +105,7 @@ fn part2(support: &[HashSet<i32>], supported_by: &[HashSet<i32>]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day22").unwrap();
-    let bricks = parse(&data);
+    let bricks = parse(&data).unwrap();
     let (support, supported_by) = fall(bricks);
     println!("part1: {}", part1(&support, &supported_by));
     println!("part2: {}", part2(&support, &supported_by));
@@ -101,6 +114,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -112,9 +126,15 @@ mod tests {
 2,0,5~2,2,5
 0,1,6~2,1,6
 1,1,8~1,1,9";
-        let bricks = parse(data);
+        let bricks = parse(data).unwrap();
         let (support, supported_by) = fall(bricks);
         assert_eq!(5, part1(&support, &supported_by));
         assert_eq!(7, part2(&support, &supported_by));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "1,0,1~1,2,1\n0,0,2~2,0,2\n0,2,3~2,2,3\n1,1,8~1,1,9";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,8 +1,18 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashMap, HashSet};
 use arrayvec::ArrayVec;
 
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|l| l.as_bytes()).collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let map = parsing::grid_of(data, "#.><^v")?;
+    if !map[0].contains(&b'.') || !map[map.len() - 1].contains(&b'.') {
+        return Err(ParseError::at(
+            data,
+            data,
+            "a path in the top and bottom rows",
+        ));
+    }
+    Ok(map)
 }
 
 fn dfs(g: &HashMap<u32, ArrayVec<(u32, i32), 4>>, end: u32, curr: u32, visited: u64) -> isize {
@@ -23,7 +33,7 @@ fn dfs(g: &HashMap<u32, ArrayVec<(u32, i32), 4>>, end: u32, curr: u32, visited:
     result
 }
 
-fn solve(map: &[&[u8]], part2: bool) -> isize {
+fn solve(map: &[Vec<u8>], part2: bool) -> isize {
     let (m, n) = (map.len() as i32, map[0].len() as i32);
     let j_start = map[0].iter().position(|e| *e == b'.').unwrap() as i32;
     let j_end = map.last().unwrap().iter().position(|e| *e == b'.').unwrap() as i32;
@@ -96,7 +106,7 @@ fn solve(map: &[&[u8]], part2: bool) -> isize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day23").unwrap();
-    let map = parse(&data);
+    let map = parse(&data).unwrap();
     println!("part1: {}", solve(&map, false));
     println!("part2: {}", solve(&map, true));
 }
@@ -104,6 +114,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -131,8 +142,14 @@ mod tests {
 #.###.###.#.###.#.#v###
 #.....###...###...#...#
 #####################.#";
-        let map = parse(data);
+        let map = parse(data).unwrap();
         assert_eq!(94, solve(&map, false));
         assert_eq!(154, solve(&map, true));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#.#####\n#.>.>.#\n#v###.#\n#.....#\n#####.#";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,6 +1,13 @@
-use std::ops::{Add, Div, Mul, RangeInclusive, Sub};
+use adventofcode::parsing::{self, ParseError, int_list};
+use adventofcode::rational::Rational;
+use nom::{
+    Parser, bytes::complete::tag, character::complete::space1, combinator::verify, error::context,
+    sequence::separated_pair,
+};
+use std::fmt;
+use std::ops::{Add, Sub};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Vec3 {
     x: i128,
     y: i128,
@@ -16,40 +23,49 @@ impl Vec3 {
         }
     }
 
-    fn dot(&self, rhs: Self) -> i128 {
-        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    /// Applies `f` on every axis, or `None` if any of them overflows.
+    fn try_zip(self, rhs: Self, f: impl Fn(i128, i128) -> Option<i128>) -> Option<Self> {
+        // without i128::MIN every axis can be negated
+        let f = |a, b| f(a, b).filter(|&n| n != i128::MIN);
+        Some(Self {
+            x: f(self.x, rhs.x)?,
+            y: f(self.y, rhs.y)?,
+            z: f(self.z, rhs.z)?,
+        })
     }
 
-    fn cross(&self, rhs: Self) -> Self {
-        Self {
-            x: self.y * rhs.z - self.z * rhs.y,
-            y: self.z * rhs.x - self.x * rhs.z,
-            z: self.x * rhs.y - self.y * rhs.x,
-        }
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.try_zip(rhs, i128::checked_add)
     }
-}
 
-impl Mul<Vec3> for i128 {
-    type Output = Vec3;
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.try_zip(rhs, i128::checked_sub)
+    }
 
-    fn mul(self, rhs: Vec3) -> Self::Output {
-        Self::Output {
-            x: self * rhs.x,
-            y: self * rhs.y,
-            z: self * rhs.z,
-        }
+    fn checked_scale(self, k: i128) -> Option<Self> {
+        self.try_zip(self, |a, _| a.checked_mul(k))
+    }
+
+    fn checked_dot(self, rhs: Self) -> Option<i128> {
+        let p = self.try_zip(rhs, i128::checked_mul)?;
+        p.x.checked_add(p.y)?.checked_add(p.z)
     }
-}
 
-impl Div<i128> for Vec3 {
-    type Output = Vec3;
+    fn checked_cross(self, rhs: Self) -> Option<Self> {
+        let a = Self::from(&[self.y, self.z, self.x]);
+        let b = Self::from(&[rhs.z, rhs.x, rhs.y]);
+        let c = Self::from(&[self.z, self.x, self.y]);
+        let d = Self::from(&[rhs.y, rhs.z, rhs.x]);
+        a.try_zip(b, i128::checked_mul)?
+            .checked_sub(c.try_zip(d, i128::checked_mul)?)
+    }
 
-    fn div(self, rhs: i128) -> Self::Output {
-        Self::Output {
-            x: self.x / rhs,
-            y: self.y / rhs,
-            z: self.z / rhs,
-        }
+    fn is_zero(&self) -> bool {
+        *self == Self::from(&[0, 0, 0])
+    }
+
+    fn axes(self) -> [i128; 3] {
+        [self.x, self.y, self.z]
     }
 }
 
@@ -77,47 +93,49 @@ impl Sub for Vec3 {
     }
 }
 
-fn parse(data: &str) -> Vec<[Vec3; 2]> {
-    data.trim()
-        .lines()
-        .map(|l| {
-            let (p, v) = l.split_once(" @ ").unwrap();
-            let p = p
-                .split(',')
-                .map(|a| a.trim().parse().unwrap())
-                .collect::<Vec<_>>();
-            let v = v
-                .split(',')
-                .map(|a| a.trim().parse().unwrap())
-                .collect::<Vec<_>>();
-            [Vec3::from(&p), Vec3::from(&v)]
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<[Vec3; 2]>, ParseError> {
+    // small enough that the normals in `throw`, their dot products with the
+    // hailstones and the cross products of two of them fit in an i128
+    let axes = |max: i128| {
+        let axes = verify(int_list::<i128>(","), move |a: &Vec<i128>| {
+            a.len() == 3 && a.iter().all(|a| a.abs() < max)
+        });
+        axes.map(|a| Vec3::from(&a))
+    };
+    let p = context("3 coordinates within 2^50", axes(1 << 50));
+    let v = context("3 velocities within 2^10", axes(1 << 10));
+    let hailstone = separated_pair(p, (tag(" @"), space1), v).map(|(p, v)| [p, v]);
+    parsing::lines(data.trim(), hailstone)
 }
 
-fn check_intersection(a: &[Vec3; 2], b: &[Vec3; 2], limit: &RangeInclusive<f64>) -> bool {
+/// Where the paths of `a` and `b` cross in the xy plane, if both stones get
+/// there in the future.
+fn crossing(a: &[Vec3; 2], b: &[Vec3; 2]) -> Option<(Rational, Rational)> {
     let d = b[0] - a[0];
     let det = b[1].x * a[1].y - b[1].y * a[1].x;
     if det == 0 {
-        return false;
+        // parallel, the puzzle doesn't count paths on the same line either
+        return None;
     }
-    let t1 = (d.y * b[1].x - d.x * b[1].y) as f64 / det as f64;
-    let t2 = (d.y * a[1].x - d.x * a[1].y) as f64 / det as f64;
-
-    if t1 <= 0.0 || t2 <= 0.0 {
-        return false;
+    let t1 = Rational::new(d.y * b[1].x - d.x * b[1].y, det);
+    let t2 = Rational::new(d.y * a[1].x - d.x * a[1].y, det);
+    if t1 <= Rational::ZERO || t2 <= Rational::ZERO {
+        return None;
     }
-    let x = a[0].x as f64 + t1 * a[1].x as f64;
-    let y = a[0].y as f64 + t1 * a[1].y as f64;
-    limit.contains(&x) && limit.contains(&y)
+    let x = Rational::from(a[0].x) + t1 * Rational::from(a[1].x);
+    let y = Rational::from(a[0].y) + t1 * Rational::from(a[1].y);
+    Some((x, y))
 }
 
 fn part1(hailstones: &[[Vec3; 2]], limit: [i128; 2]) -> usize {
-    let limit = limit[0] as f64..=limit[1] as f64;
+    let limit = Rational::from(limit[0])..=Rational::from(limit[1]);
     let mut result = 0;
     for (i, a) in hailstones.iter().enumerate() {
         for b in &hailstones[i + 1..] {
-            if check_intersection(a, b, &limit) {
+            if let Some((x, y)) = crossing(a, b)
+                && limit.contains(&x)
+                && limit.contains(&y)
+            {
                 result += 1;
             }
         }
@@ -125,32 +143,155 @@ fn part1(hailstones: &[[Vec3; 2]], limit: [i128; 2]) -> usize {
     result
 }
 
-// https://www.reddit.com/r/adventofcode/comments/18pnycy/comment/kxqjg33/
-fn part2(hailstones: &[[Vec3; 2]]) -> i128 {
-    let (h0, h1, h2) = (&hailstones[0], &hailstones[1], &hailstones[2]);
-    let (p1, v1) = (h1[0] - h0[0], h1[1] - h0[1]);
-    let (p2, v2) = (h2[0] - h0[0], h2[1] - h0[1]);
-    let t1 = -p1.cross(p2).dot(v2) / v1.cross(p2).dot(v2);
-    let t2 = -p1.cross(p2).dot(v1) / p1.cross(v2).dot(v1);
-    let (c1, c2) = (h1[0] + t1 * h1[1], h2[0] + t2 * h2[1]);
-    let v = (c2 - c1) / (t2 - t1);
-    let p = c1 - t1 * v;
-    p.x + p.y + p.z
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RockError {
+    TooFewHailstones,
+    /// No two hailstones pin down a single line with the first one.
+    Degenerate,
+    NotInteger,
+    /// The rock misses this hailstone, or hits it at a time that isn't a
+    /// positive integer.
+    Miss(usize),
+    /// The only throw, or the way to it, doesn't fit in an i128.
+    Overflow,
+}
+
+impl fmt::Display for RockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RockError::TooFewHailstones => write!(f, "need at least three hailstones"),
+            RockError::Degenerate => write!(f, "hailstones don't determine a single throw"),
+            RockError::NotInteger => {
+                write!(f, "the only throw doesn't start at integer coordinates")
+            }
+            RockError::Miss(i) => write!(f, "the only throw misses hailstone {i}"),
+            RockError::Overflow => write!(f, "the throw doesn't fit in an i128"),
+        }
+    }
+}
+
+/// Time at which `w + t v` crosses the plane through the origin normal to
+/// `n`, if it does so just once.
+fn meet((w, v): (Vec3, Vec3), n: Vec3) -> Result<Option<Rational>, RockError> {
+    let (Some(a), Some(b)) = (w.checked_dot(n), v.checked_dot(n)) else {
+        return Err(RockError::Overflow);
+    };
+    Ok((b != 0).then(|| Rational::new(-a, b)))
+}
+
+/// Position and velocity of the one rock throw that hits every hailstone.
+///
+/// Seen from hailstone 0, which rests at the origin, the rock passes the
+/// origin, so it lies in the plane through the origin and any other
+/// hailstone's path. Its velocity `q` solves `q · (w × v) = 0` for every
+/// hailstone `(w, v)`, and two of them fix its path: where their planes
+/// meet. The times at which each of the two crosses the other's plane then
+/// give the rock's speed and position.
+fn throw(hailstones: &[[Vec3; 2]]) -> Result<[Vec3; 2], RockError> {
+    let n = hailstones.len();
+    if n < 3 {
+        return Err(RockError::TooFewHailstones);
+    }
+    let h0 = &hailstones[0];
+    let relative = hailstones
+        .iter()
+        .map(|h| (h[0] - h0[0], h[1] - h0[1]))
+        .collect::<Vec<_>>();
+    let normals = relative
+        .iter()
+        .map(|&(w, v)| w.checked_cross(v).ok_or(RockError::Overflow))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut found = None;
+    for (j, k) in (1..n).flat_map(|j| (j + 1..n).map(move |k| (j, k))) {
+        let d = normals[j]
+            .checked_cross(normals[k])
+            .ok_or(RockError::Overflow)?;
+        if d.is_zero() {
+            continue;
+        }
+        if let (Some(tj), Some(tk)) = (
+            meet(relative[j], normals[k])?,
+            meet(relative[k], normals[j])?,
+        ) && tj != tk
+        {
+            found = Some((j, k, tj, tk));
+            break;
+        }
+    }
+    let (j, k, tj, tk) = found.ok_or(RockError::Degenerate)?;
+
+    let (Some(tj), Some(tk)) = (tj.to_integer(), tk.to_integer()) else {
+        return Err(RockError::NotInteger);
+    };
+    let ((wj, vj), (wk, vk)) = (relative[j], relative[k]);
+    let cj = vj.checked_scale(tj).and_then(|p| p.checked_add(wj));
+    let ck = vk.checked_scale(tk).and_then(|p| p.checked_add(wk));
+    let dt = tk.checked_sub(tj).filter(|&t| t != i128::MIN);
+    let (Some(cj), Some(ck), Some(dt)) = (cj, ck, dt) else {
+        return Err(RockError::Overflow);
+    };
+    let dc = ck.checked_sub(cj).ok_or(RockError::Overflow)?;
+    let q = dc.axes().map(|e| Rational::new(e, dt).to_integer());
+    let [Some(x), Some(y), Some(z)] = q else {
+        return Err(RockError::NotInteger);
+    };
+    let q = Vec3::from(&[x, y, z]);
+    let p = q
+        .checked_scale(tj)
+        .and_then(|s| cj.checked_sub(s))
+        .and_then(|p| h0[0].checked_add(p));
+    let (Some(p), Some(v)) = (p, h0[1].checked_add(q)) else {
+        return Err(RockError::Overflow);
+    };
+    let rock = [p, v];
+
+    for (i, h) in hailstones.iter().enumerate() {
+        // p + t q = w + t v, so t = (w - p) / (q - v) on every axis
+        let (Some(dp), Some(dv)) = (h[0].checked_sub(rock[0]), rock[1].checked_sub(h[1])) else {
+            return Err(RockError::Overflow);
+        };
+        let axes = dp.axes().into_iter().zip(dv.axes()).collect::<Vec<_>>();
+        let t = axes
+            .iter()
+            .find(|a| a.1 != 0)
+            .filter(|a| a.0 % a.1 == 0)
+            .map(|a| a.0 / a.1);
+        let hit =
+            t.is_some_and(|t| t > 0 && axes.iter().all(|&(p, v)| t.checked_mul(v) == Some(p)));
+        if !hit {
+            return Err(RockError::Miss(i));
+        }
+    }
+    Ok(rock)
+}
+
+fn part2(hailstones: &[[Vec3; 2]]) -> Result<i128, RockError> {
+    let [p, _] = throw(hailstones)?;
+    p.axes()
+        .into_iter()
+        .try_fold(0i128, i128::checked_add)
+        .ok_or(RockError::Overflow)
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day24").unwrap();
-    let hailstones = parse(&data);
+    let hailstones = parse(&data).unwrap();
     println!(
         "part1: {}",
         part1(&hailstones, [200000000000000, 400000000000000])
     );
-    println!("part2: {}", part2(&hailstones));
+    match part2(&hailstones) {
+        Ok(answer) => println!("part2: {answer}"),
+        Err(e) => println!("part2: {e}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
+    use rand::prelude::*;
 
     #[test]
     fn case1() {
@@ -160,8 +301,98 @@ mod tests {
 20, 25, 34 @ -2, -2, -4
 12, 31, 28 @ -1, -2, -1
 20, 19, 15 @  1, -5, -3";
-        let hailstones = parse(data);
+        let hailstones = parse(data).unwrap();
         assert_eq!(2, part1(&hailstones, [7, 27]));
-        assert_eq!(47, part2(&hailstones));
+        assert_eq!(Ok(47), part2(&hailstones));
+    }
+
+    #[test]
+    fn degenerate() {
+        // all parallel: any throw along their direction hits the first two
+        let data = "0, 0, 0 @ 1, 1, 1\n5, 0, 0 @ 1, 1, 1\n0, 5, 0 @ 1, 1, 1";
+        assert_eq!(Err(RockError::Degenerate), part2(&parse(data).unwrap()));
+        assert_eq!(0, part1(&parse(data).unwrap(), [-100, 100]));
+        assert_eq!(
+            Err(RockError::TooFewHailstones),
+            part2(&parse(&data[..36]).unwrap())
+        );
+    }
+
+    #[test]
+    fn miss() {
+        let data = r"
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 16 @  1, -5, -3";
+        assert_eq!(Err(RockError::Miss(4)), part2(&parse(data).unwrap()));
+    }
+
+    #[test]
+    fn random_throws() {
+        // the magnitudes of real inputs
+        let mut rng = StdRng::seed_from_u64(36);
+        for _ in 0..20 {
+            let mut random = |lo: i128, hi: i128| {
+                let v = [(); 3].map(|_| rng.random_range(lo..hi));
+                Vec3::from(&v)
+            };
+            let rock = [
+                random(100_000_000_000_000, 400_000_000_000_000),
+                random(-300, 300),
+            ];
+            let hailstones = (0..300)
+                .map(|_| {
+                    let t = random(1, 1_000_000_000_000).x;
+                    let v = random(-300, 300);
+                    let d = rock[1] - v;
+                    [rock[0] + Vec3::from(&[d.x * t, d.y * t, d.z * t]), v]
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(Ok(rock), throw(&hailstones));
+        }
+    }
+
+    #[test]
+    fn real_sized() {
+        // up to the bounds of parse, through it, with and without a throw
+        let mut rng = StdRng::seed_from_u64(24);
+        let line =
+            |[p, v]: [Vec3; 2]| format!("{}, {}, {} @ {}, {}, {}", p.x, p.y, p.z, v.x, v.y, v.z);
+        for _ in 0..20 {
+            let mut random = |lo: i128, hi: i128| {
+                let v = [(); 3].map(|_| rng.random_range(lo..hi));
+                Vec3::from(&v)
+            };
+            let rock = [
+                random(100_000_000_000_000, 300_000_000_000_000),
+                random(-500, 500),
+            ];
+            let mut hits = vec![];
+            let mut noise = vec![];
+            for _ in 0..300 {
+                let t = random(1, 500_000_000_000).x;
+                let v = random(-1023, 1024);
+                let d = rock[1] - v;
+                hits.push(line([
+                    rock[0] + Vec3::from(&[d.x * t, d.y * t, d.z * t]),
+                    v,
+                ]));
+                noise.push(line([random(1 - (1 << 50), 1 << 50), random(-1023, 1024)]));
+            }
+            assert_eq!(Ok(rock), throw(&parse(&hits.join("\n")).unwrap()));
+            let miss = throw(&parse(&noise.join("\n")).unwrap());
+            assert!(
+                matches!(miss, Err(RockError::NotInteger | RockError::Miss(_))),
+                "{miss:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "19, 13, 30 @ -2,  1, -2\n18, 19, 22 @ -1, -1, -2\n20, 25, 34 @ -2, -2, -4";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
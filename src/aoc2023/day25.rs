@@ -1,81 +1,60 @@
+use adventofcode::dot::{self, Dot};
+use adventofcode::graph::Graph;
+use adventofcode::parsing::{self, ParseError};
 use ahash::HashMap;
-use rand::prelude::*;
-use rayon::prelude::*;
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::char,
+    multi::many1,
+    sequence::{preceded, separated_pair},
+};
 
-fn parse(data: &str) -> HashMap<usize, (Vec<usize>, usize)> {
-    let mut map: HashMap<&str, usize> = HashMap::default();
-    let mut graph: HashMap<usize, (Vec<usize>, usize)> = HashMap::default();
-    data.trim().lines().for_each(|l| {
-        let (a, vs) = l.split_once(": ").unwrap();
-        let n = map.len();
-        let u = *map.entry(a).or_insert(n);
-        let vs = vs
-            .split_ascii_whitespace()
-            .map(|b| {
-                let n = map.len();
-                let v = *map.entry(b).or_insert(n);
-                graph.entry(v).or_insert((vec![], 1)).0.push(u);
-                v
-            })
-            .collect::<Vec<_>>();
-        graph.entry(u).or_insert((vec![], 1)).0.extend(vs);
-    });
-    graph
-}
-
-fn contract(mut graph: HashMap<usize, (Vec<usize>, usize)>) -> Option<usize> {
-    let mut rng = rand::rng();
-
-    // Karger's algorithm
-    // safety: different keys (u and v) of the hash map are accessed separately
-    while graph.len() > 2 {
-        let u = *graph.keys().choose(&mut rng).unwrap();
-        let (vs1, m) = {
-            let (vs, m) = graph.get_mut(&u).unwrap();
-            (vs as *mut Vec<usize>, m as *mut usize)
-        };
-        let v = unsafe { *(*vs1).choose(&mut rng).unwrap() };
-        let (vs2, n) = graph.get(&v).unwrap();
-        unsafe {
-            (*vs1).extend(vs2);
-            (*vs1).retain(|&e| e != u && e != v);
-            *m += n;
+fn parse(data: &str) -> Result<(Graph, Vec<&str>), ParseError> {
+    let name = || take_while1(|c: char| c.is_ascii_lowercase());
+    let line = separated_pair(name(), tag(":"), many1(preceded(char(' '), name())));
+    let lines = parsing::lines(data.trim(), line)?;
+    let mut ids: HashMap<&str, usize> = HashMap::default();
+    let mut names = vec![];
+    let mut id = |name| {
+        *ids.entry(name).or_insert_with(|| {
+            names.push(name);
+            names.len() - 1
+        })
+    };
+    let mut edges = vec![];
+    for (a, vs) in lines {
+        let u = id(a);
+        for b in vs {
+            edges.push((u, id(b)));
         }
-
-        for n in unsafe { &*vs1 } {
-            let vs = &mut graph.get_mut(n).unwrap().0;
-            vs.iter_mut().for_each(|e| {
-                if *e == v {
-                    *e = u;
-                }
-            });
-        }
-
-        graph.remove(&v);
     }
-    if graph.values().next().unwrap().0.len() == 3 {
-        Some(graph.values().map(|v| v.1).product())
-    } else {
-        None
+    Ok((Graph::from_edges(names.len(), &edges), names))
+}
+
+fn to_dot(graph: &Graph, names: &[&str]) -> Dot {
+    let mut dot = Dot::graph();
+    for (u, v) in graph.edges() {
+        dot.edge(names[u], names[v], None);
     }
+    dot
 }
 
-fn part1(graph: HashMap<usize, (Vec<usize>, usize)>) -> usize {
-    (0..)
-        .par_bridge()
-        .find_map_any(|_| contract(graph.clone()))
-        .unwrap()
+fn part1(graph: &Graph) -> usize {
+    let (_, side) = graph.min_cut();
+    side.len() * (graph.len() - side.len())
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day25").unwrap();
-    let graph = parse(&data);
-    println!("part1: {}", part1(graph));
+    let (graph, names) = parse(&data).unwrap();
+    dot::export(|| to_dot(&graph, &names));
+    println!("part1: {}", part1(&graph));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -93,7 +72,24 @@ nvd: lhk
 lsr: lhk
 rzs: qnr cmg lsr rsh
 frs: qnr lhk lsr";
-        let graph = parse(data);
-        assert_eq!(54, part1(graph));
+        let (graph, _) = parse(data).unwrap();
+        let (cut, side) = graph.min_cut();
+        assert_eq!((3, 6), (cut, side.len()));
+        assert_eq!(54, part1(&graph));
+    }
+
+    #[test]
+    fn dot() {
+        let (graph, names) = parse("jqt: rhn xhk\nrhn: xhk").unwrap();
+        let dot = to_dot(&graph, &names).to_string();
+        assert!(dot.starts_with("graph {\n"));
+        assert!(dot.contains("  \"jqt\" -- \"rhn\";\n"));
+        assert_eq!(3, dot.matches(" -- ").count());
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "jqt: rhn xhk nvd\nrsh: frs pzl lsr\nxhk: hfx";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
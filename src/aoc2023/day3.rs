@@ -1,10 +1,8 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::HashMap;
 
-fn parse(data: &str) -> Vec<Vec<u8>> {
-    data.trim()
-        .lines()
-        .map(|line| line.as_bytes().to_vec())
-        .collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    parsing::grid(data.trim())
 }
 
 fn part1(input: &[Vec<u8>]) -> u32 {
@@ -80,7 +78,7 @@ fn part2(input: &[Vec<u8>]) -> u32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day3").unwrap();
-    let input = parse(&data);
+    let input = parse(&data).unwrap();
     println!("part1: {}", part1(&input));
     println!("part2: {}", part2(&input));
 }
@@ -88,6 +86,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -102,8 +101,14 @@ mod tests {
 ......755.
 ...$.*....
 .664.598..";
-        let input = parse(data);
+        let input = parse(data).unwrap();
         assert_eq!(4361, part1(&input));
         assert_eq!(467835, part2(&input));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "467..114..\n...*......\n..35..633.";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,21 +1,36 @@
-fn parse(data: &str) -> Vec<usize> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let lists = line.split_once(": ").unwrap().1;
-            let (targets, numbers) = lists.split_once(" | ").unwrap();
-            let mut winning = [false; 100];
-            for n in targets.split(' ') {
-                if let Ok(n) = n.parse::<u32>() {
-                    winning[n as usize] = true;
-                }
-            }
-            numbers
-                .split(' ')
-                .filter(|n| n.parse::<usize>().is_ok_and(|n| winning[n]))
-                .count()
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, space1},
+    combinator::verify,
+    error::context,
+    multi::many1,
+    sequence::preceded,
+};
+
+fn parse(data: &str) -> Result<Vec<usize>, ParseError> {
+    let data = data.trim();
+    let number = || preceded(space1, verify(unsigned::<usize>, |&n| n < 100));
+    let numbers = || context("numbers below 100", many1(number()));
+    let card = (
+        preceded((tag("Card"), space1, unsigned::<u32>, char(':')), numbers()),
+        preceded(tag(" |"), numbers()),
+    );
+    let cards = parsing::lines(data, card)?;
+    let mut matches = vec![];
+    for (i, ((targets, numbers), line)) in cards.into_iter().zip(data.lines()).enumerate() {
+        let mut winning = [false; 100];
+        for n in targets {
+            winning[n] = true;
+        }
+        let m = numbers.into_iter().filter(|&n| winning[n]).count();
+        // part 2 wins copies of the next m cards
+        if i + m >= data.lines().count() {
+            return Err(ParseError::at(data, line, "no copies past the last card"));
+        }
+        matches.push(m);
+    }
+    Ok(matches)
 }
 
 fn part1(input: &[usize]) -> u32 {
@@ -36,7 +51,7 @@ fn part2(input: &[usize]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day4").unwrap();
-    let input = parse(&data);
+    let input = parse(&data).unwrap();
     println!("part1: {}", part1(&input));
     println!("part2: {}", part2(&input));
 }
@@ -44,6 +59,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -54,8 +70,14 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        let input = parse(data);
+        let input = parse(data).unwrap();
         assert_eq!(13, part1(&input));
         assert_eq!(30, part2(&input));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\nCard 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\nCard 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\nCard 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\nCard 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,26 +1,40 @@
 use std::collections::VecDeque;
 
-fn parse(data: &str) -> (Vec<u64>, Vec<Vec<Vec<u64>>>) {
-    let lines = data.trim().lines().collect::<Vec<_>>();
-    let seeds = lines[0]
-        .strip_prefix("seeds: ")
-        .unwrap()
-        .split(' ')
-        .map(|s| s.parse().unwrap())
-        .collect::<Vec<u64>>();
-    let maps = lines[2..]
-        .split(|s| s.is_empty())
-        .map(|g| {
-            let mut g = g
-                .iter()
-                .skip(1)
-                .map(|s| s.split(' ').map(|n| n.parse().unwrap()).collect::<Vec<_>>())
-                .collect::<Vec<_>>();
-            g.sort_unstable_by_key(|e| e[1]);
-            g
-        })
-        .collect();
-    (seeds, maps)
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::{alpha1, char, newline},
+    combinator::verify,
+    error::context,
+    multi::{many1, separated_list1},
+    sequence::preceded,
+};
+
+type Map = Vec<Vec<u64>>;
+
+fn parse(data: &str) -> Result<(Vec<u64>, Vec<Map>), ParseError> {
+    // 32-bit values keep every range end within a u64
+    let number = || unsigned::<u32>.map(u64::from);
+    let length = || context("a length", verify(number(), |&n| n > 0));
+    let seeds = separated_list1(char(' '), number());
+    let pairs = |s: &Vec<u64>| s.len().is_multiple_of(2) && s.chunks(2).all(|c| c[1] > 0);
+    let seeds = preceded(tag("seeds: "), verify(seeds, pairs));
+    let seeds = context("pairs of a start and a length", seeds);
+    let range = (
+        number(),
+        preceded(char(' '), number()),
+        preceded(char(' '), length()),
+    );
+    let range = range.map(|(dest, src, len)| vec![dest, src, len]);
+    let header = (alpha1, tag("-to-"), alpha1, tag(" map:"));
+    let map = preceded(header, many1(preceded(newline, range)));
+    let map = map.map(|mut g: Map| {
+        g.sort_unstable_by_key(|e| e[1]);
+        g
+    });
+    let almanac = (seeds, many1(preceded(tag("\n\n"), map)));
+    parsing::parse_all(data.trim(), almanac)
 }
 
 fn part1(seeds: &[u64], maps: &[Vec<Vec<u64>>]) -> u64 {
@@ -94,7 +108,7 @@ fn part2(seeds: &[u64], maps: &[Vec<Vec<u64>>]) -> u64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day5").unwrap();
-    let (seeds, maps) = parse(&data);
+    let (seeds, maps) = parse(&data).unwrap();
     println!("part1: {}", part1(&seeds, &maps));
     println!("part2: {}", part2(&seeds, &maps));
 }
@@ -102,6 +116,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -139,8 +154,14 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        let (seeds, maps) = parse(data);
+        let (seeds, maps) = parse(data).unwrap();
         assert_eq!(35, part1(&seeds, &maps));
         assert_eq!(46, part2(&seeds, &maps));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\nsoil-to-fertilizer map:\n0 15 37\n37 52 2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
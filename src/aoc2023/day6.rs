@@ -1,5 +1,30 @@
-fn parse(data: &str) -> (&str, &str) {
-    data.trim().split_once('\n').unwrap()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{newline, space1},
+    multi::many1,
+    sequence::preceded,
+};
+
+fn parse(data: &str) -> Result<(&str, &str), ParseError> {
+    let data = data.trim();
+    let numbers = || many1(preceded(space1, unsigned::<u32>));
+    let races = (
+        preceded(tag("Time:"), numbers()),
+        newline,
+        preceded(tag("Distance:"), many1(preceded(space1, unsigned::<u64>))),
+    );
+    let (times, _, distances) = parsing::parse_all(data, races)?;
+    let (time, distance) = data.split_once('\n').unwrap();
+    if times.len() != distances.len() {
+        return Err(ParseError::at(data, distance, "a distance for each race"));
+    }
+    // part 2 reads all the digits of a line as one number
+    let digits = |line: &str| line.bytes().filter(u8::is_ascii_digit).count();
+    if digits(time) > 9 || digits(distance) > 18 {
+        return Err(ParseError::at(data, data, "fewer digits"));
+    }
+    Ok((time, distance))
 }
 
 fn part1((time, distance): (&str, &str)) -> u64 {
@@ -43,7 +68,7 @@ fn part2((time, distance): (&str, &str)) -> u64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day6").unwrap();
-    let input = parse(&data);
+    let input = parse(&data).unwrap();
     println!("part1: {}", part1(input));
     println!("part2: {}", part2(input));
 }
@@ -51,14 +76,21 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "
 Time:      7  15   30
 Distance:  9  40  200";
-        let input = parse(data);
+        let input = parse(data).unwrap();
         assert_eq!(288, part1(input));
         assert_eq!(71503, part2(input));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Time:      7  15   30\nDistance:  9  40  200";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
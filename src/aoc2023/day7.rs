@@ -1,3 +1,9 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    bytes::complete::take_while_m_n, character::complete::char, error::context,
+    sequence::separated_pair,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(u8)]
 enum Card1 {
@@ -169,15 +175,10 @@ impl Hand2 {
     }
 }
 
-fn parse(data: &str) -> Vec<(&str, u32)> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (hand, bid) = line.split_once(' ').unwrap();
-            let bid = bid.parse().unwrap();
-            (hand, bid)
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<(&str, u32)>, ParseError> {
+    let hand = take_while_m_n(5, 5, |c| "AKQJT98765432".contains(c));
+    let hand = context("a hand of 5 cards", hand);
+    parsing::lines(data.trim(), separated_pair(hand, char(' '), unsigned))
 }
 
 fn part1(input: &[(&str, u32)]) -> u32 {
@@ -228,7 +229,7 @@ fn part2(input: &[(&str, u32)]) -> u32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day7").unwrap();
-    let input = parse(&data);
+    let input = parse(&data).unwrap();
     println!("part1: {}", part1(&input));
     println!("part2: {}", part2(&input));
 }
@@ -236,6 +237,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -245,8 +247,14 @@ T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483";
-        let input = parse(data);
+        let input = parse(data).unwrap();
         assert_eq!(6440, part1(&input));
         assert_eq!(5905, part2(&input));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "32T3K 765\nT55J5 684\nKK677 28";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,21 +1,38 @@
+use adventofcode::math::lcm;
+use adventofcode::parsing::{self, PResult, ParseError};
 use ahash::HashMap;
+use nom::{
+    Parser,
+    bytes::complete::{tag, take_while_m_n, take_while1},
+    character::complete::char,
+    error::context,
+    sequence::{delimited, separated_pair},
+};
 
-fn parse(data: &str) -> (&[u8], HashMap<&str, [&str; 2]>) {
-    let (instruction, network) = data.trim().split_once('\n').unwrap();
-    let network = network
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let (parent, children) = line.split_once(" = (").unwrap();
-            let (left, right) = children
-                .strip_suffix(')')
-                .unwrap()
-                .split_once(", ")
-                .unwrap();
-            (parent, [left, right])
-        })
-        .collect();
-    (instruction.trim().as_bytes(), network)
+type Network<'a> = HashMap<&'a str, [&'a str; 2]>;
+
+fn node(input: &str) -> PResult<'_, &str> {
+    let name = take_while_m_n(3, 3, |c: char| c.is_ascii_alphanumeric());
+    context("a node name", name).parse(input)
+}
+
+fn parse(data: &str) -> Result<(&[u8], Network<'_>), ParseError> {
+    let [instruction, network] = parsing::split_sections(data.trim())?;
+    let instruction = parsing::parse_all(instruction, take_while1(|c| c == 'L' || c == 'R'))?;
+    let children = delimited(char('('), separated_pair(node, tag(", "), node), char(')'));
+    let entry = separated_pair(node, tag(" = "), children);
+    let mut nodes = HashMap::default();
+    for (parent, (left, right)) in parsing::lines(network, entry)? {
+        if nodes.insert(parent, [left, right]).is_some() {
+            return Err(ParseError::at(data, parent, "a new node"));
+        }
+    }
+    for child in nodes.values().flatten() {
+        if !nodes.contains_key(child) {
+            return Err(ParseError::at(data, child, "a known node"));
+        }
+    }
+    Ok((instruction.as_bytes(), nodes))
 }
 
 fn part1(instruction: &[u8], network: &HashMap<&str, [&str; 2]>) -> usize {
@@ -34,17 +51,6 @@ fn part1(instruction: &[u8], network: &HashMap<&str, [&str; 2]>) -> usize {
     0
 }
 
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    while b != 0 {
-        (a, b) = (b, a % b)
-    }
-    a
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
-}
-
 fn part2(instruction: &[u8], network: &HashMap<&str, [&str; 2]>) -> usize {
     network
         .keys()
@@ -69,7 +75,7 @@ fn part2(instruction: &[u8], network: &HashMap<&str, [&str; 2]>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day8").unwrap();
-    let (instruction, network) = parse(&data);
+    let (instruction, network) = parse(&data).unwrap();
     println!("part1: {}", part1(instruction, &network));
     println!("part2: {}", part2(instruction, &network));
 }
@@ -77,6 +83,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -90,7 +97,7 @@ DDD = (DDD, DDD)
 EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
-        let (instruction, network) = parse(data);
+        let (instruction, network) = parse(data).unwrap();
         assert_eq!(2, part1(instruction, &network));
     }
 
@@ -103,7 +110,7 @@ AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)
 ";
-        let (instruction, network) = parse(data);
+        let (instruction, network) = parse(data).unwrap();
         assert_eq!(6, part1(instruction, &network));
     }
 
@@ -121,7 +128,13 @@ LR
 22Z = (22B, 22B)
 XXX = (XXX, XXX)
 ";
-        let (instruction, network) = parse(data);
+        let (instruction, network) = parse(data).unwrap();
         assert_eq!(6, part2(instruction, &network));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
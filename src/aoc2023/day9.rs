@@ -1,12 +1,14 @@
-fn parse(data: &str) -> Vec<Vec<i64>> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|n| n.parse().unwrap())
-                .collect()
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, signed};
+use nom::{
+    Parser, character::complete::space1, combinator::verify, error::context, multi::separated_list1,
+};
+
+fn parse(data: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    // the differences of 32-bit values fit in an i64 for a good while
+    let history =
+        separated_list1(space1, signed::<i32>).map(|h| h.into_iter().map(i64::from).collect());
+    let history = verify(history, |h: &Vec<i64>| h.len() >= 2);
+    parsing::lines(data.trim(), context("at least 2 values", history))
 }
 
 fn predict(signal: &[i64]) -> i64 {
@@ -41,7 +43,7 @@ fn part2(signals: &[Vec<i64>]) -> i64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2023/day9").unwrap();
-    let signals = parse(&data);
+    let signals = parse(&data).unwrap();
     println!("part1: {}", part1(&signals));
     println!("part2: {}", part2(&signals));
 }
@@ -49,6 +51,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -56,8 +59,14 @@ mod tests {
 0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45";
-        let signals = parse(data);
+        let signals = parse(data).unwrap();
         assert_eq!(114, part1(&signals));
         assert_eq!(2, part2(&signals));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "0 3 6 9 12 15\n1 3 6 10 15 21";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,15 +1,13 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::HashMap;
+use aoc_proc::aoc_examples;
+use nom::character::complete::space1;
+use nom::sequence::separated_pair;
 
-fn parse(data: &str) -> (Vec<i32>, Vec<i32>) {
-    let (mut l1, mut l2) = (vec![], vec![]);
-    data.lines()
-        .filter(|line| !line.is_empty())
-        .for_each(|line| {
-            let (a, b) = line.split_once("   ").unwrap();
-            l1.push(a.parse().unwrap());
-            l2.push(b.parse().unwrap());
-        });
-    (l1, l2)
+fn parse(data: &str) -> Result<(Vec<i32>, Vec<i32>), ParseError> {
+    let pairs: Vec<(i32, i32)> =
+        parsing::lines(data.trim(), separated_pair(unsigned, space1, unsigned))?;
+    Ok(pairs.into_iter().unzip())
 }
 
 fn part1(l1: &mut [i32], l2: &mut [i32]) -> i32 {
@@ -28,26 +26,33 @@ fn part2(l1: &[i32], l2: &[i32]) -> i32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2024/day1").unwrap();
-    let (mut l1, mut l2) = parse(&data);
+    let (mut l1, mut l2) = parse(&data).unwrap();
     println!("part1: {}", part1(&mut l1, &mut l2));
     println!("part2: {}", part2(&l1, &l2));
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn case1() {
-        let data = "
+aoc_examples! {
+    parse = |s| parse(s).unwrap();
+    part1 = |(l1, l2)| part1(&mut l1.clone(), &mut l2.clone());
+    part2 = |(l1, l2)| part2(l1, l2);
+    "
 3   4
 4   3
 2   5
 1   3
 3   9
-3   3";
-        let (mut l1, mut l2) = parse(data);
-        assert_eq!(11, part1(&mut l1, &mut l2));
-        assert_eq!(31, part2(&l1, &l2));
+3   3" => (11, 31),
+    file "data/2024/day1" => (_, _),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "3   4\n4   3\n2   5";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,11 +1,23 @@
 use std::collections::VecDeque;
 
-fn parse(input: &str) -> Vec<Vec<u8>> {
-    input
-        .trim()
-        .lines()
-        .map(|line| line.as_bytes().iter().map(|b| b - b'0').collect())
-        .collect()
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let input = input.trim();
+    // anything but a height is impassable
+    let map = parsing::grid_of(input, "0123456789.X")?;
+    // positions are i8
+    if map.len() > 127 || map[0].len() > 127 {
+        return Err(ParseError::at(input, input, "at most 127 rows and columns"));
+    }
+    let height = |&c: &u8| {
+        if c.is_ascii_digit() {
+            c - b'0'
+        } else {
+            u8::MAX
+        }
+    };
+    Ok(map.iter().map(|r| r.iter().map(height).collect()).collect())
 }
 
 fn part1(map: &[Vec<u8>]) -> usize {
@@ -86,7 +98,7 @@ fn part2(map: &[Vec<u8>]) -> usize {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day10").unwrap();
-    let map = parse(&input);
+    let map = parse(&input).unwrap();
     println!("part1: {}", part1(&map));
     println!("part2: {}", part2(&map));
 }
@@ -94,6 +106,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -106,7 +119,7 @@ mod tests {
 32019012
 01329801
 10456732";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         assert_eq!(36, part1(&map));
     }
 
@@ -119,7 +132,7 @@ mod tests {
 345678
 4X6789
 56789X";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         assert_eq!(227, part2(&map));
     }
 
@@ -134,7 +147,13 @@ mod tests {
 32019012
 01329801
 10456732";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         assert_eq!(81, part2(&map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "89010123\n78121874\n87430965\n96549874\n45678903\n32019012\n01329801\n10456732";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,10 +1,15 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::{HashMap, HashMapExt};
+use nom::{
+    character::complete::space1, combinator::verify, error::context, multi::separated_list1,
+};
 
-fn parse(input: &str) -> Vec<u64> {
-    input
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .collect()
+fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+    // an odd number of digits grows by at most 4 before splitting, so this
+    // keeps every stone below 10^16
+    let stone = verify(unsigned::<u64>, |&x| x < 1_000_000_000_000);
+    let stones = separated_list1(space1, context("a number below 10^12", stone));
+    parsing::parse_all(input.trim(), stones)
 }
 
 fn blink(stones: &[u64], times: &[usize]) -> Vec<usize> {
@@ -38,7 +43,7 @@ fn blink(stones: &[u64], times: &[usize]) -> Vec<usize> {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day11").unwrap();
-    let stones = parse(&input);
+    let stones = parse(&input).unwrap();
     let result = blink(&stones, &[25, 75]);
     println!("part1: {}", result[0]);
     println!("part2: {}", result[1]);
@@ -47,11 +52,18 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let input = "125 17";
-        let stones = parse(input);
+        let stones = parse(input).unwrap();
         assert_eq!(vec![22, 55312], blink(&stones, &[6, 25]));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "125 17";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,74 +1,65 @@
-use ahash::HashSet;
+use adventofcode::dsu::Dsu;
+use adventofcode::parsing::{self, ParseError};
 
-fn parse(input: &str) -> Vec<&[u8]> {
-    input.trim().lines().map(|line| line.as_bytes()).collect()
+fn parse(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let input = input.trim();
+    let map = parsing::grid_of(input, "ABCDEFGHIJKLMNOPQRSTUVWXYZ")?;
+    // positions are i16
+    if map.len() > i16::MAX as usize || map[0].len() > i16::MAX as usize {
+        return Err(ParseError::at(input, input, "a smaller map"));
+    }
+    Ok(map)
 }
 
 const DIRS: [(i16, i16); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
 
-fn is_corner(i: i16, j: i16, d: usize, region: &HashSet<(i16, i16)>) -> bool {
-    let forward = (i + DIRS[(d) % 4].0, j + DIRS[(d) % 4].1);
-    let right = (i + DIRS[(d + 1) % 4].0, j + DIRS[(d + 1) % 4].1);
-    let forward_right = (
-        i + DIRS[d].0 + DIRS[(d + 1) % 4].0,
-        j + DIRS[d].1 + DIRS[(d + 1) % 4].1,
-    );
-    if !region.contains(&forward) && !region.contains(&right) {
-        return true;
-    }
-
-    region.contains(&forward) && region.contains(&right) && !region.contains(&forward_right)
-}
-
-fn solve(map: &[&[u8]]) -> (usize, usize) {
+fn solve(map: &[Vec<u8>]) -> (usize, usize) {
     let (m, n) = (map.len(), map[0].len());
 
-    let valid = |i: i16, j: i16| i >= 0 && j >= 0 && i < m as i16 && j < n as i16;
-    let mut visited = vec![false; m * n];
-    let (mut p1, mut p2) = (0, 0);
-    for (i, r) in map.iter().enumerate() {
-        for (j, &c) in r.iter().enumerate() {
-            if visited[i * n + j] {
-                continue;
+    let plant = |i: i16, j: i16| {
+        (i >= 0 && j >= 0 && i < m as i16 && j < n as i16).then(|| map[i as usize][j as usize])
+    };
+    let mut dsu = Dsu::new(m * n);
+    for i in 0..m {
+        for j in 0..n {
+            if j + 1 < n && map[i][j] == map[i][j + 1] {
+                dsu.union(i * n + j, i * n + j + 1);
             }
-            let mut q = Vec::with_capacity(m * n);
-            q.push((i as i16, j as i16));
-            let mut region = HashSet::default();
-            region.insert((i as i16, j as i16));
-            let mut perimeter = 4;
-            while let Some((i, j)) = q.pop() {
-                visited[i as usize * n + j as usize] = true;
-                for (i1, j1) in [(i + 1, j), (i - 1, j), (i, j + 1), (i, j - 1)] {
-                    if !valid(i1, j1)
-                        || map[i1 as usize][j1 as usize] != c
-                        || region.contains(&(i1, j1))
-                    {
-                        continue;
-                    }
-                    region.insert((i1, j1));
-                    perimeter += 4;
-                    for (i2, j2) in [(i1 + 1, j1), (i1 - 1, j1), (i1, j1 + 1), (i1, j1 - 1)] {
-                        if valid(i2, j2) && region.contains(&(i2, j2)) {
-                            perimeter -= 2;
-                        }
-                    }
-                    q.push((i1, j1));
+            if i + 1 < m && map[i][j] == map[i + 1][j] {
+                dsu.union(i * n + j, (i + 1) * n + j);
+            }
+        }
+    }
+
+    let (mut p1, mut p2) = (0, 0);
+    for region in dsu.groups() {
+        let (mut perimeter, mut corners) = (0, 0);
+        for &k in &region {
+            let (i, j) = ((k / n) as i16, (k % n) as i16);
+            let c = Some(map[k / n][k % n]);
+            for d in 0..4 {
+                let (fi, fj) = DIRS[d];
+                let (ri, rj) = DIRS[(d + 1) % 4];
+                let forward = plant(i + fi, j + fj) == c;
+                let right = plant(i + ri, j + rj) == c;
+                let forward_right = plant(i + fi + ri, j + fj + rj) == c;
+                if !forward {
+                    perimeter += 1;
+                }
+                if (!forward && !right) || (forward && right && !forward_right) {
+                    corners += 1;
                 }
             }
-            let corners: usize = region
-                .iter()
-                .map(|&(i, j)| (0..4).filter(|&d| is_corner(i, j, d, &region)).count())
-                .sum();
-            p1 += region.len() * perimeter;
-            p2 += region.len() * corners;
         }
+        p1 += region.len() * perimeter;
+        p2 += region.len() * corners;
     }
     (p1, p2)
 }
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day12").unwrap();
-    let map = parse(&input);
+    let map = parse(&input).unwrap();
     let (p1, p2) = solve(&map);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -77,6 +68,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -85,7 +77,7 @@ AAAA
 BBCD
 BBCC
 EEEC";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(140, p1);
         assert_eq!(80, p2);
@@ -99,7 +91,7 @@ OXOXO
 OOOOO
 OXOXO
 OOOOO";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(772, p1);
         assert_eq!(436, p2);
@@ -118,7 +110,7 @@ VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
 MMMISSJEEE";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(1930, p1);
         assert_eq!(1206, p2);
@@ -132,7 +124,7 @@ EXXXX
 EEEEE
 EXXXX
 EEEEE";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let (_, p2) = solve(&map);
         assert_eq!(236, p2);
     }
@@ -146,8 +138,14 @@ AAABBA
 ABBAAA
 ABBAAA
 AAAAAA";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let (_, p2) = solve(&map);
         assert_eq!(368, p2);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "AAAA\nBBCD\nBBCC\nEEEC";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
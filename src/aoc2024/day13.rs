@@ -1,35 +1,38 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    Parser,
+    bytes::complete::tag,
+    character::complete::newline,
+    combinator::verify,
+    error::context,
+    sequence::{delimited, preceded, separated_pair},
+};
+
 struct ClawMachine {
     a: [i64; 2],
     b: [i64; 2],
     prize: [i64; 2],
 }
 
-fn parse_button(s: &str) -> [i64; 2] {
-    let (x, y) = s.split_once(", ").unwrap();
-    let x = x.trim_start_matches("X+").parse().unwrap();
-    let y = y.trim_start_matches("Y+").parse().unwrap();
-    [x, y]
-}
-
-fn parse_prize(s: &str) -> [i64; 2] {
-    let (x, y) = s.split_once(", ").unwrap();
-    let x = x.trim_start_matches("X=").parse().unwrap();
-    let y = y.trim_start_matches("Y=").parse().unwrap();
-    [x, y]
-}
-
-fn parse(input: &str) -> Vec<ClawMachine> {
-    input
-        .trim()
-        .split("\n\n")
-        .map(|part| {
-            let mut lines = part.lines();
-            let a = parse_button(lines.next().unwrap().trim_start_matches("Button A: "));
-            let b = parse_button(lines.next().unwrap().trim_start_matches("Button B: "));
-            let prize = parse_prize(lines.next().unwrap().trim_start_matches("Prize: "));
-            ClawMachine { a, b, prize }
-        })
-        .collect()
+fn parse(input: &str) -> Result<Vec<ClawMachine>, ParseError> {
+    // small enough that part 2 can't overflow
+    let button = |name| {
+        let moves = separated_pair(unsigned, tag(", Y+"), unsigned);
+        let moves = verify(moves, |&(x, y): &(i64, i64)| x < 100 && y < 100);
+        let moves = context("moves below 100", moves).map(|(x, y)| [x, y]);
+        delimited((tag("Button "), tag(name), tag(": X+")), moves, newline)
+    };
+    let prize = separated_pair(unsigned, tag(", Y="), unsigned);
+    let prize = verify(prize, |&(x, y): &(i64, i64)| x < 1_000_000 && y < 1_000_000);
+    let prize = context("a prize below 10^6", prize).map(|(x, y)| [x, y]);
+    let machine = (button("A"), button("B"), preceded(tag("Prize: X="), prize));
+    let machine = machine.map(|(a, b, prize)| ClawMachine { a, b, prize });
+    // the buttons must be independent for the machine to have one solution
+    let machine = verify(machine, |m| m.a[0] * m.b[1] != m.a[1] * m.b[0]);
+    parsing::sections(
+        input,
+        context("buttons that move in different directions", machine),
+    )
 }
 
 const OFFSET: i64 = 10000000000000;
@@ -56,7 +59,7 @@ fn solve(machines: &[ClawMachine], offset: i64) -> i64 {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day13").unwrap();
-    let machines = parse(&input);
+    let machines = parse(&input).unwrap();
     println!("part1: {}", solve(&machines, 0));
     println!("part2: {}", solve(&machines, OFFSET));
 }
@@ -64,6 +67,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -83,8 +87,14 @@ Prize: X=7870, Y=6450
 Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
-        let machines = parse(input);
+        let machines = parse(input).unwrap();
         assert_eq!(480, solve(&machines, 0));
         assert_eq!(875318608908, solve(&machines, OFFSET));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\nButton A: X+26, Y+66\nButton B: X+67, Y+21\nPrize: X=12748, Y=12176";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
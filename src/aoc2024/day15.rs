@@ -1,7 +1,26 @@
-fn parse(input: &str) -> (Vec<&[u8]>, &[u8]) {
-    let (map, movements) = input.trim().split_once("\n\n").unwrap();
-    let map = map.lines().map(|l| l.as_bytes()).collect();
-    (map, movements.as_bytes())
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(input: &str) -> Result<(Vec<Vec<u8>>, &[u8]), ParseError> {
+    let [map_part, movements] = parsing::split_sections(input)?;
+    let map = parsing::grid_of(map_part, "#.O@")?;
+    if map_part.matches('@').count() != 1 {
+        return Err(ParseError::at(input, map_part, "exactly one robot"));
+    }
+    // the robot never leaves the warehouse
+    let (m, n) = (map.len(), map[0].len());
+    let wall = |i: usize, j: usize| i == 0 || j == 0 || i == m - 1 || j == n - 1;
+    let walled = (0..m).all(|i| (0..n).all(|j| !wall(i, j) || map[i][j] == b'#'));
+    if !walled {
+        return Err(ParseError::at(
+            input,
+            map_part,
+            "a warehouse surrounded by walls",
+        ));
+    }
+    if let Some(i) = movements.find(|c| !"<>^v\n".contains(c)) {
+        return Err(ParseError::at(input, &movements[i..], "a movement"));
+    }
+    Ok((map, movements.as_bytes()))
 }
 
 fn find_robot(map: &[Vec<u8>]) -> (i32, i32) {
@@ -65,8 +84,8 @@ fn sum_of_coordinates(map: &[Vec<u8>]) -> usize {
     result
 }
 
-fn part1(map: &[&[u8]], movements: &[u8]) -> usize {
-    let mut map: Vec<Vec<u8>> = map.iter().map(|r| r.to_vec()).collect();
+fn part1(map: &[Vec<u8>], movements: &[u8]) -> usize {
+    let mut map = map.to_vec();
     let (mut i0, mut j0) = find_robot(&map);
     for &b in movements {
         if b == b'\n' {
@@ -142,7 +161,7 @@ fn push(i0: i32, j0: i32, di: i32, dj: i32, map: &mut [Vec<u8>]) {
     }
 }
 
-fn part2(map: &[&[u8]], movements: &[u8]) -> usize {
+fn part2(map: &[Vec<u8>], movements: &[u8]) -> usize {
     let mut map: Vec<Vec<u8>> = map
         .iter()
         .map(|r| {
@@ -174,7 +193,7 @@ fn part2(map: &[&[u8]], movements: &[u8]) -> usize {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day15").unwrap();
-    let (map, movements) = parse(&input);
+    let (map, movements) = parse(&input).unwrap();
     println!("part1: {}", part1(&map, movements));
     println!("part2: {}", part2(&map, movements));
 }
@@ -182,6 +201,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -207,7 +227,7 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
 ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
-        let (map, movements) = parse(input);
+        let (map, movements) = parse(input).unwrap();
         assert_eq!(10092, part1(&map, movements));
         assert_eq!(9021, part2(&map, movements));
     }
@@ -225,7 +245,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ########
 
 <^^>>>vv<v>>v<<";
-        let (map, movements) = parse(input);
+        let (map, movements) = parse(input).unwrap();
         assert_eq!(2028, part1(&map, movements));
     }
 
@@ -241,7 +261,13 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 #######
 
 <vv<<^^<<^^";
-        let (map, movements) = parse(input);
+        let (map, movements) = parse(input).unwrap();
         assert_eq!(618, part2(&map, movements));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "########\n#..O.O.#\n##@.O..#\n#...O..#\n#.#.O..#\n#...O..#\n#......#\n########\n\n<^^>>>vv<v>>v<<";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,10 +1,40 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use adventofcode::parsing::{self, ParseError};
+use adventofcode::search;
+use ahash::AHashSet as HashSet;
 
-fn parse(input: &str) -> Vec<&[u8]> {
-    input.trim().lines().map(|x| x.as_bytes()).collect()
+fn parse(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let input = input.trim();
+    let map = parsing::grid_of(input, "#.SE")?;
+    for p in ["S", "E"] {
+        if input.matches(p).count() != 1 {
+            return Err(ParseError::at(input, input, format!("exactly one {p}")));
+        }
+    }
+    let (m, n) = (map.len(), map[0].len());
+    let wall = |i: usize, j: usize| i == 0 || j == 0 || i == m - 1 || j == n - 1;
+    if (0..m).any(|i| (0..n).any(|j| wall(i, j) && map[i][j] != b'#')) {
+        return Err(ParseError::at(input, input, "a maze surrounded by walls"));
+    }
+    // both parts need a path from S to E
+    let (i0, j0) = find_start(&map);
+    let mut seen = vec![vec![false; n]; m];
+    let mut stack = vec![(i0 as usize, j0 as usize)];
+    seen[i0 as usize][j0 as usize] = true;
+    while let Some((i, j)) = stack.pop() {
+        for (i1, j1) in [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)] {
+            if map[i1][j1] != b'#' && !seen[i1][j1] {
+                seen[i1][j1] = true;
+                stack.push((i1, j1));
+            }
+        }
+    }
+    if !(0..m).any(|i| (0..n).any(|j| map[i][j] == b'E' && seen[i][j])) {
+        return Err(ParseError::at(input, input, "a path from S to E"));
+    }
+    Ok(map)
 }
 
-fn find_start(map: &[&[u8]]) -> (i32, i32) {
+fn find_start(map: &[Vec<u8>]) -> (i32, i32) {
     for (i, r) in map.iter().enumerate() {
         for (j, &c) in r.iter().enumerate() {
             if c == b'S' {
@@ -15,69 +45,32 @@ fn find_start(map: &[&[u8]]) -> (i32, i32) {
     unreachable!()
 }
 
-fn solve(map: &[&[u8]]) -> (i32, i32) {
-    let (m, n) = (map.len(), map[0].len());
-    let (i0, j0) = find_start(map);
-    let index = |i: i32, j: i32, d: i32| (i * n as i32 + j) * 4 + d;
-    let mut scores = vec![i32::MAX; m * n * 4];
-    let mut parent = vec![vec![]; m * n * 4];
-    let mut paths = Vec::with_capacity(m * n);
-    let mut q = BinaryHeap::with_capacity(m * n);
-    let mut best = i32::MAX;
-    let k0 = index(i0, j0, 1);
-    scores[k0 as usize] = 0;
-    q.push((Reverse(0), k0));
-    while let Some((Reverse(s), k)) = q.pop() {
-        let (i, j, d) = ((k / 4) / n as i32, (k / 4) % n as i32, k % 4);
-        if map[i as usize][j as usize] == b'E' {
-            match s.cmp(&best) {
-                std::cmp::Ordering::Less => {
-                    best = s;
-                    paths.clear();
-                    paths.push(index(i, j, d));
-                }
-                std::cmp::Ordering::Equal => {
-                    paths.push(index(i, j, d));
-                }
-                _ => {}
-            }
-            continue;
+fn solve(map: &[Vec<u8>]) -> (i32, i32) {
+    let start = find_start(map);
+    // directions: 0 north, 1 east, 2 south, 3 west
+    let neighbors = |&(i, j, d): &(i32, i32, i32)| {
+        let (di, dj) = [(-1, 0), (0, 1), (1, 0), (0, -1)][d as usize];
+        let mut next = vec![((i, j, (d + 1) % 4), 1000), ((i, j, (d + 3) % 4), 1000)];
+        if map[(i + di) as usize][(j + dj) as usize] != b'#' {
+            next.push(((i + di, j + dj, d), 1));
         }
-        for (i1, j1, d1) in [(i - 1, j, 0), (i, j - 1, 3), (i, j + 1, 1), (i + 1, j, 2)] {
-            if d1 == (d + 2) % 4 || map[i1 as usize][j1 as usize] == b'#' {
-                continue;
-            }
-            let s1 = s + if d1 == d { 1 } else { 1001 };
-            if s1 > best {
-                continue;
-            }
-            let k1 = index(i1, j1, d1) as usize;
-            match s1.cmp(&scores[k1]) {
-                std::cmp::Ordering::Less => {
-                    scores[k1] = s1;
-                    parent[k1].clear();
-                    parent[k1].push(k);
-                    q.push((Reverse(s1), k1 as i32));
-                }
-                std::cmp::Ordering::Equal => {
-                    parent[k1].push(k);
-                }
-                _ => {}
-            }
-        }
-    }
-    let mut best_tiles = vec![false; m * n];
-    while let Some(k) = paths.pop() {
-        best_tiles[(k / 4) as usize] = true;
-        paths.extend(&parent[k as usize]);
-    }
-    let count = best_tiles.into_iter().filter(|&t| t).count() as i32;
-    (best, count)
+        next
+    };
+    let paths = search::shortest_paths([(start.0, start.1, 1)], neighbors, |&(i, j, _)| {
+        map[i as usize][j as usize] == b'E'
+    })
+    .unwrap();
+    let tiles = paths
+        .states()
+        .into_iter()
+        .map(|(i, j, _)| (i, j))
+        .collect::<HashSet<_>>();
+    (paths.cost, tiles.len() as i32)
 }
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day16").unwrap();
-    let map = parse(&input);
+    let map = parse(&input).unwrap();
     let (p1, p2) = solve(&map);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -86,6 +79,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -105,7 +99,7 @@ mod tests {
 #.###.#.#.#.#.#
 #S..#.....#...#
 ###############";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(7036, p1);
         assert_eq!(45, p2);
@@ -131,9 +125,15 @@ mod tests {
 #.#.#.#########.#
 #S#.............#
 #################";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let (p1, p2) = solve(&map);
         assert_eq!(11048, p1);
         assert_eq!(64, p2);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "######\n#...E#\n#.##.#\n#S...#\n######";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,4 +1,12 @@
-use std::collections::VecDeque;
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, newline},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+    sequence::preceded,
+};
 
 #[derive(Clone, Copy)]
 struct Computer {
@@ -12,25 +20,40 @@ impl Computer {
     fn run(&mut self, program: &[u8]) -> Vec<u8> {
         let mut result = Vec::with_capacity(program.len() + 1);
         while self.ip + 1 < program.len() as i32 {
-            let (opcode, operand) = (program[self.ip as usize], program[self.ip as usize + 1]);
-            match opcode {
-                0 => self.a >>= self.combo_operand(operand),
-                1 => self.b ^= operand as u64,
-                2 => self.b = self.combo_operand(operand) & 7,
-                3 => {
-                    if self.a != 0 {
-                        self.ip = operand as i32 - 2;
-                    }
+            self.execute(program, &mut result);
+        }
+        result
+    }
+
+    /// Runs one iteration of a program checked by `check_shape`, stopping at
+    /// the trailing `jnz`.
+    fn run_body(&mut self, program: &[u8]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(1);
+        self.ip = 0;
+        while self.ip + 2 < program.len() as i32 {
+            self.execute(program, &mut result);
+        }
+        result
+    }
+
+    fn execute(&mut self, program: &[u8], output: &mut Vec<u8>) {
+        let (opcode, operand) = (program[self.ip as usize], program[self.ip as usize + 1]);
+        match opcode {
+            0 => self.a >>= self.combo_operand(operand),
+            1 => self.b ^= operand as u64,
+            2 => self.b = self.combo_operand(operand) & 7,
+            3 => {
+                if self.a != 0 {
+                    self.ip = operand as i32 - 2;
                 }
-                4 => self.b ^= self.c,
-                5 => result.push((self.combo_operand(operand) & 7) as u8),
-                6 => self.b = self.a >> self.combo_operand(operand),
-                7 => self.c = self.a >> self.combo_operand(operand),
-                _ => unreachable!(),
             }
-            self.ip += 2;
+            4 => self.b ^= self.c,
+            5 => output.push((self.combo_operand(operand) & 7) as u8),
+            6 => self.b = self.a >> self.combo_operand(operand),
+            7 => self.c = self.a >> self.combo_operand(operand),
+            _ => unreachable!(),
         }
-        result
+        self.ip += 2;
     }
 
     fn combo_operand(&self, op: u8) -> u64 {
@@ -44,25 +67,103 @@ impl Computer {
     }
 }
 
-fn parse(input: &str) -> (Computer, Vec<u8>) {
-    let (regs, prog) = input.trim().split_once("\n\n").unwrap();
-    let regs: Vec<u64> = regs
-        .lines()
-        .map(|r| r.split_once(": ").unwrap().1.parse().unwrap())
-        .collect();
-    let program = prog
-        .trim_start_matches("Program: ")
-        .trim()
-        .split(',')
-        .map(|x| x.parse().unwrap())
-        .collect();
-    let computer = Computer {
-        a: regs[0],
-        b: regs[1],
-        c: regs[2],
-        ip: 0,
-    };
-    (computer, program)
+const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+fn disassemble(program: &[u8]) -> String {
+    let mut lines = vec![];
+    for (i, ins) in program.chunks(2).enumerate() {
+        let (opcode, operand) = (ins[0], ins.get(1).copied().unwrap_or(0));
+        let operand = match opcode {
+            1 | 3 => operand.to_string(),
+            4 => String::new(),
+            _ => match operand {
+                0..=3 => operand.to_string(),
+                4 => "a".to_string(),
+                5 => "b".to_string(),
+                6 => "c".to_string(),
+                _ => format!("?{operand}"),
+            },
+        };
+        let mnemonic = MNEMONICS.get(opcode as usize).copied().unwrap_or("???");
+        lines.push(
+            format!("{:2}: {mnemonic} {operand}", i * 2)
+                .trim_end()
+                .to_string(),
+        );
+    }
+    lines.join("\n")
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum QuineError {
+    OddLength,
+    NoTrailingJump,
+    InnerJump(usize),
+    ShiftCount(usize),
+    ShiftAmount(u8),
+    OutputCount(usize),
+    NotFound,
+}
+
+impl std::fmt::Display for QuineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuineError::OddLength => write!(f, "program has an odd length"),
+            QuineError::NoTrailingJump => write!(f, "program does not end with jnz 0"),
+            QuineError::InnerJump(ip) => write!(f, "unexpected jump at {ip}"),
+            QuineError::ShiftCount(n) => write!(f, "expected a single adv, found {n}"),
+            QuineError::ShiftAmount(op) => write!(f, "expected adv 3, found adv {op}"),
+            QuineError::OutputCount(n) => write!(f, "expected a single out, found {n}"),
+            QuineError::NotFound => write!(f, "no value of register A makes a quine"),
+        }
+    }
+}
+
+/// The search assumes a loop that outputs once and shifts A by 3 bits per
+/// iteration, so every output only depends on the bits of A not yet shifted out.
+fn check_shape(program: &[u8]) -> Result<(), QuineError> {
+    if !program.len().is_multiple_of(2) {
+        return Err(QuineError::OddLength);
+    }
+    if !program.ends_with(&[3, 0]) {
+        return Err(QuineError::NoTrailingJump);
+    }
+    let body = &program[..program.len() - 2];
+    if let Some(i) = body.chunks(2).position(|ins| ins[0] == 3) {
+        return Err(QuineError::InnerJump(i * 2));
+    }
+    let shifts = body.chunks(2).filter(|ins| ins[0] == 0).collect::<Vec<_>>();
+    if shifts.len() != 1 {
+        return Err(QuineError::ShiftCount(shifts.len()));
+    }
+    if shifts[0][1] != 3 {
+        return Err(QuineError::ShiftAmount(shifts[0][1]));
+    }
+    let outputs = body.chunks(2).filter(|ins| ins[0] == 5).count();
+    if outputs != 1 {
+        return Err(QuineError::OutputCount(outputs));
+    }
+    Ok(())
+}
+
+fn parse(input: &str) -> Result<(Computer, Vec<u8>), ParseError> {
+    let [regs, prog] = parsing::split_sections(input)?;
+    let reg = |name| preceded((tag("Register "), char(name), tag(": ")), unsigned::<u64>);
+    let registers = (reg('A'), newline, reg('B'), newline, reg('C'));
+    let (a, _, b, _, c) = parsing::parse_all(regs, registers)?;
+    let op = context("a 3-bit number", verify(unsigned::<u8>, |&x| x < 8));
+    let program = preceded(tag("Program: "), separated_list1(char(','), op));
+    let program = parsing::parse_all(prog, program)?;
+    // operand 7 is reserved for combo operands
+    let combo = |ins: &[u8]| [0, 2, 5, 6, 7].contains(&ins[0]);
+    if program
+        .chunks(2)
+        .any(|ins| combo(ins) && ins.get(1) == Some(&7))
+    {
+        return Err(ParseError::at(input, prog, "valid combo operands"));
+    }
+    let computer = Computer { a, b, c, ip: 0 };
+    Ok((computer, program))
 }
 
 fn part1(mut computer: Computer, program: &[u8]) -> String {
@@ -77,39 +178,53 @@ fn part1(mut computer: Computer, program: &[u8]) -> String {
     r
 }
 
-fn part2(mut computer: Computer, program: &[u8]) -> u64 {
-    let mut q = VecDeque::with_capacity(program.len() * 2);
-    q.push_back(0);
+fn part2(mut computer: Computer, program: &[u8]) -> Result<u64, QuineError> {
+    check_shape(program)?;
+    // build A from its highest 3 bits, which are consumed by the last iteration
+    let mut candidates = vec![0];
     for &p in program.iter().rev() {
-        let n = q.len();
-        for _ in 0..n {
-            let prev = q.pop_front().unwrap();
+        let mut next = vec![];
+        for prev in candidates {
             for da in 0u64..8 {
                 let a = (prev << 3) + da;
-                let b = ((a & 7) ^ (a >> ((a & 7) ^ 7))) & 7;
-                if b as u8 == p {
-                    q.push_back(a);
+                // the loop exits as soon as A is zero
+                if a == 0 {
+                    continue;
+                }
+                let mut c = computer;
+                c.a = a;
+                if c.run_body(program) == [p] {
+                    next.push(a);
                 }
             }
         }
+        candidates = next;
     }
-    let result = q.into_iter().min().unwrap();
-    computer.a = result;
-    let output = computer.run(program);
-    assert_eq!(output, program);
-    result
+    candidates.sort_unstable();
+    candidates
+        .into_iter()
+        .find(|&a| {
+            computer.a = a;
+            computer.ip = 0;
+            computer.run(program) == program
+        })
+        .ok_or(QuineError::NotFound)
 }
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day17").unwrap();
-    let (computer, program) = parse(&input);
+    let (computer, program) = parse(&input).unwrap();
     println!("part1: {}", part1(computer, &program));
-    println!("part2: {}", part2(computer, &program));
+    match part2(computer, &program) {
+        Ok(a) => println!("part2: {a}"),
+        Err(e) => println!("part2: {e}\n{}", disassemble(&program)),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -119,14 +234,56 @@ Register B: 0
 Register C: 0
 
 Program: 0,1,5,4,3,0";
-        let (computer, program) = parse(input);
+        let (computer, program) = parse(input).unwrap();
         assert_eq!("4,6,3,5,6,3,5,2,1,0", part1(computer, &program));
     }
 
     #[test]
-    fn case2() {
-        let input: String = std::fs::read_to_string("data/2024/day17").unwrap();
-        let (c, program) = parse(&input);
-        assert_eq!(265652340990875, part2(c, &program));
+    fn case3() {
+        let input = "
+Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0";
+        let (computer, program) = parse(input).unwrap();
+        assert_eq!(Ok(117440), part2(computer, &program));
+    }
+
+    #[test]
+    fn shape() {
+        assert_eq!(
+            Err(QuineError::ShiftAmount(1)),
+            check_shape(&[0, 1, 5, 4, 3, 0])
+        );
+        assert_eq!(Err(QuineError::NoTrailingJump), check_shape(&[0, 3, 5, 4]));
+        assert_eq!(
+            Err(QuineError::ShiftCount(2)),
+            check_shape(&[0, 3, 0, 3, 5, 4, 3, 0])
+        );
+        assert_eq!(
+            Err(QuineError::InnerJump(2)),
+            check_shape(&[0, 3, 3, 0, 5, 4, 3, 0])
+        );
+        assert_eq!(Err(QuineError::OutputCount(0)), check_shape(&[0, 3, 3, 0]));
+    }
+
+    #[test]
+    fn disassembly() {
+        let program = [2, 4, 1, 7, 7, 5, 4, 1, 0, 3, 5, 5, 3, 0];
+        let expected = " 0: bst a
+ 2: bxl 7
+ 4: cdv b
+ 6: bxc
+ 8: adv 3
+10: out b
+12: jnz 0";
+        assert_eq!(expected, disassemble(&program));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,75 +1,41 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use adventofcode::parsing::{self, ParseError, unsigned};
+use adventofcode::search;
+use nom::{
+    character::complete::char, combinator::verify, error::context, sequence::separated_pair,
+};
 
-fn parse(input: &str) -> Vec<(i16, i16)> {
-    input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once(',').unwrap();
-            (a.parse().unwrap(), b.parse().unwrap())
-        })
-        .collect()
+fn parse(input: &str) -> Result<Vec<(i16, i16)>, ParseError> {
+    // the memory space is at most 71 by 71
+    let byte = separated_pair(unsigned::<i16>, char(','), unsigned);
+    let byte = verify(byte, |&(x, y)| x < 71 && y < 71);
+    parsing::lines(input.trim(), context("a position below 71", byte))
 }
 
-fn part1(bytes: &[(i16, i16)], n: i16) -> u16 {
+fn walls(bytes: &[(i16, i16)], n: i16) -> Vec<bool> {
     let mut map = vec![false; n as usize * n as usize];
     for &(x, y) in bytes {
         map[y as usize * n as usize + x as usize] = true;
     }
-    let end = n * n - 1;
-    let mut dist = vec![u16::MAX; n as usize * n as usize];
-    dist[0] = 0;
-    let mut q = BinaryHeap::with_capacity(n as usize * n as usize);
-    q.push((Reverse(0), 0));
-    while let Some((Reverse(d), k)) = q.pop() {
-        if k == end {
-            return d;
-        }
-        if dist[k as usize] < d {
-            continue;
-        }
-        let (i, j) = (k / n, k % n);
-        for (i1, j1) in [(i - 1, j), (i, j - 1), (i, j + 1), (i + 1, j)] {
-            let k1 = i1 * n + j1;
-            if i1 < 0 || j1 < 0 || i1 == n || j1 == n || map[k1 as usize] {
-                continue;
-            }
-            if d + 1 < dist[k1 as usize] {
-                dist[k1 as usize] = d + 1;
-                q.push((Reverse(d + 1), k1));
-            }
-        }
-    }
-    u16::MAX
+    map
+}
+
+fn steps(map: &[bool], n: i16) -> Option<usize> {
+    let neighbors = |&(i, j): &(i16, i16)| {
+        [(i - 1, j), (i, j - 1), (i, j + 1), (i + 1, j)]
+            .into_iter()
+            .filter(|&(i1, j1)| {
+                i1 >= 0 && j1 >= 0 && i1 < n && j1 < n && !map[(i1 * n + j1) as usize]
+            })
+    };
+    search::bfs([(0, 0)], neighbors, |&p| p == (n - 1, n - 1)).map(|(_, d)| d)
+}
+
+fn part1(bytes: &[(i16, i16)], n: i16) -> usize {
+    steps(&walls(bytes, n), n).unwrap()
 }
 
 fn connected(bytes: &[(i16, i16)], n: i16) -> bool {
-    let mut map = vec![false; n as usize * n as usize];
-    for &(x, y) in bytes {
-        map[y as usize * n as usize + x as usize] = true;
-    }
-    let end: i16 = n * n - 1;
-    let mut visited = vec![false; n as usize * n as usize];
-    visited[0] = true;
-    let mut q = Vec::with_capacity(n as usize * n as usize);
-    q.push(0);
-    while let Some(k) = q.pop() {
-        if k == end {
-            return true;
-        }
-        let (i, j) = (k / n, k % n);
-        for (i1, j1) in [(i - 1, j), (i, j - 1), (i, j + 1), (i + 1, j)] {
-            let k1 = i1 * n + j1;
-            if i1 < 0 || j1 < 0 || i1 == n || j1 == n || map[k1 as usize] {
-                continue;
-            }
-            if !visited[k1 as usize] {
-                visited[k1 as usize] = true;
-                q.push(k1);
-            }
-        }
-    }
-    false
+    steps(&walls(bytes, n), n).is_some()
 }
 
 // Union-find is faster, but this is fast enough.
@@ -88,7 +54,7 @@ fn part2(bytes: &[(i16, i16)], n: i16) -> (i16, i16) {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day18").unwrap();
-    let bytes = parse(&input);
+    let bytes = parse(&input).unwrap();
     println!("part1: {}", part1(&bytes[..1024], 71));
     let (x, y) = part2(&bytes, 71);
     println!("part2: {x},{y}");
@@ -97,6 +63,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -126,8 +93,14 @@ mod tests {
 0,5
 1,6
 2,0";
-        let bytes = parse(input);
+        let bytes = parse(input).unwrap();
         assert_eq!(22, part1(&bytes[..12], 7));
         assert_eq!((6, 1), part2(&bytes, 7));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "5,4\n4,2\n4,5\n3,0\n2,1\n6,3";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,8 +1,19 @@
-fn parse(input: &str) -> (Vec<&str>, Vec<&str>) {
-    let (patterns, designs) = input.trim().split_once("\n\n").unwrap();
-    let patterns = patterns.split(", ").collect();
-    let designs = designs.lines().collect();
-    (patterns, designs)
+use adventofcode::parsing::{self, ParseError};
+use nom::{
+    bytes::complete::{tag, take_while1},
+    combinator::verify,
+    error::context,
+    multi::separated_list1,
+};
+
+fn parse(input: &str) -> Result<(Vec<&str>, Vec<&str>), ParseError> {
+    let [patterns, designs] = parsing::split_sections(input)?;
+    let stripes = || context("colors wubrg", take_while1(|c| "wubrg".contains(c)));
+    let patterns = parsing::parse_all(patterns, separated_list1(tag(", "), stripes()))?;
+    // a design of n stripes has at most 2^(n-1) arrangements
+    let design = verify(stripes(), |d: &str| d.len() <= 64);
+    let designs = parsing::lines(designs, context("at most 64 stripes", design))?;
+    Ok((patterns, designs))
 }
 
 fn color_index(b: u8) -> usize {
@@ -69,7 +80,7 @@ fn solve(patterns: &[&str], designs: &[&str]) -> (usize, usize) {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day19").unwrap();
-    let (patterns, designs) = parse(&input);
+    let (patterns, designs) = parse(&input).unwrap();
     let (p1, p2) = solve(&patterns, &designs);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -78,6 +89,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -92,7 +104,13 @@ ubwu
 bwurrg
 brgr
 bbrgwb";
-        let (patterns, designs) = parse(input);
+        let (patterns, designs) = parse(input).unwrap();
         assert_eq!((6, 16), solve(&patterns, &designs));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "r, wr, b, g, bwu, rb, gb, br\n\nbrwrr\nbggr\nubwu";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
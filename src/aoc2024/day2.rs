@@ -1,12 +1,10 @@
-fn parse(data: &str) -> Vec<Vec<i32>> {
-    data.lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|e| e.parse().unwrap())
-                .collect()
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use aoc_proc::aoc_examples;
+use nom::character::complete::space1;
+use nom::multi::separated_list1;
+
+fn parse(data: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    parsing::lines(data.trim(), separated_list1(space1, unsigned))
 }
 
 fn is_inc(level: &[i32]) -> bool {
@@ -48,26 +46,31 @@ fn part2(levels: &[Vec<i32>]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2024/day2").unwrap();
-    let levels = parse(&data);
+    let levels = parse(&data).unwrap();
     println!("part1: {}", part1(&levels));
     println!("part2: {}", part2(&levels));
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn case1() {
-        let data = "
+aoc_examples! {
+    parse = |s| parse(s).unwrap();
+    "
 7 6 4 2 1
 1 2 7 8 9
 9 7 6 2 1
 1 3 2 4 5
 8 6 4 4 1
-1 3 6 7 9";
-        let levels = parse(data);
-        assert_eq!(2, part1(&levels));
-        assert_eq!(4, part2(&levels));
+1 3 6 7 9" => (2, 4),
+    file "data/2024/day2" => (_, _),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "7 6 4 2 1\n1 2 7 8 9";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,12 +1,25 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
-
+use adventofcode::parsing::{self, ParseError};
+use adventofcode::search;
 use rayon::prelude::*;
 
-fn parse(input: &str) -> Vec<&[u8]> {
-    input.trim().lines().map(|line| line.as_bytes()).collect()
+fn parse(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let input = input.trim();
+    let map = parsing::grid_of(input, "#.SE")?;
+    for p in ["S", "E"] {
+        if input.matches(p).count() != 1 {
+            return Err(ParseError::at(input, input, format!("exactly one {p}")));
+        }
+    }
+    // cheats compare distances from S, so every track tile must have one
+    let dist = distances(&map, find_start(&map));
+    let (m, n) = (map.len(), map[0].len());
+    if (0..m * n).any(|k| map[k / n][k % n] != b'#' && dist[k] == i32::MAX) {
+        return Err(ParseError::at(input, input, "a track connected to S"));
+    }
+    Ok(map)
 }
 
-fn find_start(map: &[&[u8]]) -> (i32, i32) {
+fn find_start(map: &[Vec<u8>]) -> (i32, i32) {
     for (i, r) in map.iter().enumerate() {
         for (j, &c) in r.iter().enumerate() {
             if c == b'S' {
@@ -17,38 +30,27 @@ fn find_start(map: &[&[u8]]) -> (i32, i32) {
     unreachable!()
 }
 
-fn dijkstra(map: &[&[u8]], (i0, j0): (i32, i32)) -> Vec<i32> {
+fn distances(map: &[Vec<u8>], start: (i32, i32)) -> Vec<i32> {
     let (m, n) = (map.len(), map[0].len());
+    let neighbors = |&(i, j): &(i32, i32)| {
+        [(i - 1, j), (i, j - 1), (i, j + 1), (i + 1, j)]
+            .into_iter()
+            .filter(|&(i1, j1)| {
+                i1 >= 0
+                    && j1 >= 0
+                    && i1 < m as i32
+                    && j1 < n as i32
+                    && map[i1 as usize][j1 as usize] != b'#'
+            })
+    };
     let mut dist = vec![i32::MAX; m * n];
-    let mut q = BinaryHeap::new();
-    let k0 = i0 * n as i32 + j0;
-    dist[k0 as usize] = 0;
-    q.push((Reverse(0), k0));
-    while let Some((Reverse(d), k)) = q.pop() {
-        if d > dist[k as usize] {
-            continue;
-        }
-        let (i, j) = (k / n as i32, k % n as i32);
-        for (i1, j1) in [(i - 1, j), (i, j - 1), (i, j + 1), (i + 1, j)] {
-            if i1 < 0
-                || j1 < 0
-                || i1 == m as i32
-                || j1 == n as i32
-                || map[i1 as usize][j1 as usize] == b'#'
-            {
-                continue;
-            }
-            let k1 = i1 * n as i32 + j1;
-            if d + 1 < dist[k1 as usize] {
-                dist[k1 as usize] = d + 1;
-                q.push((Reverse(d + 1), k1));
-            }
-        }
+    for (&(i, j), d) in search::bfs_all([start], neighbors).iter() {
+        dist[i as usize * n + j as usize] = d as i32;
     }
     dist
 }
 
-fn cheat(map: &[&[u8]], dist: &[i32], i: i32, j: i32, save: i32) -> (usize, usize) {
+fn cheat(map: &[Vec<u8>], dist: &[i32], i: i32, j: i32, save: i32) -> (usize, usize) {
     let (m, n) = (map.len(), map[0].len());
     let (mut p1, mut p2) = (0, 0);
     let d1 = dist[i as usize * n + j as usize];
@@ -99,9 +101,9 @@ fn cheat(map: &[&[u8]], dist: &[i32], i: i32, j: i32, save: i32) -> (usize, usiz
     (p1, p2)
 }
 
-fn solve(map: &[&[u8]], save: i32) -> (usize, usize) {
+fn solve(map: &[Vec<u8>], save: i32) -> (usize, usize) {
     let start = find_start(map);
-    let dist = dijkstra(map, start);
+    let dist = distances(map, start);
     let (m, n) = (map.len(), map[0].len());
 
     (0..m * n)
@@ -119,7 +121,7 @@ fn solve(map: &[&[u8]], save: i32) -> (usize, usize) {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day20").unwrap();
-    let map = parse(&input);
+    let map = parse(&input).unwrap();
     let (p1, p2) = solve(&map, 100);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -128,6 +130,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -147,7 +150,7 @@ mod tests {
 #.#.#.#.#.#.###
 #...#...#...###
 ###############";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         assert_eq!((44, 3081), solve(&map, 2));
         assert_eq!((2, 593), solve(&map, 40));
         assert_eq!((1, 86), solve(&map, 64));
@@ -155,4 +158,10 @@ mod tests {
         assert_eq!((0, 7), solve(&map, 74));
         assert_eq!((0, 3), solve(&map, 76));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#######\n#S#...#\n#.#.#.#\n#...#E#\n#######";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,7 +1,15 @@
 use std::collections::VecDeque;
 
-fn parse(input: &str) -> Vec<&[u8]> {
-    input.trim().lines().map(|line| line.as_bytes()).collect()
+use adventofcode::parsing::{self, ParseError};
+use nom::{
+    Parser, bytes::complete::take_while_m_n, character::complete::char, combinator::recognize,
+    error::context,
+};
+
+fn parse(input: &str) -> Result<Vec<&[u8]>, ParseError> {
+    let digits = take_while_m_n(1, 3, |c: char| c.is_ascii_digit());
+    let code = context("up to 3 digits and A", recognize((digits, char('A'))));
+    parsing::lines(input.trim(), code.map(str::as_bytes))
 }
 
 const NUMERICAL: &[u8] = b"789456123 0A";
@@ -216,7 +224,7 @@ fn solve(codes: &[&[u8]], directional_robots: u8) -> usize {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day21").unwrap();
-    let codes = parse(&input);
+    let codes = parse(&input).unwrap();
     println!("part1: {}", solve(&codes, 2));
     println!("part2: {}", solve(&codes, 25));
 }
@@ -224,6 +232,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -233,7 +242,13 @@ mod tests {
 179A
 456A
 379A";
-        let codes = parse(input);
+        let codes = parse(input).unwrap();
         assert_eq!(126384, solve(&codes, 2));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "029A\n980A\n179A";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
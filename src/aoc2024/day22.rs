@@ -3,14 +3,13 @@ use std::sync::{
     Arc,
 };
 
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::{combinator::verify, error::context};
 use rayon::prelude::*;
 
-fn parse(input: &str) -> Vec<i64> {
-    input
-        .trim()
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect()
+fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    let secret = verify(unsigned::<i64>, |&x| x < 1 << 24);
+    parsing::lines(input.trim(), context("a 24-bit secret", secret))
 }
 
 fn pseudorandom(mut x: i64) -> i64 {
@@ -62,7 +61,7 @@ fn solve(mut secrets: Vec<i64>) -> (i64, i32) {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day22").unwrap();
-    let secrets = parse(&input);
+    let secrets = parse(&input).unwrap();
     let (p1, p2) = solve(secrets);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -71,6 +70,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn random() {
@@ -90,7 +90,7 @@ mod tests {
 10
 100
 2024";
-        let secrets = parse(input);
+        let secrets = parse(input).unwrap();
         assert_eq!(37327623, solve(secrets).0);
     }
 
@@ -101,7 +101,13 @@ mod tests {
 2
 3
 2024";
-        let secrets = parse(input);
+        let secrets = parse(input).unwrap();
         assert_eq!(23, solve(secrets).1);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "1\n10\n100\n2024";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,91 +1,60 @@
-use ahash::{HashMap, HashSet};
+use adventofcode::graph::Graph;
+use adventofcode::parsing::{self, ParseError};
+use ahash::HashMap;
+use nom::{
+    bytes::complete::take_while1, character::complete::char, error::context,
+    sequence::separated_pair,
+};
 
-fn parse(input: &str) -> HashMap<u16, HashSet<u16>> {
-    let mut g: HashMap<u16, HashSet<u16>> = HashMap::default();
-    for line in input.trim().lines() {
-        let (a, b) = line.split_once('-').unwrap();
-        let (a, b) = (a.as_bytes(), b.as_bytes());
-        let a = ((a[0] as u16) << 8) + a[1] as u16;
-        let b = ((b[0] as u16) << 8) + b[1] as u16;
-        g.entry(a).or_default().insert(b);
-        g.entry(b).or_default().insert(a);
-    }
-    g
+struct Network<'a> {
+    graph: Graph,
+    names: Vec<&'a str>,
 }
 
-fn bron_kerbosch(
-    g: &HashMap<u16, HashSet<u16>>,
-    r: &mut HashSet<u16>,
-    mut x: HashSet<u16>,
-    mut p: HashSet<u16>,
-    max: &mut HashSet<u16>,
-) {
-    if p.is_empty() && x.is_empty() {
-        if max.len() < r.len() {
-            *max = r.clone();
-        }
-        return;
-    }
-    while let Some(&v) = p.iter().next() {
-        r.insert(v);
-        let empty = HashSet::default();
-        let n_v = g.get(&v).unwrap_or(&empty);
-        let p1: HashSet<u16> = p.intersection(n_v).cloned().collect();
-        if p1.len() + r.len() > max.len() {
-            let x1 = x.intersection(n_v).cloned().collect();
-            bron_kerbosch(g, r, x1, p1, max);
-        }
-        r.remove(&v);
-        p.remove(&v);
-        if p.len() + r.len() <= max.len() {
-            return;
-        }
-        x.insert(v);
+fn parse(input: &str) -> Result<Network<'_>, ParseError> {
+    let name = || context("a name", take_while1(|c: char| c.is_ascii_lowercase()));
+    let links = parsing::lines(input.trim(), separated_pair(name(), char('-'), name()))?;
+    let mut ids: HashMap<&str, usize> = HashMap::default();
+    let mut names = vec![];
+    let mut edges = vec![];
+    for (a, b) in links {
+        let [a, b] = [a, b].map(|name| {
+            *ids.entry(name).or_insert_with(|| {
+                names.push(name);
+                names.len() - 1
+            })
+        });
+        edges.push((a, b));
     }
+    Ok(Network {
+        graph: Graph::from_edges(names.len(), &edges),
+        names,
+    })
 }
 
-fn part1(g: &HashMap<u16, HashSet<u16>>) -> usize {
-    let mut sets = HashSet::default();
-    for (&a, neighbors) in g {
-        if (a / 256) != b't' as u16 {
-            continue;
-        }
-        for (j, &b) in neighbors.iter().enumerate() {
-            for &c in neighbors.iter().skip(j + 1) {
-                if g.get(&b).is_some_and(|s| s.contains(&c)) {
-                    let mut k = [a, b, c];
-                    k.sort_unstable();
-                    sets.insert(k);
-                }
-            }
-        }
-    }
-    sets.len()
+fn part1(network: &Network) -> usize {
+    network
+        .graph
+        .triangles()
+        .iter()
+        .filter(|t| t.iter().any(|&v| network.names[v].starts_with('t')))
+        .count()
 }
 
-fn part2(g: &HashMap<u16, HashSet<u16>>) -> String {
-    let mut max = HashSet::default();
-    bron_kerbosch(
-        g,
-        &mut HashSet::default(),
-        HashSet::default(),
-        g.keys().cloned().collect(),
-        &mut max,
-    );
-    let mut names: Vec<String> = max
+fn part2(network: &Network) -> String {
+    let mut names = network
+        .graph
+        .max_clique()
         .into_iter()
-        .map(|x| {
-            let b = vec![(x / 256) as u8, (x % 256) as u8];
-            String::from_utf8(b).unwrap()
-        })
-        .collect();
+        .map(|v| network.names[v])
+        .collect::<Vec<_>>();
     names.sort_unstable();
     names.join(",")
 }
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day23").unwrap();
-    let g = parse(&input);
+    let g = parse(&input).unwrap();
     println!("part1: {}", part1(&g));
     println!("part2: {}", part2(&g));
 }
@@ -93,6 +62,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -129,8 +99,14 @@ co-tc
 wh-qp
 tb-vc
 td-yn";
-        let g = parse(input);
+        let g = parse(input).unwrap();
         assert_eq!(7, part1(&g));
         assert_eq!("co,de,ka,ta", part2(&g));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "kh-tc\nqp-kh\nde-cg\nka-co\ntc-td\nwh-td";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,6 +1,16 @@
 use std::fmt::Display;
 
+use adventofcode::dot::{self, Dot};
+use adventofcode::parsing::{self, PResult, ParseError};
 use ahash::HashMap;
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, one_of},
+    error::context,
+    sequence::separated_pair,
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
 struct Wire(u16);
@@ -66,36 +76,98 @@ impl Gate {
     }
 }
 
-fn parse(input: &str) -> (HashMap<Wire, u8>, HashMap<Wire, Gate>) {
-    let (a, b) = input.trim().split_once("\n\n").unwrap();
-    let mut wires: HashMap<Wire, u8> = HashMap::default();
-    for line in a.lines() {
-        let (key, v) = line.split_once(": ").unwrap();
-        wires.insert(Wire::from_name(key.as_bytes()), v.parse().unwrap());
+type Wires = HashMap<Wire, u8>;
+type Gates = HashMap<Wire, Gate>;
+
+fn wire(input: &str) -> PResult<'_, &str> {
+    let name = take_while_m_n(3, 3, |c: char| c.is_ascii_digit() || c.is_ascii_lowercase());
+    context("a wire name", name).parse(input)
+}
+
+fn parse(input: &str) -> Result<(Wires, Gates), ParseError> {
+    let [a, b] = parsing::split_sections(input)?;
+    let value = one_of("01").map(|c| c as u8 - b'0');
+    let mut wires: Wires = HashMap::default();
+    for (name, v) in parsing::lines(a, separated_pair(wire, tag(": "), value))? {
+        if wires.insert(Wire::from_name(name.as_bytes()), v).is_some() {
+            return Err(ParseError::at(input, name, "a new wire"));
+        }
     }
-    let mut g = HashMap::default();
-    for line in b.lines() {
-        let (gate, wire) = line.split_once(" -> ").unwrap();
-        let gate = gate.split_ascii_whitespace().collect::<Vec<_>>();
-        let kind = match gate[1] {
-            "AND" => GateType::And,
-            "OR" => GateType::Or,
-            "XOR" => GateType::Xor,
-            _ => unreachable!(),
-        };
-        g.insert(
-            Wire::from_name(wire.as_bytes()),
-            Gate::new(
-                Wire::from_name(gate[0].as_bytes()),
-                Wire::from_name(gate[2].as_bytes()),
-                kind,
-            ),
-        );
+    let kind = alt((
+        tag("AND").map(|_| GateType::And),
+        tag("OR").map(|_| GateType::Or),
+        tag("XOR").map(|_| GateType::Xor),
+    ));
+    let gate = (wire, char(' '), kind, char(' '), wire, tag(" -> "), wire);
+    let gates = parsing::lines(b, gate)?;
+    let mut g: Gates = HashMap::default();
+    for &(in1, _, kind, _, in2, _, out) in &gates {
+        let [in1, in2, wire] = [in1, in2, out].map(|name| Wire::from_name(name.as_bytes()));
+        if wires.contains_key(&wire) || g.insert(wire, Gate::new(in1, in2, kind)).is_some() {
+            return Err(ParseError::at(input, out, "a new wire"));
+        }
+    }
+    for (in1, _, _, _, in2, _, _) in gates {
+        for name in [in1, in2] {
+            let wire = Wire::from_name(name.as_bytes());
+            if !wires.contains_key(&wire) && !g.contains_key(&wire) {
+                return Err(ParseError::at(input, name, "a known wire"));
+            }
+        }
+    }
+    // part 1 evaluates the gates recursively, and reads z00 onwards into a u64
+    if !acyclic(&g) {
+        return Err(ParseError::at(input, b, "gates without a loop"));
+    }
+    let z = (0..).take_while(|&i| g.contains_key(&Wire::from_xyz(b'z', i)));
+    if !(1..=64).contains(&z.count()) {
+        return Err(ParseError::at(input, b, "1 to 64 output bits"));
+    }
+    Ok((wires, g))
+}
+
+fn acyclic(g: &Gates) -> bool {
+    // false while a wire is on the path being explored, true once it's done
+    let mut done: HashMap<Wire, bool> = HashMap::default();
+    for &start in g.keys() {
+        let mut stack = vec![(start, false)];
+        while let Some((wire, explored)) = stack.pop() {
+            if explored {
+                done.insert(wire, true);
+                continue;
+            }
+            match done.get(&wire) {
+                Some(true) => continue,
+                Some(false) => return false,
+                None => {}
+            }
+            done.insert(wire, false);
+            stack.push((wire, true));
+            if let Some(gate) = g.get(&wire) {
+                stack.push((gate.in1, false));
+                stack.push((gate.in2, false));
+            }
+        }
     }
-    (wires, g)
+    true
 }
 
-fn dfs(wire: Wire, g: &HashMap<Wire, Gate>, wires: &mut HashMap<Wire, u8>) -> u8 {
+/// Wires as nodes, every gate drawn on the edges from its inputs to its output.
+fn to_dot(wires: &Wires, g: &Gates) -> Dot {
+    let mut dot = Dot::digraph();
+    for wire in wires.keys() {
+        dot.node(&wire.to_string(), &[("shape", "box")]);
+    }
+    for (out, gate) in g {
+        let label = format!("{:?}", gate.kind).to_uppercase();
+        for wire in [gate.in1, gate.in2] {
+            dot.edge(&wire.to_string(), &out.to_string(), Some(&label));
+        }
+    }
+    dot
+}
+
+fn dfs(wire: Wire, g: &Gates, wires: &mut Wires) -> u8 {
     if let Some(&r) = wires.get(&wire) {
         return r;
     }
@@ -111,7 +183,7 @@ fn dfs(wire: Wire, g: &HashMap<Wire, Gate>, wires: &mut HashMap<Wire, u8>) -> u8
     result
 }
 
-fn part1(mut wires: HashMap<Wire, u8>, g: &HashMap<Wire, Gate>) -> usize {
+fn part1(mut wires: HashMap<Wire, u8>, g: &Gates) -> usize {
     let (mut result, mut n) = (0, 0);
     loop {
         let wire = Wire::from_xyz(b'z', n);
@@ -126,7 +198,7 @@ fn part1(mut wires: HashMap<Wire, u8>, g: &HashMap<Wire, Gate>) -> usize {
     result.reverse_bits() >> (64 - n)
 }
 
-// fn get_num(wires: &HashMap<Wire, u8>, b: u8) -> usize {
+// fn get_num(wires: &Wires, b: u8) -> usize {
 //     let (mut result, mut n) = (0, 0);
 //     loop {
 //         let wire = Wire::from_xyz(b, n);
@@ -141,7 +213,7 @@ fn part1(mut wires: HashMap<Wire, u8>, g: &HashMap<Wire, Gate>) -> usize {
 //     result.reverse_bits() >> (64 - n)
 // }
 
-fn part2(_wires: HashMap<Wire, u8>, mut _g: HashMap<Wire, Gate>) -> String {
+fn part2(_wires: Wires, mut _g: Gates) -> String {
     // //swap
     // let mut swapped = Vec::with_capacity(8);
     // let mut x_p = Wire::from_xyz(b'x', 0);
@@ -186,7 +258,8 @@ fn part2(_wires: HashMap<Wire, u8>, mut _g: HashMap<Wire, Gate>) -> String {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day24").unwrap();
-    let (wires, g) = parse(&input);
+    let (wires, g) = parse(&input).unwrap();
+    dot::export(|| to_dot(&wires, &g));
     println!("part1: {}", part1(wires.clone(), &g));
     println!("part2: {}", part2(wires, g));
 }
@@ -194,6 +267,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -208,7 +282,7 @@ y02: 0
 x00 AND y00 -> z00
 x01 XOR y01 -> z01
 x02 OR y02 -> z02";
-        let (wires, g) = parse(input);
+        let (wires, g) = parse(input).unwrap();
         assert_eq!(4, part1(wires, &g));
     }
 
@@ -262,7 +336,13 @@ y03 OR x01 -> nrd
 hwm AND bqk -> z03
 tgd XOR rvg -> z12
 tnw OR pbm -> gnj";
-        let (wires, g) = parse(input);
+        let (wires, g) = parse(input).unwrap();
         assert_eq!(2024, part1(wires, &g));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "x00: 1\nx01: 1\nx02: 1\ny00: 0\ny01: 1\ny02: 0\n\nx00 AND y00 -> z00\nx01 XOR y01 -> z01\nx02 OR y02 -> z02";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,27 +1,38 @@
-fn parse(input: &str) -> (Vec<[u8; 5]>, Vec<[u8; 5]>) {
+use adventofcode::parsing::{self, ParseError};
+use nom::combinator::rest;
+
+type Heights = [u8; 5];
+
+fn parse(input: &str) -> Result<(Vec<Heights>, Vec<Heights>), ParseError> {
     let (mut locks, mut keys) = (vec![], vec![]);
-    for part in input.trim().split("\n\n") {
-        let rows = part.lines().collect::<Vec<_>>();
-        if rows[0] == "#####" {
+    for part in parsing::sections(input, rest)? {
+        let rows = parsing::grid_of(part, ".#")?;
+        let full = |i: usize| rows[i] == b"#####";
+        let empty = |i: usize| rows[i] == b".....";
+        let wide = rows.iter().all(|r| r.len() == 5);
+        if rows.len() != 7 || !wide || !(full(0) && empty(6) || empty(0) && full(6)) {
+            return Err(ParseError::at(input, part, "a 5 by 7 lock or key"));
+        }
+        if full(0) {
             let mut lock = [0; 5];
             for (j, e) in lock.iter_mut().enumerate() {
-                let h = (1..=6).find(|&h| rows[h].as_bytes()[j] == b'.').unwrap();
+                let h = (1..=6).find(|&h| rows[h][j] == b'.').unwrap();
                 *e = (h - 1) as u8;
             }
             locks.push(lock);
         } else {
             let mut key = [0; 5];
             for (j, e) in key.iter_mut().enumerate() {
-                let h = (1..=6).find(|&h| rows[h].as_bytes()[j] == b'#').unwrap();
+                let h = (1..=6).find(|&h| rows[h][j] == b'#').unwrap();
                 *e = (6 - h) as u8;
             }
             keys.push(key);
         }
     }
-    (locks, keys)
+    Ok((locks, keys))
 }
 
-fn part1(locks: Vec<[u8; 5]>, keys: Vec<[u8; 5]>) -> usize {
+fn part1(locks: Vec<Heights>, keys: Vec<Heights>) -> usize {
     locks
         .iter()
         .map(|l| {
@@ -34,13 +45,14 @@ fn part1(locks: Vec<[u8; 5]>, keys: Vec<[u8; 5]>) -> usize {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day25").unwrap();
-    let (locks, keys) = parse(&input);
+    let (locks, keys) = parse(&input).unwrap();
     println!("part1: {}", part1(locks, keys));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -84,7 +96,13 @@ mod tests {
 #.#..
 #.#.#
 #####";
-        let (locks, keys) = parse(input);
+        let (locks, keys) = parse(input).unwrap();
         assert_eq!(3, part1(locks, keys));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....\n\n.....\n#....\n#....\n#...#\n#.#.#\n#.###\n#####";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
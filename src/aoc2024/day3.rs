@@ -1,40 +1,77 @@
-use nom::character::complete::i32 as n_i32;
-use nom::{bytes::complete::tag, IResult, Parser};
+use adventofcode::parsing::{PResult, ParseError};
+use nom::{
+    Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::char,
+    combinator::map_res,
+};
 
-fn mul(input: &str) -> IResult<&str, i32> {
-    let (input, (_, a, _, b, _)) = (tag("mul("), n_i32, tag(","), n_i32, tag(")")).parse(input)?;
-    Ok((input, a * b))
+enum Instruction {
+    Mul(i64),
+    Do,
+    Dont,
 }
 
-fn part1(mut input: &str) -> i32 {
-    let mut result = 0;
-    while !input.is_empty() {
-        if let Ok((r, x)) = mul(input) {
-            input = r;
-            result += x;
-        } else {
-            input = &input[1..];
+fn factor(input: &str) -> PResult<'_, i64> {
+    map_res(
+        take_while_m_n(1, 3, |c: char| c.is_ascii_digit()),
+        str::parse,
+    )
+    .parse(input)
+}
+
+fn instruction(input: &str) -> PResult<'_, Instruction> {
+    alt((
+        tag("do()").map(|_| Instruction::Do),
+        tag("don't()").map(|_| Instruction::Dont),
+        (tag("mul("), factor, char(','), factor, char(')'))
+            .map(|(_, a, _, b, _)| Instruction::Mul(a * b)),
+    ))
+    .parse(input)
+}
+
+/// The instructions in the corrupted memory, skipping everything else.
+fn parse(data: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut instructions = vec![];
+    let mut rest = data;
+    while let Some(c) = rest.chars().next() {
+        match instruction(rest) {
+            Ok((r, i)) => {
+                instructions.push(i);
+                rest = r;
+            }
+            Err(_) => rest = &rest[c.len_utf8()..],
         }
     }
-    result
+    if !instructions
+        .iter()
+        .any(|i| matches!(i, Instruction::Mul(_)))
+    {
+        return Err(ParseError::at(data, data, "a mul instruction"));
+    }
+    Ok(instructions)
 }
 
-fn part2(mut input: &str) -> i32 {
+fn part1(instructions: &[Instruction]) -> i64 {
+    instructions
+        .iter()
+        .map(|i| match i {
+            Instruction::Mul(x) => *x,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn part2(instructions: &[Instruction]) -> i64 {
+    let mut enabled = true;
     let mut result = 0;
-    while !input.is_empty() {
-        if input.starts_with("don't()") {
-            if let Some(i) = input.find("do()") {
-                input = &input[i + "do()".len()..];
-                continue;
-            } else {
-                break;
-            }
-        }
-        if let Ok((r, x)) = mul(input) {
-            input = r;
-            result += x;
-        } else {
-            input = &input[1..];
+    for i in instructions {
+        match i {
+            Instruction::Mul(x) if enabled => result += x,
+            Instruction::Mul(_) => {}
+            Instruction::Do => enabled = true,
+            Instruction::Dont => enabled = false,
         }
     }
     result
@@ -42,23 +79,31 @@ fn part2(mut input: &str) -> i32 {
 
 pub fn main() {
     let data: String = std::fs::read_to_string("data/2024/day3").unwrap();
-    println!("part1: {}", part1(&data));
-    println!("part2: {}", part2(&data));
+    let instructions = parse(&data).unwrap();
+    println!("part1: {}", part1(&instructions));
+    println!("part2: {}", part2(&instructions));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
-        assert_eq!(161, part1(data));
+        assert_eq!(161, part1(&parse(data).unwrap()));
     }
 
     #[test]
     fn case2() {
         let data = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-        assert_eq!(48, part2(data));
+        assert_eq!(48, part2(&parse(data).unwrap()));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,8 +1,11 @@
-fn parse(input: &str) -> Vec<&[u8]> {
-    input.trim().lines().map(|line| line.as_bytes()).collect()
+use adventofcode::parsing::{self, ParseError};
+use aoc_proc::aoc_examples;
+
+fn parse(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    parsing::grid_of(input.trim(), "XMAS")
 }
 
-fn part1(words: &[&[u8]]) -> i32 {
+fn part1(words: &[Vec<u8>]) -> i32 {
     let (m, n) = (words.len(), words[0].len());
     let mut result = 0;
 
@@ -38,14 +41,14 @@ fn part1(words: &[&[u8]]) -> i32 {
     result
 }
 
-fn is_x_mas(i: usize, j: usize, words: &[&[u8]]) -> bool {
+fn is_x_mas(i: usize, j: usize, words: &[Vec<u8>]) -> bool {
     ((words[i - 1][j - 1] == b'S' && words[i + 1][j + 1] == b'M')
         || (words[i - 1][j - 1] == b'M' && words[i + 1][j + 1] == b'S'))
         && ((words[i + 1][j - 1] == b'S' && words[i - 1][j + 1] == b'M')
             || (words[i + 1][j - 1] == b'M' && words[i - 1][j + 1] == b'S'))
 }
 
-fn part2(words: &[&[u8]]) -> i32 {
+fn part2(words: &[Vec<u8>]) -> i32 {
     let (m, n) = (words.len(), words[0].len());
     let mut result = 0;
     for (i, r) in words.iter().enumerate().skip(1).take(m - 2) {
@@ -63,18 +66,14 @@ fn part2(words: &[&[u8]]) -> i32 {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day4").unwrap();
-    let words = parse(&input);
+    let words = parse(&input).unwrap();
     println!("part1: {}", part1(&words));
     println!("part2: {}", part2(&words));
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn case1() {
-        let input = "
+aoc_examples! {
+    parse = |s| parse(s).unwrap();
+    "
 MMMSXXMASM
 MSAMXMSMSA
 AMXSXMAAMM
@@ -84,9 +83,18 @@ XXAMMXXAMA
 SMSMSASXSS
 SAXAMASAAA
 MAMMMXMMMM
-MXMXAXMASX";
-        let words = parse(input);
-        assert_eq!(18, part1(&words));
-        assert_eq!(9, part2(&words));
+MXMXAXMASX" => (18, 9),
+    file "data/2024/day4" => (_, _),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
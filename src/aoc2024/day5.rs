@@ -1,22 +1,21 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
 use ahash::{HashMap, HashMapExt, HashSet};
+use nom::{character::complete::char, multi::separated_list1, sequence::separated_pair};
 
-fn parse(input: &str) -> (HashMap<u8, HashSet<u8>>, Vec<Vec<u8>>) {
-    let (rules, update) = input.trim().split_once("\n\n").unwrap();
-    let mut g: HashMap<u8, HashSet<u8>> = HashMap::with_capacity(100);
-    for rule in rules.lines() {
-        let (a, b) = rule.split_once('|').unwrap();
-        let a: u8 = a.parse().unwrap();
-        let b: u8 = b.parse().unwrap();
+type Rules = HashMap<u8, HashSet<u8>>;
+
+fn parse(input: &str) -> Result<(Rules, Vec<Vec<u8>>), ParseError> {
+    let [rules, updates] = parsing::split_sections(input)?;
+    let rules = parsing::lines(rules, separated_pair(unsigned::<u8>, char('|'), unsigned))?;
+    let mut g: Rules = HashMap::with_capacity(100);
+    for (a, b) in rules {
         g.entry(b).or_default().insert(a);
     }
-    let updates = update
-        .lines()
-        .map(|line| line.split(',').map(|e| e.parse().unwrap()).collect())
-        .collect();
-    (g, updates)
+    let updates = parsing::lines(updates, separated_list1(char(','), unsigned))?;
+    Ok((g, updates))
 }
 
-fn part1(g: &HashMap<u8, HashSet<u8>>, updates: &[Vec<u8>]) -> i32 {
+fn part1(g: &Rules, updates: &[Vec<u8>]) -> i32 {
     updates
         .iter()
         .filter(|u| {
@@ -34,7 +33,7 @@ fn part1(g: &HashMap<u8, HashSet<u8>>, updates: &[Vec<u8>]) -> i32 {
         .sum()
 }
 
-fn part2(g: &HashMap<u8, HashSet<u8>>, updates: &mut [Vec<u8>]) -> i32 {
+fn part2(g: &Rules, updates: &mut [Vec<u8>]) -> i32 {
     updates
         .iter_mut()
         .map(|u| {
@@ -59,7 +58,7 @@ fn part2(g: &HashMap<u8, HashSet<u8>>, updates: &mut [Vec<u8>]) -> i32 {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day5").unwrap();
-    let (g, mut updates) = parse(&input);
+    let (g, mut updates) = parse(&input).unwrap();
     println!("part1: {}", part1(&g, &updates));
     println!("part2: {}", part2(&g, &mut updates));
 }
@@ -67,6 +66,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -99,16 +99,14 @@ mod tests {
 75,97,47,61,53
 61,13,29
 97,13,75,29,47";
-        let (g, mut updates) = parse(input);
+        let (g, mut updates) = parse(input).unwrap();
         assert_eq!(143, part1(&g, &updates));
         assert_eq!(123, part2(&g, &mut updates));
     }
 
     #[test]
-    fn case2() {
-        let input: String = std::fs::read_to_string("data/2024/day5").unwrap();
-        let (g, mut updates) = parse(&input);
-        assert_eq!(5991, part1(&g, &updates));
-        assert_eq!(5479, part2(&g, &mut updates));
+    fn fuzz() {
+        let seed = "47|53\n97|13\n75|29\n\n75,47,61,53,29\n61,13,29";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,13 +1,20 @@
+use adventofcode::cycle::cycle_length;
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashSet, HashSetExt};
 use rayon::prelude::*;
 
 const DIRS: [(i32, i32); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
 
-fn parse(input: &str) -> Vec<&[u8]> {
-    input.trim().lines().map(|line| line.as_bytes()).collect()
+fn parse(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let input = input.trim();
+    let map = parsing::grid_of(input, ".#^")?;
+    if input.matches('^').count() != 1 {
+        return Err(ParseError::at(input, input, "exactly one guard"));
+    }
+    Ok(map)
 }
 
-fn find_guard(map: &[&[u8]]) -> (i32, i32) {
+fn find_guard(map: &[Vec<u8>]) -> (i32, i32) {
     for (i, r) in map.iter().enumerate() {
         for (j, &c) in r.iter().enumerate() {
             if c == b'^' {
@@ -18,7 +25,7 @@ fn find_guard(map: &[&[u8]]) -> (i32, i32) {
     unreachable!()
 }
 
-fn part1(map: &[&[u8]], (mut i, mut j): (i32, i32)) -> HashSet<(i32, i32)> {
+fn part1(map: &[Vec<u8>], (mut i, mut j): (i32, i32)) -> HashSet<(i32, i32)> {
     let mut d = 0;
     let (m, n) = (map.len() as i32, map[0].len() as i32);
     let mut visited = HashSet::with_capacity((m * n) as usize);
@@ -40,33 +47,25 @@ fn part1(map: &[&[u8]], (mut i, mut j): (i32, i32)) -> HashSet<(i32, i32)> {
     }
 }
 
-fn is_loop(map: &[&[u8]], (mut i, mut j): (i32, i32), obstacle: (i32, i32)) -> bool {
-    let mut d = 0;
+fn is_loop(map: &[Vec<u8>], start: (i32, i32), obstacle: (i32, i32)) -> bool {
     let (m, n) = (map.len() as i32, map[0].len() as i32);
-    let mut visited = vec![false; (m * n * 4) as usize];
-    loop {
-        let idx = ((i * n + j) * 4 + d) as usize;
-        if visited[idx] {
-            return true;
-        }
-        visited[idx] = true;
-        loop {
-            let (di, dj) = DIRS[d as usize];
-            let (i1, j1) = (i + di, j + dj);
-            if i1 < 0 || j1 < 0 || i1 == m || j1 == n {
-                return false;
-            }
-            if map[i1 as usize][j1 as usize] == b'#' || (i1, j1) == obstacle {
-                d = (d + 1) % 4;
-            } else {
-                (i, j) = (i1, j1);
-                break;
-            }
+    // leaving the map is a fixed point, a loop needs at least 4 turns
+    let step = |&guard: &Option<(i32, i32, usize)>| {
+        let (i, j, d) = guard?;
+        let (di, dj) = DIRS[d];
+        let (i1, j1) = (i + di, j + dj);
+        if i1 < 0 || j1 < 0 || i1 == m || j1 == n {
+            None
+        } else if map[i1 as usize][j1 as usize] == b'#' || (i1, j1) == obstacle {
+            Some((i, j, (d + 1) % 4))
+        } else {
+            Some((i1, j1, d))
         }
-    }
+    };
+    cycle_length(Some((start.0, start.1, 0)), step) > 1
 }
 
-fn part2(map: &[&[u8]], start: (i32, i32), mut path: HashSet<(i32, i32)>) -> usize {
+fn part2(map: &[Vec<u8>], start: (i32, i32), mut path: HashSet<(i32, i32)>) -> usize {
     path.remove(&start);
     path.into_par_iter()
         .filter(|&p| is_loop(map, start, p))
@@ -75,7 +74,7 @@ fn part2(map: &[&[u8]], start: (i32, i32), mut path: HashSet<(i32, i32)>) -> usi
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day6").unwrap();
-    let map = parse(&input);
+    let map = parse(&input).unwrap();
     let start = find_guard(&map);
     let path = part1(&map, start);
     println!("part1: {}", path.len());
@@ -85,6 +84,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -99,10 +99,17 @@ mod tests {
 ........#.
 #.........
 ......#...";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let start = find_guard(&map);
         let path = part1(&map, start);
         assert_eq!(41, path.len());
         assert_eq!(6, part2(&map, start, path));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed =
+            "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
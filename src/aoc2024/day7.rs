@@ -1,18 +1,20 @@
+use adventofcode::parsing::{self, ParseError, unsigned};
+use aoc_proc::aoc_examples;
+use nom::Parser;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
 use rayon::prelude::*;
 
-fn parse(input: &str) -> Vec<Vec<i64>> {
-    input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once(": ").unwrap();
-            let mut result = vec![a.parse().unwrap()];
-            for x in b.split(' ') {
-                result.push(x.parse().unwrap());
-            }
-            result
-        })
-        .collect()
+/// Each equation as its test value followed by the numbers.
+fn parse(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    let numbers = separated_list1(char(' '), unsigned);
+    let equation = separated_pair(unsigned, tag(": "), numbers).map(|(value, mut numbers)| {
+        numbers.insert(0, value);
+        numbers
+    });
+    parsing::lines(input.trim(), equation)
 }
 
 fn evaluate(test: &[i64], i: usize, curr: i64) -> bool {
@@ -61,18 +63,14 @@ fn part2(tests: &[Vec<i64>]) -> i64 {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day7").unwrap();
-    let tests = parse(&input);
+    let tests = parse(&input).unwrap();
     println!("part1: {}", part1(&tests));
     println!("part2: {}", part2(&tests));
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn case1() {
-        let input = "
+aoc_examples! {
+    parse = |s| parse(s).unwrap();
+    "
 190: 10 19
 3267: 81 40 27
 83: 17 5
@@ -81,9 +79,18 @@ mod tests {
 161011: 16 10 13
 192: 17 8 14
 21037: 9 7 18 13
-292: 11 6 16 20";
-        let tests = parse(input);
-        assert_eq!(3749, part1(&tests));
-        assert_eq!(11387, part2(&tests));
+292: 11 6 16 20" => (3749, 11387),
+    file "data/2024/day7" => (_, _),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "190: 10 19\n3267: 81 40 27";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,19 +1,29 @@
+use adventofcode::math::gcd;
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashMap, HashSet, HashSetExt};
 
 type Freq = HashMap<u8, Vec<(i8, i8)>>;
 
-fn parse(input: &str) -> (usize, usize, Freq) {
-    let map: Vec<&[u8]> = input.trim().lines().map(|line| line.as_bytes()).collect();
+fn parse(input: &str) -> Result<(usize, usize, Freq), ParseError> {
+    let input = input.trim();
+    let map = parsing::grid(input)?;
+    // antinodes are at most twice as far as an antenna and must fit in an i8
+    if map.len() > 64 || map[0].len() > 64 {
+        return Err(ParseError::at(input, input, "at most 64 rows and columns"));
+    }
     let mut freq: HashMap<u8, Vec<_>> = HashMap::default();
-    for (i, r) in map.iter().enumerate() {
+    for (i, (r, line)) in map.iter().zip(input.lines()).enumerate() {
         for (j, &c) in r.iter().enumerate() {
             if c == b'.' {
                 continue;
             }
+            if !c.is_ascii_alphanumeric() {
+                return Err(ParseError::at(input, &line[j..], "'.' or an antenna"));
+            }
             freq.entry(c).or_default().push((i as i8, j as i8));
         }
     }
-    (map.len(), map[0].len(), freq)
+    Ok((map.len(), map[0].len(), freq))
 }
 
 fn part1(m: usize, n: usize, freq: &Freq) -> usize {
@@ -33,14 +43,6 @@ fn part1(m: usize, n: usize, freq: &Freq) -> usize {
     antinodes.len()
 }
 
-fn gcd(a: i8, b: i8) -> i8 {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
 fn part2(m: usize, n: usize, freq: &Freq) -> usize {
     let mut antinodes = HashSet::with_capacity(m * n);
     for positions in freq.values() {
@@ -68,7 +70,7 @@ fn part2(m: usize, n: usize, freq: &Freq) -> usize {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day8").unwrap();
-    let (m, n, freq) = parse(&input);
+    let (m, n, freq) = parse(&input).unwrap();
     println!("part1: {}", part1(m, n, &freq));
     println!("part2: {}", part2(m, n, &freq));
 }
@@ -76,6 +78,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -92,7 +95,7 @@ mod tests {
 .........A..
 ............
 ............";
-        let (m, n, freq) = parse(input);
+        let (m, n, freq) = parse(input).unwrap();
         assert_eq!(14, part1(m, n, &freq));
         assert_eq!(34, part2(m, n, &freq));
     }
@@ -110,7 +113,13 @@ T.........
 ..........
 ..........
 ..........";
-        let (m, n, freq) = parse(input);
+        let (m, n, freq) = parse(input).unwrap();
         assert_eq!(9, part2(m, n, &freq));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "............\n........0...\n.....0......\n.......0....\n....0.......\n......A.....\n............\n........A...\n.........A..";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -3,8 +3,30 @@ use std::{
     collections::{BinaryHeap, VecDeque},
 };
 
-fn parse(input: &str) -> &[u8] {
-    input.trim().as_bytes()
+use adventofcode::parsing::ParseError;
+
+fn parse(input: &str) -> Result<&[u8], ParseError> {
+    let input = input.trim();
+    let map = input.as_bytes();
+    if map.is_empty() {
+        return Err(ParseError::at(input, input, "a disk map"));
+    }
+    for (i, c) in map.iter().enumerate() {
+        // files take up at least one block
+        let digits = if i.is_multiple_of(2) {
+            b'1'..=b'9'
+        } else {
+            b'0'..=b'9'
+        };
+        if !digits.contains(c) {
+            return Err(ParseError::at(
+                input,
+                &input[i..],
+                "a file or free space length",
+            ));
+        }
+    }
+    Ok(map)
 }
 
 fn part1(mut map: &[u8]) -> usize {
@@ -101,7 +123,7 @@ fn part2(mut map: &[u8]) -> usize {
 
 pub fn main() {
     let input: String = std::fs::read_to_string("data/2024/day9").unwrap();
-    let map = parse(&input);
+    let map = parse(&input).unwrap();
     println!("part1: {}", part1(map));
     println!("part2: {}", part2(map));
 }
@@ -109,12 +131,19 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let input = "2333133121414131402";
-        let map = parse(input);
+        let map = parse(input).unwrap();
         assert_eq!(1928, part1(map));
         assert_eq!(2858, part2(map));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "2333133121414131402";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
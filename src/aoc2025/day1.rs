@@ -1,15 +1,11 @@
-fn parse(data: &str) -> Vec<i32> {
-    data.lines()
-        .map(|line| {
-            let dir = match line.as_bytes()[0] {
-                b'L' => -1,
-                b'R' => 1,
-                _ => unreachable!(),
-            };
-            let dist = line[1..].parse::<i32>().unwrap();
-            dir * dist
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::Parser;
+use nom::character::complete::one_of;
+
+fn parse(data: &str) -> Result<Vec<i32>, ParseError> {
+    let rotation = (one_of("LR"), unsigned::<i32>);
+    let rotation = rotation.map(|(dir, dist)| if dir == 'L' { -dist } else { dist });
+    parsing::lines(data, rotation)
 }
 
 fn part1(rotations: &[i32]) -> i32 {
@@ -38,7 +34,7 @@ fn part2(rotations: &[i32]) -> i32 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day1").unwrap();
-    let rotations = parse(&data);
+    let rotations = parse(&data).unwrap();
     println!("part1: {}", part1(&rotations));
     println!("part2: {}", part2(&rotations));
 }
@@ -46,6 +42,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -60,8 +57,14 @@ L1
 L99
 R14
 L82";
-        let rotations = parse(data.trim());
+        let rotations = parse(data.trim()).unwrap();
         assert_eq!(3, part1(&rotations));
         assert_eq!(6, part2(&rotations));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "L68\nR30\nL5";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
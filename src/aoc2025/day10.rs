@@ -1,51 +1,49 @@
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use adventofcode::linalg;
+use adventofcode::parsing::{self, PResult, ParseError, int_list};
+use nom::Parser;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, one_of};
+use nom::combinator::verify;
+use nom::error::context;
+use nom::multi::many_m_n;
+use nom::sequence::{delimited, preceded};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 type Lights = u16;
 type Buttons = Vec<u16>;
 type Joltages = Vec<u16>;
 
-fn parse(data: &str) -> Vec<(Lights, Buttons, Joltages)> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (lights, rem) = line.split_once(']').unwrap();
-
-            let lights = lights.strip_prefix("[").unwrap();
-            let lights = lights
-                .as_bytes()
-                .iter()
-                .enumerate()
-                .fold(
-                    0,
-                    |acc, (i, &x)| {
-                        if x == b'#' { acc | (1 << i) } else { acc }
-                    },
-                );
-
-            let (buttons, joltages) = rem.split_once('{').unwrap();
-
-            let buttons = buttons
-                .split(' ')
-                .filter(|b| !b.is_empty())
-                .map(|b| {
-                    let toggles = b.strip_prefix('(').unwrap().strip_suffix(')').unwrap();
-                    toggles
-                        .split(',')
-                        .map(|t| t.parse::<u8>().unwrap())
-                        .fold(0, |acc, t| acc | (1 << t))
-                })
-                .collect();
-
-            let joltages = joltages
-                .strip_suffix('}')
-                .unwrap()
-                .split(',')
-                .map(|j| j.parse().unwrap())
-                .collect();
+fn machine(line: &str) -> PResult<'_, (Lights, Buttons, Joltages)> {
+    let lights = delimited(char('['), many_m_n(1, 16, one_of(".#")), char(']'));
+    let button = delimited(char('('), int_list::<u8>(","), char(')'));
+    let buttons = many_m_n(1, 15, preceded(char(' '), button));
+    let joltages = delimited(tag(" {"), int_list::<u16>(","), char('}'));
+    let machine = verify(
+        (lights, buttons, joltages),
+        |(lights, buttons, joltages): &(Vec<char>, Vec<Vec<u8>>, Vec<u16>)| {
+            joltages.len() == lights.len()
+                && buttons
+                    .iter()
+                    .flatten()
+                    .all(|&t| (t as usize) < lights.len())
+        },
+    );
+    let machine = machine.map(|(lights, buttons, joltages)| {
+        let lights = lights.iter().enumerate().fold(
+            0,
+            |acc, (i, &x)| if x == '#' { acc | (1 << i) } else { acc },
+        );
+        let buttons = buttons
+            .iter()
+            .map(|toggles| toggles.iter().fold(0, |acc, t| acc | (1 << t)))
+            .collect();
+        (lights, buttons, joltages)
+    });
+    context("buttons and joltages that match the lights", machine).parse(line)
+}
 
-            (lights, buttons, joltages)
-        })
-        .collect()
+fn parse(data: &str) -> Result<Vec<(Lights, Buttons, Joltages)>, ParseError> {
+    parsing::lines(data.trim(), machine)
 }
 
 fn part1(manuals: &[(Lights, Buttons, Joltages)]) -> u32 {
@@ -78,208 +76,50 @@ fn part1(manuals: &[(Lights, Buttons, Joltages)]) -> u32 {
     ans
 }
 
-fn gaussian_elimilation(a: &mut [Vec<f64>]) -> Vec<usize> {
-    const PIVOT_EPS: f64 = 1e-12;
-
-    let (m, n) = (a.len(), a[0].len() - 1);
-
-    let mut pivots: Vec<usize> = Vec::new();
-    let mut row = 0;
-    for col in 0..n {
-        if row >= m {
-            break;
-        }
-        let piv = (row..m)
-            .max_by(|&r, &s| a[r][col].abs().total_cmp(&a[s][col].abs()))
-            .unwrap();
-        if a[piv][col].abs() < PIVOT_EPS {
-            continue;
-        }
-        a.swap(row, piv);
-        let d0 = a[row][col];
-        a[row].iter_mut().for_each(|e| *e /= d0);
-
-        let prow = a[row].clone();
-        for (i, r) in a.iter_mut().enumerate() {
-            if i != row && r[col].abs() > PIVOT_EPS {
-                let f = r[col];
-                r.iter_mut()
-                    .enumerate()
-                    .for_each(|(j, e)| *e -= f * prow[j]);
-            }
-        }
-        pivots.push(col);
-        row += 1;
-    }
-
-    pivots
-}
-
-struct State {
-    u: Vec<i64>,
-    w: Vec<f64>,
-    expr: Vec<(f64, Vec<f64>)>,
-    suffix_min: Vec<f64>,
-    x: Vec<i64>,
-    c0: f64,
-    best: f64,
-}
-
-impl State {
-    const EPS: f64 = 1e-9;
-
-    fn new(buttons: &[u16], targets: &[u16]) -> Self {
-        let m = targets.len();
-        let n = buttons.len();
-
-        // augmented matrix [A | b]
-        let mut a = vec![vec![0.0f64; n + 1]; m];
-        for (j, b) in buttons.iter().enumerate() {
-            for (i, row) in a.iter_mut().enumerate() {
-                if b & (1 << i) > 0 {
-                    row[j] = 1.0;
-                }
-            }
-        }
-        for i in 0..m {
-            a[i][n] = f64::from(targets[i]);
-        }
-
-        let pivots = gaussian_elimilation(&mut a);
-
-        for r in &a[pivots.len()..m] {
-            assert!(r[n].abs() <= Self::EPS, "no feasible solution");
-        }
-
-        // free variables in ascending upper bound order
-        let mut free: Vec<(usize, i64)> = (0..n)
-            .filter(|c| !pivots.contains(c))
-            .map(|c| {
-                let mut ub = u16::MAX;
-                for (i, &t) in targets.iter().enumerate() {
-                    if buttons[c] & (1 << i) > 0 {
-                        ub = ub.min(t);
-                    }
-                }
-                (c, ub as i64)
-            })
-            .collect();
-        let d = free.len();
-        free.sort_unstable_by_key(|e| e.1);
-        let u: Vec<i64> = free.iter().map(|e| e.1).collect();
-
-        // x_pivot = c - Σ coefₖ·xₖ, cost = C0 + Σ wₖ·xₖ
-        let expr: Vec<(f64, Vec<f64>)> = (0..pivots.len())
-            .map(|i| (a[i][n], free.iter().map(|&(f, _)| a[i][f]).collect()))
-            .collect();
-        let c0: f64 = expr.iter().map(|e| e.0).sum();
-        let w: Vec<f64> = (0..d)
-            .map(|k| 1.0 - expr.iter().map(|e| e.1[k]).sum::<f64>())
-            .collect();
-        let mut suffix_min = vec![0.0; d + 1];
-        for i in (0..d).rev() {
-            suffix_min[i] = suffix_min[i + 1] + (w[i] * u[i] as f64).min(0.0);
-        }
-
-        Self {
-            u,
-            w,
-            expr,
-            suffix_min,
-            x: vec![0; d],
-            c0,
-            best: f64::INFINITY,
-        }
-    }
-
-    fn dfs(&mut self, idx: usize, cost: f64) {
-        // pruning by cost lower bound
-        if self.c0 + cost + self.suffix_min[idx] >= self.best - Self::EPS {
-            return;
-        }
-
-        if idx == self.x.len() {
-            for (c, coefs) in &self.expr {
-                let mut v = *c;
-                for (k, &cf) in coefs.iter().enumerate() {
-                    v -= cf * self.x[k] as f64;
-                }
-                if v < -Self::EPS || (v - v.round()).abs() > Self::EPS {
-                    return;
-                }
-            }
-            self.best = self.best.min(self.c0 + cost);
-            return;
-        }
-
-        // tighten x[idx] upper bound
-        let mut hi = self.u[idx];
-        for (c, coefs) in &self.expr {
-            let mut resid = *c;
-            for (&c, &x) in coefs.iter().zip(&self.x).take(idx) {
-                resid -= c * x as f64;
-            }
-            let mut others_min = 0.0;
-            for (&c, &u) in coefs.iter().zip(&self.u).skip(idx + 1) {
-                if c < 0.0 {
-                    others_min += c * u as f64;
-                }
-            }
-            let cf = coefs[idx];
-            let self_min = if cf < 0.0 {
-                cf * self.u[idx] as f64
-            } else {
-                0.0
-            };
-            if resid < others_min + self_min - Self::EPS {
-                return;
-            }
-            if cf > Self::EPS {
-                let bound = ((resid - others_min) / cf + Self::EPS).floor() as i64;
-                if bound < hi {
-                    hi = bound;
-                }
-            }
-        }
-        if hi < 0 {
-            return;
-        }
-        for v in 0..=hi {
-            self.x[idx] = v;
-            self.dfs(idx + 1, cost + self.w[idx] * v as f64);
-        }
-        self.x[idx] = 0;
-    }
-}
-
-fn solve_machine(buttons: &[u16], targets: &[u16]) -> i64 {
-    let mut state = State::new(buttons, targets);
-    state.dfs(0, 0.0);
-    if state.best.is_finite() {
-        state.best.round() as i64
-    } else {
-        eprintln!("no feasible solution for {buttons:?} {targets:?}");
-        0
-    }
+fn solve_machine(buttons: &[u16], targets: &[u16]) -> Option<i64> {
+    let a = (0..targets.len())
+        .map(|i| buttons.iter().map(|b| i64::from(b >> i & 1)).collect())
+        .collect::<Vec<_>>();
+    let b = targets.iter().map(|&t| i64::from(t)).collect::<Vec<_>>();
+    // a button can't be pressed more often than any counter it increases allows
+    let upper = buttons
+        .iter()
+        .map(|&button| {
+            (0..targets.len())
+                .filter(|i| button & (1 << i) > 0)
+                .map(|i| b[i])
+                .min()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    linalg::ilp_min(&a, &b, &vec![1; buttons.len()], &upper).map(|(presses, _)| presses)
 }
 
-fn part2(manuals: &[(Lights, Buttons, Joltages)]) -> i64 {
+fn part2(manuals: &[(Lights, Buttons, Joltages)]) -> Result<i64, String> {
     manuals
         .par_iter()
-        .map(|(_, buttons, joltages)| solve_machine(buttons, joltages))
+        .enumerate()
+        .map(|(i, (_, buttons, joltages))| {
+            solve_machine(buttons, joltages)
+                .ok_or_else(|| format!("machine {} can't reach {joltages:?}", i + 1))
+        })
         .sum()
 }
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day10").unwrap();
-    let manuals = parse(&data);
+    let manuals = parse(&data).unwrap();
     println!("part1: {}", part1(&manuals));
-    println!("part2: {}", part2(&manuals));
+    match part2(&manuals) {
+        Ok(presses) => println!("part2: {presses}"),
+        Err(e) => println!("part2: {e}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -287,8 +127,20 @@ mod tests {
 [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        let manuals = parse(data);
+        let manuals = parse(data).unwrap();
         assert_eq!(7, part1(&manuals));
-        assert_eq!(33, part2(&manuals));
+        assert_eq!(Ok(33), part2(&manuals));
+    }
+
+    #[test]
+    fn infeasible() {
+        let manuals = parse("[.#] (0) (0,1) {1,2}").unwrap();
+        assert_eq!(Err("machine 1 can't reach [1, 2]".into()), part2(&manuals));
+    }
+
+    #[test]
+    fn fuzz() {
+        let seed = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
     }
 }
@@ -1,17 +1,15 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashMap, HashMapExt};
+use nom::bytes::complete::tag;
+use nom::character::complete::{alphanumeric1, char};
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
 
-fn parse(data: &str) -> HashMap<&str, Vec<&str>> {
-    data.trim()
-        .lines()
-        .map(|line| {
-            let (dev, outputs) = line.split_once(": ").unwrap();
-
-            let dev = dev.trim();
-            let outputs = outputs.trim().split(' ').map(|o| o.trim()).collect();
-
-            (dev, outputs)
-        })
-        .collect()
+fn parse(data: &str) -> Result<HashMap<&str, Vec<&str>>, ParseError> {
+    let outputs = separated_list1(char(' '), alphanumeric1);
+    let device = separated_pair(alphanumeric1, tag(": "), outputs);
+    let devices = parsing::lines(data.trim(), device)?;
+    Ok(devices.into_iter().collect())
 }
 
 fn dfs<'a>(
@@ -53,7 +51,7 @@ fn part2(server: &HashMap<&str, Vec<&str>>) -> u64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day11").unwrap();
-    let server = parse(&data);
+    let server = parse(&data).unwrap();
     println!("part1: {}", part1(&server, "you", "out"));
     println!("part2: {}", part2(&server));
 }
@@ -61,6 +59,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -75,7 +74,7 @@ fff: out
 ggg: out
 hhh: ccc fff iii
 iii: out";
-        let server = parse(data);
+        let server = parse(data).unwrap();
         assert_eq!(5, part1(&server, "you", "out"));
     }
 
@@ -95,7 +94,13 @@ dac: fff
 fff: ggg hhh
 ggg: out
 hhh: out";
-        let server = parse(data);
+        let server = parse(data).unwrap();
         assert_eq!(2, part2(&server));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "you: bbb ccc\nbbb: out\nccc: out";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,12 @@
+use adventofcode::parsing::{self, PResult, ParseError, unsigned};
+use nom::Parser;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{char, newline};
+use nom::combinator::cut;
+use nom::error::context;
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{preceded, separated_pair, terminated};
+
 type Shape = Vec<Vec<u8>>;
 
 struct Region {
@@ -6,42 +15,27 @@ struct Region {
     quantities: Vec<u32>,
 }
 
-fn parse(data: &str) -> (Vec<Shape>, Vec<Region>) {
-    let parts = data.trim().split("\n\n").collect::<Vec<_>>();
+fn shape(input: &str) -> PResult<'_, Shape> {
+    let row = take_while1(|c| c == '#' || c == '.').map(|row: &str| row.as_bytes().to_vec());
+    let rows = context("rows of # and .", separated_list1(newline, row));
+    preceded((unsigned::<usize>, tag(":\n")), cut(rows)).parse(input)
+}
 
-    let shapes = parts
-        .iter()
-        .take(parts.len() - 1)
-        .map(|p| {
-            p.trim()
-                .lines()
-                .skip(1)
-                .map(|row| row.as_bytes().to_vec())
-                .collect()
+fn region(input: &str) -> PResult<'_, Region> {
+    let size = separated_pair(unsigned, char('x'), unsigned);
+    let quantities = separated_list1(char(' '), unsigned);
+    separated_pair(size, tag(": "), quantities)
+        .map(|((width, height), quantities)| Region {
+            width,
+            height,
+            quantities,
         })
-        .collect();
-
-    let regions = parts
-        .last()
-        .unwrap()
-        .lines()
-        .map(|line| {
-            let (size, quantities) = line.split_once(": ").unwrap();
-
-            let (w, h) = size.split_once('x').unwrap();
-            let (width, height) = (w.parse().unwrap(), h.parse().unwrap());
-
-            let quantities = quantities.split(' ').map(|q| q.parse().unwrap()).collect();
-
-            Region {
-                width,
-                height,
-                quantities,
-            }
-        })
-        .collect();
+        .parse(input)
+}
 
-    (shapes, regions)
+fn parse(data: &str) -> Result<(Vec<Shape>, Vec<Region>), ParseError> {
+    let shapes = many1(terminated(shape, cut(tag("\n\n"))));
+    parsing::parse_all(data.trim(), (shapes, separated_list1(newline, region)))
 }
 
 fn part1(shapes: &[Shape], regions: &[Region]) -> usize {
@@ -72,6 +66,30 @@ fn part1(shapes: &[Shape], regions: &[Region]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day12").unwrap();
-    let (shapes, regions) = parse(&data);
+    let (shapes, regions) = parse(&data).unwrap();
     println!("part1: {}", part1(&shapes, &regions));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adventofcode::fuzz;
+
+    #[test]
+    fn fuzz() {
+        let seed = "
+0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
+}
@@ -1,10 +1,21 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashSet, HashSetExt};
+use nom::character::complete::{char, digit1};
+use nom::combinator::verify;
+use nom::error::context;
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
 
-fn parse(data: &str) -> Vec<(&str, &str)> {
-    data.trim()
-        .split(',')
-        .map(|range| range.split_once('-').unwrap())
-        .collect()
+/// Ranges of ids without leading zeros, kept as digits since the invalid ids
+/// are found by repeating prefixes of the bounds.
+fn parse(data: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    let id = || verify(digit1, |s: &str| s.len() <= 18 && !s.starts_with('0'));
+    let range = verify(
+        separated_pair(id(), char('-'), id()),
+        |(a, b): &(&str, &str)| (a.len(), *a) <= (b.len(), *b),
+    );
+    let range = context("an ascending range of ids", range);
+    parsing::parse_all(data.trim(), separated_list1(char(','), range))
 }
 
 fn check_invalid(a: &str, b: &str, k: usize, invalid: &mut HashSet<u64>) {
@@ -53,7 +64,7 @@ fn part2(ranges: &[(&str, &str)]) -> u64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day2").unwrap();
-    let ranges = parse(&data);
+    let ranges = parse(&data).unwrap();
     println!("part1: {}", part1(&ranges));
     println!("part2: {}", part2(&ranges));
 }
@@ -61,12 +72,19 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
         let data = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
-        let ranges = parse(data);
+        let ranges = parse(data).unwrap();
         assert_eq!(1227775554, part1(&ranges));
         assert_eq!(4174379265, part2(&ranges));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "11-22,95-115,998-1012";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,5 +1,9 @@
-fn parse(data: &str) -> Vec<&str> {
-    data.trim().lines().collect()
+use adventofcode::parsing::{self, ParseError};
+use nom::character::complete::digit1;
+use nom::error::context;
+
+fn parse(data: &str) -> Result<Vec<&str>, ParseError> {
+    parsing::lines(data.trim(), context("a bank of digits", digit1))
 }
 
 fn part1(banks: &[&str]) -> u64 {
@@ -39,7 +43,7 @@ fn part2(banks: &[&str]) -> u64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day3").unwrap();
-    let ranges = parse(&data);
+    let ranges = parse(&data).unwrap();
     println!("part1: {}", part1(&ranges));
     println!("part2: {}", part2(&ranges));
 }
@@ -47,6 +51,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -55,8 +60,14 @@ mod tests {
 811111111111119
 234234234234278
 818181911112111";
-        let banks = parse(data);
+        let banks = parse(data).unwrap();
         assert_eq!(357, part1(&banks));
         assert_eq!(3121910778619, part2(&banks));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "987654321111111\n811111111111119";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
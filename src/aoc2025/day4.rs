@@ -1,7 +1,8 @@
+use adventofcode::parsing::{self, ParseError};
 use ahash::{HashSet, HashSetExt};
 
-fn parse(data: &str) -> Vec<Vec<u8>> {
-    data.trim().lines().map(|s| s.as_bytes().to_vec()).collect()
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    parsing::grid_of(data.trim(), ".@")
 }
 
 fn is_accessible(grid: &[Vec<u8>], i: i32, j: i32) -> bool {
@@ -76,7 +77,7 @@ fn part2(mut grid: Vec<Vec<u8>>, mut to_remove: Vec<i32>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day4").unwrap();
-    let grid = parse(&data);
+    let grid = parse(&data).unwrap();
     let accessible = accessible_places(&grid);
     println!("part1: {}", accessible.len());
     println!("part2: {}", part2(grid, accessible));
@@ -85,6 +86,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -99,9 +101,15 @@ mod tests {
 @.@@@.@@@@
 .@@@@@@@@.
 @.@.@@@.@.";
-        let grid = parse(data);
+        let grid = parse(data).unwrap();
         let accessible = accessible_places(&grid);
         assert_eq!(13, accessible.len());
         assert_eq!(43, part2(grid, accessible));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "..@@.\n@@@.@\n@.@@@";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,27 +1,24 @@
-fn parse(data: &str) -> (Vec<(usize, usize)>, Vec<usize>) {
-    let (p1, p2) = data.trim().split_once("\n\n").unwrap();
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, newline};
+use nom::combinator::verify;
+use nom::error::context;
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
 
-    let ranges = p1
-        .trim()
-        .lines()
-        .map(|line| {
-            let (start, end) = line.split_once("-").unwrap();
-            let start = start.parse().unwrap();
-            let end = end.parse().unwrap();
-            (start, end)
-        })
-        .collect();
-
-    let ids = p2
-        .trim()
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect();
+type Range = (usize, usize);
 
-    (ranges, ids)
+fn parse(data: &str) -> Result<(Vec<Range>, Vec<usize>), ParseError> {
+    let range = verify(
+        separated_pair(unsigned, char('-'), unsigned),
+        |(start, end): &Range| start <= end,
+    );
+    let ranges = separated_list1(newline, context("an ascending range", range));
+    let ids = separated_list1(newline, unsigned);
+    parsing::parse_all(data.trim(), separated_pair(ranges, tag("\n\n"), ids))
 }
 
-fn part1(ranges: &[(usize, usize)], ids: &[usize]) -> usize {
+fn part1(ranges: &[Range], ids: &[usize]) -> usize {
     ids.iter()
         .filter(|&id| {
             ranges
@@ -31,7 +28,7 @@ fn part1(ranges: &[(usize, usize)], ids: &[usize]) -> usize {
         .count()
 }
 
-fn part2(mut ranges: Vec<(usize, usize)>) -> usize {
+fn part2(mut ranges: Vec<Range>) -> usize {
     ranges.sort_unstable();
     let mut prev = ranges[0];
     let mut ans = 0;
@@ -48,7 +45,7 @@ fn part2(mut ranges: Vec<(usize, usize)>) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day5").unwrap();
-    let (ranges, ids) = parse(&data);
+    let (ranges, ids) = parse(&data).unwrap();
     println!("part1: {}", part1(&ranges, &ids));
     println!("part2: {}", part2(ranges));
 }
@@ -56,6 +53,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -71,8 +69,14 @@ mod tests {
 11
 17
 32";
-        let (ranges, ids) = parse(data);
+        let (ranges, ids) = parse(data).unwrap();
         assert_eq!(3, part1(&ranges, &ids));
         assert_eq!(14, part2(ranges));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "3-5\n10-14\n\n1\n5\n32";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,5 +1,47 @@
-fn parse(data: &str) -> Vec<&str> {
-    data.lines().filter(|line| !line.is_empty()).collect()
+use adventofcode::parsing::ParseError;
+
+/// The worksheet's rows: equally long rows of numbers in columns, then a row
+/// of operators each aligned with the left edge of its problem.
+fn parse(data: &str) -> Result<Vec<&str>, ParseError> {
+    let lines: Vec<&str> = data.lines().filter(|line| !line.is_empty()).collect();
+    let Some((&ops, nums)) = lines.split_last().filter(|(_, nums)| !nums.is_empty()) else {
+        let end = &data[data.len()..];
+        return Err(ParseError::at(
+            data,
+            end,
+            "rows of numbers and a row of operators",
+        ));
+    };
+    for line in &lines {
+        if line.len() != ops.len() {
+            let rest = &line[line.floor_char_boundary(ops.len())..];
+            return Err(ParseError::at(
+                data,
+                rest,
+                format!("a row of {}", ops.len()),
+            ));
+        }
+    }
+    if let Some(i) = ops.find(|c| !"+* ".contains(c)) {
+        return Err(ParseError::at(data, &ops[i..], "an operator"));
+    }
+    if ops.starts_with(' ') {
+        return Err(ParseError::at(data, ops, "an operator"));
+    }
+    let problems = ops.split_ascii_whitespace().count();
+    for line in nums {
+        if let Some(i) = line.find(|c: char| !c.is_ascii_digit() && c != ' ') {
+            return Err(ParseError::at(data, &line[i..], "a digit"));
+        }
+        let numbers: Vec<&str> = line.split_ascii_whitespace().collect();
+        if numbers.len() != problems {
+            return Err(ParseError::at(data, line, format!("{problems} numbers")));
+        }
+        if let Some(n) = numbers.iter().find(|n| n.parse::<usize>().is_err()) {
+            return Err(ParseError::at(data, n, "a smaller number"));
+        }
+    }
+    Ok(lines)
 }
 
 fn part1(lines: &[&str]) -> usize {
@@ -60,7 +102,7 @@ fn part2(lines: &[&str]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day6").unwrap();
-    let lines = parse(&data);
+    let lines = parse(&data).unwrap();
     println!("part1: {}", part1(&lines));
     println!("part2: {}", part2(&lines));
 }
@@ -68,6 +110,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -76,8 +119,14 @@ mod tests {
  45 64  387 23 
   6 98  215 314
 *   +   *   +  ";
-        let lines = parse(data);
+        let lines = parse(data).unwrap();
         assert_eq!(4277556, part1(&lines));
         assert_eq!(3263827, part2(&lines));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "123 328\n 45 64 \n*   +  ";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
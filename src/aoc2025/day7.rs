@@ -1,8 +1,15 @@
-fn parse(data: &str) -> Vec<&[u8]> {
-    data.trim().lines().map(|l| l.as_bytes()).collect()
+use adventofcode::parsing::{self, ParseError};
+
+fn parse(data: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let data = data.trim();
+    let grid = parsing::grid_of(data, ".S^")?;
+    if !grid[0].contains(&b'S') {
+        return Err(ParseError::at(data, data, "a start on the first row"));
+    }
+    Ok(grid)
 }
 
-fn part1(lines: &[&[u8]]) -> usize {
+fn part1(lines: &[Vec<u8>]) -> usize {
     let n = lines[0].len();
     let s = lines[0].iter().position(|&b| b == b'S').unwrap();
     let mut beams = vec![false; n];
@@ -33,7 +40,7 @@ fn part1(lines: &[&[u8]]) -> usize {
     ans
 }
 
-fn part2(lines: &[&[u8]]) -> usize {
+fn part2(lines: &[Vec<u8>]) -> usize {
     let n = lines[0].len();
     let s = lines[0].iter().position(|&b| b == b'S').unwrap();
     let mut beams = vec![0usize; n];
@@ -64,7 +71,7 @@ fn part2(lines: &[&[u8]]) -> usize {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day7").unwrap();
-    let grid = parse(&data);
+    let grid = parse(&data).unwrap();
     println!("part1: {}", part1(&grid));
     println!("part2: {}", part2(&grid));
 }
@@ -72,6 +79,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -92,8 +100,14 @@ mod tests {
 ...............
 .^.^.^.^.^...^.
 ...............";
-        let grid = parse(data);
+        let grid = parse(data).unwrap();
         assert_eq!(21, part1(&grid));
         assert_eq!(40, part2(&grid));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = ".S.\n...\n.^.\n...";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -1,3 +1,9 @@
+use adventofcode::dsu::Dsu;
+use adventofcode::parsing::{self, ParseError, signed};
+use nom::Parser;
+use nom::character::complete::char;
+use nom::sequence::preceded;
+
 struct Point {
     x: i64,
     y: i64,
@@ -12,59 +18,13 @@ impl Point {
     }
 }
 
-struct Dsu {
-    parent: Vec<usize>,
-    size: Vec<u32>,
+fn parse(data: &str) -> Result<Vec<Point>, ParseError> {
+    let coord = || preceded(char(','), signed);
+    let point = (signed, coord(), coord()).map(|(x, y, z)| Point { x, y, z });
+    parsing::lines(data.trim(), point)
 }
 
-impl Dsu {
-    fn new(n: usize) -> Self {
-        Self {
-            parent: (0..n).collect(),
-            size: vec![1; n],
-        }
-    }
-
-    fn find(&mut self, x: usize) -> usize {
-        if self.parent[x] != x {
-            self.parent[x] = self.find(self.parent[x]);
-        }
-        self.parent[x]
-    }
-
-    fn union(&mut self, x: usize, y: usize) {
-        let (px, py) = (self.find(x), self.find(y));
-        if px == py {
-            return;
-        }
-        match self.size[px].cmp(&self.size[py]) {
-            std::cmp::Ordering::Less => {
-                self.size[py] += self.size[px];
-                self.parent[px] = py;
-            }
-            _ => {
-                self.size[px] += self.size[py];
-                self.parent[py] = px;
-            }
-        }
-    }
-}
-
-fn parse(data: &str) -> Vec<Point> {
-    data.trim()
-        .lines()
-        .map(|l| {
-            let coords: Vec<i64> = l.split(',').map(|s| s.parse().unwrap()).collect();
-            Point {
-                x: coords[0],
-                y: coords[1],
-                z: coords[2],
-            }
-        })
-        .collect()
-}
-
-fn solve(points: &[Point], mut max_connections: usize) -> (u32, i64) {
+fn solve(points: &[Point], mut max_connections: usize) -> (usize, i64) {
     let n = points.len();
     let mut dists = Vec::with_capacity(n * n / 2);
     for (i, x) in points.iter().enumerate() {
@@ -83,25 +43,14 @@ fn solve(points: &[Point], mut max_connections: usize) -> (u32, i64) {
         if max_connections > 0 {
             max_connections -= 1;
             if max_connections == 0 {
-                let mut roots = vec![false; n];
-                for i in 0..n {
-                    roots[dsu.find(i)] = true;
-                }
-
-                let mut sizes: Vec<u32> = roots
-                    .into_iter()
-                    .enumerate()
-                    .filter(|e| e.1)
-                    .map(|e| dsu.size[e.0])
-                    .collect();
+                let mut sizes: Vec<usize> = dsu.groups().map(|g| g.len()).collect();
                 sizes.sort_unstable();
 
                 p1 = sizes.iter().rev().take(3).product();
             }
         }
 
-        let r = dsu.find(i);
-        if dsu.size[r] == n as u32 {
+        if dsu.components() == 1 {
             p2 = points[i].x * points[j].x;
             break;
         }
@@ -112,7 +61,7 @@ fn solve(points: &[Point], mut max_connections: usize) -> (u32, i64) {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day8").unwrap();
-    let points = parse(&data);
+    let points = parse(&data).unwrap();
     let (p1, p2) = solve(&points, 1000);
     println!("part1: {p1}");
     println!("part2: {p2}");
@@ -121,6 +70,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -145,9 +95,15 @@ mod tests {
 862,61,35
 984,92,344
 425,690,689";
-        let points = parse(data);
+        let points = parse(data).unwrap();
         let (p1, p2) = solve(&points, 10);
         assert_eq!(40, p1);
         assert_eq!(25272, p2);
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "162,817,812\n57,618,57\n906,360,560";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
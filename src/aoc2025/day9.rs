@@ -1,11 +1,9 @@
-fn parse(data: &str) -> Vec<(u32, u32)> {
-    data.trim()
-        .lines()
-        .map(|l| {
-            let (x, y) = l.split_once(',').unwrap();
-            (x.parse().unwrap(), y.parse().unwrap())
-        })
-        .collect()
+use adventofcode::parsing::{self, ParseError, unsigned};
+use nom::character::complete::char;
+use nom::sequence::separated_pair;
+
+fn parse(data: &str) -> Result<Vec<(u32, u32)>, ParseError> {
+    parsing::lines(data.trim(), separated_pair(unsigned, char(','), unsigned))
 }
 
 fn part1(tiles: &[(u32, u32)]) -> u64 {
@@ -59,7 +57,7 @@ fn part2(tiles: &[(u32, u32)]) -> u64 {
 
 pub fn main() {
     let data = std::fs::read_to_string("data/2025/day9").unwrap();
-    let tiles = parse(&data);
+    let tiles = parse(&data).unwrap();
     println!("part1: {}", part1(&tiles));
     println!("part2: {}", part2(&tiles));
 }
@@ -67,6 +65,7 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use adventofcode::fuzz;
 
     #[test]
     fn case1() {
@@ -79,7 +78,13 @@ mod tests {
 2,5
 2,3
 7,3";
-        let tiles = parse(data);
+        let tiles = parse(data).unwrap();
         assert_eq!(50, part1(&tiles));
     }
+
+    #[test]
+    fn fuzz() {
+        let seed = "7,1\n11,1\n11,7\n9,7";
+        fuzz::check(&[seed], |s| parse(s).map(drop));
+    }
 }
@@ -0,0 +1,228 @@
+//! Cycle detection for simulate-until-repeat puzzles.
+//!
+//! A sequence `x0, f(x0), f(f(x0)), ...` over a finite state space runs for
+//! `mu` steps before entering a loop of `lambda` states.
+
+use ahash::AHashMap as HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// The first step whose state equals the state at step `n`.
+    pub fn index(&self, n: usize) -> usize {
+        if n < self.mu {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+
+    /// Number of whole loops between step `index(n)` and step `n`.
+    pub fn laps(&self, n: usize) -> usize {
+        if n < self.mu {
+            0
+        } else {
+            (n - self.mu) / self.lambda
+        }
+    }
+
+    /// Value at step `n` of a quantity that grows by the same amount on
+    /// every loop, like the height of a tower. `values` must hold the
+    /// steps `0..=mu + lambda`.
+    pub fn extrapolate(&self, values: &[i64], n: usize) -> i64 {
+        let gain = values[self.mu + self.lambda] - values[self.mu];
+        values[self.index(n)] + self.laps(n) as i64 * gain
+    }
+}
+
+/// Remembers the step at which every key was first seen, for callers that
+/// drive the simulation themselves or key states by a projection.
+#[derive(Debug, Clone)]
+pub struct Detector<K> {
+    seen: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq> Default for Detector<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq> Detector<K> {
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Records the key of the next step, returns the cycle once a key repeats.
+    pub fn push(&mut self, key: K) -> Option<Cycle> {
+        let n = self.seen.len();
+        match self.seen.entry(key) {
+            Entry::Occupied(e) => Some(Cycle {
+                mu: *e.get(),
+                lambda: n - e.get(),
+            }),
+            Entry::Vacant(e) => {
+                e.insert(n);
+                None
+            }
+        }
+    }
+}
+
+/// Hash-based detection, also returns the states of the steps `0..mu + lambda`.
+pub fn find_cycle<S, F>(x0: S, mut step: F) -> (Cycle, Vec<S>)
+where
+    S: Clone + Hash + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut detector = Detector::new();
+    let mut states = vec![];
+    let mut x = x0;
+    loop {
+        if let Some(cycle) = detector.push(x.clone()) {
+            return (cycle, states);
+        }
+        let next = step(&x);
+        states.push(x);
+        x = next;
+    }
+}
+
+/// Only the `lambda` half of [`brent`], for callers that just need to know
+/// what kind of loop they are in.
+pub fn cycle_length<S, F>(x0: S, mut step: F) -> usize
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let (mut power, mut lambda) = (1, 1);
+    let mut tortoise = x0.clone();
+    let mut hare = step(&x0);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+    lambda
+}
+
+/// Brent's algorithm, keeps only two states alive.
+pub fn brent<S, F>(x0: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let lambda = cycle_length(x0.clone(), &mut step);
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    for _ in 0..lambda {
+        hare = step(&hare);
+    }
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+/// State at step `n`, taking at most a few times `mu + lambda` steps.
+pub fn nth<S, F>(x0: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let cycle = brent(x0.clone(), &mut step);
+    let mut x = x0;
+    for _ in 0..cycle.index(n) {
+        x = step(&x);
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn naive(x0: u32, step: impl Fn(&u32) -> u32) -> Cycle {
+        let mut states = vec![x0];
+        loop {
+            let x = step(states.last().unwrap());
+            if let Some(mu) = states.iter().position(|&s| s == x) {
+                return Cycle {
+                    mu,
+                    lambda: states.len() - mu,
+                };
+            }
+            states.push(x);
+        }
+    }
+
+    #[test]
+    fn rho() {
+        // 0 1 2 3 4 5 6 7 3 4 ...
+        let step = |&x: &u32| if x == 7 { 3 } else { x + 1 };
+        let expected = Cycle { mu: 3, lambda: 5 };
+        assert_eq!(expected, brent(0, step));
+        let (cycle, states) = find_cycle(0, step);
+        assert_eq!(expected, cycle);
+        assert_eq!((0..8).collect::<Vec<_>>(), states);
+        assert_eq!(2, nth(0, step, 2));
+        assert_eq!(5, nth(0, step, 1_000_000_000));
+        assert_eq!(5, states[cycle.index(1_000_000_000)]);
+        assert_eq!(0, cycle.laps(2));
+        assert_eq!(1, cycle.laps(8));
+    }
+
+    #[test]
+    fn fixed_point() {
+        let step = |_: &u32| 9;
+        assert_eq!(Cycle { mu: 1, lambda: 1 }, brent(0, step));
+        assert_eq!(Cycle { mu: 0, lambda: 1 }, brent(9, step));
+    }
+
+    #[test]
+    fn extrapolate() {
+        // grows by 10 per loop of 3 after 2 steps
+        let values = [0, 1, 5, 8, 12, 15];
+        let cycle = Cycle { mu: 2, lambda: 3 };
+        assert_eq!(8, cycle.extrapolate(&values, 3));
+        assert_eq!(22, cycle.extrapolate(&values, 7));
+        assert_eq!(10 * 3 + 12, cycle.extrapolate(&values, 2 + 3 * 3 + 2));
+    }
+
+    #[test]
+    fn random_maps() {
+        let mut rng = StdRng::seed_from_u64(30);
+        for _ in 0..200 {
+            let n = rng.random_range(1..300);
+            let table = (0..n).map(|_| rng.random_range(0..n)).collect::<Vec<u32>>();
+            let step = |&x: &u32| table[x as usize];
+            let x0 = rng.random_range(0..n);
+            let expected = naive(x0, step);
+            assert_eq!(expected, brent(x0, step));
+            assert_eq!(expected.lambda, cycle_length(x0, step));
+            assert_eq!(expected, find_cycle(x0, step).0);
+            let k = rng.random_range(0..1000);
+            let mut x = x0;
+            for _ in 0..k {
+                x = step(&x);
+            }
+            assert_eq!(x, nth(x0, step, k));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Graphviz DOT output, for looking at the structure of graph-shaped inputs.
+//!
+//! Days that support it write their graph when run as `2023 20 --dot out.dot`,
+//! render it with e.g. `dot -Tsvg out.dot > out.svg`.
+
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, Default)]
+pub struct Dot {
+    directed: bool,
+    body: String,
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn attributes(attrs: &[(&str, &str)]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let list = attrs
+        .iter()
+        .map(|(k, v)| format!("{k}={}", quote(v)))
+        .collect::<Vec<_>>();
+    format!(" [{}]", list.join(", "))
+}
+
+impl Dot {
+    pub fn digraph() -> Self {
+        Self {
+            directed: true,
+            body: String::new(),
+        }
+    }
+
+    pub fn graph() -> Self {
+        Self::default()
+    }
+
+    /// Declares a node, only needed to give it attributes like `label` or `shape`.
+    pub fn node(&mut self, id: &str, attrs: &[(&str, &str)]) {
+        writeln!(self.body, "  {}{};", quote(id), attributes(attrs)).unwrap();
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, label: Option<&str>) {
+        let arrow = if self.directed { "->" } else { "--" };
+        let attrs = label.map(|l| [("label", l)]);
+        writeln!(
+            self.body,
+            "  {} {arrow} {}{};",
+            quote(from),
+            quote(to),
+            attributes(attrs.as_ref().map_or(&[], |a| a))
+        )
+        .unwrap();
+    }
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        write!(f, "{kind} {{\n{}}}\n", self.body)
+    }
+}
+
+/// The file named after `--dot` on the command line.
+pub fn requested_path() -> Option<String> {
+    let mut args = std::env::args().skip_while(|a| a != "--dot");
+    args.next()?;
+    args.next()
+}
+
+/// Writes the graph from `build` when `--dot` was given, so days only pay
+/// for building it when asked.
+pub fn export(build: impl FnOnce() -> Dot) {
+    if let Some(path) = requested_path() {
+        std::fs::write(&path, build().to_string()).unwrap();
+        println!("graph written to {path}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let mut dot = Dot::digraph();
+        dot.node("a", &[("shape", "box")]);
+        dot.node("b", &[]);
+        dot.edge("a", "b", Some("say \"hi\""));
+        dot.edge("b", "a", None);
+        let expected = r#"digraph {
+  "a" [shape="box"];
+  "b";
+  "a" -> "b" [label="say \"hi\""];
+  "b" -> "a";
+}
+"#;
+        assert_eq!(expected, dot.to_string());
+
+        let mut dot = Dot::graph();
+        dot.edge("x", "y", None);
+        assert_eq!("graph {\n  \"x\" -- \"y\";\n}\n", dot.to_string());
+    }
+}
@@ -0,0 +1,201 @@
+//! Disjoint-set union over `0..n`.
+
+/// Union by size with iterative path compression.
+#[derive(Debug, Clone)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl Dsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    /// Merges the sets of `x` and `y`, returns false if they were already joined.
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let (mut px, mut py) = (self.find(x), self.find(y));
+        if px == py {
+            return false;
+        }
+        if self.size[px] < self.size[py] {
+            (px, py) = (py, px);
+        }
+        self.parent[py] = px;
+        self.size[px] += self.size[py];
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    /// Size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let r = self.find(x);
+        self.size[r]
+    }
+
+    /// Number of disjoint sets.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Members of every set, each set in ascending order.
+    pub fn groups(&mut self) -> impl Iterator<Item = Vec<usize>> + use<> {
+        let n = self.len();
+        let mut index = vec![usize::MAX; n];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..n {
+            let r = self.find(x);
+            if index[r] == usize::MAX {
+                index[r] = groups.len();
+                groups.push(Vec::with_capacity(self.size[r]));
+            }
+            groups[index[r]].push(x);
+        }
+        groups.into_iter()
+    }
+}
+
+/// Union by size without path compression, so unions can be undone in
+/// reverse order, e.g. for offline dynamic connectivity.
+#[derive(Debug, Clone)]
+pub struct RollbackDsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+    history: Vec<Option<(usize, usize)>>,
+}
+
+impl RollbackDsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+            history: vec![],
+        }
+    }
+
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let (mut px, mut py) = (self.find(x), self.find(y));
+        if px == py {
+            self.history.push(None);
+            return false;
+        }
+        if self.size[px] < self.size[py] {
+            (px, py) = (py, px);
+        }
+        self.parent[py] = px;
+        self.size[px] += self.size[py];
+        self.components -= 1;
+        self.history.push(Some((px, py)));
+        true
+    }
+
+    pub fn same(&self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Marker to pass to [`RollbackDsu::rollback`].
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes every union made since `snapshot`.
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            if let Some((px, py)) = self.history.pop().unwrap() {
+                self.parent[py] = py;
+                self.size[px] -= self.size[py];
+                self.components += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find() {
+        let mut dsu = Dsu::new(8);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert!(dsu.union(5, 6));
+        assert!(dsu.same(0, 3));
+        assert!(!dsu.same(0, 4));
+        assert_eq!(4, dsu.size(2));
+        assert_eq!(2, dsu.size(6));
+        assert_eq!(4, dsu.components());
+        let groups = dsu.groups().collect::<Vec<_>>();
+        assert_eq!(vec![vec![0, 1, 2, 3], vec![4], vec![5, 6], vec![7]], groups);
+    }
+
+    #[test]
+    fn rollback() {
+        let mut dsu = RollbackDsu::new(5);
+        dsu.union(0, 1);
+        let s = dsu.snapshot();
+        dsu.union(1, 2);
+        dsu.union(0, 2);
+        dsu.union(3, 4);
+        assert_eq!(3, dsu.size(0));
+        assert_eq!(2, dsu.components());
+        dsu.rollback(s);
+        assert_eq!(2, dsu.size(0));
+        assert!(!dsu.same(0, 2));
+        assert!(!dsu.same(3, 4));
+        assert_eq!(4, dsu.components());
+        dsu.rollback(0);
+        assert_eq!(5, dsu.components());
+        assert_eq!(1, dsu.size(1));
+    }
+}
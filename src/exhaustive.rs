@@ -0,0 +1,367 @@
+//! Depth-first exhaustive search with memoization and branch-and-bound.
+//!
+//! The value of a state combines the value of stopping there with the values
+//! of its children, each reached by a step with a value of its own. How
+//! values combine is the problem's [`Objective`]: the best path for [`Max`]
+//! and [`Min`], the number of paths for [`Count`].
+
+use ahash::AHashMap as HashMap;
+use rayon::prelude::*;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul};
+
+pub trait Objective<V> {
+    /// Value of the empty path.
+    fn unit() -> V;
+
+    /// Value of having both `a` and `b` to choose from.
+    fn combine(a: V, b: V) -> V;
+
+    /// Value of taking `step` and then following a path worth `rest`.
+    fn extend(step: V, rest: V) -> V;
+
+    /// Whether no path worth at best `bound` can improve on `best`.
+    fn prunes(_bound: V, _best: V) -> bool {
+        false
+    }
+}
+
+/// Largest sum of the steps along a path.
+pub struct Max;
+
+/// Smallest sum of the steps along a path.
+pub struct Min;
+
+/// Number of paths, each step counting as that many alternatives.
+pub struct Count;
+
+impl<V: Copy + Ord + Default + Add<Output = V>> Objective<V> for Max {
+    fn unit() -> V {
+        V::default()
+    }
+
+    fn combine(a: V, b: V) -> V {
+        a.max(b)
+    }
+
+    fn extend(step: V, rest: V) -> V {
+        step + rest
+    }
+
+    fn prunes(bound: V, best: V) -> bool {
+        bound <= best
+    }
+}
+
+impl<V: Copy + Ord + Default + Add<Output = V>> Objective<V> for Min {
+    fn unit() -> V {
+        V::default()
+    }
+
+    fn combine(a: V, b: V) -> V {
+        a.min(b)
+    }
+
+    fn extend(step: V, rest: V) -> V {
+        step + rest
+    }
+
+    fn prunes(bound: V, best: V) -> bool {
+        bound >= best
+    }
+}
+
+impl<V: Copy + From<u8> + Add<Output = V> + Mul<Output = V>> Objective<V> for Count {
+    fn unit() -> V {
+        V::from(1)
+    }
+
+    fn combine(a: V, b: V) -> V {
+        a + b
+    }
+
+    fn extend(step: V, rest: V) -> V {
+        step * rest
+    }
+}
+
+pub trait Problem {
+    type State;
+    type Key;
+    type Value: Copy;
+    type Objective: Objective<Self::Value>;
+
+    /// Memo key of `state`, `None` to never cache it.
+    fn key(&self, state: &Self::State) -> Option<Self::Key>;
+
+    /// Value of ending a path at `state`, if it may end there.
+    fn stop(&self, state: &Self::State) -> Option<Self::Value>;
+
+    /// Pushes the children of `state`, each with the value of the step to it.
+    fn children(&self, state: &Self::State, out: &mut Vec<(Self::Value, Self::State)>);
+
+    /// Optimistic value of any path from `state` on, children are skipped
+    /// when it cannot beat the best complete path found so far.
+    fn bound(&self, _state: &Self::State) -> Option<Self::Value> {
+        None
+    }
+}
+
+/// Storage for the values of solved states, `None` meaning no path.
+pub trait Memo<K, V> {
+    fn get(&mut self, key: &K) -> Option<Option<V>>;
+    fn insert(&mut self, key: K, value: Option<V>);
+}
+
+impl<K: Hash + Eq, V: Copy> Memo<K, V> for HashMap<K, Option<V>> {
+    fn get(&mut self, key: &K) -> Option<Option<V>> {
+        HashMap::get(self, key).copied()
+    }
+
+    fn insert(&mut self, key: K, value: Option<V>) {
+        HashMap::insert(self, key, value);
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub expanded: usize,
+    pub cache_hits: usize,
+    pub pruned: usize,
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.expanded += rhs.expanded;
+        self.cache_hits += rhs.cache_hits;
+        self.pruned += rhs.pruned;
+    }
+}
+
+type DefaultMemo<P> = HashMap<<P as Problem>::Key, Option<<P as Problem>::Value>>;
+
+/// Keeps its memo across calls to [`Search::solve`], so roots sharing
+/// subproblems can reuse them.
+pub struct Search<'p, P: Problem, M = DefaultMemo<P>> {
+    problem: &'p P,
+    memo: M,
+    best: Option<P::Value>,
+    pub stats: Stats,
+}
+
+impl<'p, P: Problem> Search<'p, P>
+where
+    P::Key: Hash + Eq,
+{
+    pub fn new(problem: &'p P) -> Self {
+        Self::with_memo(problem, HashMap::new())
+    }
+}
+
+impl<'p, P: Problem, M: Memo<P::Key, P::Value>> Search<'p, P, M> {
+    pub fn with_memo(problem: &'p P, memo: M) -> Self {
+        Self {
+            problem,
+            memo,
+            best: None,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Value of `root`, `None` if no path from it can end.
+    pub fn solve(&mut self, root: &P::State) -> Option<P::Value> {
+        self.best = None;
+        self.visit(root, P::Objective::unit()).0
+    }
+
+    fn improve(&mut self, value: P::Value) {
+        self.best = Some(match self.best {
+            Some(best) => P::Objective::combine(best, value),
+            None => value,
+        });
+    }
+
+    /// Returns the value of `state` and whether it is exact, i.e. no branch
+    /// below it was pruned. `prefix` is the value of the path to `state`.
+    fn visit(&mut self, state: &P::State, prefix: P::Value) -> (Option<P::Value>, bool) {
+        let key = self.problem.key(state);
+        if let Some(k) = &key
+            && let Some(value) = self.memo.get(k)
+        {
+            self.stats.cache_hits += 1;
+            if let Some(v) = value {
+                self.improve(P::Objective::extend(prefix, v));
+            }
+            return (value, true);
+        }
+        self.stats.expanded += 1;
+
+        let mut value = self.problem.stop(state);
+        if let Some(v) = value {
+            self.improve(P::Objective::extend(prefix, v));
+        }
+        let mut exact = true;
+        let mut children = vec![];
+        self.problem.children(state, &mut children);
+        for (step, child) in children {
+            let prefix = P::Objective::extend(prefix, step);
+            if let (Some(best), Some(bound)) = (self.best, self.problem.bound(&child))
+                && P::Objective::prunes(P::Objective::extend(prefix, bound), best)
+            {
+                self.stats.pruned += 1;
+                exact = false;
+                continue;
+            }
+            let (v, e) = self.visit(&child, prefix);
+            exact &= e;
+            if let Some(v) = v {
+                let v = P::Objective::extend(step, v);
+                value = Some(match value {
+                    Some(a) => P::Objective::combine(a, v),
+                    None => v,
+                });
+            }
+        }
+
+        if exact && let Some(k) = key {
+            self.memo.insert(k, value);
+        }
+        (value, exact)
+    }
+}
+
+/// Solves every root on the rayon pool, each worker with a memo of its own.
+pub fn par_solve<P>(problem: &P, roots: &[P::State]) -> (Vec<Option<P::Value>>, Stats)
+where
+    P: Problem + Sync,
+    P::State: Sync,
+    P::Key: Hash + Eq + Send,
+    P::Value: Send,
+{
+    let results = roots
+        .par_iter()
+        .map_init(
+            || Search::new(problem),
+            |search, root| {
+                let value = search.solve(root);
+                (value, std::mem::take(&mut search.stats))
+            },
+        )
+        .collect::<Vec<_>>();
+    let mut stats = Stats::default();
+    let values = results
+        .into_iter()
+        .map(|(value, s)| {
+            stats += s;
+            value
+        })
+        .collect();
+    (values, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0/1 knapsack over (item, room left).
+    struct Knapsack {
+        items: Vec<(u32, u32)>,
+        bounded: bool,
+    }
+
+    impl Problem for Knapsack {
+        type State = (usize, u32);
+        type Key = (usize, u32);
+        type Value = u32;
+        type Objective = Max;
+
+        fn key(&self, &state: &Self::State) -> Option<Self::Key> {
+            Some(state)
+        }
+
+        fn stop(&self, _: &Self::State) -> Option<u32> {
+            Some(0)
+        }
+
+        fn children(&self, &(i, room): &Self::State, out: &mut Vec<(u32, Self::State)>) {
+            if let Some(&(weight, value)) = self.items.get(i) {
+                if weight <= room {
+                    out.push((value, (i + 1, room - weight)));
+                }
+                out.push((0, (i + 1, room)));
+            }
+        }
+
+        fn bound(&self, &(i, _): &Self::State) -> Option<u32> {
+            self.bounded
+                .then(|| self.items[i..].iter().map(|e| e.1).sum())
+        }
+    }
+
+    /// Ways to climb down `n` stairs by 2 or 3 at a time.
+    struct Stairs;
+
+    impl Problem for Stairs {
+        type State = u64;
+        type Key = u64;
+        type Value = u64;
+        type Objective = Count;
+
+        fn key(&self, &n: &u64) -> Option<u64> {
+            Some(n)
+        }
+
+        fn stop(&self, &n: &u64) -> Option<u64> {
+            (n == 0).then_some(1)
+        }
+
+        fn children(&self, &n: &u64, out: &mut Vec<(u64, u64)>) {
+            for k in [2, 3] {
+                if n >= k {
+                    out.push((1, n - k));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn knapsack() {
+        let items = vec![(12, 4), (2, 2), (1, 1), (1, 2), (4, 10), (3, 7), (9, 9)];
+        let brute = (0..1u32 << items.len())
+            .filter_map(|mask| {
+                let chosen = items.iter().enumerate().filter(|e| mask >> e.0 & 1 == 1);
+                let (w, v) = chosen.fold((0, 0), |(w, v), (_, e)| (w + e.0, v + e.1));
+                (w <= 15).then_some(v)
+            })
+            .max();
+
+        let plain = Knapsack {
+            items: items.clone(),
+            bounded: false,
+        };
+        let mut search = Search::new(&plain);
+        assert_eq!(brute, search.solve(&(0, 15)));
+        assert_eq!(0, search.stats.pruned);
+        let expanded = search.stats.expanded;
+
+        let bounded = Knapsack {
+            items,
+            bounded: true,
+        };
+        let mut search = Search::new(&bounded);
+        assert_eq!(brute, search.solve(&(0, 15)));
+        assert!(search.stats.pruned > 0);
+        assert!(search.stats.expanded < expanded);
+    }
+
+    #[test]
+    fn count() {
+        let mut search = Search::new(&Stairs);
+        assert_eq!(Some(7), search.solve(&10));
+        assert!(search.stats.cache_hits > 0);
+        assert_eq!(None, search.solve(&1));
+        let (values, stats) = par_solve(&Stairs, &[0, 1, 2, 5, 9]);
+        assert_eq!(vec![Some(1), None, Some(1), Some(2), Some(5)], values);
+        assert!(stats.expanded > 0);
+    }
+}
@@ -0,0 +1,230 @@
+//! An offline fuzzer for puzzle parsers: feeds each one mangled copies of
+//! its examples and strings of puzzle-ish noise, and fails with the
+//! smallest input it can find that makes the parser panic. Parsers are
+//! expected to reject bad input with an `Err` instead.
+//!
+//! Runs are deterministic. `AOC_FUZZ_CASES` sets the number of inputs per
+//! parser and `AOC_FUZZ_SEED` picks a different sequence of them, e.g.
+//!
+//! ```text
+//! AOC_FUZZ_CASES=100000 AOC_FUZZ_SEED=7 cargo test --bin 2025 fuzz
+//! ```
+
+use rand::prelude::*;
+use std::fmt::Debug;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+const DEFAULT_CASES: usize = 2000;
+
+/// Fragments that tend to break hand-rolled parsers.
+const NASTY: &[&str] = &[
+    "",
+    "\n",
+    "\n\n",
+    "\r\n",
+    " ",
+    "  ",
+    "\t",
+    "-",
+    "+",
+    ",",
+    ":",
+    "0",
+    "-0",
+    "9",
+    "255",
+    "256",
+    "65536",
+    "-2147483649",
+    "4294967296",
+    "18446744073709551616",
+    "é",
+    "⚠",
+];
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Fuzzes `parse`, which must accept every seed.
+pub fn check<E: Debug>(seeds: &[&str], parse: impl Fn(&str) -> Result<(), E>) {
+    for seed in seeds {
+        if let Err(e) = parse(seed) {
+            panic!("seed {seed:?} was rejected: {e:?}");
+        }
+    }
+    let cases = env_or("AOC_FUZZ_CASES", DEFAULT_CASES as u64) as usize;
+    let mut rng = StdRng::seed_from_u64(env_or("AOC_FUZZ_SEED", 0));
+    let fragments = fragments(seeds);
+    let panics = |s: &str| catch_unwind(AssertUnwindSafe(|| parse(s))).is_err();
+    for _ in 0..cases {
+        let input = if seeds.is_empty() || rng.random_bool(0.25) {
+            noise(&mut rng, &fragments)
+        } else {
+            let seed = seeds.choose(&mut rng).unwrap();
+            mutate(&mut rng, seed, &fragments)
+        };
+        if panics(&input) {
+            let small = shrink(&input, panics);
+            panic!("parser panicked on {small:?} (shrunk from {input:?})");
+        }
+    }
+}
+
+/// Every token of the seeds, each separator char, and [`NASTY`].
+fn fragments(seeds: &[&str]) -> Vec<String> {
+    let mut out: Vec<String> = NASTY.iter().map(|s| s.to_string()).collect();
+    for seed in seeds {
+        let mut token = String::new();
+        for c in seed.chars() {
+            if c.is_alphanumeric() {
+                token.push(c);
+                continue;
+            }
+            if !token.is_empty() {
+                out.push(std::mem::take(&mut token));
+            }
+            out.push(c.to_string());
+        }
+        out.extend((!token.is_empty()).then_some(token));
+    }
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+fn noise(rng: &mut StdRng, fragments: &[String]) -> String {
+    let n = rng.random_range(0..40);
+    (0..n)
+        .map(|_| fragments.choose(rng).unwrap().as_str())
+        .collect()
+}
+
+/// A random char boundary of `s`.
+fn boundary(rng: &mut StdRng, s: &str) -> usize {
+    let i = rng.random_range(0..=s.len());
+    (i..=s.len()).find(|&i| s.is_char_boundary(i)).unwrap()
+}
+
+/// The boundary `chars` chars after `i`, or the end of `s`.
+fn advance(s: &str, i: usize, chars: usize) -> usize {
+    s[i..]
+        .char_indices()
+        .nth(chars)
+        .map_or(s.len(), |(j, _)| i + j)
+}
+
+fn mutate(rng: &mut StdRng, seed: &str, fragments: &[String]) -> String {
+    let mut s = seed.to_string();
+    for _ in 0..rng.random_range(1..=4) {
+        let i = boundary(rng, &s);
+        match rng.random_range(0..6) {
+            0 => {
+                let j = advance(&s, i, rng.random_range(1..=8));
+                s.replace_range(i..j, "");
+            }
+            1 => s.insert_str(i, fragments.choose(rng).unwrap()),
+            2 => {
+                let j = advance(&s, i, rng.random_range(1..=4));
+                s.replace_range(i..j, fragments.choose(rng).unwrap());
+            }
+            3 => s.truncate(i),
+            4 => {
+                let lines: Vec<&str> = s.lines().collect();
+                if let Some(line) = lines.choose(rng) {
+                    s = format!("{s}\n{line}");
+                }
+            }
+            _ => {
+                let mut lines: Vec<&str> = s.lines().collect();
+                lines.retain(|_| rng.random_bool(0.8));
+                s = lines.join("\n");
+            }
+        }
+    }
+    s
+}
+
+/// Deletes lines, then runs of chars, while `fails` keeps failing.
+fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut best = input.to_string();
+    let mut i = 0;
+    while i < best.lines().count() {
+        let mut lines: Vec<&str> = best.lines().collect();
+        lines.remove(i);
+        let candidate = lines.join("\n");
+        if fails(&candidate) {
+            best = candidate;
+        } else {
+            i += 1;
+        }
+    }
+    let mut width = best.chars().count();
+    while width > 0 {
+        let mut start = 0;
+        while start < best.chars().count() {
+            let candidate: String = best
+                .chars()
+                .enumerate()
+                .filter(|&(k, _)| k < start || k >= start + width)
+                .map(|(_, c)| c)
+                .collect();
+            if fails(&candidate) {
+                best = candidate;
+            } else {
+                start += width;
+            }
+        }
+        width /= 2;
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragile(s: &str) -> Result<(), ()> {
+        for line in s.lines() {
+            let _ = line.as_bytes()[0];
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn finds_and_shrinks() {
+        let result = catch_unwind(|| check(&["L68\nR30"], fragile));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(
+            message.starts_with("parser panicked on \"\\n\""),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn robust() {
+        check(&["1,2\n3,4", "5"], |s| {
+            s.lines()
+                .flat_map(|line| line.split(','))
+                .try_for_each(|n| n.parse::<u8>().map(drop))
+        });
+    }
+
+    #[test]
+    fn rejected_seed() {
+        assert!(catch_unwind(|| check(&["x"], |s| s.parse::<u8>().map(drop))).is_err());
+    }
+
+    #[test]
+    fn mutations_stay_valid_utf8() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let fragments = fragments(&["é⚠x\n12"]);
+        for _ in 0..1000 {
+            let s = mutate(&mut rng, "é⚠x\n12", &fragments);
+            assert!(s.chars().count() < 100);
+        }
+    }
+}
@@ -0,0 +1,659 @@
+//! Undirected simple graphs over `0..n` with bitset adjacency, for the
+//! puzzles that ask about cliques, components or cuts of a small network.
+
+use std::collections::VecDeque;
+
+/// Fixed-capacity set of `0..n`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new(n: usize) -> Self {
+        Self {
+            words: vec![0; n.div_ceil(64)],
+        }
+    }
+
+    /// The set of all of `0..n`.
+    pub fn full(n: usize) -> Self {
+        let mut s = Self::new(n);
+        s.words.fill(!0);
+        if !n.is_multiple_of(64) {
+            *s.words.last_mut().unwrap() = (1u64 << (n % 64)) - 1;
+        }
+        s
+    }
+
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (w, b) = (i / 64, 1u64 << (i % 64));
+        let fresh = self.words[w] & b == 0;
+        self.words[w] |= b;
+        fresh
+    }
+
+    pub fn remove(&mut self, i: usize) -> bool {
+        let (w, b) = (i / 64, 1u64 << (i % 64));
+        let present = self.words[w] & b != 0;
+        self.words[w] &= !b;
+        present
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / 64)
+            .is_some_and(|w| w >> (i % 64) & 1 == 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Elements in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &w)| {
+            let mut w = w;
+            std::iter::from_fn(move || {
+                (w != 0).then(|| {
+                    let b = w.trailing_zeros() as usize;
+                    w &= w - 1;
+                    i * 64 + b
+                })
+            })
+        })
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let words = self.words.iter().zip(&other.words);
+        Self {
+            words: words.map(|(a, b)| a & b).collect(),
+        }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let words = self.words.iter().zip(&other.words);
+        Self {
+            words: words.map(|(a, b)| a & !b).collect(),
+        }
+    }
+
+    pub fn intersection_len(&self, other: &Self) -> usize {
+        let words = self.words.iter().zip(&other.words);
+        words.map(|(a, b)| (a & b).count_ones() as usize).sum()
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    adj: Vec<BitSet>,
+    edges: usize,
+}
+
+impl Graph {
+    pub fn new(n: usize) -> Self {
+        Self {
+            adj: vec![BitSet::new(n); n],
+            edges: 0,
+        }
+    }
+
+    pub fn from_edges(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut g = Self::new(n);
+        for &(u, v) in edges {
+            g.add_edge(u, v);
+        }
+        g
+    }
+
+    /// Self-loops are ignored, returns false if the edge was already there.
+    pub fn add_edge(&mut self, u: usize, v: usize) -> bool {
+        if u == v || !self.adj[u].insert(v) {
+            return false;
+        }
+        self.adj[v].insert(u);
+        self.edges += 1;
+        true
+    }
+
+    pub fn has_edge(&self, u: usize, v: usize) -> bool {
+        self.adj[u].contains(v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    pub fn neighbors(&self, u: usize) -> &BitSet {
+        &self.adj[u]
+    }
+
+    pub fn degree(&self, u: usize) -> usize {
+        self.adj[u].len()
+    }
+
+    /// Every edge once, as `(u, v)` with `u < v`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.len()).flat_map(move |u| {
+            self.adj[u]
+                .iter()
+                .filter(move |&v| u < v)
+                .map(move |v| (u, v))
+        })
+    }
+
+    /// Vertices by repeatedly taking one of smallest degree among those left,
+    /// so each has few neighbors later in the order.
+    pub fn degeneracy_order(&self) -> Vec<usize> {
+        let n = self.len();
+        let mut degree = (0..n).map(|u| self.degree(u)).collect::<Vec<_>>();
+        let mut buckets = vec![vec![]; n.max(1)];
+        for u in 0..n {
+            buckets[degree[u]].push(u);
+        }
+        let mut done = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut d = 0usize;
+        while order.len() < n {
+            // degrees only drop by one at a time, so the minimum never falls
+            // more than one bucket below the last one
+            d = d.saturating_sub(1);
+            while buckets[d].is_empty() {
+                d += 1;
+            }
+            let u = buckets[d].pop().unwrap();
+            // stale entries from before a degree dropped
+            if done[u] || degree[u] != d {
+                continue;
+            }
+            done[u] = true;
+            order.push(u);
+            for v in self.adj[u].iter() {
+                if !done[v] {
+                    degree[v] -= 1;
+                    buckets[degree[v]].push(v);
+                }
+            }
+        }
+        order
+    }
+
+    /// Bron–Kerbosch with pivoting, the top level in degeneracy order.
+    /// `visit` gets each maximal clique and returns the smallest size still
+    /// worth reporting, branches that can't reach it are cut.
+    fn cliques_from(&self, visit: &mut impl FnMut(&[usize]) -> usize) {
+        let n = self.len();
+        let mut p = BitSet::full(n);
+        let mut x = BitSet::new(n);
+        let mut want = 0;
+        let mut r = vec![];
+        for v in self.degeneracy_order() {
+            let nv = &self.adj[v];
+            r.push(v);
+            self.expand(
+                &mut r,
+                p.intersection(nv),
+                x.intersection(nv),
+                &mut want,
+                visit,
+            );
+            r.pop();
+            p.remove(v);
+            x.insert(v);
+        }
+    }
+
+    fn expand(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: BitSet,
+        mut x: BitSet,
+        want: &mut usize,
+        visit: &mut impl FnMut(&[usize]) -> usize,
+    ) {
+        if r.len() + p.len() < *want {
+            return;
+        }
+        if p.is_empty() {
+            if x.is_empty() {
+                *want = visit(r);
+            }
+            return;
+        }
+        let pivot = p
+            .iter()
+            .chain(x.iter())
+            .max_by_key(|&u| p.intersection_len(&self.adj[u]))
+            .unwrap();
+        for v in p.difference(&self.adj[pivot]).iter() {
+            let nv = &self.adj[v];
+            r.push(v);
+            self.expand(r, p.intersection(nv), x.intersection(nv), want, visit);
+            r.pop();
+            p.remove(v);
+            x.insert(v);
+            if r.len() + p.len() < *want {
+                return;
+            }
+        }
+    }
+
+    /// Calls `f` with every maximal clique, in no particular order.
+    pub fn maximal_cliques(&self, mut f: impl FnMut(&[usize])) {
+        self.cliques_from(&mut |c| {
+            f(c);
+            0
+        });
+    }
+
+    /// A largest clique, sorted.
+    pub fn max_clique(&self) -> Vec<usize> {
+        let mut best = vec![];
+        self.cliques_from(&mut |c| {
+            if c.len() > best.len() {
+                best = c.to_vec();
+            }
+            best.len() + 1
+        });
+        best.sort_unstable();
+        best
+    }
+
+    /// Calls `f` with every clique of `k` vertices, each sorted.
+    pub fn k_cliques(&self, k: usize, mut f: impl FnMut(&[usize])) {
+        fn extend(
+            g: &Graph,
+            k: usize,
+            clique: &mut Vec<usize>,
+            candidates: BitSet,
+            f: &mut impl FnMut(&[usize]),
+        ) {
+            if clique.len() == k {
+                f(clique);
+                return;
+            }
+            for v in candidates.iter() {
+                let mut next = candidates.intersection(&g.adj[v]);
+                // only larger vertices, so each clique shows up once
+                (0..=v).for_each(|u| {
+                    next.remove(u);
+                });
+                if clique.len() + 1 + next.len() >= k {
+                    clique.push(v);
+                    extend(g, k, clique, next, f);
+                    clique.pop();
+                }
+            }
+        }
+        if k == 0 {
+            f(&[]);
+            return;
+        }
+        extend(self, k, &mut vec![], BitSet::full(self.len()), &mut f);
+    }
+
+    /// Every triangle as sorted vertices.
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut result = vec![];
+        for (u, v) in self.edges() {
+            for w in self.adj[u].intersection(&self.adj[v]).iter() {
+                if w > v {
+                    result.push([u, v, w]);
+                }
+            }
+        }
+        result
+    }
+
+    /// Connected components, each sorted, ordered by smallest vertex.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut unseen = BitSet::full(self.len());
+        let mut result = vec![];
+        let mut q = VecDeque::new();
+        for s in 0..self.len() {
+            if !unseen.remove(s) {
+                continue;
+            }
+            let mut component = vec![s];
+            q.push_back(s);
+            while let Some(u) = q.pop_front() {
+                for v in self.adj[u].intersection(&unseen).iter() {
+                    unseen.remove(v);
+                    component.push(v);
+                    q.push_back(v);
+                }
+            }
+            component.sort_unstable();
+            result.push(component);
+        }
+        result
+    }
+
+    /// Tarjan's low-link DFS, iterative. Returns the articulation points and
+    /// the bridges, both sorted.
+    fn low_links(&self) -> (Vec<usize>, Vec<(usize, usize)>) {
+        let n = self.len();
+        let mut order = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut points = vec![];
+        let mut bridges = vec![];
+        let mut time = 0;
+        for root in 0..n {
+            if order[root] != usize::MAX {
+                continue;
+            }
+            order[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            let mut is_point = vec![];
+            // (vertex, parent, neighbors still to try)
+            let mut stack = vec![(root, usize::MAX, self.adj[root].iter().collect::<Vec<_>>())];
+            while let Some((u, parent, next)) = stack.last_mut() {
+                let (u, parent) = (*u, *parent);
+                if let Some(v) = next.pop() {
+                    if v == parent {
+                        continue;
+                    }
+                    if order[v] == usize::MAX {
+                        order[v] = time;
+                        low[v] = time;
+                        time += 1;
+                        stack.push((v, u, self.adj[v].iter().collect()));
+                    } else {
+                        low[u] = low[u].min(order[v]);
+                    }
+                    continue;
+                }
+                stack.pop();
+                if parent == usize::MAX {
+                    continue;
+                }
+                low[parent] = low[parent].min(low[u]);
+                if low[u] > order[parent] {
+                    bridges.push((parent.min(u), parent.max(u)));
+                }
+                if parent == root {
+                    root_children += 1;
+                } else if low[u] >= order[parent] {
+                    is_point.push(parent);
+                }
+            }
+            if root_children > 1 {
+                is_point.push(root);
+            }
+            points.extend(is_point);
+        }
+        points.sort_unstable();
+        points.dedup();
+        bridges.sort_unstable();
+        (points, bridges)
+    }
+
+    /// Vertices whose removal splits their component.
+    pub fn articulation_points(&self) -> Vec<usize> {
+        self.low_links().0
+    }
+
+    /// Edges whose removal splits their component, as `(u, v)` with `u < v`.
+    pub fn bridges(&self) -> Vec<(usize, usize)> {
+        self.low_links().1
+    }
+
+    /// Smallest set of edges whose removal disconnects the graph, as its size
+    /// and the vertices left on the side of vertex 0. Every cut separates
+    /// vertex 0 from some other vertex, so a max-flow from 0 to each of them
+    /// with unit capacity augmenting paths finds it.
+    pub fn min_cut(&self) -> (usize, Vec<usize>) {
+        let n = self.len();
+        // arcs in compressed sparse row form, with the index of the opposite arc
+        let mut start = vec![0; n + 1];
+        let mut target = vec![];
+        for u in 0..n {
+            target.extend(self.adj[u].iter());
+            start[u + 1] = target.len();
+        }
+        let reverse = (0..n)
+            .flat_map(|u| (start[u]..start[u + 1]).map(move |a| (u, a)))
+            .map(|(u, a)| {
+                let v = target[a];
+                start[v] + target[start[v]..start[v + 1]].binary_search(&u).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let mut best = (usize::MAX, vec![]);
+        let mut flow = vec![0i8; target.len()];
+        let mut parent = vec![usize::MAX; n];
+        let mut q = VecDeque::new();
+        for t in 1..n {
+            flow.fill(0);
+            for value in 0..best.0 {
+                parent.fill(usize::MAX);
+                parent[0] = 0;
+                q.clear();
+                q.push_back(0);
+                while let Some(u) = q.pop_front() {
+                    for arc in start[u]..start[u + 1] {
+                        let v = target[arc];
+                        if parent[v] == usize::MAX && flow[arc] < 1 {
+                            parent[v] = arc;
+                            q.push_back(v);
+                        }
+                    }
+                    if parent[t] != usize::MAX {
+                        break;
+                    }
+                }
+                if parent[t] == usize::MAX {
+                    let side = (0..n).filter(|&v| parent[v] != usize::MAX).collect();
+                    best = (value, side);
+                    break;
+                }
+                let mut v = t;
+                while v != 0 {
+                    let arc = parent[v];
+                    flow[arc] += 1;
+                    flow[reverse[arc]] -= 1;
+                    v = target[reverse[arc]];
+                }
+            }
+        }
+        if n < 2 {
+            best = (0, (0..n).collect());
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn random_graph(rng: &mut StdRng, n: usize, p: f64) -> Graph {
+        let mut g = Graph::new(n);
+        for u in 0..n {
+            for v in u + 1..n {
+                if rng.random_bool(p) {
+                    g.add_edge(u, v);
+                }
+            }
+        }
+        g
+    }
+
+    fn is_clique(g: &Graph, vs: &[usize]) -> bool {
+        vs.iter()
+            .enumerate()
+            .all(|(i, &u)| vs[i + 1..].iter().all(|&v| g.has_edge(u, v)))
+    }
+
+    fn subsets(n: usize) -> impl Iterator<Item = Vec<usize>> {
+        (0..1u32 << n).map(move |m| (0..n).filter(|&i| m >> i & 1 == 1).collect())
+    }
+
+    fn without(g: &Graph, skip: impl Fn(usize, usize) -> bool) -> Graph {
+        let edges = g.edges().filter(|&(u, v)| !skip(u, v)).collect::<Vec<_>>();
+        Graph::from_edges(g.len(), &edges)
+    }
+
+    #[test]
+    fn bitset() {
+        let mut s = BitSet::new(130);
+        assert!(s.is_empty());
+        assert!(s.insert(3) && s.insert(64) && s.insert(129));
+        assert!(!s.insert(64));
+        assert_eq!(vec![3, 64, 129], s.iter().collect::<Vec<_>>());
+        assert_eq!(130, BitSet::full(130).len());
+        let mut low = BitSet::new(130);
+        (0..10).for_each(|i| {
+            low.insert(i);
+        });
+        assert_eq!(
+            vec![64, 129],
+            BitSet::full(130)
+                .intersection(&s)
+                .difference(&low)
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        assert!(s.remove(3) && !s.contains(3));
+        assert_eq!(2, s.len());
+    }
+
+    #[test]
+    fn small() {
+        // two triangles sharing vertex 2, and a tail 4-5
+        let g = Graph::from_edges(7, &[(0, 1), (1, 2), (0, 2), (2, 3), (3, 4), (2, 4), (4, 5)]);
+        assert_eq!(vec![[0, 1, 2], [2, 3, 4]], g.triangles());
+        assert_eq!(vec![2, 4], g.articulation_points());
+        assert_eq!(vec![(4, 5)], g.bridges());
+        assert_eq!(vec![vec![0, 1, 2, 3, 4, 5], vec![6]], g.components());
+        assert_eq!(3, g.max_clique().len());
+        assert_eq!(0, g.min_cut().0);
+        let mut cliques = vec![];
+        g.maximal_cliques(|c| {
+            let mut c = c.to_vec();
+            c.sort_unstable();
+            cliques.push(c);
+        });
+        cliques.sort_unstable();
+        assert_eq!(
+            vec![vec![0, 1, 2], vec![2, 3, 4], vec![4, 5], vec![6]],
+            cliques
+        );
+    }
+
+    #[test]
+    fn random_cliques() {
+        let mut rng = StdRng::seed_from_u64(37);
+        for _ in 0..100 {
+            let n = rng.random_range(1..12);
+            let p = rng.random_range(0.1..0.9);
+            let g = random_graph(&mut rng, n, p);
+            let cliques = subsets(n).filter(|s| is_clique(&g, s)).collect::<Vec<_>>();
+
+            let largest = g.max_clique();
+            assert!(is_clique(&g, &largest));
+            assert_eq!(
+                cliques.iter().map(|c| c.len()).max().unwrap(),
+                largest.len()
+            );
+
+            let maximal = cliques
+                .iter()
+                .filter(|c| (0..n).all(|v| c.contains(&v) || !c.iter().all(|&u| g.has_edge(u, v))))
+                .count();
+            let mut count = 0;
+            g.maximal_cliques(|c| {
+                assert!(is_clique(&g, c));
+                count += 1;
+            });
+            assert_eq!(maximal, count);
+
+            for k in 0..5 {
+                let mut found = vec![];
+                g.k_cliques(k, |c| found.push(c.to_vec()));
+                found.sort_unstable();
+                let mut expected = cliques
+                    .iter()
+                    .filter(|c| c.len() == k)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                expected.sort_unstable();
+                assert_eq!(expected, found);
+            }
+            let triangles = g.triangles().iter().map(|t| t.to_vec()).collect::<Vec<_>>();
+            let mut expected = vec![];
+            g.k_cliques(3, |c| expected.push(c.to_vec()));
+            assert_eq!(expected.len(), triangles.len());
+
+            let order = g.degeneracy_order();
+            let mut sorted = order.clone();
+            sorted.sort_unstable();
+            assert_eq!((0..n).collect::<Vec<_>>(), sorted);
+        }
+    }
+
+    #[test]
+    fn random_cuts() {
+        let mut rng = StdRng::seed_from_u64(38);
+        for _ in 0..100 {
+            let n = rng.random_range(1..12);
+            let p = rng.random_range(0.1..0.6);
+            let g = random_graph(&mut rng, n, p);
+            let parts = g.components().len();
+
+            let points = (0..n)
+                .filter(|&x| without(&g, |u, v| u == x || v == x).components().len() > parts + 1)
+                .collect::<Vec<_>>();
+            assert_eq!(points, g.articulation_points());
+            let bridges = g
+                .edges()
+                .filter(|&e| without(&g, |u, v| (u, v) == e).components().len() > parts)
+                .collect::<Vec<_>>();
+            assert_eq!(bridges, g.bridges());
+
+            let (value, side) = g.min_cut();
+            if parts > 1 {
+                assert_eq!(0, value);
+            } else if n > 1 {
+                let edges = g.edges().collect::<Vec<_>>();
+                let brute = subsets(n)
+                    .filter(|s| s.contains(&0) && s.len() < n)
+                    .map(|s| {
+                        edges
+                            .iter()
+                            .filter(|e| s.contains(&e.0) != s.contains(&e.1))
+                            .count()
+                    })
+                    .min()
+                    .unwrap();
+                assert_eq!(brute, value);
+                let crossing = edges
+                    .iter()
+                    .filter(|e| side.contains(&e.0) != side.contains(&e.1));
+                assert_eq!(value, crossing.count());
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Axis-aligned boxes in `N` dimensions, for carving a space of inputs up
+//! into the pieces that take each branch of some rule.
+
+/// Half-open on every axis: `lo[i] <= x[i] < hi[i]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HyperBox<const N: usize> {
+    pub lo: [i64; N],
+    pub hi: [i64; N],
+}
+
+impl<const N: usize> HyperBox<N> {
+    pub fn new(lo: [i64; N], hi: [i64; N]) -> Self {
+        Self { lo, hi }
+    }
+
+    /// The same `lo..hi` on every axis.
+    pub fn cube(lo: i64, hi: i64) -> Self {
+        Self::new([lo; N], [hi; N])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|i| self.lo[i] >= self.hi[i])
+    }
+
+    pub fn volume(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..N).map(|i| (self.hi[i] - self.lo[i]) as u64).product()
+    }
+
+    pub fn contains(&self, point: &[i64; N]) -> bool {
+        (0..N).all(|i| self.lo[i] <= point[i] && point[i] < self.hi[i])
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let b = Self::new(
+            std::array::from_fn(|i| self.lo[i].max(other.lo[i])),
+            std::array::from_fn(|i| self.hi[i].min(other.hi[i])),
+        );
+        (!b.is_empty()).then_some(b)
+    }
+
+    /// The parts below and at-or-above `at` along `axis`, either of which
+    /// may be empty.
+    pub fn split(&self, axis: usize, at: i64) -> (Option<Self>, Option<Self>) {
+        let at = at.clamp(self.lo[axis], self.hi[axis]);
+        let (mut below, mut above) = (*self, *self);
+        below.hi[axis] = at;
+        above.lo[axis] = at;
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// Up to `2 * N` disjoint boxes covering `self` without `other`.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if self.intersection(other).is_none() {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        }
+        let mut pieces = vec![];
+        let mut rest = *self;
+        for axis in 0..N {
+            let (below, middle) = rest.split(axis, other.lo[axis]);
+            pieces.extend(below);
+            let (middle, above) = middle.unwrap().split(axis, other.hi[axis]);
+            pieces.extend(above);
+            rest = middle.unwrap();
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn basics() {
+        let b = HyperBox::new([0, 0], [3, 4]);
+        assert_eq!(12, b.volume());
+        assert!(b.contains(&[2, 3]));
+        assert!(!b.contains(&[3, 0]));
+        assert_eq!((None, Some(b)), b.split(0, -5));
+        let (below, above) = b.split(1, 1);
+        assert_eq!(Some(HyperBox::new([0, 0], [3, 1])), below);
+        assert_eq!(Some(HyperBox::new([0, 1], [3, 4])), above);
+        assert_eq!(None, b.intersection(&HyperBox::new([3, 0], [5, 5])));
+        assert_eq!(0, HyperBox::new([2, 0], [1, 5]).volume());
+    }
+
+    #[test]
+    fn random_subtract() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let random_box = |rng: &mut StdRng| {
+            let lo: [i64; 3] = std::array::from_fn(|_| rng.random_range(0..6));
+            let hi = std::array::from_fn(|i| lo[i] + rng.random_range(0..5));
+            HyperBox::new(lo, hi)
+        };
+        for _ in 0..200 {
+            let (a, b) = (random_box(&mut rng), random_box(&mut rng));
+            let pieces = a.subtract(&b);
+            for x in 0..12 {
+                for y in 0..12 {
+                    for z in 0..12 {
+                        let p = [x, y, z];
+                        let expected = a.contains(&p) && !b.contains(&p);
+                        let hits = pieces.iter().filter(|c| c.contains(&p)).count();
+                        assert_eq!(expected as usize, hits);
+                    }
+                }
+            }
+            let overlap = a.intersection(&b).map_or(0, |c| c.volume());
+            let total = pieces.iter().map(|c| c.volume()).sum::<u64>();
+            assert_eq!(a.volume() - overlap, total);
+        }
+    }
+}
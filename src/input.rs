@@ -0,0 +1,72 @@
+//! Access to puzzle inputs under `data/`, which aren't checked in, and
+//! answers under `answers/`. Tests go through here so a missing file is a
+//! skip rather than a panic.
+
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// The contents of `path`, or `None` after printing a skip notice when it
+/// doesn't exist. Any other I/O error still panics.
+pub fn read_or_skip(path: impl AsRef<Path>) -> Option<String> {
+    let path = path.as_ref();
+    match std::fs::read_to_string(path) {
+        Ok(s) => Some(s),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            println!("skipped: no input ({})", path.display());
+            None
+        }
+        Err(e) => panic!("{}: {e}", path.display()),
+    }
+}
+
+/// Expected answers from an `answers/YEAR/dayN` file, one `partN: value`
+/// per line. Blank lines and lines starting with `#` are ignored.
+pub fn parse_answers(s: &str) -> Result<Vec<(u8, &str)>, String> {
+    s.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("expected `partN: value`, got {line:?}"))?;
+            match key.trim() {
+                "part1" => Ok((1, value.trim())),
+                "part2" => Ok((2, value.trim())),
+                key => Err(format!("unknown key {key:?}")),
+            }
+        })
+        .collect()
+}
+
+/// The answer a solution printed for `part`, taken from the first output
+/// line containing `partN:` (e.g. `day 3 part1: 42`).
+pub fn printed_answer(stdout: &str, part: u8) -> Option<&str> {
+    let key = format!("part{part}:");
+    stdout
+        .lines()
+        .find_map(|line| line.split_once(&key))
+        .map(|(_, value)| value.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() {
+        let file = "# 2024 day 17\npart1: 4,6,3\n\npart2:  1234 \n";
+        assert_eq!(Ok(vec![(1, "4,6,3"), (2, "1234")]), parse_answers(file));
+        assert!(parse_answers("part3: 1").is_err());
+        assert!(parse_answers("42").is_err());
+
+        let stdout = "day 3:\nday 3 part1: 42\npart2: abc\ntime: 1ms\n";
+        assert_eq!(Some("42"), printed_answer(stdout, 1));
+        assert_eq!(Some("abc"), printed_answer(stdout, 2));
+        assert_eq!(None, printed_answer("time: 1ms", 1));
+    }
+
+    #[test]
+    fn missing() {
+        assert_eq!(None, read_or_skip("data/1999/day0"));
+    }
+}
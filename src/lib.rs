@@ -0,0 +1,14 @@
+pub mod cycle;
+pub mod dot;
+pub mod dsu;
+pub mod exhaustive;
+pub mod fuzz;
+pub mod graph;
+pub mod hyperbox;
+pub mod input;
+pub mod linalg;
+pub mod math;
+pub mod ocr;
+pub mod parsing;
+pub mod rational;
+pub mod search;
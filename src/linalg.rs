@@ -0,0 +1,265 @@
+//! Exact linear algebra over [`Rational`], and a small integer linear
+//! programming solver built on it.
+
+use crate::rational::Rational;
+
+/// Brings `a` to reduced row echelon form, eliminating over its first
+/// `cols` columns only (the rest, e.g. right-hand sides, just follow along).
+/// Returns the pivot column of every non-zero row, in row order.
+pub fn rref(a: &mut [Vec<Rational>], cols: usize) -> Vec<usize> {
+    let mut pivots = vec![];
+    let mut row = 0;
+    for col in 0..cols {
+        if row == a.len() {
+            break;
+        }
+        let Some(p) = (row..a.len()).find(|&r| !a[r][col].is_zero()) else {
+            continue;
+        };
+        a.swap(row, p);
+        let d = a[row][col].recip();
+        a[row].iter_mut().for_each(|e| *e *= d);
+        let pivot_row = a[row].clone();
+        for (i, r) in a.iter_mut().enumerate() {
+            let f = r[col];
+            if i != row && !f.is_zero() {
+                r.iter_mut().zip(&pivot_row).for_each(|(e, &p)| *e -= f * p);
+            }
+        }
+        pivots.push(col);
+        row += 1;
+    }
+    pivots
+}
+
+/// The unique solution of `a · x = b`, if there is one.
+pub fn solve(a: &[Vec<Rational>], b: &[Rational]) -> Option<Vec<Rational>> {
+    let n = a.first().map_or(0, |r| r.len());
+    let mut m = a
+        .iter()
+        .zip(b)
+        .map(|(r, &b)| {
+            let mut r = r.clone();
+            r.push(b);
+            r
+        })
+        .collect::<Vec<_>>();
+    let pivots = rref(&mut m, n);
+    if pivots.len() < n || m[n..].iter().any(|r| !r[n].is_zero()) {
+        return None;
+    }
+    Some(m[..n].iter().map(|r| r[n]).collect())
+}
+
+/// Branch-and-bound over the free variables of the echelon form. Every pivot
+/// variable is `rhs - coefs · free`, the cost is `c0 + weights · free`.
+struct Ilp {
+    upper: Vec<i64>,
+    pivot_upper: Vec<i64>,
+    rows: Vec<(Rational, Vec<Rational>)>,
+    weights: Vec<Rational>,
+    /// Smallest possible cost of the free variables from `i` on.
+    suffix_min: Vec<Rational>,
+    free: Vec<i64>,
+    c0: Rational,
+    best: Option<(Rational, Vec<i64>)>,
+}
+
+impl Ilp {
+    fn dfs(&mut self, idx: usize, cost: Rational) {
+        if let Some((best, _)) = &self.best
+            && self.c0 + cost + self.suffix_min[idx] >= *best
+        {
+            return;
+        }
+
+        if idx == self.free.len() {
+            let mut pivots = Vec::with_capacity(self.rows.len());
+            for ((rhs, coefs), &upper) in self.rows.iter().zip(&self.pivot_upper) {
+                let mut v = *rhs;
+                for (&c, &x) in coefs.iter().zip(&self.free) {
+                    v -= c * Rational::from(x);
+                }
+                match v.to_integer() {
+                    Some(v) if v >= 0 && v <= upper as i128 => pivots.push(v as i64),
+                    _ => return,
+                }
+            }
+            self.best = Some((
+                self.c0 + cost,
+                pivots.into_iter().chain(self.free.clone()).collect(),
+            ));
+            return;
+        }
+
+        // every pivot variable must stay within its bounds for some choice
+        // of the free variables after `idx`
+        let mut hi = self.upper[idx] as i128;
+        for (rhs, coefs) in &self.rows {
+            let mut resid = *rhs;
+            for (&c, &x) in coefs.iter().zip(&self.free).take(idx) {
+                resid -= c * Rational::from(x);
+            }
+            let mut others_min = Rational::ZERO;
+            for (&c, &u) in coefs.iter().zip(&self.upper).skip(idx + 1) {
+                if c < Rational::ZERO {
+                    others_min += c * Rational::from(u);
+                }
+            }
+            let c = coefs[idx];
+            let self_min = c.min(Rational::ZERO) * Rational::from(self.upper[idx]);
+            if resid < others_min + self_min {
+                return;
+            }
+            if c > Rational::ZERO {
+                hi = hi.min(((resid - others_min) / c).floor());
+            }
+        }
+        for v in 0..=hi as i64 {
+            self.free[idx] = v;
+            self.dfs(idx + 1, cost + self.weights[idx] * Rational::from(v));
+        }
+        self.free[idx] = 0;
+    }
+}
+
+/// Minimizes `c · x` over integer vectors `0 <= x <= upper` with
+/// `a · x == b`. Returns the minimum and a vector reaching it.
+pub fn ilp_min(a: &[Vec<i64>], b: &[i64], c: &[i64], upper: &[i64]) -> Option<(i64, Vec<i64>)> {
+    let n = c.len();
+    let mut m = a
+        .iter()
+        .zip(b)
+        .map(|(r, &b)| r.iter().chain([&b]).map(|&e| Rational::from(e)).collect())
+        .collect::<Vec<Vec<_>>>();
+    let pivots = rref(&mut m, n);
+    if m[pivots.len()..].iter().any(|r| !r[n].is_zero()) {
+        return None;
+    }
+
+    // free variables with the smallest range first
+    let mut free = (0..n).filter(|j| !pivots.contains(j)).collect::<Vec<_>>();
+    free.sort_unstable_by_key(|&j| upper[j]);
+    let rows = (0..pivots.len())
+        .map(|i| (m[i][n], free.iter().map(|&j| m[i][j]).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    // substitute the pivot variables into the cost
+    let cost = |j: usize| Rational::from(c[j]);
+    let c0 = pivots
+        .iter()
+        .zip(&rows)
+        .fold(Rational::ZERO, |s, (&p, row)| s + cost(p) * row.0);
+    let weights = free
+        .iter()
+        .enumerate()
+        .map(|(k, &j)| {
+            pivots
+                .iter()
+                .zip(&rows)
+                .fold(cost(j), |s, (&p, row)| s - cost(p) * row.1[k])
+        })
+        .collect::<Vec<_>>();
+    let upper_free = free.iter().map(|&j| upper[j]).collect::<Vec<_>>();
+    let mut suffix_min = vec![Rational::ZERO; free.len() + 1];
+    for k in (0..free.len()).rev() {
+        let w = weights[k] * Rational::from(upper_free[k]);
+        suffix_min[k] = suffix_min[k + 1] + w.min(Rational::ZERO);
+    }
+
+    let mut ilp = Ilp {
+        upper: upper_free,
+        pivot_upper: pivots.iter().map(|&p| upper[p]).collect(),
+        rows,
+        weights,
+        suffix_min,
+        free: vec![0; free.len()],
+        c0,
+        best: None,
+    };
+    ilp.dfs(0, Rational::ZERO);
+
+    let (best, values) = ilp.best?;
+    let mut x = vec![0; n];
+    for (&j, v) in pivots.iter().chain(&free).zip(values) {
+        x[j] = v;
+    }
+    Some((best.to_integer().unwrap() as i64, x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn brute(a: &[Vec<i64>], b: &[i64], c: &[i64], upper: &[i64]) -> Option<i64> {
+        let mut x = vec![0; c.len()];
+        let mut best = None;
+        loop {
+            let fits = a
+                .iter()
+                .zip(b)
+                .all(|(r, &b)| r.iter().zip(&x).map(|(a, x)| a * x).sum::<i64>() == b);
+            if fits {
+                let cost = c.iter().zip(&x).map(|(c, x)| c * x).sum::<i64>();
+                best = Some(best.map_or(cost, |b: i64| b.min(cost)));
+            }
+            // next vector in the box, little-endian
+            let Some(i) = (0..x.len()).find(|&i| x[i] < upper[i]) else {
+                return best;
+            };
+            x[i] += 1;
+            x[..i].fill(0);
+        }
+    }
+
+    #[test]
+    fn solve_unique() {
+        let r = |v: &[i64]| v.iter().map(|&e| Rational::from(e)).collect::<Vec<_>>();
+        let a = vec![r(&[2, 1, -1]), r(&[-3, -1, 2]), r(&[-2, 1, 2])];
+        assert_eq!(Some(r(&[2, 3, -1])), solve(&a, &r(&[8, -11, -3])));
+        let singular = vec![r(&[1, 2]), r(&[2, 4])];
+        assert_eq!(None, solve(&singular, &r(&[3, 6])));
+        let x = solve(&[r(&[3])], &r(&[1])).unwrap();
+        assert_eq!(Rational::new(1, 3), x[0]);
+    }
+
+    #[test]
+    fn random_machines() {
+        let mut rng = StdRng::seed_from_u64(35);
+        for _ in 0..300 {
+            let (m, n) = (rng.random_range(1..5), rng.random_range(1..6));
+            let a = (0..m)
+                .map(|_| (0..n).map(|_| rng.random_range(0..2)).collect())
+                .collect::<Vec<Vec<i64>>>();
+            let b = if rng.random_bool(0.8) {
+                let x = (0..n).map(|_| rng.random_range(0..4)).collect::<Vec<i64>>();
+                a.iter()
+                    .map(|r| r.iter().zip(&x).map(|(a, x)| a * x).sum())
+                    .collect()
+            } else {
+                (0..m).map(|_| rng.random_range(0..8)).collect::<Vec<i64>>()
+            };
+            let c = (0..n).map(|_| rng.random_range(1..4)).collect::<Vec<i64>>();
+            let upper = (0..n)
+                .map(|j| {
+                    (0..m)
+                        .filter(|&i| a[i][j] > 0)
+                        .map(|i| b[i])
+                        .min()
+                        .unwrap_or(7)
+                })
+                .collect::<Vec<_>>();
+
+            let expected = brute(&a, &b, &c, &upper);
+            let result = ilp_min(&a, &b, &c, &upper);
+            assert_eq!(expected, result.as_ref().map(|r| r.0), "{a:?} {b:?} {c:?}");
+            if let Some((cost, x)) = result {
+                assert!(x.iter().zip(&upper).all(|(&x, &u)| 0 <= x && x <= u));
+                assert_eq!(cost, c.iter().zip(&x).map(|(c, x)| c * x).sum::<i64>());
+                for (r, &b) in a.iter().zip(&b) {
+                    assert_eq!(b, r.iter().zip(&x).map(|(a, x)| a * x).sum::<i64>());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,605 @@
+//! Number theory over every primitive integer width.
+//!
+//! Modular arithmetic is carried out on `u128` magnitudes, so intermediate
+//! products never overflow whatever the width of the caller's integers.
+
+use std::fmt::Debug;
+
+pub trait Int: Copy + Ord + Debug {
+    const ZERO: Self;
+
+    fn is_negative(self) -> bool;
+    fn magnitude(self) -> u128;
+    fn from_magnitude(v: u128) -> Option<Self>;
+}
+
+pub trait Signed: Int {
+    fn to_i128(self) -> i128;
+    fn from_i128(v: i128) -> Option<Self>;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl Int for $t {
+            const ZERO: Self = 0;
+
+            fn is_negative(self) -> bool {
+                false
+            }
+
+            fn magnitude(self) -> u128 {
+                self as u128
+            }
+
+            fn from_magnitude(v: u128) -> Option<Self> {
+                Self::try_from(v).ok()
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl Int for $t {
+            const ZERO: Self = 0;
+
+            fn is_negative(self) -> bool {
+                self < 0
+            }
+
+            fn magnitude(self) -> u128 {
+                self.unsigned_abs() as u128
+            }
+
+            fn from_magnitude(v: u128) -> Option<Self> {
+                Self::try_from(v).ok()
+            }
+        }
+
+        impl Signed for $t {
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128(v: i128) -> Option<Self> {
+                Self::try_from(v).ok()
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    loop {
+        b >>= b.trailing_zeros();
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= a;
+        if b == 0 {
+            return a << shift;
+        }
+    }
+}
+
+/// Non-negative greatest common divisor, `gcd(0, 0) == 0`.
+pub fn gcd<T: Int>(a: T, b: T) -> T {
+    T::from_magnitude(gcd_u128(a.magnitude(), b.magnitude())).expect("gcd overflow")
+}
+
+/// Non-negative least common multiple, panics if it does not fit in `T`.
+pub fn lcm<T: Int>(a: T, b: T) -> T {
+    let (a, b) = (a.magnitude(), b.magnitude());
+    if a == 0 || b == 0 {
+        return T::ZERO;
+    }
+    (a / gcd_u128(a, b))
+        .checked_mul(b)
+        .and_then(T::from_magnitude)
+        .expect("lcm overflow")
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a.to_i128(), b.to_i128());
+    let (mut s0, mut s1) = (1i128, 0i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 < 0 {
+        (r0, s0, t0) = (-r0, -s0, -t0);
+    }
+    let from = |v| T::from_i128(v).expect("extended_gcd overflow");
+    (from(r0), from(s0), from(t0))
+}
+
+/// Full 256-bit product as `(high, low)`.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (s, overflow) = a.overflowing_add(b);
+    if overflow || s >= m {
+        s.wrapping_sub(m)
+    } else {
+        s
+    }
+}
+
+fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b { a - b } else { a + (m - b) }
+}
+
+fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u64::MAX as u128 {
+        return a * b % m;
+    }
+    let (mut a, mut b, mut r) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            r = add_mod(r, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    r
+}
+
+fn pow_mod_u128(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    if m > u64::MAX as u128 && m & 1 == 1 {
+        return Montgomery::new(m).pow(base, exp);
+    }
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u128(result, base, m);
+        }
+        base = mul_mod_u128(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+fn inv_mod_u128(a: u128, m: u128) -> Option<u128> {
+    let (mut r0, mut r1) = (m, a % m);
+    let (mut t0, mut t1) = (0, 1 % m);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, sub_mod(t0, mul_mod_u128(q % m, t1, m), m));
+    }
+    (r0 == 1).then_some(t0)
+}
+
+/// Montgomery multiplication modulo an odd `u128`.
+///
+/// Values passed to [`Montgomery::mul`] are in Montgomery form, convert with
+/// [`Montgomery::to_mont`] and [`Montgomery::from_mont`].
+#[derive(Debug, Clone, Copy)]
+pub struct Montgomery {
+    m: u128,
+    neg_inv: u128,
+    r2: u128,
+}
+
+impl Montgomery {
+    pub fn new(m: u128) -> Self {
+        assert!(m & 1 == 1, "Montgomery modulus must be odd");
+        // Newton iteration, every step doubles the number of correct low bits
+        let mut inv = m;
+        for _ in 0..7 {
+            inv = inv.wrapping_mul(2u128.wrapping_sub(m.wrapping_mul(inv)));
+        }
+        let r = (u128::MAX % m + 1) % m;
+        Self {
+            m,
+            neg_inv: inv.wrapping_neg(),
+            r2: mul_mod_u128(r, r, m),
+        }
+    }
+
+    fn redc(&self, (hi, lo): (u128, u128)) -> u128 {
+        let u = lo.wrapping_mul(self.neg_inv);
+        let (uh, ul) = mul_wide(u, self.m);
+        let carry = lo.overflowing_add(ul).1 as u128;
+        let (t, o1) = hi.overflowing_add(uh);
+        let (t, o2) = t.overflowing_add(carry);
+        if o1 || o2 || t >= self.m {
+            t.wrapping_sub(self.m)
+        } else {
+            t
+        }
+    }
+
+    pub fn to_mont(&self, a: u128) -> u128 {
+        self.redc(mul_wide(a % self.m, self.r2))
+    }
+
+    pub fn from_mont(&self, a: u128) -> u128 {
+        self.redc((0, a))
+    }
+
+    pub fn mul(&self, a: u128, b: u128) -> u128 {
+        self.redc(mul_wide(a, b))
+    }
+
+    /// `a * b % m` for plain (non-Montgomery) operands.
+    pub fn mul_mod(&self, a: u128, b: u128) -> u128 {
+        self.mul(self.mul(a % self.m, b % self.m), self.r2)
+    }
+
+    /// `base.pow(exp) % m` for a plain operand.
+    pub fn pow(&self, base: u128, mut exp: u128) -> u128 {
+        let mut base = self.to_mont(base);
+        let mut result = self.to_mont(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        self.from_mont(result)
+    }
+}
+
+fn modulus<T: Int>(m: T) -> u128 {
+    assert!(m > T::ZERO, "modulus must be positive, got {m:?}");
+    m.magnitude()
+}
+
+fn residue<T: Int>(a: T, m: u128) -> u128 {
+    let r = a.magnitude() % m;
+    if a.is_negative() && r != 0 { m - r } else { r }
+}
+
+fn narrow<T: Int>(v: u128) -> T {
+    T::from_magnitude(v).unwrap()
+}
+
+/// `a` reduced into `[0, m)`.
+pub fn mod_reduce<T: Int>(a: T, m: T) -> T {
+    let m = modulus(m);
+    narrow(residue(a, m))
+}
+
+pub fn mod_add<T: Int>(a: T, b: T, m: T) -> T {
+    let m = modulus(m);
+    narrow(add_mod(residue(a, m), residue(b, m), m))
+}
+
+pub fn mod_sub<T: Int>(a: T, b: T, m: T) -> T {
+    let m = modulus(m);
+    narrow(sub_mod(residue(a, m), residue(b, m), m))
+}
+
+pub fn mod_mul<T: Int>(a: T, b: T, m: T) -> T {
+    let m = modulus(m);
+    narrow(mul_mod_u128(residue(a, m), residue(b, m), m))
+}
+
+/// Multiplicative inverse of `a` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inv<T: Int>(a: T, m: T) -> Option<T> {
+    let m = modulus(m);
+    inv_mod_u128(residue(a, m), m).map(narrow)
+}
+
+/// `base.pow(exp) % m`, a negative `exp` raises the inverse of `base`.
+///
+/// Panics if `exp` is negative and `base` is not invertible.
+pub fn mod_pow<T: Int>(base: T, exp: T, m: T) -> T {
+    let m = modulus(m);
+    let mut base = residue(base, m);
+    if exp.is_negative() {
+        base = inv_mod_u128(base, m).expect("base is not invertible");
+    }
+    narrow(pow_mod_u128(base, exp.magnitude(), m))
+}
+
+/// Solves a system of congruences `x ≡ r (mod m)` for `(r, m)` pairs.
+///
+/// The moduli do not need to be pairwise coprime. Returns `(x, lcm)` with
+/// `0 <= x < lcm` of all moduli, or `None` if the system is inconsistent or
+/// the combined modulus does not fit in `T`.
+pub fn crt<T: Int>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let (mut x, mut l) = (0u128, 1u128);
+    for (r, m) in congruences {
+        let m = modulus(m);
+        let r = residue(r, m);
+        let g = gcd_u128(l, m);
+        let diff = sub_mod(r, x % m, m);
+        if !diff.is_multiple_of(g) {
+            return None;
+        }
+        let mg = m / g;
+        let k = mul_mod_u128(diff / g % mg, inv_mod_u128(l / g % mg, mg)?, mg);
+        let lcm = l.checked_mul(mg)?;
+        // k < m / g, so x + l * k < lcm(l, m)
+        x += l * k;
+        l = lcm;
+    }
+    Some((T::from_magnitude(x)?, T::from_magnitude(l)?))
+}
+
+/// The map `x -> a * x + b` modulo `m`, as built by linear congruential
+/// shuffles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Affine<T> {
+    pub a: T,
+    pub b: T,
+    pub m: T,
+}
+
+impl<T: Int> Affine<T> {
+    pub fn new(a: T, b: T, m: T) -> Self {
+        Self {
+            a: mod_reduce(a, m),
+            b: mod_reduce(b, m),
+            m,
+        }
+    }
+
+    pub fn identity(m: T) -> Self {
+        Self {
+            a: narrow(1 % modulus(m)),
+            b: T::ZERO,
+            m,
+        }
+    }
+
+    fn parts(&self) -> (u128, u128, u128) {
+        (self.a.magnitude(), self.b.magnitude(), modulus(self.m))
+    }
+
+    fn from_parts((a, b, m): (u128, u128, u128)) -> Self {
+        Self {
+            a: narrow(a),
+            b: narrow(b),
+            m: narrow(m),
+        }
+    }
+
+    pub fn apply(&self, x: T) -> T {
+        let (a, b, m) = self.parts();
+        narrow(add_mod(mul_mod_u128(a, residue(x, m), m), b, m))
+    }
+
+    /// The map applying `self` first, then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        assert_eq!(self.m, next.m, "moduli differ");
+        let (a, b, m) = self.parts();
+        let (c, d, _) = next.parts();
+        Self::from_parts((
+            mul_mod_u128(c, a, m),
+            add_mod(mul_mod_u128(c, b, m), d, m),
+            m,
+        ))
+    }
+
+    /// Exists when `a` and `m` are coprime.
+    pub fn inverse(&self) -> Option<Self> {
+        let (a, b, m) = self.parts();
+        let inv = inv_mod_u128(a, m)?;
+        Some(Self::from_parts((
+            inv,
+            sub_mod(0, mul_mod_u128(inv, b, m), m),
+            m,
+        )))
+    }
+
+    /// `self` applied `k` times, a negative `k` applies the inverse.
+    ///
+    /// Panics if `k` is negative and the map is not invertible.
+    pub fn pow(&self, k: T) -> Self {
+        let mut base = if k.is_negative() {
+            self.inverse().expect("map is not invertible")
+        } else {
+            *self
+        };
+        let mut result = Self::identity(self.m);
+        let mut k = k.magnitude();
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result.then(&base);
+            }
+            base = base.then(&base);
+            k >>= 1;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn naive_mul_mod(a: u128, b: u128, m: u128) -> u128 {
+        // bitwise long division of the 256-bit product
+        let (hi, lo) = mul_wide(a, b);
+        let mut r = 0u128;
+        for i in (0..256).rev() {
+            let bit = if i >= 128 { hi >> (i - 128) } else { lo >> i } & 1;
+            let carry = r >> 127;
+            r = (r << 1) | bit;
+            if carry == 1 || r >= m {
+                r = r.wrapping_sub(m);
+            }
+        }
+        r
+    }
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(6, gcd(48i8, -18));
+        assert_eq!(6, gcd(-48i64, -18));
+        assert_eq!(5, gcd(0u32, 5));
+        assert_eq!(0, gcd(0usize, 0));
+        assert_eq!(144, lcm(48u16, 18));
+        assert_eq!(0, lcm(0i32, 7));
+        assert_eq!(u128::MAX, lcm(u128::MAX, 5));
+        assert_eq!(1 << 62, gcd(i64::MIN, 1 << 62));
+    }
+
+    #[test]
+    #[should_panic(expected = "lcm overflow")]
+    fn lcm_overflow() {
+        lcm(200u8, 3);
+    }
+
+    #[test]
+    fn egcd() {
+        assert_eq!((2, -9, 47), extended_gcd(240, 46));
+        assert_eq!((1, 4, -31), extended_gcd(101, 13));
+        assert_eq!((1, 13, -9), extended_gcd(25, 36));
+        assert_eq!((4, 0, -1), extended_gcd(0i8, -4));
+    }
+
+    #[test]
+    fn modular() {
+        assert_eq!(4, mod_reduce(-3i8, 7));
+        assert_eq!(1, mod_add(120i8, 9, 8));
+        assert_eq!(6, mod_sub(1u8, 3, 8));
+        assert_eq!(445, mod_pow(4u64, 13, 497));
+        assert_eq!(Some(4), mod_inv(3i32, 11));
+        assert_eq!(None, mod_inv(4i32, 12));
+        assert_eq!(9, mod_pow(3i32, -1, 13));
+        let p = 170141183460469231731687303715884105727u128; // 2^127 - 1
+        assert_eq!(1, mod_pow(12345, p - 1, p));
+        assert_eq!(p - 1, mod_mul(p - 1, 1, p));
+        assert_eq!(1, mod_mul(p - 1, p - 1, p));
+        assert_eq!(1, mod_mul(u128::MAX - 1, u128::MAX - 1, u128::MAX));
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(Some((23, 105)), crt([(2, 3), (3, 5), (2, 7)]));
+        assert_eq!(Some((10, 12)), crt([(2u8, 4), (4, 6)]));
+        assert_eq!(None, crt([(1u8, 4), (2, 6)]));
+        assert_eq!(Some((0, 1)), crt::<i32>([]));
+        assert_eq!(Some((4, 7)), crt([(-3i64, 7)]));
+        assert_eq!(None, crt([(0u8, 16), (0, 17)]));
+        assert_eq!(None, crt([(1u128, u128::MAX), (0, 2)]));
+    }
+
+    #[test]
+    fn random_mul_pow_inv() {
+        let mut rng = StdRng::seed_from_u64(27);
+        for _ in 0..2000 {
+            let m = match rng.random_range(0..3) {
+                0 => rng.random_range(1..1000u128),
+                1 => rng.random_range(1..=u64::MAX as u128),
+                _ => rng.random_range(1..=u128::MAX),
+            };
+            let (a, b) = (rng.random::<u128>() % m, rng.random::<u128>() % m);
+            let ab = naive_mul_mod(a, b, m);
+            assert_eq!(ab, mod_mul(a, b, m));
+            if m & 1 == 1 {
+                assert_eq!(ab, Montgomery::new(m).mul_mod(a, b));
+            }
+            let e = rng.random_range(0..64u128);
+            let mut p = 1 % m;
+            (0..e).for_each(|_| p = naive_mul_mod(p, a, m));
+            assert_eq!(p, mod_pow(a, e, m));
+            match mod_inv(a, m) {
+                Some(inv) => assert_eq!(1 % m, naive_mul_mod(a, inv, m)),
+                None => assert_ne!(1, gcd(a, m)),
+            }
+        }
+    }
+
+    #[test]
+    fn random_narrow_widths() {
+        let mut rng = StdRng::seed_from_u64(8);
+        for _ in 0..2000 {
+            let (a, b) = (rng.random::<i8>(), rng.random::<i8>());
+            let m = rng.random_range(1..=i8::MAX);
+            let expected = (a as i32 * b as i32).rem_euclid(m as i32) as i8;
+            assert_eq!(expected, mod_mul(a, b, m));
+            let g = gcd(a as i32, b as i32);
+            if g <= i8::MAX as i32 {
+                assert_eq!(g as i8, gcd(a, b));
+            }
+            let (g, x, y) = extended_gcd(a as i64, b as i64);
+            assert_eq!(g, a as i64 * x + b as i64 * y);
+            assert_eq!(g, gcd(a as i64, b as i64));
+        }
+    }
+
+    #[test]
+    fn random_crt() {
+        let mut rng = StdRng::seed_from_u64(14);
+        for _ in 0..1000 {
+            let n = rng.random_range(1..4);
+            let ms = (0..n)
+                .map(|_| rng.random_range(1..30u64))
+                .collect::<Vec<_>>();
+            let x = rng.random_range(0..100_000u64);
+            let system = ms.iter().map(|&m| (x % m, m)).collect::<Vec<_>>();
+            let l = ms.iter().fold(1, |l, &m| lcm(l, m));
+            assert_eq!(Some((x % l, l)), crt(system.iter().copied()));
+
+            let mut bad = system.clone();
+            bad.push((x % 5 + 1, 6));
+            let brute = (0..l * 6).find(|y| bad.iter().all(|&(r, m)| y % m == r));
+            assert_eq!(brute, crt(bad).map(|(x, _)| x));
+        }
+    }
+
+    #[test]
+    fn affine() {
+        let m = u128::MAX - 158; // prime
+        let f = Affine::new(u128::MAX - 1000, 12345, m);
+        let g = Affine::new(3, m - 7, m);
+        let x = 1u128 << 100;
+        assert_eq!(g.apply(f.apply(x)), f.then(&g).apply(x));
+        assert_eq!(x, f.inverse().unwrap().apply(f.apply(x)));
+        assert_eq!(f.apply(f.apply(f.apply(x))), f.pow(3).apply(x));
+        assert_eq!(Affine::identity(m), f.pow(0));
+
+        let h = Affine::new(-3i64, 5, 11);
+        assert_eq!(Affine { a: 8, b: 5, m: 11 }, h);
+        assert_eq!(h.inverse(), Some(h.pow(-1)));
+        assert_eq!(Affine::identity(11), h.pow(10).then(&h.pow(-10)));
+        assert_eq!(None, Affine::new(4, 1, 10).inverse());
+    }
+
+    #[test]
+    fn random_affine() {
+        let mut rng = StdRng::seed_from_u64(33);
+        for _ in 0..1000 {
+            let m = rng.random_range(1..50i64);
+            let f = Affine::new(rng.random::<i64>(), rng.random::<i64>(), m);
+            let x = rng.random_range(-100..100i64);
+            let k = rng.random_range(0..20);
+            let mut y = x.rem_euclid(m);
+            for _ in 0..k {
+                y = (f.a * y + f.b) % m;
+            }
+            assert_eq!(y, f.pow(k).apply(x));
+            if let Some(inv) = f.inverse() {
+                assert_eq!(x.rem_euclid(m), inv.pow(k).apply(y));
+                assert_eq!(inv.pow(k), f.pow(-k));
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Reads the block letters some puzzles draw as their answer.
+//!
+//! Two fonts are known, 6 pixels tall (2019 day 8 and 11, 2021 day 13,
+//! 2022 day 10) and 10 pixels tall (2018 day 10). Letters are separated by
+//! at least one blank column.
+
+use std::fmt;
+
+const FONT_6: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const FONT_10: [(char, &str); 15] = [
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    Empty,
+    UnsupportedHeight(usize),
+    UnknownGlyph { column: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Empty => write!(f, "no lit pixels"),
+            OcrError::UnsupportedHeight(h) => write!(f, "no font is {h} pixels tall"),
+            OcrError::UnknownGlyph { column, glyph } => {
+                write!(f, "unknown glyph at column {column}:\n{glyph}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+fn render(pixels: &[Vec<bool>], cols: std::ops::Range<usize>) -> String {
+    pixels
+        .iter()
+        .map(|row| {
+            row[cols.clone()]
+                .iter()
+                .map(|&p| if p { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Recognizes the letters in a grid of pixels given as rows.
+///
+/// Blank rows and columns around the text are ignored.
+pub fn recognize(pixels: &[Vec<bool>]) -> Result<String, OcrError> {
+    let lit_row = |r: &Vec<bool>| r.iter().any(|&p| p);
+    let top = pixels.iter().position(lit_row).ok_or(OcrError::Empty)?;
+    let bottom = pixels.iter().rposition(lit_row).unwrap();
+    let pixels = &pixels[top..=bottom];
+    let font: &[(char, &str)] = match pixels.len() {
+        6 => &FONT_6,
+        10 => &FONT_10,
+        h => return Err(OcrError::UnsupportedHeight(h)),
+    };
+    let width = pixels.iter().map(|r| r.len()).max().unwrap();
+    let pixels = pixels
+        .iter()
+        .map(|r| {
+            let mut r = r.clone();
+            r.resize(width, false);
+            r
+        })
+        .collect::<Vec<_>>();
+    let lit_col = |j: usize| pixels.iter().any(|r| r[j]);
+
+    let mut text = String::new();
+    let mut j = 0;
+    while j < width {
+        if !lit_col(j) {
+            j += 1;
+            continue;
+        }
+        let start = j;
+        while j < width && lit_col(j) {
+            j += 1;
+        }
+        let glyph = render(&pixels, start..j);
+        match font.iter().find(|(_, g)| *g == glyph) {
+            Some(&(c, _)) => text.push(c),
+            None => {
+                return Err(OcrError::UnknownGlyph {
+                    column: start,
+                    glyph,
+                });
+            }
+        }
+    }
+    Ok(text)
+}
+
+/// Recognizes text drawn with `#` (or `█`) on any other background character.
+pub fn recognize_str(s: &str) -> Result<String, OcrError> {
+    let pixels = s
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#' || c == '█').collect())
+        .collect::<Vec<_>>();
+    recognize(&pixels)
+}
+
+/// Recognizes text drawn by the lit `(x, y)` points, `y` growing downwards.
+pub fn recognize_points(points: impl IntoIterator<Item = (i64, i64)>) -> Result<String, OcrError> {
+    let points = points.into_iter().collect::<Vec<_>>();
+    if points.is_empty() {
+        return Err(OcrError::Empty);
+    }
+    let (x0, y0) = points
+        .iter()
+        .fold((i64::MAX, i64::MAX), |(x0, y0), &(x, y)| {
+            (x0.min(x), y0.min(y))
+        });
+    let (x1, y1) = points
+        .iter()
+        .fold((i64::MIN, i64::MIN), |(x1, y1), &(x, y)| {
+            (x1.max(x), y1.max(y))
+        });
+    let mut pixels = vec![vec![false; (x1 - x0 + 1) as usize]; (y1 - y0 + 1) as usize];
+    for (x, y) in points {
+        pixels[(y - y0) as usize][(x - x0) as usize] = true;
+    }
+    recognize(&pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(font: &[(char, &str)], text: &str, gap: usize) -> String {
+        let glyphs = text
+            .chars()
+            .map(|c| font.iter().find(|g| g.0 == c).unwrap().1)
+            .collect::<Vec<_>>();
+        let height = glyphs[0].lines().count();
+        (0..height)
+            .map(|i| {
+                glyphs
+                    .iter()
+                    .map(|g| g.lines().nth(i).unwrap())
+                    .collect::<Vec<_>>()
+                    .join(&".".repeat(gap))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn small_font() {
+        let alphabet = FONT_6.iter().map(|g| g.0).collect::<String>();
+        let drawing = draw(&FONT_6, &alphabet, 1);
+        assert_eq!(Ok(alphabet), recognize_str(&drawing));
+    }
+
+    #[test]
+    fn large_font() {
+        let alphabet = FONT_10.iter().map(|g| g.0).collect::<String>();
+        let drawing = draw(&FONT_10, &alphabet, 2);
+        assert_eq!(Ok(alphabet), recognize_str(&drawing));
+    }
+
+    #[test]
+    fn padded() {
+        let drawing = "
+..........................
+..#..#.####.#....#.....##.
+..#..#.#....#....#....#..#
+..####.###..#....#....#..#
+..#..#.#....#....#....#..#
+..#..#.#....#....#....#..#
+..#..#.####.####.####..##.
+..........................";
+        assert_eq!(Ok("HELLO".to_string()), recognize_str(drawing));
+        let points = drawing
+            .trim()
+            .lines()
+            .enumerate()
+            .flat_map(|(y, l)| {
+                l.bytes()
+                    .enumerate()
+                    .filter(|e| e.1 == b'#')
+                    .map(move |(x, _)| (x as i64 - 10, y as i64 + 3))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(Ok("HELLO".to_string()), recognize_points(points));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(OcrError::Empty), recognize_str("....\n...."));
+        assert_eq!(Err(OcrError::UnsupportedHeight(2)), recognize_str("#\n#"));
+        let drawing = "#..#.####\n#..#.#..#\n####.#..#\n#..#.#..#\n#..#.#..#\n#..#.####";
+        assert_eq!(
+            Err(OcrError::UnknownGlyph {
+                column: 5,
+                glyph: "####\n#..#\n#..#\n#..#\n#..#\n####".to_string()
+            }),
+            recognize_str(drawing)
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! nom parsers for the shapes puzzle inputs come in, with errors that say
+//! where the input went wrong:
+//!
+//! ```text
+//! expected an integer at line 3, column 9
+//!   3 | 1-3 a: x7
+//!     |         ^
+//! ```
+//!
+//! Parsers return [`PResult`]; [`parse_all`], [`lines`] and [`sections`]
+//! run them and turn failures into a [`ParseError`] against the whole input.
+
+use nom::bytes::complete::{tag, take_till1, take_while1};
+use nom::character::complete::{char, digit1, multispace1, one_of, space0};
+use nom::combinator::{all_consuming, cut, map_res, opt, recognize};
+use nom::error::{ContextError, ErrorKind, FromExternalError, context};
+use nom::multi::{many0, separated_list1};
+use nom::sequence::{preceded, separated_pair};
+use nom::{IResult, Parser};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Where a parser gave up, and what it wanted there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<'a> {
+    pub input: &'a str,
+    kind: ErrorKind,
+    context: Option<&'static str>,
+}
+
+impl Error<'_> {
+    fn expected(&self) -> String {
+        match self.context {
+            Some(c) => c.to_string(),
+            None if self.kind == ErrorKind::Eof => "end of input".to_string(),
+            None => self.kind.description().to_lowercase(),
+        }
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Self {
+            input,
+            kind,
+            context: None,
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+/// The innermost context wins, being the most specific.
+impl<'a> ContextError<&'a str> for Error<'a> {
+    fn add_context(_: &'a str, ctx: &'static str, mut other: Self) -> Self {
+        other.context.get_or_insert(ctx);
+        other
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for Error<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _: E) -> Self {
+        nom::error::ParseError::from_error_kind(input, kind)
+    }
+}
+
+pub type PResult<'a, T> = IResult<&'a str, T, Error<'a>>;
+
+/// A failure located in the whole input, 1-based. `Debug` shows the same
+/// as `Display` so that `unwrap` points at the problem.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    excerpt: String,
+}
+
+impl ParseError {
+    /// `rest` is the part of `input` from where things went wrong.
+    pub fn at(input: &str, rest: &str, expected: impl Into<String>) -> Self {
+        let offset = (rest.as_ptr() as usize)
+            .wrapping_sub(input.as_ptr() as usize)
+            .min(input.len());
+        let before = &input[..offset];
+        let start = before.rfind('\n').map_or(0, |i| i + 1);
+        let end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: input[start..offset].chars().count() + 1,
+            expected: expected.into(),
+            excerpt: input[start..end].trim_end_matches('\r').to_string(),
+        }
+    }
+
+    fn from_nom(input: &str, e: nom::Err<Error>) -> Self {
+        match e {
+            nom::Err::Incomplete(_) => Self::at(input, &input[input.len()..], "more input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => Self::at(input, e.input, e.expected()),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = self.line.to_string().len();
+        writeln!(
+            f,
+            "expected {} at line {}, column {}",
+            self.expected, self.line, self.column
+        )?;
+        writeln!(f, "  {} | {}", self.line, self.excerpt)?;
+        write!(f, "  {:gutter$} | {:>2$}", "", "^", self.column)
+    }
+}
+
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn run<'a, T>(
+    input: &'a str,
+    part: &'a str,
+    parser: &mut impl Parser<&'a str, Output = T, Error = Error<'a>>,
+) -> Result<T, ParseError> {
+    all_consuming(|i| parser.parse(i))
+        .parse(part)
+        .map(|(_, t)| t)
+        .map_err(|e| ParseError::from_nom(input, e))
+}
+
+/// Runs `parser` over all of `input`.
+pub fn parse_all<'a, T>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, Output = T, Error = Error<'a>>,
+) -> Result<T, ParseError> {
+    run(input, input, &mut parser)
+}
+
+/// Runs `parser` over each whole line, of which there must be at least one.
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, Output = T, Error = Error<'a>>,
+) -> Result<Vec<T>, ParseError> {
+    if input.is_empty() {
+        return Err(ParseError::at(input, input, "a line"));
+    }
+    input
+        .lines()
+        .map(|line| run(input, line, &mut parser))
+        .collect()
+}
+
+/// Runs `parser` over each block of lines between blank lines, without the
+/// final newline. Extra blank lines are skipped, but there must be at least
+/// one block.
+pub fn sections<'a, T>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, Output = T, Error = Error<'a>>,
+) -> Result<Vec<T>, ParseError> {
+    if blocks(input).next().is_none() {
+        return Err(ParseError::at(input, input, "a section"));
+    }
+    blocks(input).map(|s| run(input, s, &mut parser)).collect()
+}
+
+fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|s| s.trim_start_matches('\n').trim_end_matches('\n'))
+        .filter(|s| !s.is_empty())
+}
+
+/// The blocks between blank lines, as for [`sections`], for inputs that come
+/// in exactly `N` parts.
+pub fn split_sections<const N: usize>(input: &str) -> Result<[&str; N], ParseError> {
+    let mut blocks = blocks(input);
+    let mut parts = [""; N];
+    for (i, part) in parts.iter_mut().enumerate() {
+        let end = &input[input.len()..];
+        *part = blocks
+            .next()
+            .ok_or_else(|| ParseError::at(input, end, format!("{N} sections, found {i}")))?;
+    }
+    if let Some(extra) = blocks.next() {
+        return Err(ParseError::at(input, extra, format!("only {N} sections")));
+    }
+    Ok(parts)
+}
+
+pub fn unsigned<T: FromStr>(input: &str) -> PResult<'_, T> {
+    context("an unsigned integer", map_res(digit1, str::parse)).parse(input)
+}
+
+/// With an optional `+` or `-`.
+pub fn signed<T: FromStr>(input: &str) -> PResult<'_, T> {
+    let number = recognize((opt(one_of("+-")), digit1));
+    context("an integer", map_res(number, str::parse)).parse(input)
+}
+
+/// Signed integers separated by `sep`, with any spaces around it. A
+/// separator must be followed by another integer.
+pub fn int_list<'a, T: FromStr>(
+    sep: &'static str,
+) -> impl Parser<&'a str, Output = Vec<T>, Error = Error<'a>> {
+    let rest = many0(preceded((space0, tag(sep), space0), cut(signed)));
+    (signed, rest).map(|(first, mut rest)| {
+        rest.insert(0, first);
+        rest
+    })
+}
+
+/// Where the next integer in `s` is, as matched by the regex `-?\d+`.
+fn next_int(s: &str) -> Option<Range<usize>> {
+    let b = s.as_bytes();
+    let start = (0..b.len()).find(|&i| {
+        b[i].is_ascii_digit() || b[i] == b'-' && b.get(i + 1).is_some_and(u8::is_ascii_digit)
+    })?;
+    let digits = b[start + 1..].iter().take_while(|c| c.is_ascii_digit());
+    Some(start..start + 1 + digits.count())
+}
+
+fn fail<'a>(input: &'a str, expected: &'static str) -> nom::Err<Error<'a>> {
+    nom::Err::Error(Error {
+        input,
+        kind: ErrorKind::Verify,
+        context: Some(expected),
+    })
+}
+
+/// Every integer in the rest of the line, skipping anything else, like the
+/// regex `-?\d+`. Fails only on numbers too big for `T`.
+pub fn all_numbers<T: FromStr>(input: &str) -> PResult<'_, Vec<T>> {
+    let mut numbers = vec![];
+    let mut rest = input;
+    while let Some(r) = next_int(rest) {
+        let n = rest[r.clone()]
+            .parse()
+            .map_err(|_| fail(&rest[r.start..], "an integer"))?;
+        numbers.push(n);
+        rest = &rest[r.end..];
+    }
+    Ok((&rest[rest.len()..], numbers))
+}
+
+/// Exactly `N` integers in the rest of the line, as for [`all_numbers`].
+pub fn fixed_ints<const N: usize, T: FromStr + Default + Copy>(input: &str) -> PResult<'_, [T; N]> {
+    let mut numbers = [T::default(); N];
+    let mut rest = input;
+    for n in &mut numbers {
+        let r = next_int(rest).ok_or_else(|| fail(&rest[rest.len()..], "another integer"))?;
+        *n = rest[r.clone()]
+            .parse()
+            .map_err(|_| fail(&rest[r.start..], "an integer that fits"))?;
+        rest = &rest[r.end..];
+    }
+    if let Some(r) = next_int(rest) {
+        return Err(fail(&rest[r.start..], "no more integers"));
+    }
+    Ok((&rest[rest.len()..], numbers))
+}
+
+/// The `N` integers in a line of prose, e.g. `ints::<4, i32>("p=0,4 v=3,-3")`.
+pub fn ints<const N: usize, T: FromStr + Default + Copy>(line: &str) -> Result<[T; N], ParseError> {
+    parse_all(line, fixed_ints::<N, T>)
+}
+
+/// Iterator over the integers in a string, as for [`all_numbers`], without
+/// allocating. Panics on a number too big for `T`.
+#[derive(Debug, Clone)]
+pub struct Integers<'a, T> {
+    rest: &'a str,
+    number: PhantomData<T>,
+}
+
+pub fn integers<T>(s: &str) -> Integers<'_, T> {
+    Integers {
+        rest: s,
+        number: PhantomData,
+    }
+}
+
+impl<T: FromStr> Iterator for Integers<'_, T>
+where
+    T::Err: fmt::Debug,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let r = next_int(self.rest)?;
+        let s = &self.rest[r.clone()];
+        self.rest = &self.rest[r.end..];
+        Some(s.parse().unwrap_or_else(|e| panic!("{s}: {e:?}")))
+    }
+}
+
+/// Whitespace-separated `key<sep>value` pairs, like `ecl:gry pid:860033327`.
+pub fn key_values<'a>(
+    sep: char,
+) -> impl Parser<&'a str, Output = Vec<(&'a str, &'a str)>, Error = Error<'a>> {
+    let key = context(
+        "a key",
+        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+    );
+    let value = context("a value", take_till1(char::is_whitespace));
+    let pair = separated_pair(key, cut(context("a separator", char(sep))), cut(value));
+    separated_list1(multispace1, pair)
+}
+
+/// A non-empty rectangle of bytes, one row per line.
+pub fn grid(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    grid_cells(input, None)
+}
+
+/// A rectangle of bytes from `cells` only.
+pub fn grid_of(input: &str, cells: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    grid_cells(input, Some(cells))
+}
+
+fn grid_cells(input: &str, cells: Option<&str>) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut rows = vec![];
+    for line in input.lines() {
+        let b = line.as_bytes();
+        if b.is_empty() {
+            return Err(ParseError::at(input, line, "a row"));
+        }
+        if let Some(cells) = cells
+            && let Some(i) = b.iter().position(|c| !cells.as_bytes().contains(c))
+        {
+            return Err(ParseError::at(
+                input,
+                &line[i..],
+                format!("one of {cells:?}"),
+            ));
+        }
+        if let Some(first) = rows.first().map(Vec::len)
+            && first != b.len()
+        {
+            let rest = &line[line.floor_char_boundary(first)..];
+            return Err(ParseError::at(input, rest, format!("a row of {first}")));
+        }
+        rows.push(b.to_vec());
+    }
+    if rows.is_empty() {
+        return Err(ParseError::at(input, input, "a row"));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let input = "1, 2, 3\n4, x, 6\n";
+        let e = lines(input, int_list::<i32>(",")).unwrap_err();
+        assert_eq!((2, 4), (e.line, e.column));
+        let expected = "expected an integer at line 2, column 4\n  2 | 4, x, 6\n    |    ^";
+        assert_eq!(expected, e.to_string());
+        let e = lines("", unsigned::<u8>).unwrap_err();
+        assert_eq!((1, 1, "a line"), (e.line, e.column, e.expected.as_str()));
+        let e = parse_all("7\n8\n-", unsigned::<u8>).unwrap_err();
+        assert_eq!(
+            "expected end of input at line 1, column 2\n  1 | 7\n    |  ^",
+            e.to_string()
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(
+            Ok(vec![2, -18, -2, 15]),
+            parse_all(line, all_numbers::<i32>)
+        );
+        assert_eq!(Ok(vec![1, -2, 3]), parse_all("1-2 - 3 -", all_numbers));
+        assert_eq!(Ok(Vec::<u8>::new()), parse_all("none here", all_numbers));
+        let e = parse_all("a 300 b", all_numbers::<u8>).unwrap_err();
+        assert_eq!(("an integer", 3), (e.expected.as_str(), e.column));
+        assert_eq!(
+            Ok(vec![3, -4, 5]),
+            parse_all("3 ->  -4->5", int_list::<i8>("->"))
+        );
+        assert_eq!(Ok(12), parse_all("+12", signed::<i64>));
+    }
+
+    #[test]
+    fn fixed_arity() {
+        assert_eq!(Ok([0, 4, 3, -3]), ints::<4, i32>("p=0,4 v=3,-3"));
+        let e = ints::<4, i32>("p=0,4 v=3").unwrap_err();
+        assert_eq!(("another integer", 10), (e.expected.as_str(), e.column));
+        let e = ints::<2, i32>("p=0,4 v=3").unwrap_err();
+        assert_eq!(("no more integers", 9), (e.expected.as_str(), e.column));
+        let e = ints::<1, u8>("x=-1").unwrap_err();
+        assert_eq!(("an integer that fits", 3), (e.expected.as_str(), e.column));
+        let e = lines("1 2\n3\n", fixed_ints::<2, u8>).unwrap_err();
+        assert_eq!((2, 2), (e.line, e.column));
+
+        let line = "Blueprint 12: Each ore robot costs 4 ore. Each obsidian robot costs 3 ore and 14 clay.";
+        let all = integers::<u16>(line).collect::<Vec<_>>();
+        assert_eq!(vec![12, 4, 3, 14], all);
+        assert_eq!(Some(-7), integers::<i64>("a-b -7").next());
+    }
+
+    #[test]
+    fn split() {
+        let input = "a\nb\n\nc\n\n\nd\n";
+        assert_eq!(Ok(["a\nb", "c", "d"]), split_sections::<3>(input));
+        let e = split_sections::<2>(input).unwrap_err();
+        assert_eq!(
+            (7, 1, "only 2 sections"),
+            (e.line, e.column, e.expected.as_str())
+        );
+        let e = split_sections::<4>(input).unwrap_err();
+        assert_eq!("4 sections, found 3", e.expected);
+        let e = sections("\n\n", unsigned::<u8>).unwrap_err();
+        assert_eq!("a section", e.expected);
+    }
+
+    #[test]
+    fn blocks() {
+        let input = "ecl:gry pid:860033327\nbyr:1937\n\n\niyr:2013 ecl:amb\n";
+        let blocks = sections(input, key_values(':')).unwrap();
+        assert_eq!(2, blocks.len());
+        assert_eq!(
+            vec![("ecl", "gry"), ("pid", "860033327"), ("byr", "1937")],
+            blocks[0]
+        );
+        let e = sections("a:1\n\nb:2 c3\n", key_values(':')).unwrap_err();
+        assert_eq!(
+            (3, 7, "a separator"),
+            (e.line, e.column, e.expected.as_str())
+        );
+    }
+
+    #[test]
+    fn grids() {
+        assert_eq!(Ok(vec![b"#.".to_vec(), b".#".to_vec()]), grid("#.\n.#\n"));
+        let e = grid("#..\n#.\n").unwrap_err();
+        assert_eq!(
+            (2, 3, "a row of 3"),
+            (e.line, e.column, e.expected.as_str())
+        );
+        let e = grid("ab\nxé\n").unwrap_err();
+        assert_eq!(
+            (2, 2, "a row of 2"),
+            (e.line, e.column, e.expected.as_str())
+        );
+        assert!(grid("").is_err());
+        assert!(grid("#\n\n#").is_err());
+        let e = grid_of("#..\n#x.\n", "#.").unwrap_err();
+        let expected = "expected one of \"#.\" at line 2, column 2\n  2 | #x.\n    |  ^";
+        assert_eq!(expected, e.to_string());
+    }
+}
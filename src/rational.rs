@@ -0,0 +1,251 @@
+//! Exact fractions over `i128`, always kept in lowest terms. Overflow
+//! panics, in release builds too, rather than give a wrong result.
+
+use crate::math::gcd;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// `num / den` with `den > 0` and `gcd(num, den) == 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self { num: 0, den: 1 };
+    pub const ONE: Self = Self { num: 1, den: 1 };
+
+    /// Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "zero denominator");
+        let g = gcd(num, den);
+        let (num, den) = (num / g, den / g);
+        if den < 0 {
+            Self {
+                num: -num,
+                den: -den,
+            }
+        } else {
+            Self { num, den }
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        -(-self.num).div_euclid(self.den)
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            num: self.num.abs(),
+            den: self.den,
+        }
+    }
+
+    pub fn signum(&self) -> i128 {
+        self.num.signum()
+    }
+
+    /// Panics on zero.
+    pub fn recip(&self) -> Self {
+        Self::new(self.den, self.num)
+    }
+}
+
+const OVERFLOW: &str = "rational overflow";
+
+fn mul(a: i128, b: i128) -> i128 {
+    a.checked_mul(b).expect(OVERFLOW)
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+macro_rules! impl_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Rational {
+            fn from(n: $t) -> Self {
+                Self {
+                    num: n as i128,
+                    den: 1,
+                }
+            }
+        }
+    )*};
+}
+
+impl_from!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let g = gcd(self.den, rhs.den);
+        let (a, b) = (self.den / g, rhs.den / g);
+        Self::new(
+            mul(self.num, b)
+                .checked_add(mul(rhs.num, a))
+                .expect(OVERFLOW),
+            mul(self.den, b),
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // cross-reduce first so the products stay small
+        let g1 = gcd(self.num, rhs.den);
+        let g2 = gcd(rhs.num, self.den);
+        Self::new(
+            mul(self.num / g1, rhs.num / g2),
+            mul(self.den / g2, rhs.den / g1),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "division by zero");
+        let g1 = gcd(self.num, rhs.num);
+        let g2 = gcd(self.den, rhs.den);
+        Self::new(
+            mul(self.num / g1, rhs.den / g2),
+            mul(self.den / g2, rhs.num / g1),
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive
+        mul(self.num, other.den).cmp(&mul(other.num, self.den))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(num: i128, den: i128) -> Rational {
+        Rational::new(num, den)
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(r(1, 2), r(-2, -4));
+        assert_eq!(r(-1, 3), r(2, -6));
+        assert_eq!(r(5, 6), r(1, 2) + r(1, 3));
+        assert_eq!(r(1, 6), r(1, 2) - r(1, 3));
+        assert_eq!(r(1, 6), r(1, 2) * r(1, 3));
+        assert_eq!(r(3, 2), r(1, 2) / r(1, 3));
+        assert_eq!(Rational::ZERO, r(0, 5));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert!(r(7, 3) > Rational::from(2));
+        assert_eq!((-4, -3), (r(-7, 2).floor(), r(-7, 2).ceil()));
+        assert_eq!((3, 4), (r(7, 2).floor(), r(7, 2).ceil()));
+        assert_eq!(Some(-3), r(9, -3).to_integer());
+        assert_eq!(None, r(1, 3).to_integer());
+        assert_eq!("-7/2", r(7, -2).to_string());
+        assert_eq!("4", r(8, 2).to_string());
+    }
+
+    #[test]
+    fn large() {
+        let big = Rational::from(i64::MAX) * Rational::from(i64::MAX);
+        assert_eq!(Rational::from(i64::MAX), big / Rational::from(i64::MAX));
+        let x = r(i64::MAX as i128, 3) * r(3, i64::MAX as i128);
+        assert_eq!(Rational::ONE, x);
+    }
+
+    #[test]
+    #[should_panic(expected = "rational overflow")]
+    fn overflow() {
+        let _ = r(1, i64::MAX as i128) + r(1, i64::MAX as i128 - 1);
+        let _ = r(1, i128::MAX) + r(1, i128::MAX - 1);
+    }
+}
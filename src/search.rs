@@ -0,0 +1,546 @@
+//! Graph searches over implicit graphs described by a `neighbors` closure.
+
+use ahash::AHashMap as HashMap;
+use ahash::AHashSet as HashSet;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque, hash_map::Entry},
+    hash::Hash,
+    ops::Add,
+};
+
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+struct Node<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Node<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Node<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Node<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Node<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // min-heap on priority, prefer deeper nodes on ties
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+/// Costs and a shortest path tree of every state reachable from the starts.
+pub struct Reachable<S, C> {
+    dist: HashMap<S, C>,
+    parent: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Reachable<S, C> {
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.dist.get(state).copied()
+    }
+
+    pub fn path(&self, target: &S) -> Option<Vec<S>> {
+        self.dist.get(target)?;
+        Some(walk_back(&self.parent, target.clone()))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&S, C)> {
+        self.dist.iter().map(|(s, &c)| (s, c))
+    }
+
+    pub fn into_map(self) -> HashMap<S, C> {
+        self.dist
+    }
+}
+
+/// Predecessor DAG of all shortest paths to the cheapest goal states.
+pub struct ShortestPaths<S, C> {
+    pub cost: C,
+    pub goals: Vec<S>,
+    parents: HashMap<S, Vec<S>>,
+}
+
+impl<S: Clone + Eq + Hash, C> ShortestPaths<S, C> {
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.parents.get(state).map_or(&[], |p| p.as_slice())
+    }
+
+    /// One of the shortest paths, from a start to the first goal found.
+    pub fn path(&self) -> Vec<S> {
+        let mut path = vec![self.goals[0].clone()];
+        while let Some(p) = self.predecessors(path.last().unwrap()).first() {
+            path.push(p.clone());
+        }
+        path.reverse();
+        path
+    }
+
+    /// Every state lying on at least one shortest path.
+    pub fn states(&self) -> HashSet<S> {
+        let mut seen: HashSet<S> = self.goals.iter().cloned().collect();
+        let mut q = self.goals.clone();
+        while let Some(s) = q.pop() {
+            for p in self.predecessors(&s) {
+                if seen.insert(p.clone()) {
+                    q.push(p.clone());
+                }
+            }
+        }
+        seen
+    }
+}
+
+fn walk_back<S: Clone + Eq + Hash>(parent: &HashMap<S, S>, target: S) -> Vec<S> {
+    let mut path = vec![target];
+    while let Some(p) = parent.get(path.last().unwrap()) {
+        path.push(p.clone());
+    }
+    path.reverse();
+    path
+}
+
+fn best_first<S, C, FN, IN, FH, FG>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: FN,
+    mut heuristic: FH,
+    mut goal: FG,
+    mut parent: Option<&mut HashMap<S, S>>,
+) -> (Option<(S, C)>, HashMap<S, C>)
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FH: FnMut(&S) -> C,
+    FG: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut q = BinaryHeap::new();
+    for s in starts {
+        let cost = C::default();
+        dist.insert(s.clone(), cost);
+        q.push(Node {
+            priority: heuristic(&s),
+            cost,
+            state: s,
+        });
+    }
+    while let Some(Node { cost, state, .. }) = q.pop() {
+        if dist[&state] < cost {
+            continue;
+        }
+        if goal(&state) {
+            return (Some((state, cost)), dist);
+        }
+        for (next, c) in neighbors(&state) {
+            let cost = cost + c;
+            match dist.entry(next.clone()) {
+                Entry::Occupied(e) if *e.get() <= cost => continue,
+                Entry::Occupied(mut e) => *e.get_mut() = cost,
+                Entry::Vacant(e) => {
+                    e.insert(cost);
+                }
+            }
+            if let Some(parent) = parent.as_deref_mut() {
+                parent.insert(next.clone(), state.clone());
+            }
+            q.push(Node {
+                priority: cost + heuristic(&next),
+                cost,
+                state: next,
+            });
+        }
+    }
+    (None, dist)
+}
+
+/// Cheapest goal state and its cost.
+pub fn dijkstra<S, C, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: FN,
+    goal: FG,
+) -> Option<(S, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FG: FnMut(&S) -> bool,
+{
+    best_first(starts, neighbors, |_| C::default(), goal, None).0
+}
+
+/// Cheapest path from a start to a goal state, both ends included.
+pub fn dijkstra_path<S, C, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: FN,
+    goal: FG,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut parent = HashMap::new();
+    let (end, _) = best_first(starts, neighbors, |_| C::default(), goal, Some(&mut parent));
+    end.map(|(s, c)| (walk_back(&parent, s), c))
+}
+
+/// Distances to every reachable state.
+pub fn dijkstra_all<S, C, FN, IN>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: FN,
+) -> Reachable<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+{
+    let mut parent = HashMap::new();
+    let (_, dist) = best_first(
+        starts,
+        neighbors,
+        |_| C::default(),
+        |_| false,
+        Some(&mut parent),
+    );
+    Reachable { dist, parent }
+}
+
+/// Dijkstra guided by `heuristic`, which must never overestimate the remaining cost.
+pub fn astar<S, C, FN, IN, FH, FG>(
+    start: S,
+    neighbors: FN,
+    heuristic: FH,
+    goal: FG,
+) -> Option<(S, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FH: FnMut(&S) -> C,
+    FG: FnMut(&S) -> bool,
+{
+    best_first([start], neighbors, heuristic, goal, None).0
+}
+
+pub fn astar_path<S, C, FN, IN, FH, FG>(
+    start: S,
+    neighbors: FN,
+    heuristic: FH,
+    goal: FG,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FH: FnMut(&S) -> C,
+    FG: FnMut(&S) -> bool,
+{
+    let mut parent = HashMap::new();
+    let (end, _) = best_first([start], neighbors, heuristic, goal, Some(&mut parent));
+    end.map(|(s, c)| (walk_back(&parent, s), c))
+}
+
+/// All shortest paths to the goal states of minimum cost.
+pub fn shortest_paths<S, C, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: FN,
+    mut goal: FG,
+) -> Option<ShortestPaths<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut parents: HashMap<S, Vec<S>> = HashMap::new();
+    let mut goals = vec![];
+    let mut best = None;
+    let mut q = BinaryHeap::new();
+    for s in starts {
+        let cost = C::default();
+        dist.insert(s.clone(), cost);
+        q.push(Node {
+            priority: cost,
+            cost,
+            state: s,
+        });
+    }
+    while let Some(Node { cost, state, .. }) = q.pop() {
+        if dist[&state] < cost {
+            continue;
+        }
+        if best.is_some_and(|b| cost > b) {
+            break;
+        }
+        if goal(&state) {
+            best = Some(cost);
+            goals.push(state);
+            continue;
+        }
+        for (next, c) in neighbors(&state) {
+            let cost = cost + c;
+            match dist.entry(next.clone()) {
+                Entry::Occupied(e) if *e.get() < cost => continue,
+                Entry::Occupied(e) if *e.get() == cost => {
+                    parents.get_mut(&next).unwrap().push(state.clone());
+                    continue;
+                }
+                Entry::Occupied(mut e) => *e.get_mut() = cost,
+                Entry::Vacant(e) => {
+                    e.insert(cost);
+                }
+            }
+            parents.insert(next.clone(), vec![state.clone()]);
+            q.push(Node {
+                priority: cost,
+                cost,
+                state: next,
+            });
+        }
+    }
+    best.map(|cost| ShortestPaths {
+        cost,
+        goals,
+        parents,
+    })
+}
+
+fn breadth_first<S, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: FN,
+    mut goal: FG,
+    mut parent: Option<&mut HashMap<S, S>>,
+) -> (Option<(S, usize)>, HashMap<S, usize>)
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = S>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut q = VecDeque::new();
+    for s in starts {
+        if dist.insert(s.clone(), 0).is_none() {
+            q.push_back(s);
+        }
+    }
+    while let Some(state) = q.pop_front() {
+        let d = dist[&state];
+        if goal(&state) {
+            return (Some((state, d)), dist);
+        }
+        for next in neighbors(&state) {
+            if let Entry::Vacant(e) = dist.entry(next.clone()) {
+                e.insert(d + 1);
+                if let Some(parent) = parent.as_deref_mut() {
+                    parent.insert(next.clone(), state.clone());
+                }
+                q.push_back(next);
+            }
+        }
+    }
+    (None, dist)
+}
+
+/// Nearest goal state and its distance, every edge costing 1.
+pub fn bfs<S, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: FN,
+    goal: FG,
+) -> Option<(S, usize)>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = S>,
+    FG: FnMut(&S) -> bool,
+{
+    breadth_first(starts, neighbors, goal, None).0
+}
+
+pub fn bfs_path<S, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: FN,
+    goal: FG,
+) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = S>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut parent = HashMap::new();
+    let (end, _) = breadth_first(starts, neighbors, goal, Some(&mut parent));
+    end.map(|(s, _)| walk_back(&parent, s))
+}
+
+pub fn bfs_all<S, FN, IN>(starts: impl IntoIterator<Item = S>, neighbors: FN) -> Reachable<S, usize>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = S>,
+{
+    let mut parent = HashMap::new();
+    let (_, dist) = breadth_first(starts, neighbors, |_| false, Some(&mut parent));
+    Reachable { dist, parent }
+}
+
+/// Shortest distance on a graph whose edges cost either 0 or 1.
+pub fn bfs_01<S, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: FN,
+    mut goal: FG,
+) -> Option<(S, usize)>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, bool)>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut q = VecDeque::new();
+    for s in starts {
+        dist.insert(s.clone(), 0);
+        q.push_back((s, 0));
+    }
+    while let Some((state, d)) = q.pop_front() {
+        if dist[&state] < d {
+            continue;
+        }
+        if goal(&state) {
+            return Some((state, d));
+        }
+        for (next, expensive) in neighbors(&state) {
+            let d = d + expensive as usize;
+            match dist.entry(next.clone()) {
+                Entry::Occupied(e) if *e.get() <= d => continue,
+                Entry::Occupied(mut e) => *e.get_mut() = d,
+                Entry::Vacant(e) => {
+                    e.insert(d);
+                }
+            }
+            if expensive {
+                q.push_back((next, d));
+            } else {
+                q.push_front((next, d));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "
+S.#.....
+..#.##.#
+.##..#..
+....#..E";
+
+    fn grid() -> Vec<&'static [u8]> {
+        GRID.trim().lines().map(|l| l.as_bytes()).collect()
+    }
+
+    fn moves(g: &[&[u8]], (i, j): (i32, i32)) -> Vec<(i32, i32)> {
+        [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)]
+            .into_iter()
+            .filter(|&(i, j)| {
+                i >= 0
+                    && j >= 0
+                    && (i as usize) < g.len()
+                    && (j as usize) < g[0].len()
+                    && g[i as usize][j as usize] != b'#'
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unit_grid() {
+        let g = grid();
+        let end = (3, 7);
+        let unit = |&p: &(i32, i32)| moves(&g, p).into_iter().map(|q| (q, 1u32));
+        let manhattan =
+            |&(i, j): &(i32, i32)| (end.0 - i).unsigned_abs() + (end.1 - j).unsigned_abs();
+
+        assert_eq!(
+            Some((end, 16)),
+            bfs([(0, 0)], |&p| moves(&g, p), |&p| p == end)
+        );
+        assert_eq!(Some((end, 16)), dijkstra([(0, 0)], unit, |&p| p == end));
+        assert_eq!(
+            Some((end, 16)),
+            astar((0, 0), unit, manhattan, |&p| p == end)
+        );
+        let zero_one = |&p: &(i32, i32)| moves(&g, p).into_iter().map(|q| (q, true));
+        assert_eq!(Some((end, 16)), bfs_01([(0, 0)], zero_one, |&p| p == end));
+
+        let path = bfs_path([(0, 0)], |&p| moves(&g, p), |&p| p == end).unwrap();
+        assert_eq!(17, path.len());
+        assert_eq!((0, 0), path[0]);
+        assert!(path.windows(2).all(|w| moves(&g, w[0]).contains(&w[1])));
+        let (path, cost) = astar_path((0, 0), unit, manhattan, |&p| p == end).unwrap();
+        assert_eq!((17, 16), (path.len(), cost));
+
+        let all = bfs_all([(0, 0)], |&p| moves(&g, p));
+        assert_eq!(Some(16), all.cost(&end));
+        assert_eq!(None, all.cost(&(0, 2)));
+        assert_eq!(Some(17), all.path(&end).map(|p| p.len()));
+        assert_eq!(Some(16), dijkstra_all([(0, 0)], unit).cost(&end));
+    }
+
+    #[test]
+    fn weighted() {
+        // 0 -> 1 -> 3 and 0 -> 2 -> 3 both cost 5, 0 -> 3 costs 6
+        let edges = [
+            vec![(1, 2), (2, 1), (3, 6)],
+            vec![(3, 3)],
+            vec![(3, 4)],
+            vec![],
+        ];
+        let next = |&s: &usize| edges[s].clone();
+        assert_eq!(
+            Some((vec![0, 2, 3], 5)),
+            dijkstra_path([0], next, |&s| s == 3)
+        );
+
+        let sp = shortest_paths([0], next, |&s| s == 3).unwrap();
+        assert_eq!(5, sp.cost);
+        assert_eq!(vec![3], sp.goals);
+        assert_eq!(2, sp.predecessors(&3).len());
+        let mut states = sp.states().into_iter().collect::<Vec<_>>();
+        states.sort_unstable();
+        assert_eq!(vec![0, 1, 2, 3], states);
+
+        let zero_one = |&s: &usize| match s {
+            0 => vec![(1, true), (2, false)],
+            2 => vec![(1, false)],
+            _ => vec![],
+        };
+        assert_eq!(Some((1, 0)), bfs_01([0], zero_one, |&s| s == 1));
+    }
+}
@@ -0,0 +1,75 @@
+//! Runs every day that has an input in `data/YEAR/dayN` and checks what it
+//! prints against `answers/YEAR/dayN`. Inputs aren't checked in, so days
+//! without one are reported and skipped, and days without answers only have
+//! to exit cleanly.
+
+use adventofcode::input::{parse_answers, printed_answer, read_or_skip};
+use std::path::Path;
+use std::process::Command;
+
+fn check_year(year: u16, binary: &str) {
+    let mut failures = vec![];
+    for day in 1..=25 {
+        let input = format!("data/{year}/day{day}");
+        if !Path::new(&input).exists() {
+            println!("{year} day {day}: skipped: no input");
+            continue;
+        }
+        let output = Command::new(binary)
+            .arg(day.to_string())
+            .output()
+            .expect("failed to run the solution binary");
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            failures.push(format!("{year} day {day}: {}\n{stderr}", output.status));
+            continue;
+        }
+        let Some(answers) = read_or_skip(format!("answers/{year}/day{day}")) else {
+            println!("{year} day {day}: ran without answers to check");
+            continue;
+        };
+        let answers = match parse_answers(&answers) {
+            Ok(answers) => answers,
+            Err(e) => {
+                failures.push(format!("answers/{year}/day{day}: {e}"));
+                continue;
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let before = failures.len();
+        for (part, expected) in answers {
+            match printed_answer(&stdout, part) {
+                Some(actual) if actual == expected => {}
+                actual => failures.push(format!(
+                    "{year} day {day} part{part}: expected {expected}, got {actual:?}"
+                )),
+            }
+        }
+        if failures.len() == before {
+            println!("{year} day {day}: ok");
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+macro_rules! years {
+    ($($name:ident => $year:literal),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                check_year($year, env!(concat!("CARGO_BIN_EXE_", stringify!($year))));
+            }
+        )*
+    };
+}
+
+years!(
+    aoc2018 => 2018,
+    aoc2019 => 2019,
+    aoc2020 => 2020,
+    aoc2021 => 2021,
+    aoc2022 => 2022,
+    aoc2023 => 2023,
+    aoc2024 => 2024,
+    aoc2025 => 2025,
+);